@@ -181,7 +181,7 @@ oxwm.bar.set_blocks({
     oxwm.bar.block.static({
         text = " │  ",
         format = "",
-        interval = 999999999,
+        interval = 86400,
         color = colors.lavender,
         underline = false
     }),
@@ -194,21 +194,21 @@ oxwm.bar.set_blocks({
     oxwm.bar.block.static({
         text = " │  ",
         format = "",
-        interval = 999999999,
+        interval = 86400,
         color = colors.lavender,
         underline = false
     }),
     oxwm.bar.block.shell({
         command = "uname -r",
         format = " {}",
-        interval = 999999999,
+        interval = 86400,
         color = colors.red,
         underline = true
     }),
     oxwm.bar.block.static({
         text = " │  ",
         format = "",
-        interval = 999999999,
+        interval = 86400,
         color = colors.lavender,
         underline = false
     }),
@@ -222,7 +222,7 @@ oxwm.bar.set_blocks({
     oxwm.bar.block.static({
         text = " │  ",
         format = "",
-        interval = 999999999,
+        interval = 86400,
         color = colors.lavender,
         underline = false
     }),
@@ -230,14 +230,14 @@ oxwm.bar.set_blocks({
         command = "bluetoothctl show | awk '/Powered/ {print ($2==\"yes\"?\"on\":\"off\")}'",
         onclick_command = "bluetoothctl show | awk '/Powered/ {print $2}' | grep -q yes && bluetoothctl power off >/dev/null && echo off || { bluetoothctl power on >/dev/null; dev=$(bluetoothctl info | awk -F': ' '/Name/ {print $2; exit}'); [ -n \"$dev\" ] && echo \"on ($dev)\" || echo on; }",
         format = "BT {}",
-        interval = 999999999,
+        interval = 86400,
         color = colors.blue,
         underline = true
     }),
     oxwm.bar.block.button({
         format = "󰐥",
         command = "xmessage 'Test button'",
-        interval = 999999999,
+        interval = 86400,
         color = colors.red,
         underline = false,
     })
@@ -0,0 +1,57 @@
+use super::{AnimationConfig, Easing};
+use crate::layout::WindowGeometry;
+use std::time::{Duration, Instant};
+use x11rb::protocol::xproto::Window;
+
+/// Relayouts block the event loop while they run, so they never take longer
+/// than this regardless of the configured duration.
+const MAX_DURATION: Duration = Duration::from_millis(150);
+
+pub struct WindowTransition {
+    pub window: Window,
+    pub from: WindowGeometry,
+    pub to: WindowGeometry,
+}
+
+impl WindowTransition {
+    pub fn at(&self, progress: f64) -> WindowGeometry {
+        let lerp = |from: f64, to: f64| from + (to - from) * progress;
+        WindowGeometry {
+            x_coordinate: lerp(self.from.x_coordinate as f64, self.to.x_coordinate as f64).round()
+                as i32,
+            y_coordinate: lerp(self.from.y_coordinate as f64, self.to.y_coordinate as f64).round()
+                as i32,
+            width: lerp(self.from.width as f64, self.to.width as f64)
+                .round()
+                .max(1.0) as u32,
+            height: lerp(self.from.height as f64, self.to.height as f64)
+                .round()
+                .max(1.0) as u32,
+        }
+    }
+}
+
+pub struct LayoutAnimation {
+    start_time: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl LayoutAnimation {
+    pub fn new(config: &AnimationConfig) -> Self {
+        Self {
+            start_time: Instant::now(),
+            duration: config.duration.min(MAX_DURATION),
+            easing: config.easing,
+        }
+    }
+
+    /// Eased progress in `0.0..=1.0`; reaches 1.0 once the duration has passed.
+    pub fn progress(&self) -> f64 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        let t = (self.start_time.elapsed().as_secs_f64() / self.duration.as_secs_f64()).min(1.0);
+        if t >= 1.0 { 1.0 } else { self.easing.apply(t) }
+    }
+}
@@ -1,7 +1,10 @@
+mod layout;
 mod scroll;
 
+pub use layout::{LayoutAnimation, WindowTransition};
 pub use scroll::ScrollAnimation;
 
+use std::str::FromStr;
 use std::time::Duration;
 
 #[derive(Debug, Clone, Copy)]
@@ -11,6 +14,19 @@ pub enum Easing {
     EaseInOut,
 }
 
+impl FromStr for Easing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "linear" => Ok(Self::Linear),
+            "ease_out" => Ok(Self::EaseOut),
+            "ease_in_out" => Ok(Self::EaseInOut),
+            _ => Err(format!("Invalid easing: {}", s)),
+        }
+    }
+}
+
 impl Easing {
     pub fn apply(&self, t: f64) -> f64 {
         match self {
@@ -27,11 +43,21 @@ impl Easing {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct AnimationConfig {
     pub duration: Duration,
     pub easing: Easing,
 }
 
+impl AnimationConfig {
+    pub fn disabled() -> Self {
+        Self {
+            duration: Duration::ZERO,
+            ..Self::default()
+        }
+    }
+}
+
 impl Default for AnimationConfig {
     fn default() -> Self {
         Self {
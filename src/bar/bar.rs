@@ -1,5 +1,6 @@
-use super::blocks::Block;
+use super::blocks::{Block, BlockAlign, BlockCommand, BlockWidth, SharedTitle};
 use super::font::{DrawingSurface, Font};
+use super::segment::SegmentPosition;
 use crate::Config;
 use crate::errors::X11Error;
 use std::time::Instant;
@@ -22,9 +23,19 @@ pub struct Bar {
     blocks_width: Vec<u16>,
     block_last_updates: Vec<Instant>,
     block_underlines: Vec<bool>,
+    block_min_widths: Vec<Option<BlockWidth>>,
+    block_aligns: Vec<BlockAlign>,
+    /// Last text of each block, so a change to one block doesn't rerun the others.
+    block_texts: Vec<Option<String>>,
+    /// Blocks that show the focused window's title.
+    title_blocks: Vec<usize>,
     status_text: String,
+    segments: Vec<(SegmentPosition, String, u32)>,
+    focused_title: SharedTitle,
 
     tags: Vec<String>,
+    tag_labels_occupied: Vec<String>,
+    tag_labels_empty: Vec<String>,
     scheme_normal: crate::ColorScheme,
     scheme_occupied: crate::ColorScheme,
     scheme_selected: crate::ColorScheme,
@@ -96,45 +107,57 @@ impl Bar {
             colormap,
         )?;
 
-        let horizontal_padding = (font.height() as f32 * 0.4) as u16;
+        let focused_title = SharedTitle::default();
+        let blocks: Vec<Box<dyn Block>> = config
+            .status_blocks
+            .iter()
+            .map(|block_config| block_config.to_block(&focused_title))
+            .collect();
 
-        let tag_widths = config
-            .tags
+        let block_underlines: Vec<bool> = config
+            .status_blocks
             .iter()
-            .map(|tag| {
-                let text_width = font.text_width(tag);
-                text_width + (horizontal_padding * 2)
-            })
+            .map(|block_config| block_config.underline)
             .collect();
 
-        let blocks: Vec<Box<dyn Block>> = config
+        let block_min_widths: Vec<Option<BlockWidth>> = config
             .status_blocks
             .iter()
-            .map(|block_config| block_config.to_block())
+            .map(|block_config| block_config.min_width.clone())
             .collect();
 
-        let block_underlines: Vec<bool> = config
+        let block_aligns: Vec<BlockAlign> = config
             .status_blocks
             .iter()
-            .map(|block_config| block_config.underline)
+            .map(|block_config| block_config.align)
             .collect();
 
         let block_last_updates = vec![Instant::now(); blocks.len()];
         let blocks_width = vec![0; blocks.len()];
+        let block_texts = vec![None; blocks.len()];
+        let title_blocks = title_blocks(config);
 
-        Ok(Bar {
+        let mut bar = Bar {
             window,
             width,
             height,
             graphics_context,
             surface,
-            tag_widths,
+            tag_widths: Vec::new(),
             needs_redraw: true,
             blocks,
             block_last_updates,
             block_underlines,
+            block_min_widths,
+            block_aligns,
+            block_texts,
+            title_blocks,
             status_text: String::new(),
+            segments: Vec::new(),
+            focused_title,
             tags: config.tags.clone(),
+            tag_labels_occupied: config.tag_labels_occupied.clone(),
+            tag_labels_empty: config.tag_labels_empty.clone(),
             scheme_normal: config.scheme_normal,
             scheme_occupied: config.scheme_occupied,
             scheme_selected: config.scheme_selected,
@@ -143,7 +166,9 @@ impl Bar {
             last_occupied_tags: 0,
             last_current_tags: 0,
             blocks_width,
-        })
+        };
+        bar.update_all_blocks();
+        Ok(bar)
     }
 
     pub fn window(&self) -> Window {
@@ -154,33 +179,169 @@ impl Bar {
         self.height
     }
 
+    fn tag_label(&self, tag_index: usize, is_occupied: bool) -> &str {
+        let labels = if is_occupied {
+            &self.tag_labels_occupied
+        } else {
+            &self.tag_labels_empty
+        };
+
+        labels
+            .get(tag_index)
+            .or_else(|| self.tags.get(tag_index))
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    fn update_tag_widths(&mut self, font: &Font, occupied_tags: u32) {
+        let horizontal_padding = (font.height() as f32 * 0.4) as u16;
+
+        self.tag_widths = (0..self.tags.len())
+            .map(|tag_index| {
+                let is_occupied = (occupied_tags & (1 << tag_index)) != 0;
+                font.text_width(self.tag_label(tag_index, is_occupied)) + (horizontal_padding * 2)
+            })
+            .collect();
+    }
+
+    fn block_slot_width(&self, block_index: usize, font: &Font, text_width: u16) -> u16 {
+        let min_width = self
+            .block_min_widths
+            .get(block_index)
+            .and_then(Option::as_ref);
+        BlockWidth::slot_width(min_width, text_width, |sample| font.text_width(sample))
+    }
+
+    fn fill_rectangle(
+        &self,
+        display: *mut x11::xlib::Display,
+        color: u32,
+        x: i16,
+        width: u16,
+        height: u16,
+    ) {
+        unsafe {
+            let gc = x11::xlib::XCreateGC(display, self.surface.pixmap(), 0, std::ptr::null_mut());
+            x11::xlib::XSetForeground(display, gc, color as u64);
+            x11::xlib::XFillRectangle(
+                display,
+                self.surface.pixmap(),
+                gc,
+                x as i32,
+                0,
+                width as u32,
+                height as u32,
+            );
+            x11::xlib::XFreeGC(display, gc);
+        }
+    }
+
+    pub fn reposition(
+        &mut self,
+        connection: &RustConnection,
+        x: i16,
+        y: i16,
+        width: u16,
+    ) -> Result<(), X11Error> {
+        connection.configure_window(
+            self.window,
+            &ConfigureWindowAux::new()
+                .x(x as i32)
+                .y(y as i32)
+                .width(width as u32),
+        )?;
+
+        let display = self.surface.display();
+        let visual = unsafe { x11::xlib::XDefaultVisual(display, 0) };
+        let colormap = unsafe { x11::xlib::XDefaultColormap(display, 0) };
+
+        self.surface = DrawingSurface::new(
+            display,
+            self.window as x11::xlib::Drawable,
+            width as u32,
+            self.height as u32,
+            visual,
+            colormap,
+        )?;
+        self.width = width;
+        self.needs_redraw = true;
+
+        connection.flush()?;
+        Ok(())
+    }
+
     pub fn invalidate(&mut self) {
         self.needs_redraw = true;
     }
 
+    pub fn set_segments(&mut self, segments: Vec<(SegmentPosition, String, u32)>) {
+        if self.segments != segments {
+            self.segments = segments;
+            self.needs_redraw = true;
+        }
+    }
+
     pub fn update_blocks(&mut self) {
         let now = Instant::now();
         let mut changed = false;
 
-        for (i, block) in self.blocks.iter_mut().enumerate() {
+        for i in 0..self.blocks.len() {
             let elapsed = now.duration_since(self.block_last_updates[i]);
-
-            if elapsed >= block.interval() && block.content().is_ok() {
-                self.block_last_updates[i] = now;
-                changed = true;
+            if elapsed >= self.blocks[i].interval() {
+                changed |= self.update_block(i);
             }
         }
 
         if changed {
-            let mut parts = Vec::new();
-            for block in &mut self.blocks {
-                if let Ok(text) = block.content() {
-                    parts.push(text);
-                }
-            }
-            self.status_text = parts.join("");
-            self.needs_redraw = true;
+            self.refresh_status_text();
+        }
+    }
+
+    pub fn update_focused_title(&mut self, title: Option<&str>) {
+        if self.focused_title.borrow().as_deref() == title {
+            return;
+        }
+        *self.focused_title.borrow_mut() = title.map(str::to_string);
+        let mut changed = false;
+        for i in self.title_blocks.clone() {
+            changed |= self.update_block(i);
         }
+        if changed {
+            self.refresh_status_text();
+        }
+    }
+
+    fn update_all_blocks(&mut self) {
+        for i in 0..self.blocks.len() {
+            self.update_block(i);
+        }
+        self.refresh_status_text();
+    }
+
+    /// Reruns one block, returning whether its text changed.
+    fn update_block(&mut self, index: usize) -> bool {
+        let Some(block) = self.blocks.get_mut(index) else {
+            return false;
+        };
+        let text = block.content().ok();
+        if text.is_some() {
+            self.block_last_updates[index] = Instant::now();
+        }
+        if self.block_texts[index] == text {
+            return false;
+        }
+        self.block_texts[index] = text;
+        true
+    }
+
+    fn refresh_status_text(&mut self) {
+        self.status_text = self
+            .block_texts
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        self.needs_redraw = true;
     }
 
     pub fn draw(
@@ -222,10 +383,11 @@ impl Bar {
 
         self.last_occupied_tags = occupied_tags;
         self.last_current_tags = current_tags;
+        self.update_tag_widths(font, occupied_tags);
 
         let mut x_position: i16 = 0;
 
-        for (tag_index, tag) in self.tags.iter().enumerate() {
+        for tag_index in 0..self.tags.len() {
             let tag_mask = 1 << tag_index;
             let is_selected = (current_tags & tag_mask) != 0;
             let is_occupied = (occupied_tags & tag_mask) != 0;
@@ -247,7 +409,18 @@ impl Bar {
                 &self.scheme_normal
             };
 
-            let text_width = font.text_width(tag);
+            if scheme.background != self.scheme_normal.background {
+                self.fill_rectangle(
+                    display,
+                    scheme.background,
+                    x_position,
+                    tag_width,
+                    self.height,
+                );
+            }
+
+            let label = self.tag_label(tag_index, is_occupied);
+            let text_width = font.text_width(label);
             let text_x = x_position + ((tag_width - text_width) / 2) as i16;
 
             let top_padding = 4;
@@ -255,7 +428,7 @@ impl Bar {
 
             self.surface
                 .font_draw()
-                .draw_text(font, scheme.foreground, text_x, text_y, tag);
+                .draw_text(font, scheme.foreground, text_x, text_y, label);
 
             if is_selected || is_urgent {
                 let font_height = font.height();
@@ -320,30 +493,68 @@ impl Bar {
                 text_y,
                 indicator,
             );
+
+            x_position += font.text_width(indicator) as i16;
+        }
+
+        for (_, text, color) in self
+            .segments
+            .iter()
+            .filter(|(position, _, _)| *position == SegmentPosition::Left)
+        {
+            x_position += 10;
+            let text_y = top_padding + font.ascent();
+            self.surface
+                .font_draw()
+                .draw_text(font, *color, x_position, text_y, text);
+            x_position += font.text_width(text) as i16;
         }
 
+        let mut right_edge = self.width as i16;
+
         if draw_blocks && !self.status_text.is_empty() {
             let padding = 10;
             let mut x_position = self.width as i16 - padding;
 
-            for (i, block) in self.blocks.iter_mut().enumerate().rev() {
-                if let Ok(text) = block.content() {
-                    let text_width = font.text_width(&text);
+            let block_texts = self.block_texts.clone();
+
+            if self.scheme_selected.background != self.scheme_normal.background {
+                let blocks_total_width: u16 = block_texts
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, text)| text.as_ref().map(|text| (i, text)))
+                    .map(|(i, text)| self.block_slot_width(i, font, font.text_width(text)))
+                    .sum();
+                let area_width = blocks_total_width + padding as u16 * 2;
+                let area_x = self.width as i16 - area_width as i16;
+
+                self.fill_rectangle(
+                    display,
+                    self.scheme_selected.background,
+                    area_x,
+                    area_width,
+                    self.height,
+                );
+            }
+
+            for (i, block) in self.blocks.iter().enumerate().rev() {
+                if let Some(text) = &block_texts[i] {
+                    let text_width = font.text_width(text);
+                    let slot_width = self.block_slot_width(i, font, text_width);
                     if let Some(block_width) = self.blocks_width.get_mut(i) {
-                        *block_width = text_width;
+                        *block_width = slot_width;
                     }
-                    x_position -= text_width as i16;
+                    x_position -= slot_width as i16;
+
+                    let align = self.block_aligns.get(i).copied().unwrap_or_default();
+                    let text_x = x_position + align.offset(text_width, slot_width) as i16;
 
                     let top_padding = 4;
                     let text_y = top_padding + font.ascent();
 
-                    self.surface.font_draw().draw_text(
-                        font,
-                        block.color(),
-                        x_position,
-                        text_y,
-                        &text,
-                    );
+                    self.surface
+                        .font_draw()
+                        .draw_text(font, block.color(), text_x, text_y, text);
 
                     if self.block_underlines[i] {
                         let font_height = font.height();
@@ -352,7 +563,7 @@ impl Bar {
                         let underline_y = self.height as i16 - underline_height as i16 - bottom_gap;
 
                         let underline_padding = 8;
-                        let underline_width = text_width + underline_padding;
+                        let underline_width = slot_width + underline_padding;
                         let underline_x = x_position - (underline_padding / 2) as i16;
 
                         unsafe {
@@ -377,6 +588,20 @@ impl Bar {
                     }
                 }
             }
+
+            right_edge = x_position - padding;
+        }
+
+        for (_, text, color) in self
+            .segments
+            .iter()
+            .filter(|(position, _, _)| *position == SegmentPosition::Right)
+        {
+            right_edge -= 10 + font.text_width(text) as i16;
+            let text_y = top_padding + font.ascent();
+            self.surface
+                .font_draw()
+                .draw_text(font, *color, right_edge, text_y, text);
         }
 
         unsafe {
@@ -425,14 +650,31 @@ impl Bar {
             current_x_position += tag_width as i16;
         }
 
+        if let Some(index) = self.block_at(click_x)
+            && let Some(block) = self.blocks.get_mut(index)
+        {
+            block.on_click(click_x);
+        }
+        None
+    }
+
+    pub fn handle_scroll(&mut self, scroll_x: i16, up: bool) {
+        if let Some(index) = self.block_at(scroll_x)
+            && let Some(block) = self.blocks.get_mut(index)
+        {
+            block.on_scroll(up);
+            if self.update_block(index) {
+                self.refresh_status_text();
+            }
+        }
+    }
+
+    fn block_at(&self, x: i16) -> Option<usize> {
         let padding = 10;
-        current_x_position = self.width as i16 - padding;
+        let mut current_x_position = self.width as i16 - padding;
         for (i, &block_width) in self.blocks_width.iter().enumerate().rev() {
-            if click_x >= current_x_position - block_width as i16 && click_x < current_x_position {
-                if let Some(block) = self.blocks.get_mut(i) {
-                    block.on_click(click_x);
-                }
-                return None;
+            if x >= current_x_position - block_width as i16 && x < current_x_position {
+                return Some(i);
             }
             current_x_position -= block_width as i16;
         }
@@ -443,11 +685,26 @@ impl Bar {
         self.needs_redraw
     }
 
-    pub fn update_from_config(&mut self, config: &Config) {
+    pub fn update_tags(&mut self, config: &Config, monitor_index: usize) {
+        self.tags = config.monitor_tags(monitor_index).to_vec();
+        self.tag_labels_occupied = config.tag_labels_occupied.clone();
+        self.tag_labels_empty = config.tag_labels_empty.clone();
+        self.needs_redraw = true;
+    }
+
+    pub fn update_schemes(&mut self, config: &Config) {
+        self.scheme_normal = config.scheme_normal;
+        self.scheme_occupied = config.scheme_occupied;
+        self.scheme_selected = config.scheme_selected;
+        self.scheme_urgent = config.scheme_urgent;
+        self.needs_redraw = true;
+    }
+
+    pub fn update_from_config(&mut self, config: &Config, monitor_index: usize) {
         self.blocks = config
             .status_blocks
             .iter()
-            .map(|block_config| block_config.to_block())
+            .map(|block_config| block_config.to_block(&self.focused_title))
             .collect();
 
         self.block_underlines = config
@@ -456,16 +713,38 @@ impl Bar {
             .map(|block_config| block_config.underline)
             .collect();
 
+        self.block_min_widths = config
+            .status_blocks
+            .iter()
+            .map(|block_config| block_config.min_width.clone())
+            .collect();
+
+        self.block_aligns = config
+            .status_blocks
+            .iter()
+            .map(|block_config| block_config.align)
+            .collect();
+
         self.block_last_updates = vec![Instant::now(); self.blocks.len()];
+        self.block_texts = vec![None; self.blocks.len()];
+        self.title_blocks = title_blocks(config);
 
-        self.tags = config.tags.clone();
-        self.scheme_normal = config.scheme_normal;
-        self.scheme_occupied = config.scheme_occupied;
-        self.scheme_selected = config.scheme_selected;
-        self.scheme_urgent = config.scheme_urgent;
+        self.update_tags(config, monitor_index);
+        self.update_schemes(config);
         self.hide_vacant_tags = config.hide_vacant_tags;
 
-        self.status_text.clear();
-        self.needs_redraw = true;
+        self.update_all_blocks();
     }
 }
+
+fn title_blocks(config: &Config) -> Vec<usize> {
+    config
+        .status_blocks
+        .iter()
+        .enumerate()
+        .filter(|(_, block_config)| {
+            matches!(block_config.command, BlockCommand::WindowTitle { .. })
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
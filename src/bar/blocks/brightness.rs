@@ -0,0 +1,110 @@
+use super::Block;
+use crate::errors::BlockError;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const BACKLIGHT_BASE: &str = "/sys/class/backlight";
+
+pub struct Brightness {
+    format: String,
+    interval: Duration,
+    color: u32,
+    device: Option<String>,
+    set_command: Option<String>,
+    step: u32,
+    /// Level just requested by a scroll, shown until the backlight is reread.
+    requested: Option<u32>,
+}
+
+fn detect_device() -> Option<String> {
+    let mut names: Vec<String> = fs::read_dir(BACKLIGHT_BASE)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names.into_iter().next()
+}
+
+impl Brightness {
+    pub fn new(
+        format: &str,
+        interval_secs: u64,
+        color: u32,
+        device: Option<String>,
+        set_command: Option<String>,
+        step: u32,
+    ) -> Self {
+        Self {
+            format: format.to_string(),
+            interval: Duration::from_secs(interval_secs),
+            color,
+            device,
+            set_command,
+            step,
+            requested: None,
+        }
+    }
+
+    fn device_path(&self) -> Result<PathBuf, BlockError> {
+        let name = self
+            .device
+            .clone()
+            .or_else(detect_device)
+            .ok_or_else(|| BlockError::MissingFile(format!("{}/*", BACKLIGHT_BASE)))?;
+        Ok(PathBuf::from(BACKLIGHT_BASE).join(name))
+    }
+
+    fn get_percent(&self) -> Result<u32, BlockError> {
+        let path = self.device_path()?;
+        let current: u64 = fs::read_to_string(path.join("brightness"))?
+            .trim()
+            .parse()?;
+        let max: u64 = fs::read_to_string(path.join("max_brightness"))?
+            .trim()
+            .parse()?;
+        if max == 0 {
+            return Err(BlockError::InvalidData(
+                "max_brightness is zero".to_string(),
+            ));
+        }
+        Ok(((current * 100 + max / 2) / max) as u32)
+    }
+}
+
+impl Block for Brightness {
+    fn content(&mut self) -> Result<String, BlockError> {
+        let percent = match self.requested.take() {
+            Some(percent) => percent,
+            None => self.get_percent()?,
+        };
+        Ok(self.format.replace("{}", &percent.to_string()))
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn color(&self) -> u32 {
+        self.color
+    }
+
+    fn on_scroll(&mut self, up: bool) {
+        let Some(command) = &self.set_command else {
+            return;
+        };
+        let Ok(percent) = self.get_percent() else {
+            return;
+        };
+
+        let target = if up {
+            (percent + self.step).min(100)
+        } else {
+            percent.saturating_sub(self.step)
+        };
+
+        crate::signal::spawn_detached(&command.replace("{}", &target.to_string()));
+        self.requested = Some(target);
+    }
+}
@@ -0,0 +1,203 @@
+use super::Block;
+use crate::errors::BlockError;
+use mlua::{Function, HookTriggers, Lua, RegistryKey, VmState};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+const EXECUTION_BUDGET: Duration = Duration::from_millis(50);
+const ERROR_TEXT: &str = "ERR";
+
+#[derive(Clone)]
+pub struct LuaFunction {
+    lua: Lua,
+    key: Rc<RegistryKey>,
+}
+
+impl LuaFunction {
+    pub fn new(lua: &Lua, function: Function) -> mlua::Result<Self> {
+        Ok(Self {
+            lua: lua.clone(),
+            key: Rc::new(lua.create_registry_value(function)?),
+        })
+    }
+
+    fn call(&self) -> mlua::Result<String> {
+        let function: Function = self.lua.registry_value(&self.key)?;
+        let started = Instant::now();
+
+        self.lua.set_hook(
+            HookTriggers::new().every_nth_instruction(1000),
+            move |_, _| {
+                if started.elapsed() > EXECUTION_BUDGET {
+                    Err(mlua::Error::RuntimeError(format!(
+                        "exceeded execution budget of {}ms",
+                        EXECUTION_BUDGET.as_millis()
+                    )))
+                } else {
+                    Ok(VmState::Continue)
+                }
+            },
+        );
+        let result = function.call::<String>(());
+        self.lua.remove_hook();
+
+        result
+    }
+}
+
+impl std::fmt::Debug for LuaFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LuaFunction")
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+pub struct LuaBlock {
+    format: String,
+    function: LuaFunction,
+    interval: Duration,
+    color: u32,
+    cached_output: Option<String>,
+    last_run: Option<Instant>,
+    reported_error: bool,
+}
+
+impl LuaBlock {
+    pub fn new(format: &str, function: &LuaFunction, interval_secs: u64, color: u32) -> Self {
+        Self {
+            format: format.to_string(),
+            function: function.clone(),
+            interval: Duration::from_secs(interval_secs),
+            color,
+            cached_output: None,
+            last_run: None,
+            reported_error: false,
+        }
+    }
+
+    fn execute(&mut self) -> String {
+        let output = match self.function.call() {
+            Ok(result) => {
+                self.reported_error = false;
+                if self.format.is_empty() {
+                    result
+                } else {
+                    self.format.replace("{}", &result)
+                }
+            }
+            Err(error) => {
+                if !self.reported_error {
+                    eprintln!("Lua block failed: {}", error);
+                    self.reported_error = true;
+                }
+                ERROR_TEXT.to_string()
+            }
+        };
+
+        self.cached_output = Some(output.clone());
+        self.last_run = Some(Instant::now());
+        output
+    }
+}
+
+impl Block for LuaBlock {
+    fn content(&mut self) -> Result<String, BlockError> {
+        let should_refresh = match self.last_run {
+            None => true,
+            Some(last) => last.elapsed() >= self.interval,
+        };
+
+        if should_refresh {
+            return Ok(self.execute());
+        }
+
+        self.cached_output
+            .clone()
+            .ok_or_else(|| BlockError::CommandFailed("No cached output".to_string()))
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn color(&self) -> u32 {
+        self.color
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(lua: &Lua, source: &str, format: &str) -> LuaBlock {
+        let function: Function = lua.load(source).eval().unwrap();
+        let function = LuaFunction::new(lua, function).unwrap();
+        LuaBlock::new(format, &function, 0, 0)
+    }
+
+    #[test]
+    fn formats_the_returned_text() {
+        let lua = Lua::new();
+        let mut block = block(&lua, "function() return 'user@host' end", "[{}]");
+        assert_eq!(block.content().unwrap(), "[user@host]");
+    }
+
+    #[test]
+    fn renders_err_for_a_failing_function() {
+        let lua = Lua::new();
+        let mut block = block(&lua, "function() error('boom') end", "");
+        assert_eq!(block.content().unwrap(), ERROR_TEXT);
+        assert!(block.reported_error);
+        assert_eq!(block.content().unwrap(), ERROR_TEXT);
+    }
+
+    #[test]
+    fn renders_err_for_a_non_string_result() {
+        let lua = Lua::new();
+        let mut block = block(&lua, "function() return {} end", "");
+        assert_eq!(block.content().unwrap(), ERROR_TEXT);
+    }
+
+    #[test]
+    fn stops_a_function_over_the_budget() {
+        let lua = Lua::new();
+        let mut block = block(&lua, "function() while true do end end", "");
+        let started = Instant::now();
+        assert_eq!(block.content().unwrap(), ERROR_TEXT);
+        assert!(started.elapsed() < EXECUTION_BUDGET * 20);
+    }
+
+    #[test]
+    fn recovers_and_reports_again_after_a_later_failure() {
+        let lua = Lua::new();
+        let mut block = block(
+            &lua,
+            "local calls = 0
+             return function()
+                 calls = calls + 1
+                 if calls == 2 then return 'ok' end
+                 error('failing call ' .. calls)
+             end",
+            "",
+        );
+        assert_eq!(block.content().unwrap(), ERROR_TEXT);
+        assert_eq!(block.content().unwrap(), "ok");
+        assert!(!block.reported_error);
+        assert_eq!(block.content().unwrap(), ERROR_TEXT);
+        assert!(block.reported_error);
+    }
+
+    #[test]
+    fn serves_the_cached_output_within_the_interval() {
+        let lua = Lua::new();
+        let function: Function = lua
+            .load("local calls = 0 return function() calls = calls + 1 return tostring(calls) end")
+            .eval()
+            .unwrap();
+        let function = LuaFunction::new(&lua, function).unwrap();
+        let mut block = LuaBlock::new("", &function, 3600, 0);
+        assert_eq!(block.content().unwrap(), "1");
+        assert_eq!(block.content().unwrap(), "1");
+    }
+}
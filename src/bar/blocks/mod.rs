@@ -2,21 +2,32 @@ use crate::{bar::blocks::button::ButtonBlock, errors::BlockError};
 use std::time::Duration;
 
 mod battery;
+mod brightness;
 mod button;
 mod datetime;
+mod lua;
 mod ram;
 mod shell;
+mod temperature;
+mod window_title;
 
 use battery::Battery;
+use brightness::Brightness;
 use datetime::DateTime;
+use lua::LuaBlock;
+pub use lua::LuaFunction;
 use ram::Ram;
 use shell::ShellBlock;
+use temperature::Temperature;
+pub use window_title::SharedTitle;
+use window_title::WindowTitle;
 
 pub trait Block {
     fn content(&mut self) -> Result<String, BlockError>;
     fn interval(&self) -> Duration;
     fn color(&self) -> u32;
     fn on_click(&mut self, _click_x: i16) {}
+    fn on_scroll(&mut self, _up: bool) {}
 }
 
 #[derive(Debug, Clone)]
@@ -26,6 +37,50 @@ pub struct BlockConfig {
     pub interval_secs: u64,
     pub color: u32,
     pub underline: bool,
+    pub min_width: Option<BlockWidth>,
+    pub align: BlockAlign,
+}
+
+#[derive(Debug, Clone)]
+pub enum BlockWidth {
+    Pixels(u16),
+    Sample(String),
+}
+
+impl BlockWidth {
+    /// Width reserved for a block whose text is `text_width` wide, measuring sample
+    /// strings with `measure`.
+    pub fn slot_width(
+        min_width: Option<&BlockWidth>,
+        text_width: u16,
+        measure: impl Fn(&str) -> u16,
+    ) -> u16 {
+        let min_width = match min_width {
+            Some(BlockWidth::Pixels(pixels)) => *pixels,
+            Some(BlockWidth::Sample(sample)) => measure(sample),
+            None => 0,
+        };
+        text_width.max(min_width)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlockAlign {
+    Left,
+    Center,
+    #[default]
+    Right,
+}
+
+impl BlockAlign {
+    pub fn offset(self, text_width: u16, slot_width: u16) -> u16 {
+        let free = slot_width.saturating_sub(text_width);
+        match self {
+            BlockAlign::Left => 0,
+            BlockAlign::Center => free / 2,
+            BlockAlign::Right => free,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -42,12 +97,24 @@ pub enum BlockCommand {
         battery_name: Option<String>,
     },
     Ram,
+    Brightness {
+        device: Option<String>,
+        set_command: Option<String>,
+        step: u32,
+    },
+    Temperature {
+        zone: Option<String>,
+    },
     Static(String),
     Button(String),
+    Lua(LuaFunction),
+    WindowTitle {
+        max_len: usize,
+    },
 }
 
 impl BlockConfig {
-    pub fn to_block(&self) -> Box<dyn Block> {
+    pub fn to_block(&self, focused_title: &SharedTitle) -> Box<dyn Block> {
         match &self.command {
             BlockCommand::Shell {
                 command,
@@ -79,6 +146,24 @@ impl BlockConfig {
                 battery_name.clone(),
             )),
             BlockCommand::Ram => Box::new(Ram::new(&self.format, self.interval_secs, self.color)),
+            BlockCommand::Brightness {
+                device,
+                set_command,
+                step,
+            } => Box::new(Brightness::new(
+                &self.format,
+                self.interval_secs,
+                self.color,
+                device.clone(),
+                set_command.clone(),
+                *step,
+            )),
+            BlockCommand::Temperature { zone } => Box::new(Temperature::new(
+                &self.format,
+                self.interval_secs,
+                self.color,
+                zone.clone(),
+            )),
             BlockCommand::Static(text) => Box::new(StaticBlock::new(
                 &format!("{}{}", self.format, text),
                 self.color,
@@ -86,6 +171,19 @@ impl BlockConfig {
             BlockCommand::Button(command) => {
                 Box::new(ButtonBlock::new(&self.format, self.color, command))
             }
+            BlockCommand::Lua(function) => Box::new(LuaBlock::new(
+                &self.format,
+                function,
+                self.interval_secs,
+                self.color,
+            )),
+            BlockCommand::WindowTitle { max_len } => Box::new(WindowTitle::new(
+                &self.format,
+                *max_len,
+                self.interval_secs,
+                self.color,
+                focused_title.clone(),
+            )),
         }
     }
 }
@@ -117,3 +215,52 @@ impl Block for StaticBlock {
         self.color
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stands in for a monospace font that is 7 pixels per character.
+    fn stub_font(text: &str) -> u16 {
+        text.chars().count() as u16 * 7
+    }
+
+    #[test]
+    fn slot_is_the_text_width_without_a_minimum() {
+        assert_eq!(BlockWidth::slot_width(None, 42, stub_font), 42);
+    }
+
+    #[test]
+    fn pixel_minimum_widens_narrow_text_only() {
+        let min_width = BlockWidth::Pixels(60);
+        assert_eq!(BlockWidth::slot_width(Some(&min_width), 21, stub_font), 60);
+        assert_eq!(BlockWidth::slot_width(Some(&min_width), 84, stub_font), 84);
+    }
+
+    #[test]
+    fn sample_minimum_is_measured_with_the_font() {
+        let min_width = BlockWidth::Sample("100%".to_string());
+        assert_eq!(
+            BlockWidth::slot_width(Some(&min_width), stub_font("7%"), stub_font),
+            28
+        );
+        assert_eq!(
+            BlockWidth::slot_width(Some(&min_width), stub_font("100.0%"), stub_font),
+            42
+        );
+    }
+
+    #[test]
+    fn align_places_the_text_inside_the_slot() {
+        assert_eq!(BlockAlign::Left.offset(14, 28), 0);
+        assert_eq!(BlockAlign::Center.offset(14, 28), 7);
+        assert_eq!(BlockAlign::Right.offset(14, 28), 14);
+    }
+
+    #[test]
+    fn align_never_offsets_text_wider_than_the_slot() {
+        for align in [BlockAlign::Left, BlockAlign::Center, BlockAlign::Right] {
+            assert_eq!(align.offset(40, 28), 0);
+        }
+    }
+}
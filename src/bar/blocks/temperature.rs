@@ -0,0 +1,96 @@
+use super::Block;
+use crate::errors::BlockError;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const THERMAL_BASE: &str = "/sys/class/thermal";
+const PREFERRED_ZONE_TYPE: &str = "x86_pkg_temp";
+
+pub struct Temperature {
+    format: String,
+    interval: Duration,
+    color: u32,
+    zone: Option<String>,
+}
+
+fn zone_type(path: &Path) -> Option<String> {
+    fs::read_to_string(path.join("type"))
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+fn thermal_zones() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(THERMAL_BASE) else {
+        return Vec::new();
+    };
+
+    let mut zones: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("thermal_zone"))
+                && path.join("temp").exists()
+        })
+        .collect();
+    zones.sort();
+    zones
+}
+
+/// Finds a zone by directory name (e.g. "thermal_zone2") or by type (e.g.
+/// "acpitz"). Without a name the package sensor is preferred, falling back
+/// to the first zone.
+fn find_zone(name: Option<&str>) -> Option<PathBuf> {
+    let zones = thermal_zones();
+    match name {
+        Some(name) => zones.into_iter().find(|path| {
+            path.file_name().and_then(|n| n.to_str()) == Some(name)
+                || zone_type(path).as_deref() == Some(name)
+        }),
+        None => zones
+            .iter()
+            .find(|path| zone_type(path).as_deref() == Some(PREFERRED_ZONE_TYPE))
+            .or(zones.first())
+            .cloned(),
+    }
+}
+
+impl Temperature {
+    pub fn new(format: &str, interval_secs: u64, color: u32, zone: Option<String>) -> Self {
+        Self {
+            format: format.to_string(),
+            interval: Duration::from_secs(interval_secs),
+            color,
+            zone,
+        }
+    }
+
+    fn get_celsius(&self) -> Result<f32, BlockError> {
+        let path = find_zone(self.zone.as_deref()).ok_or_else(|| {
+            BlockError::MissingFile(match &self.zone {
+                Some(zone) => format!("{}/{}", THERMAL_BASE, zone),
+                None => format!("{}/thermal_zone*", THERMAL_BASE),
+            })
+        })?;
+
+        let millidegrees: i64 = fs::read_to_string(path.join("temp"))?.trim().parse()?;
+        Ok(millidegrees as f32 / 1000.0)
+    }
+}
+
+impl Block for Temperature {
+    fn content(&mut self) -> Result<String, BlockError> {
+        let celsius = self.get_celsius()?;
+        Ok(self.format.replace("{}", &format!("{:.0}", celsius)))
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn color(&self) -> u32 {
+        self.color
+    }
+}
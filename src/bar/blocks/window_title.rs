@@ -0,0 +1,61 @@
+use super::Block;
+use crate::errors::BlockError;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+pub type SharedTitle = Rc<RefCell<Option<String>>>;
+
+pub struct WindowTitle {
+    format: String,
+    max_len: usize,
+    interval: Duration,
+    color: u32,
+    title: SharedTitle,
+}
+
+impl WindowTitle {
+    pub fn new(
+        format: &str,
+        max_len: usize,
+        interval_secs: u64,
+        color: u32,
+        title: SharedTitle,
+    ) -> Self {
+        Self {
+            format: format.to_string(),
+            max_len,
+            interval: Duration::from_secs(interval_secs),
+            color,
+            title,
+        }
+    }
+}
+
+impl Block for WindowTitle {
+    fn content(&mut self) -> Result<String, BlockError> {
+        let title = self.title.borrow();
+        let Some(title) = title.as_deref().filter(|title| !title.is_empty()) else {
+            return Ok(String::new());
+        };
+
+        let text = if title.chars().count() > self.max_len {
+            let mut truncated: String =
+                title.chars().take(self.max_len.saturating_sub(1)).collect();
+            truncated.push('…');
+            truncated
+        } else {
+            title.to_string()
+        };
+
+        Ok(self.format.replace("{}", &text))
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn color(&self) -> u32 {
+        self.color
+    }
+}
@@ -6,58 +6,132 @@ use x11::xrender::XRenderColor;
 use crate::errors::X11Error;
 
 pub struct Font {
-    xft_font: *mut XftFont,
+    xft_fonts: Vec<*mut XftFont>,
     display: *mut Display,
 }
 
 impl Font {
-    pub fn new(display: *mut Display, screen: i32, font_name: &str) -> Result<Self, X11Error> {
-        let font_name_cstr =
-            CString::new(font_name).map_err(|_| X11Error::FontLoadFailed(font_name.to_string()))?;
+    pub fn new(
+        display: *mut Display,
+        screen: i32,
+        font_names: &[String],
+    ) -> Result<Self, X11Error> {
+        let mut xft_fonts = Vec::new();
+
+        for font_name in font_names {
+            let Ok(font_name_cstr) = CString::new(font_name.as_str()) else {
+                eprintln!("Skipping invalid font name: {}", font_name);
+                continue;
+            };
 
-        let xft_font = unsafe { XftFontOpenName(display, screen, font_name_cstr.as_ptr()) };
+            let xft_font = unsafe { XftFontOpenName(display, screen, font_name_cstr.as_ptr()) };
+
+            if xft_font.is_null() {
+                eprintln!("Failed to load fallback font: {}", font_name);
+                continue;
+            }
+
+            xft_fonts.push(xft_font);
+        }
 
-        if xft_font.is_null() {
-            return Err(X11Error::FontLoadFailed(font_name.to_string()));
+        if xft_fonts.is_empty() {
+            return Err(X11Error::FontLoadFailed(font_names.join(", ")));
         }
 
-        Ok(Font { xft_font, display })
+        Ok(Font { xft_fonts, display })
     }
 
     pub fn height(&self) -> u16 {
-        unsafe {
-            let font = &*self.xft_font;
-            font.height as u16
-        }
+        self.xft_fonts
+            .iter()
+            .map(|&xft_font| unsafe { (*xft_font).height as u16 })
+            .max()
+            .unwrap_or(0)
     }
 
     pub fn ascent(&self) -> i16 {
-        unsafe {
-            let font = &*self.xft_font;
-            font.ascent as i16
-        }
+        self.xft_fonts
+            .iter()
+            .map(|&xft_font| unsafe { (*xft_font).ascent as i16 })
+            .max()
+            .unwrap_or(0)
     }
 
     pub fn text_width(&self, text: &str) -> u16 {
+        let segments = self.segments(text);
+        let last = segments.len().saturating_sub(1);
+
+        segments
+            .iter()
+            .enumerate()
+            .map(|(index, &(xft_font, segment))| {
+                let extents = self.extents(xft_font, segment);
+                if index == last {
+                    extents.width
+                } else {
+                    extents.xOff as u16
+                }
+            })
+            .sum()
+    }
+
+    fn extents(&self, xft_font: *mut XftFont, text: &str) -> x11::xrender::XGlyphInfo {
         unsafe {
             let mut extents = std::mem::zeroed();
             x11::xft::XftTextExtentsUtf8(
                 self.display,
-                self.xft_font,
+                xft_font,
                 text.as_ptr(),
                 text.len() as i32,
                 &mut extents,
             );
-            extents.width
+            extents
+        }
+    }
+
+    fn font_for_char(&self, character: char) -> *mut XftFont {
+        self.xft_fonts
+            .iter()
+            .copied()
+            .find(|&xft_font| unsafe {
+                x11::xft::XftCharExists(self.display, xft_font, character as u32) != 0
+            })
+            .unwrap_or(self.xft_fonts[0])
+    }
+
+    fn segments<'a>(&self, text: &'a str) -> Vec<(*mut XftFont, &'a str)> {
+        let mut segments = Vec::new();
+        let mut segment_start = 0;
+        let mut segment_font = None;
+
+        for (index, character) in text.char_indices() {
+            let xft_font = self.font_for_char(character);
+            match segment_font {
+                Some(current) if current != xft_font => {
+                    segments.push((current, &text[segment_start..index]));
+                    segment_start = index;
+                    segment_font = Some(xft_font);
+                }
+                None => segment_font = Some(xft_font),
+                _ => {}
+            }
+        }
+
+        if let Some(current) = segment_font {
+            segments.push((current, &text[segment_start..]));
         }
+
+        segments
     }
 }
 
 impl Drop for Font {
     fn drop(&mut self) {
-        unsafe {
-            if !self.xft_font.is_null() {
-                x11::xft::XftFontClose(self.display, self.xft_font);
+        for &xft_font in &self.xft_fonts {
+            unsafe {
+                if !xft_font.is_null() {
+                    x11::xft::XftFontClose(self.display, xft_font);
+                }
             }
         }
     }
@@ -106,15 +180,19 @@ impl FontDraw {
                 &mut xft_color,
             );
 
-            XftDrawStringUtf8(
-                self.xft_draw,
-                &xft_color,
-                font.xft_font,
-                x as i32,
-                y as i32,
-                text.as_ptr(),
-                text.len() as i32,
-            );
+            let mut x_position = x as i32;
+            for (xft_font, segment) in font.segments(text) {
+                XftDrawStringUtf8(
+                    self.xft_draw,
+                    &xft_color,
+                    xft_font,
+                    x_position,
+                    y as i32,
+                    segment.as_ptr(),
+                    segment.len() as i32,
+                );
+                x_position += font.extents(xft_font, segment).xOff as i32;
+            }
 
             x11::xft::XftColorFree(
                 x11::xft::XftDrawDisplay(self.xft_draw),
@@ -183,6 +261,10 @@ impl DrawingSurface {
         self.pixmap
     }
 
+    pub fn display(&self) -> *mut Display {
+        self.display
+    }
+
     pub fn font_draw(&self) -> &FontDraw {
         &self.font_draw
     }
@@ -1,9 +1,11 @@
 mod bar;
 mod blocks;
 pub mod font;
+mod segment;
 
 pub use bar::Bar;
-pub use blocks::{BlockCommand, BlockConfig};
+pub use blocks::{BlockAlign, BlockCommand, BlockConfig, BlockWidth, LuaFunction};
+pub use segment::{Segment, SegmentPosition};
 
 // Bar position (for future use)
 #[derive(Debug, Clone, Copy)]
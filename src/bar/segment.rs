@@ -0,0 +1,26 @@
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentPosition {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub struct Segment {
+    pub text: String,
+    pub color: Option<u32>,
+    pub position: SegmentPosition,
+    pub monitor: Option<usize>,
+    pub expires_at: Option<Instant>,
+}
+
+impl Segment {
+    pub fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    pub fn shows_on(&self, monitor_index: usize) -> bool {
+        self.monitor.is_none_or(|monitor| monitor == monitor_index)
+    }
+}
@@ -8,10 +8,16 @@ const TEMPLATE: &str = include_str!("../../templates/config.lua");
 
 enum Args {
     Exit,
-    Arguments(Vec<String>),
+    Arguments(Arguments),
     Error(MainError),
 }
 
+#[derive(Default)]
+struct Arguments {
+    config_path: Option<String>,
+    fresh_state: bool,
+}
+
 fn main() -> Result<(), MainError> {
     let arguments = match process_args() {
         Args::Exit => return Ok(()),
@@ -19,15 +25,28 @@ fn main() -> Result<(), MainError> {
         Args::Error(e) => return Err(e),
     };
 
-    let (config, config_warning) = load_config(arguments.get(2))?;
+    let (config, config_warning) = load_config(arguments.config_path.as_ref())?;
+    let fallback_warnings = config.warnings.clone();
 
     let mut window_manager = match oxwm::window_manager::WindowManager::new(config) {
         Ok(wm) => wm,
         Err(e) => return Err(MainError::CouldNotStartWm(e)),
     };
 
+    if !arguments.fresh_state
+        && let Err(e) = window_manager.restore_saved_state()
+    {
+        eprintln!("Failed to restore saved state: {:?}", e);
+    }
+
     if let Some(warning) = config_warning {
         window_manager.show_startup_config_error(warning);
+    } else if !fallback_warnings.is_empty() {
+        for warning in &fallback_warnings {
+            eprintln!("Config warning: {}", warning);
+        }
+        window_manager
+            .show_startup_config_error(ConfigError::ValidationError(fallback_warnings.join("\n")));
     }
 
     if let Err(e) = window_manager.run() {
@@ -55,10 +74,8 @@ fn load_config(
         Err(e) => return Err(MainError::FailedReadConfig(e)),
     };
 
-    let config_directory = path.parent();
-
     let (mut config, config_warning) =
-        match oxwm::config::parse_lua_config(&config_string, config_directory) {
+        match oxwm::config::parse_lua_config(&config_string, Some(&path)) {
             Ok(config) => (config, None),
             Err(warning) => {
                 let config = match oxwm::config::parse_lua_config(TEMPLATE, None) {
@@ -105,10 +122,13 @@ fn print_help() {
     println!("OPTIONS:");
     println!("    --init              Create default config in ~/.config/oxwm/config.lua");
     println!("    --config <PATH>     Use custom config file");
+    println!("    --check [PATH]      Validate config (including conf.d/*.lua) and exit");
+    println!("    --fresh-state       Ignore tag, layout and window state saved by a previous run");
     println!("    --version           Print version information");
     println!("    --help              Print this help message\n");
     println!("CONFIG:");
     println!("    Location: ~/.config/oxwm/config.lua");
+    println!("    Snippets in ~/.config/oxwm/conf.d/*.lua are loaded afterwards in order");
     println!("    Edit the config file and use Mod+Shift+R to reload");
     println!("    No compilation needed - instant hot-reload!");
     println!("    LSP support included with oxwm.lua type definitions\n");
@@ -123,32 +143,62 @@ fn process_args() -> Args {
         Some(n) => n,
         None => return Args::Error(MainError::NoProgramName),
     };
-    let switch = args.next();
-    let path = args.next();
 
-    let switch = match switch {
-        Some(s) => s,
-        None => return Args::Arguments(vec![name]),
+    let mut arguments = Arguments::default();
+    while let Some(switch) = args.next() {
+        match switch.as_str() {
+            "--version" => {
+                println!("{name} {}", env!("CARGO_PKG_VERSION"));
+                return Args::Exit;
+            }
+            "--help" => {
+                print_help();
+                return Args::Exit;
+            }
+            "--fresh-state" => arguments.fresh_state = true,
+            "--init" => {
+                return match init_config() {
+                    Ok(_) => Args::Exit,
+                    Err(e) => Args::Error(e),
+                };
+            }
+            "--check" => {
+                return match check_config(args.next()) {
+                    Ok(_) => Args::Exit,
+                    Err(e) => Args::Error(e),
+                };
+            }
+            "--config" => match check_custom_config(args.next()) {
+                Ok(p) => arguments.config_path = Some(p),
+                Err(e) => return Args::Error(e),
+            },
+            _ => return Args::Error(MainError::InvalidArguments),
+        }
+    }
+
+    Args::Arguments(arguments)
+}
+
+fn check_config(path: Option<String>) -> Result<(), MainError> {
+    let path = match path {
+        Some(p) => PathBuf::from(check_custom_config(Some(p))?),
+        None => get_config_path()?.join(CONFIG_FILE),
     };
 
-    match switch.as_str() {
-        "--version" => {
-            println!("{name} {}", env!("CARGO_PKG_VERSION"));
-            Args::Exit
-        }
-        "--help" => {
-            print_help();
-            Args::Exit
+    let config_string = match std::fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(e) => return Err(MainError::FailedReadConfig(e)),
+    };
+
+    match oxwm::config::parse_lua_config(&config_string, Some(&path)) {
+        Ok(config) => {
+            for warning in &config.warnings {
+                println!("⚠ {}", warning);
+            }
+            println!("✓ Config at {:?} is valid", path);
+            Ok(())
         }
-        "--init" => match init_config() {
-            Ok(_) => Args::Exit,
-            Err(e) => Args::Error(e),
-        },
-        "--config" => match check_custom_config(path) {
-            Ok(p) => Args::Arguments(vec![name, switch, p]),
-            Err(e) => Args::Error(e),
-        },
-        _ => Args::Error(MainError::InvalidArguments),
+        Err(e) => Err(MainError::InvalidConfig(e)),
     }
 }
 
@@ -0,0 +1,76 @@
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args[0] == "--help" || args[0] == "-h" {
+        print_help();
+        return ExitCode::SUCCESS;
+    }
+
+    let path = oxwm::ipc::socket_path();
+    let mut stream = match UnixStream::connect(&path) {
+        Ok(stream) => stream,
+        Err(e) => {
+            eprintln!("Failed to connect to {}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let line: Vec<String> = args.iter().map(|arg| oxwm::ipc::quote_arg(arg)).collect();
+    if let Err(e) = writeln!(stream, "{}", line.join(" ")) {
+        eprintln!("Failed to send command: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    let mut response = String::new();
+    if let Err(e) = stream.read_to_string(&mut response) {
+        eprintln!("Failed to read response: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    match response.strip_prefix("ok\n") {
+        Some(output) => {
+            print!("{}", output);
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprint!("{}", response);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn print_help() {
+    println!("Usage: oxwm-msg <command> [args...]\n");
+    println!("Commands:");
+    println!("  set-segment <name> <text> [--color COLOR] [--position left|right]");
+    println!("              [--monitor N] [--ttl SECONDS]   Show text in the bar");
+    println!("  clear-segment <name>                        Remove a bar segment");
+    println!("  dump                                        Print the current state as JSON");
+    println!("  get-tag-state <N>                           Print a tag's master settings as JSON");
+    println!("  find-client [--class CLASS] [--instance INSTANCE] [--id STABLE_ID]");
+    println!(
+        "                                              List matching clients, most recent first"
+    );
+    println!("  focus-client <stable-id|window-id>          Switch to and focus a client");
+    println!();
+    println!("  view-tag <N>, toggle-view <N>, move-to-tag <N>, toggle-tag <N>");
+    println!("  view-tag-all <N>, view-next-tag, view-previous-tag, move-tag <DIR>");
+    println!(
+        "  focus-stack <DIR>, move-stack <DIR>, rotate-stack <DIR>, cycle-class <DIR>, focus-urgent, zoom, kill-client"
+    );
+    println!("  focus-floating <DIR>, kill-others, kill-all");
+    println!("  focus-monitor <DIR>, tag-monitor <DIR>, tag-monitor-keep-tag <DIR>");
+    println!(
+        "  set-layout <NAME>, cycle-layout, mirror-layout, set-master-factor <PERCENT>, inc-num-master <N>"
+    );
+    println!("  set-client-factor <DELTA>");
+    println!(
+        "  toggle-floating, toggle-fullscreen, toggle-gaps, toggle-bar, toggle-scratchpad <NAME>"
+    );
+    println!("  toggle-above, toggle-fake-fullscreen, toggle-focus-follows-mouse, cycle-theme");
+    println!("  notify <TEXT>, osd <LABEL> <PERCENT>, run-or-raise <COMMAND> <CLASS>");
+    println!("  spawn <PROGRAM> [ARGS...], spawn-terminal, quit");
+}
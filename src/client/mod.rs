@@ -5,6 +5,15 @@ pub type TagMask = u32;
 #[derive(Debug, Clone)]
 pub struct Client {
     pub name: String,
+    pub class: String,
+    pub instance: String,
+    pub stable_id: String,
+    pub last_focused: u64,
+    pub scroll_column_factor: Option<f32>,
+    pub size_factor: f32,
+    pub pid: u32,
+    pub is_terminal: bool,
+    pub swallowing: Option<Window>,
     pub min_aspect: f32,
     pub max_aspect: f32,
     pub x_position: i16,
@@ -31,8 +40,17 @@ pub struct Client {
     pub is_floating: bool,
     pub is_urgent: bool,
     pub never_focus: bool,
+    pub is_borderless: bool,
+    pub border_rule: Option<bool>,
+    pub is_above: bool,
+    pub is_below: bool,
+    pub is_sticky: bool,
+    pub is_popup: bool,
     pub old_state: bool,
     pub is_fullscreen: bool,
+    pub fake_fullscreen: bool,
+    pub is_fake_fullscreen: bool,
+    pub click_through: bool,
     pub next: Option<Window>,
     pub stack_next: Option<Window>,
     pub monitor_index: usize,
@@ -43,6 +61,15 @@ impl Client {
     pub fn new(window: Window, monitor_index: usize, tags: TagMask) -> Self {
         Self {
             name: String::new(),
+            class: String::new(),
+            instance: String::new(),
+            stable_id: String::new(),
+            last_focused: 0,
+            scroll_column_factor: None,
+            size_factor: 1.0,
+            pid: 0,
+            is_terminal: false,
+            swallowing: None,
             min_aspect: 0.0,
             max_aspect: 0.0,
             x_position: 0,
@@ -69,8 +96,17 @@ impl Client {
             is_floating: false,
             is_urgent: false,
             never_focus: false,
+            is_borderless: false,
+            border_rule: None,
+            is_above: false,
+            is_below: false,
+            is_sticky: false,
+            is_popup: false,
             old_state: false,
             is_fullscreen: false,
+            fake_fullscreen: false,
+            is_fake_fullscreen: false,
+            click_through: false,
             next: None,
             stack_next: None,
             monitor_index,
@@ -85,4 +121,22 @@ impl Client {
     pub fn height_with_border(&self) -> u16 {
         self.height.saturating_add(2 * self.border_width)
     }
+
+    /// Whether the client has been told it is fullscreen, covering the monitor or not.
+    pub fn reports_fullscreen(&self) -> bool {
+        self.is_fullscreen || self.is_fake_fullscreen
+    }
+}
+
+/// FNV-1a over the given parts, so the identifier stays the same across
+/// oxwm builds and restarts of the application.
+pub fn stable_id(parts: &[&str]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{:016x}", hash)
 }
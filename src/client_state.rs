@@ -0,0 +1,131 @@
+use crate::client::TagMask;
+
+/// Per-client state stored as CARDINALs on each client window so a restarted
+/// window manager can put every window back where it was.
+///
+/// Layout: `[VERSION, tags, monitor, floating, x, y, width, height, order, stack]`
+/// where `order` and `stack` are positions in the monitor's client list and
+/// focus stack, and negative coordinates are stored as two's complement.
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SavedClientState {
+    pub tags: TagMask,
+    pub monitor: usize,
+    pub is_floating: bool,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub order: usize,
+    pub stack: usize,
+}
+
+impl SavedClientState {
+    pub fn to_cardinals(&self) -> Vec<u32> {
+        vec![
+            VERSION,
+            self.tags,
+            self.monitor as u32,
+            self.is_floating as u32,
+            self.x as u32,
+            self.y as u32,
+            self.width,
+            self.height,
+            self.order as u32,
+            self.stack as u32,
+        ]
+    }
+
+    /// Returns `None` for data written by an unknown version or truncated data.
+    pub fn from_cardinals(values: &[u32]) -> Option<Self> {
+        let &[
+            VERSION,
+            tags,
+            monitor,
+            floating,
+            x,
+            y,
+            width,
+            height,
+            order,
+            stack,
+        ] = values
+        else {
+            return None;
+        };
+
+        Some(Self {
+            tags,
+            monitor: monitor as usize,
+            is_floating: floating != 0,
+            x: x as i32,
+            y: y as i32,
+            width,
+            height,
+            order: order as usize,
+            stack: stack as usize,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> SavedClientState {
+        SavedClientState {
+            tags: 1 << 31 | 1 << 4,
+            monitor: 1,
+            is_floating: true,
+            x: -120,
+            y: 40,
+            width: 800,
+            height: 600,
+            order: 3,
+            stack: 0,
+        }
+    }
+
+    #[test]
+    fn round_trips_through_cardinals() {
+        let state = sample();
+        assert_eq!(
+            SavedClientState::from_cardinals(&state.to_cardinals()),
+            Some(state)
+        );
+    }
+
+    #[test]
+    fn keeps_negative_coordinates() {
+        let state = SavedClientState {
+            x: i32::MIN,
+            y: -1,
+            ..sample()
+        };
+        let restored = SavedClientState::from_cardinals(&state.to_cardinals()).unwrap();
+        assert_eq!((restored.x, restored.y), (i32::MIN, -1));
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut values = sample().to_cardinals();
+        values[0] = VERSION + 1;
+        assert_eq!(SavedClientState::from_cardinals(&values), None);
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let values = sample().to_cardinals();
+        for length in 0..values.len() {
+            assert_eq!(SavedClientState::from_cardinals(&values[..length]), None);
+        }
+    }
+
+    #[test]
+    fn rejects_trailing_data() {
+        let mut values = sample().to_cardinals();
+        values.push(0);
+        assert_eq!(SavedClientState::from_cardinals(&values), None);
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::Display;
+
+pub struct Limit<T> {
+    pub name: &'static str,
+    pub min: T,
+    pub max: T,
+}
+
+pub const BORDER_WIDTH: Limit<i64> = Limit {
+    name: "border width",
+    min: 0,
+    max: 100,
+};
+
+pub const GAP: Limit<i64> = Limit {
+    name: "gap",
+    min: 0,
+    max: 500,
+};
+
+pub const SNAP_DISTANCE: Limit<i64> = Limit {
+    name: "snap distance",
+    min: 0,
+    max: 500,
+};
+
+pub const KEYCHORD_TIMEOUT: Limit<i64> = Limit {
+    name: "keychord timeout",
+    min: 100,
+    max: 60_000,
+};
+
+pub const BLOCK_INTERVAL: Limit<i64> = Limit {
+    name: "block interval",
+    min: 1,
+    max: 86400,
+};
+
+pub const MASTER_FACTOR: Limit<f32> = Limit {
+    name: "master factor",
+    min: 0.05,
+    max: 0.95,
+};
+
+pub const SCROLL_COLUMN_FACTOR: Limit<f32> = Limit {
+    name: "scroll column factor",
+    min: 0.1,
+    max: 1.0,
+};
+
+pub const CLIENT_FACTOR: Limit<f32> = Limit {
+    name: "client factor",
+    min: 0.25,
+    max: 4.0,
+};
+
+pub const SCRATCHPAD_SIZE: Limit<f32> = Limit {
+    name: "scratchpad size",
+    min: 0.05,
+    max: 1.0,
+};
+
+pub const SCRATCHPAD_OFFSET: Limit<f32> = Limit {
+    name: "scratchpad offset",
+    min: 0.0,
+    max: 1.0,
+};
+
+pub const TAG_COUNT: Limit<usize> = Limit {
+    name: "tag count",
+    min: 1,
+    max: 32,
+};
+
+pub const TAG_INDEX: Limit<usize> = Limit {
+    name: "tag index",
+    min: 0,
+    max: TAG_COUNT.max - 1,
+};
+
+pub const MONITOR_INDEX: Limit<usize> = Limit {
+    name: "monitor index",
+    min: 0,
+    max: 63,
+};
+
+impl<T: PartialOrd + Copy + Display> Limit<T> {
+    pub fn check(&self, value: T) -> Result<T, String> {
+        if value < self.min || value > self.max {
+            return Err(format!(
+                "{} {} is out of range (allowed {}..={})",
+                self.name, value, self.min, self.max
+            ));
+        }
+        Ok(value)
+    }
+
+    pub fn clamp(&self, value: T) -> T {
+        if value < self.min {
+            self.min
+        } else if value > self.max {
+            self.max
+        } else {
+            value
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_accepts_the_bounds_and_names_the_range() {
+        assert_eq!(GAP.check(0), Ok(0));
+        assert_eq!(GAP.check(500), Ok(500));
+        assert_eq!(
+            GAP.check(501),
+            Err("gap 501 is out of range (allowed 0..=500)".to_string())
+        );
+    }
+
+    #[test]
+    fn clamp_pulls_runtime_values_into_range() {
+        assert_eq!(MASTER_FACTOR.clamp(0.0), 0.05);
+        assert_eq!(MASTER_FACTOR.clamp(0.5), 0.5);
+        assert_eq!(MASTER_FACTOR.clamp(1.2), 0.95);
+        assert_eq!(CLIENT_FACTOR.clamp(8.0), 4.0);
+    }
+}
@@ -1,13 +1,19 @@
 use crate::errors::ConfigError;
-use mlua::Lua;
+use mlua::{Lua, Table};
+use std::path::{Path, PathBuf};
 
 use super::lua_api;
 
+pub(super) const CONF_D_DIR: &str = "conf.d";
+
+/// Parses `input` as the config read from `config_path`, whose directory holds
+/// the modules, includes and conf.d snippets the config may load.
 pub fn parse_lua_config(
     input: &str,
-    config_dir: Option<&std::path::Path>,
+    config_path: Option<&Path>,
 ) -> Result<crate::Config, ConfigError> {
     let lua = Lua::new();
+    let config_dir = config_path.and_then(Path::parent);
 
     if let Some(dir) = config_dir
         && let Some(dir_str) = dir.to_str()
@@ -19,39 +25,324 @@ pub fn parse_lua_config(
     }
 
     let builder = lua_api::register_api(&lua)?;
+    register_include(&lua, config_dir)?;
 
+    // Named like conf.d snippets so errors point at the file instead of quoting the source.
+    let chunk_name = config_path.map_or_else(
+        || "=config".to_string(),
+        |path| format!("@{}", path.display()),
+    );
     lua.load(input)
+        .set_name(chunk_name)
         .exec()
         .map_err(|e| ConfigError::LuaError(format!("{}", e)))?;
 
-    let builder_data = builder.borrow().clone();
+    if let Some(dir) = config_dir {
+        for path in conf_d_files(dir)? {
+            load_file(&lua, &path)?;
+        }
+    }
+
+    let mut builder_data = builder.borrow().clone();
+    let warnings = apply_fallbacks(&mut builder_data);
+
+    if let Some(schedule) = &builder_data.theme_schedule {
+        for name in [&schedule.first, &schedule.second] {
+            if !builder_data.themes.iter().any(|theme| &theme.name == name) {
+                return Err(ConfigError::LuaError(format!(
+                    "oxwm.theme.set_schedule: theme '{}' is not defined with oxwm.theme.define",
+                    name
+                )));
+            }
+        }
+    }
+
+    for theme in &mut builder_data.themes {
+        theme
+            .border_focused
+            .get_or_insert(builder_data.border_focused);
+        theme
+            .border_unfocused
+            .get_or_insert(builder_data.border_unfocused);
+        theme
+            .scheme_normal
+            .get_or_insert(builder_data.scheme_normal);
+        theme
+            .scheme_occupied
+            .get_or_insert(builder_data.scheme_occupied);
+        theme
+            .scheme_selected
+            .get_or_insert(builder_data.scheme_selected);
+        theme
+            .scheme_urgent
+            .get_or_insert(builder_data.scheme_urgent);
+    }
+
+    for (index, monitor) in builder_data.monitor_configs.iter().enumerate() {
+        if let Some(tags) = &monitor.tags
+            && tags.len() != builder_data.tags.len()
+        {
+            return Err(ConfigError::LuaError(format!(
+                "oxwm.monitor.configure({}) defines {} tags, but oxwm.set_tags defines {}",
+                index,
+                tags.len(),
+                builder_data.tags.len()
+            )));
+        }
+        if let Some(tag) = monitor.start_tag
+            && tag >= builder_data.tags.len()
+        {
+            return Err(ConfigError::LuaError(format!(
+                "oxwm.monitor.configure({}): tag {} is out of range (0..{})",
+                index,
+                tag,
+                builder_data.tags.len()
+            )));
+        }
+    }
 
     Ok(crate::Config {
         border_width: builder_data.border_width,
         border_focused: builder_data.border_focused,
         border_unfocused: builder_data.border_unfocused,
-        font: builder_data.font,
+        border_urgent: builder_data.border_urgent,
+        smartborders_enabled: builder_data.smartborders_enabled,
+        fonts: builder_data.fonts,
         gaps_enabled: builder_data.gaps_enabled,
         smartgaps_enabled: builder_data.smartgaps_enabled,
         gap_inner_horizontal: builder_data.gap_inner_horizontal,
         gap_inner_vertical: builder_data.gap_inner_vertical,
-        gap_outer_horizontal: builder_data.gap_outer_horizontal,
-        gap_outer_vertical: builder_data.gap_outer_vertical,
+        gap_outer_top: builder_data.gap_outer_top,
+        gap_outer_bottom: builder_data.gap_outer_bottom,
+        gap_outer_left: builder_data.gap_outer_left,
+        gap_outer_right: builder_data.gap_outer_right,
         terminal: builder_data.terminal,
+        terminal_follows_cwd: builder_data.terminal_follows_cwd,
         modkey: builder_data.modkey,
         tags: builder_data.tags,
+        tag_labels_occupied: builder_data.tag_labels_occupied,
+        tag_labels_empty: builder_data.tag_labels_empty,
         layout_symbols: builder_data.layout_symbols,
         keybindings: builder_data.keybindings,
         tag_back_and_forth: builder_data.tag_back_and_forth,
+        tag_layouts: builder_data.tag_layouts,
+        monitor_configs: builder_data.monitor_configs,
         window_rules: builder_data.window_rules,
+        scratchpads: builder_data.scratchpads,
         status_blocks: builder_data.status_blocks,
         scheme_normal: builder_data.scheme_normal,
         scheme_occupied: builder_data.scheme_occupied,
         scheme_selected: builder_data.scheme_selected,
         scheme_urgent: builder_data.scheme_urgent,
         autostart: builder_data.autostart,
+        tag_change_hook: builder_data.tag_change_hook,
+        layout_change_hook: builder_data.layout_change_hook,
         auto_tile: builder_data.auto_tile,
+        focus_new_windows: builder_data.focus_new_windows,
+        steal_monitor_focus: builder_data.steal_monitor_focus,
+        focus_on_activate: builder_data.focus_on_activate,
+        focus_follows_mouse: builder_data.focus_follows_mouse,
+        warp_cursor: builder_data.warp_cursor,
+        snap_distance: builder_data.snap_distance,
+        keychord_timeout_ms: builder_data.keychord_timeout_ms,
+        animations_enabled: builder_data.animations_enabled,
+        tag_switch_animation: builder_data.tag_switch_animation,
+        layout_animation: builder_data.layout_animation,
+        presentation_blank_titles: builder_data.presentation_blank_titles,
+        presentation_indicator: builder_data.presentation_indicator,
+        auto_reload: builder_data.auto_reload,
+        scroll_column_factor: builder_data.scroll_column_factor,
         hide_vacant_tags: builder_data.hide_vacant_tags,
+        show_bar: builder_data.show_bar,
+        themes: builder_data.themes,
+        theme_schedule: builder_data.theme_schedule,
+        night_light: builder_data.night_light,
+        warnings,
         path: None,
     })
 }
+
+/// Keeps a config usable when it leaves out the essentials, returning a
+/// warning for every default that had to be filled in. A fallback key that the
+/// config already uses for something else is left alone.
+fn apply_fallbacks(builder: &mut lua_api::ConfigBuilder) -> Vec<String> {
+    use crate::keyboard::handlers::{Arg, KeyAction, KeyBinding};
+    use crate::keyboard::keysyms;
+    use x11rb::protocol::xproto::KeyButMask;
+
+    let mut warnings = Vec::new();
+
+    if builder.tags.is_empty() {
+        builder.tags = vec!["1".to_string()];
+        warnings.push("oxwm.set_tags was given no tags, using a single tag \"1\"".to_string());
+    }
+
+    let is_bound = |builder: &lua_api::ConfigBuilder, action: KeyAction| {
+        builder
+            .keybindings
+            .iter()
+            .any(|binding| binding.func == action || binding.on_release == Some(action))
+    };
+
+    let mask = |modifiers: &[KeyButMask]| {
+        modifiers
+            .iter()
+            .fold(0u16, |mask, &modifier| mask | u16::from(modifier))
+    };
+
+    for (action, keysym, name) in [
+        (KeyAction::Restart, keysyms::XK_R, "R"),
+        (KeyAction::Quit, keysyms::XK_Q, "Q"),
+    ] {
+        if is_bound(builder, action) {
+            continue;
+        }
+        let modifiers = vec![builder.modkey, KeyButMask::SHIFT];
+        let taken = builder.keybindings.iter().any(|binding| {
+            binding
+                .keys
+                .first()
+                .is_some_and(|key| key.keysym == keysym && mask(&key.modifiers) == mask(&modifiers))
+        });
+        if taken {
+            warnings.push(format!(
+                "No key is bound to {:?} and Mod+Shift+{} is already in use",
+                action, name
+            ));
+            continue;
+        }
+        builder
+            .keybindings
+            .push(KeyBinding::single_key(modifiers, keysym, action, Arg::None));
+        warnings.push(format!(
+            "No key is bound to {:?}, added Mod+Shift+{}",
+            action, name
+        ));
+    }
+
+    let launches_terminal = builder.keybindings.iter().any(|binding| {
+        binding.func == KeyAction::SpawnTerminal
+            || (binding.func == KeyAction::Spawn
+                && match &binding.arg {
+                    Arg::Str(command) => *command == builder.terminal,
+                    Arg::Array(command) => command.first() == Some(&builder.terminal),
+                    _ => false,
+                })
+    });
+    if !launches_terminal {
+        eprintln!("Note: no key launches the terminal ({})", builder.terminal);
+    }
+
+    warnings
+}
+
+fn register_include(lua: &Lua, config_dir: Option<&Path>) -> Result<(), ConfigError> {
+    let config_dir = config_dir.map(Path::to_path_buf);
+
+    let include = lua.create_function(move |lua, file: String| {
+        let path = match &config_dir {
+            Some(dir) => dir.join(&file),
+            None => PathBuf::from(&file),
+        };
+        load_file(lua, &path).map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+    })?;
+
+    let oxwm_table: Table = lua.globals().get("oxwm")?;
+    oxwm_table.set("include", include)?;
+    Ok(())
+}
+
+fn load_file(lua: &Lua, path: &Path) -> Result<(), ConfigError> {
+    let source = std::fs::read_to_string(path).map_err(|e| {
+        ConfigError::LuaError(format!("Failed to read {}: {}", path.display(), e))
+    })?;
+
+    lua.load(&source)
+        .set_name(format!("@{}", path.display()))
+        .exec()
+        .map_err(|e| ConfigError::LuaError(format!("{}", e)))
+}
+
+pub(super) fn conf_d_files(config_dir: &Path) -> Result<Vec<PathBuf>, ConfigError> {
+    let conf_d = config_dir.join(CONF_D_DIR);
+    if !conf_d.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let entries = std::fs::read_dir(&conf_d).map_err(|e| {
+        ConfigError::LuaError(format!("Failed to read {}: {}", conf_d.display(), e))
+    })?;
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "lua"))
+        .collect();
+    files.sort();
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_the_config_chunk_in_errors() {
+        let Err(ConfigError::LuaError(message)) = parse_lua_config("\nerror('boom')", None) else {
+            panic!("expected a Lua error");
+        };
+        assert!(message.contains("config:2: boom"), "{}", message);
+    }
+
+    #[test]
+    fn names_the_config_file_in_errors() {
+        let path = Path::new("/nonexistent/oxwm/config.lua");
+        let Err(ConfigError::LuaError(message)) = parse_lua_config("\nerror('boom')", Some(path))
+        else {
+            panic!("expected a Lua error");
+        };
+        assert!(
+            message.contains("/nonexistent/oxwm/config.lua:2: boom"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn adds_missing_restart_and_quit_keys() {
+        let config = parse_lua_config("", None).unwrap();
+        assert!(
+            config
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("added Mod+Shift+R"))
+        );
+        assert!(
+            config
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("added Mod+Shift+Q"))
+        );
+    }
+
+    #[test]
+    fn leaves_fallback_keys_the_config_uses() {
+        let config = parse_lua_config(
+            r#"oxwm.key.bind({ "Mod4", "Shift" }, "R", oxwm.spawn("xterm"))"#,
+            None,
+        )
+        .unwrap();
+        assert!(
+            config
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("Mod+Shift+R is already in use"))
+        );
+        let restart_bound = config
+            .keybindings
+            .iter()
+            .any(|binding| binding.func == crate::keyboard::handlers::KeyAction::Restart);
+        assert!(!restart_bound);
+    }
+}
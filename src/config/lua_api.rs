@@ -4,6 +4,7 @@ use std::rc::Rc;
 
 use crate::ColorScheme;
 use crate::bar::BlockConfig;
+use crate::config::limits::{self, Limit};
 use crate::errors::ConfigError;
 use crate::keyboard::handlers::{Arg, KeyAction, KeyBinding, KeyPress};
 use crate::keyboard::keysyms::{self, Keysym};
@@ -14,28 +15,58 @@ pub struct ConfigBuilder {
     pub border_width: u32,
     pub border_focused: u32,
     pub border_unfocused: u32,
-    pub font: String,
+    pub border_urgent: u32,
+    pub smartborders_enabled: bool,
+    pub fonts: Vec<String>,
     pub gaps_enabled: bool,
     pub smartgaps_enabled: bool,
     pub gap_inner_horizontal: u32,
     pub gap_inner_vertical: u32,
-    pub gap_outer_horizontal: u32,
-    pub gap_outer_vertical: u32,
+    pub gap_outer_top: u32,
+    pub gap_outer_bottom: u32,
+    pub gap_outer_left: u32,
+    pub gap_outer_right: u32,
     pub terminal: String,
+    pub terminal_follows_cwd: bool,
     pub modkey: KeyButMask,
     pub tags: Vec<String>,
+    pub tag_labels_occupied: Vec<String>,
+    pub tag_labels_empty: Vec<String>,
     pub layout_symbols: Vec<crate::LayoutSymbolOverride>,
     pub keybindings: Vec<KeyBinding>,
     pub tag_back_and_forth: bool,
+    pub tag_layouts: Vec<Option<String>>,
+    pub monitor_configs: Vec<crate::MonitorConfig>,
     pub window_rules: Vec<crate::WindowRule>,
+    pub scratchpads: Vec<crate::Scratchpad>,
     pub status_blocks: Vec<BlockConfig>,
     pub scheme_normal: ColorScheme,
     pub scheme_occupied: ColorScheme,
     pub scheme_selected: ColorScheme,
     pub scheme_urgent: ColorScheme,
-    pub autostart: Vec<String>,
+    pub autostart: Vec<crate::AutostartCommand>,
+    pub tag_change_hook: Option<String>,
+    pub layout_change_hook: Option<String>,
     pub auto_tile: bool,
+    pub focus_new_windows: bool,
+    pub steal_monitor_focus: bool,
+    pub focus_on_activate: bool,
+    pub focus_follows_mouse: bool,
+    pub warp_cursor: bool,
+    pub snap_distance: u32,
+    pub keychord_timeout_ms: u64,
+    pub animations_enabled: bool,
+    pub tag_switch_animation: Option<crate::animations::AnimationConfig>,
+    pub layout_animation: Option<crate::animations::AnimationConfig>,
+    pub presentation_blank_titles: bool,
+    pub presentation_indicator: String,
+    pub auto_reload: bool,
+    pub scroll_column_factor: Option<f32>,
     pub hide_vacant_tags: bool,
+    pub show_bar: bool,
+    pub themes: Vec<crate::Theme>,
+    pub theme_schedule: Option<crate::ThemeSchedule>,
+    pub night_light: Option<crate::gamma::NightLight>,
 }
 
 impl Default for ConfigBuilder {
@@ -44,20 +75,30 @@ impl Default for ConfigBuilder {
             border_width: 2,
             border_focused: 0x6dade3,
             border_unfocused: 0xbbbbbb,
-            font: "monospace:style=Bold:size=10".to_string(),
+            border_urgent: 0xff5555,
+            smartborders_enabled: false,
+            fonts: vec!["monospace:style=Bold:size=10".to_string()],
             gaps_enabled: true,
             smartgaps_enabled: true,
             gap_inner_horizontal: 5,
             gap_inner_vertical: 5,
-            gap_outer_horizontal: 5,
-            gap_outer_vertical: 5,
+            gap_outer_top: 5,
+            gap_outer_bottom: 5,
+            gap_outer_left: 5,
+            gap_outer_right: 5,
             terminal: "st".to_string(),
+            terminal_follows_cwd: true,
             modkey: KeyButMask::MOD4,
             tags: vec!["1".into(), "2".into(), "3".into()],
+            tag_labels_occupied: Vec::new(),
+            tag_labels_empty: Vec::new(),
             layout_symbols: Vec::new(),
             keybindings: Vec::new(),
             tag_back_and_forth: false,
+            tag_layouts: Vec::new(),
+            monitor_configs: Vec::new(),
             window_rules: Vec::new(),
+            scratchpads: Vec::new(),
             status_blocks: Vec::new(),
             scheme_normal: ColorScheme {
                 foreground: 0xffffff,
@@ -80,8 +121,28 @@ impl Default for ConfigBuilder {
                 underline: 0xff5555,
             },
             autostart: Vec::new(),
+            tag_change_hook: None,
+            layout_change_hook: None,
             auto_tile: false,
+            focus_new_windows: true,
+            steal_monitor_focus: true,
+            focus_on_activate: false,
+            focus_follows_mouse: true,
+            warp_cursor: false,
+            snap_distance: 32,
+            keychord_timeout_ms: 1500,
+            animations_enabled: true,
+            tag_switch_animation: None,
+            layout_animation: None,
+            presentation_blank_titles: true,
+            presentation_indicator: "[presenting]".to_string(),
+            auto_reload: false,
+            scroll_column_factor: None,
             hide_vacant_tags: false,
+            show_bar: true,
+            themes: Vec::new(),
+            theme_schedule: None,
+            night_light: None,
         }
     }
 }
@@ -98,11 +159,15 @@ pub fn register_api(lua: &Lua) -> Result<SharedBuilder, ConfigError> {
     register_gaps_module(lua, &oxwm_table, builder.clone())?;
     register_border_module(lua, &oxwm_table, builder.clone())?;
     register_client_module(lua, &oxwm_table)?;
-    register_layout_module(lua, &oxwm_table)?;
+    register_layout_module(lua, &oxwm_table, builder.clone())?;
     register_tag_module(lua, &oxwm_table, builder.clone())?;
-    register_monitor_module(lua, &oxwm_table)?;
+    register_monitor_module(lua, &oxwm_table, builder.clone())?;
     register_rule_module(lua, &oxwm_table, builder.clone())?;
     register_bar_module(lua, &oxwm_table, builder.clone())?;
+    register_system_module(lua, &oxwm_table, builder.clone())?;
+    register_scratchpad_module(lua, &oxwm_table, builder.clone())?;
+    register_theme_module(lua, &oxwm_table, builder.clone())?;
+    register_animations_module(lua, &oxwm_table, builder.clone())?;
     register_misc(lua, &oxwm_table, builder.clone())?;
 
     lua.globals().set("oxwm", oxwm_table)?;
@@ -111,7 +176,13 @@ pub fn register_api(lua: &Lua) -> Result<SharedBuilder, ConfigError> {
 }
 
 fn register_spawn(lua: &Lua, parent: &Table, _builder: SharedBuilder) -> Result<(), ConfigError> {
-    let spawn = lua.create_function(|lua, cmd: Value| create_action_table(lua, "Spawn", cmd))?;
+    let spawn = lua.create_function(|lua, (cmd, env): (Value, Option<Table>)| {
+        let action = create_action_table(lua, "Spawn", cmd)?;
+        if let Some(env) = env {
+            action.set("__env", env)?;
+        }
+        Ok(action)
+    })?;
     let spawn_terminal =
         lua.create_function(|lua, ()| create_action_table(lua, "SpawnTerminal", Value::Nil))?;
     parent.set("spawn", spawn)?;
@@ -138,6 +209,20 @@ fn register_key_module(
         Ok(())
     })?;
 
+    let builder_clone = builder.clone();
+    let bind_release =
+        lua.create_function(move |lua, (mods, key, action): (Value, String, Value)| {
+            let modifiers = parse_modifiers_value(lua, mods)?;
+            let keysym = parse_keysym(&key)?;
+            let (key_action, arg) = parse_action_value(lua, action)?;
+
+            let binding = KeyBinding::single_key(modifiers, keysym, KeyAction::None, arg)
+                .with_release(key_action);
+            builder_clone.borrow_mut().keybindings.push(binding);
+
+            Ok(())
+        })?;
+
     let builder_clone = builder.clone();
     let chord = lua.create_function(move |lua, (keys, action): (Table, Value)| {
         let mut key_presses = Vec::new();
@@ -160,8 +245,17 @@ fn register_key_module(
         Ok(())
     })?;
 
+    let builder_clone = builder.clone();
+    let set_chord_timeout = lua.create_function(move |_, ms: i64| {
+        builder_clone.borrow_mut().keychord_timeout_ms =
+            check_limit(&limits::KEYCHORD_TIMEOUT, ms)? as u64;
+        Ok(())
+    })?;
+
     key_table.set("bind", bind)?;
+    key_table.set("bind_release", bind_release)?;
     key_table.set("chord", chord)?;
+    key_table.set("set_chord_timeout", set_chord_timeout)?;
     parent.set("key", key_table)?;
     Ok(())
 }
@@ -192,21 +286,36 @@ fn register_gaps_module(
     })?;
 
     let builder_clone = builder.clone();
-    let set_inner = lua.create_function(move |_, (h, v): (u32, u32)| {
+    let set_inner = lua.create_function(move |_, (h, v): (i64, i64)| {
+        let (h, v) = (check_limit(&limits::GAP, h)?, check_limit(&limits::GAP, v)?);
         let mut b = builder_clone.borrow_mut();
-        b.gap_inner_horizontal = h;
-        b.gap_inner_vertical = v;
+        b.gap_inner_horizontal = h as u32;
+        b.gap_inner_vertical = v as u32;
         Ok(())
     })?;
 
     let builder_clone = builder.clone();
-    let set_outer = lua.create_function(move |_, (h, v): (u32, u32)| {
+    let set_outer = lua.create_function(move |_, (h, v): (i64, i64)| {
+        let (h, v) = (check_limit(&limits::GAP, h)?, check_limit(&limits::GAP, v)?);
         let mut b = builder_clone.borrow_mut();
-        b.gap_outer_horizontal = h;
-        b.gap_outer_vertical = v;
+        b.gap_outer_top = h as u32;
+        b.gap_outer_bottom = h as u32;
+        b.gap_outer_left = v as u32;
+        b.gap_outer_right = v as u32;
         Ok(())
     })?;
 
+    let builder_clone = builder.clone();
+    let set_outer_sides =
+        lua.create_function(move |_, (top, bottom, left, right): (i64, i64, i64, i64)| {
+            let mut b = builder_clone.borrow_mut();
+            b.gap_outer_top = check_limit(&limits::GAP, top)? as u32;
+            b.gap_outer_bottom = check_limit(&limits::GAP, bottom)? as u32;
+            b.gap_outer_left = check_limit(&limits::GAP, left)? as u32;
+            b.gap_outer_right = check_limit(&limits::GAP, right)? as u32;
+            Ok(())
+        })?;
+
     let builder_clone = builder.clone();
     let set_smart = lua.create_function(move |_, enabled: bool| {
         builder_clone.borrow_mut().smartgaps_enabled = enabled;
@@ -218,6 +327,7 @@ fn register_gaps_module(
     gaps_table.set("disable", disable)?;
     gaps_table.set("set_inner", set_inner)?;
     gaps_table.set("set_outer", set_outer)?;
+    gaps_table.set("set_outer_sides", set_outer_sides)?;
     gaps_table.set("set_smart", set_smart)?;
     parent.set("gaps", gaps_table)?;
     Ok(())
@@ -231,8 +341,14 @@ fn register_border_module(
     let border_table = lua.create_table()?;
 
     let builder_clone = builder.clone();
-    let set_width = lua.create_function(move |_, width: u32| {
-        builder_clone.borrow_mut().border_width = width;
+    let set_width = lua.create_function(move |_, width: i64| {
+        builder_clone.borrow_mut().border_width = check_limit(&limits::BORDER_WIDTH, width)? as u32;
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_smart = lua.create_function(move |_, enabled: bool| {
+        builder_clone.borrow_mut().smartborders_enabled = enabled;
         Ok(())
     })?;
 
@@ -250,9 +366,18 @@ fn register_border_module(
         Ok(())
     })?;
 
+    let builder_clone = builder.clone();
+    let set_urgent_color = lua.create_function(move |_, color: Value| {
+        let color_u32 = parse_color_value(color)?;
+        builder_clone.borrow_mut().border_urgent = color_u32;
+        Ok(())
+    })?;
+
     border_table.set("set_width", set_width)?;
+    border_table.set("set_smart", set_smart)?;
     border_table.set("set_focused_color", set_focused_color)?;
     border_table.set("set_unfocused_color", set_unfocused_color)?;
+    border_table.set("set_urgent_color", set_urgent_color)?;
     parent.set("border", border_table)?;
     Ok(())
 }
@@ -262,31 +387,122 @@ fn register_client_module(lua: &Lua, parent: &Table) -> Result<(), ConfigError>
 
     let kill = lua.create_function(|lua, ()| create_action_table(lua, "KillClient", Value::Nil))?;
 
+    let kill_others =
+        lua.create_function(|lua, ()| create_action_table(lua, "KillOthers", Value::Nil))?;
+
+    let kill_all =
+        lua.create_function(|lua, ()| create_action_table(lua, "KillAll", Value::Nil))?;
+
     let toggle_fullscreen =
         lua.create_function(|lua, ()| create_action_table(lua, "ToggleFullScreen", Value::Nil))?;
 
     let toggle_floating =
         lua.create_function(|lua, ()| create_action_table(lua, "ToggleFloating", Value::Nil))?;
 
+    let toggle_above =
+        lua.create_function(|lua, ()| create_action_table(lua, "ToggleAlwaysOnTop", Value::Nil))?;
+
+    let toggle_fake_fullscreen = lua
+        .create_function(|lua, ()| create_action_table(lua, "ToggleFakeFullscreen", Value::Nil))?;
+
     let focus_stack = lua.create_function(|lua, dir: i32| {
         create_action_table(lua, "FocusStack", Value::Integer(dir as i64))
     })?;
 
+    let focus_floating = lua.create_function(|lua, dir: i32| {
+        create_action_table(lua, "FocusFloating", Value::Integer(dir as i64))
+    })?;
+
     let move_stack = lua.create_function(|lua, dir: i32| {
         create_action_table(lua, "MoveStack", Value::Integer(dir as i64))
     })?;
 
+    let rotate_stack = lua.create_function(|lua, dir: i32| {
+        create_action_table(lua, "RotateStack", Value::Integer(dir as i64))
+    })?;
+
+    let cycle_class = lua.create_function(|lua, dir: i32| {
+        create_action_table(lua, "CycleSameClass", Value::Integer(dir as i64))
+    })?;
+
+    let set_factor = lua.create_function(|lua, delta: i32| {
+        create_action_table(lua, "SetClientFactor", Value::Integer(delta as i64))
+    })?;
+
+    let focus_urgent =
+        lua.create_function(|lua, ()| create_action_table(lua, "FocusUrgent", Value::Nil))?;
+
+    let cycle_recent =
+        lua.create_function(|lua, ()| create_action_table(lua, "CycleRecent", Value::Nil))?;
+
+    let zoom = lua.create_function(|lua, ()| create_action_table(lua, "Zoom", Value::Nil))?;
+
+    let center = lua.create_function(|lua, float_tiled: Option<bool>| {
+        let arg = match float_tiled {
+            Some(float_tiled) => Value::Integer(float_tiled as i64),
+            None => Value::Nil,
+        };
+        create_action_table(lua, "CenterFloating", arg)
+    })?;
+
+    let toggle_click_through =
+        lua.create_function(|lua, ()| create_action_table(lua, "ToggleClickThrough", Value::Nil))?;
+
+    let snap = lua.create_function(|lua, region: String| {
+        if !crate::window_manager::is_snap_region(&region) {
+            return Err(mlua::Error::RuntimeError(format!(
+                "oxwm.client.snap: unknown region '{}'",
+                region
+            )));
+        }
+        create_action_table(
+            lua,
+            "SnapFloating",
+            Value::String(lua.create_string(&region)?),
+        )
+    })?;
+
+    let move_floating = lua.create_function(|lua, (dx, dy): (Value, Value)| {
+        let delta = lua.create_table_from([("x", dx), ("y", dy)])?;
+        create_action_table(lua, "MoveFloating", Value::Table(delta))
+    })?;
+
+    let resize_floating = lua.create_function(|lua, (dw, dh): (Value, Value)| {
+        let delta = lua.create_table_from([("x", dw), ("y", dh)])?;
+        create_action_table(lua, "ResizeFloating", Value::Table(delta))
+    })?;
+
     client_table.set("kill", kill)?;
+    client_table.set("kill_others", kill_others)?;
+    client_table.set("kill_all", kill_all)?;
     client_table.set("toggle_fullscreen", toggle_fullscreen)?;
     client_table.set("toggle_floating", toggle_floating)?;
+    client_table.set("toggle_above", toggle_above)?;
+    client_table.set("toggle_fake_fullscreen", toggle_fake_fullscreen)?;
     client_table.set("focus_stack", focus_stack)?;
+    client_table.set("focus_floating", focus_floating)?;
     client_table.set("move_stack", move_stack)?;
+    client_table.set("rotate_stack", rotate_stack)?;
+    client_table.set("cycle_class", cycle_class)?;
+    client_table.set("focus_urgent", focus_urgent)?;
+    client_table.set("cycle_recent", cycle_recent)?;
+    client_table.set("set_factor", set_factor)?;
+    client_table.set("move", move_floating)?;
+    client_table.set("zoom", zoom)?;
+    client_table.set("center", center)?;
+    client_table.set("snap", snap)?;
+    client_table.set("toggle_click_through", toggle_click_through)?;
+    client_table.set("resize", resize_floating)?;
 
     parent.set("client", client_table)?;
     Ok(())
 }
 
-fn register_layout_module(lua: &Lua, parent: &Table) -> Result<(), ConfigError> {
+fn register_layout_module(
+    lua: &Lua,
+    parent: &Table,
+    builder: SharedBuilder,
+) -> Result<(), ConfigError> {
     let layout_table = lua.create_table()?;
 
     let cycle =
@@ -300,16 +516,35 @@ fn register_layout_module(lua: &Lua, parent: &Table) -> Result<(), ConfigError>
         )
     })?;
 
+    let mirror =
+        lua.create_function(|lua, ()| create_action_table(lua, "MirrorLayout", Value::Nil))?;
+
     let scroll_left =
         lua.create_function(|lua, ()| create_action_table(lua, "ScrollLeft", Value::Nil))?;
 
     let scroll_right =
         lua.create_function(|lua, ()| create_action_table(lua, "ScrollRight", Value::Nil))?;
 
+    let scroll_column_grow =
+        lua.create_function(|lua, ()| create_action_table(lua, "ScrollColumnGrow", Value::Nil))?;
+
+    let scroll_column_shrink =
+        lua.create_function(|lua, ()| create_action_table(lua, "ScrollColumnShrink", Value::Nil))?;
+
+    let set_scroll_column_factor = lua.create_function(move |_, factor: f32| {
+        builder.borrow_mut().scroll_column_factor =
+            Some(check_limit(&limits::SCROLL_COLUMN_FACTOR, factor)?);
+        Ok(())
+    })?;
+
     layout_table.set("cycle", cycle)?;
     layout_table.set("set", set)?;
+    layout_table.set("mirror", mirror)?;
     layout_table.set("scroll_left", scroll_left)?;
     layout_table.set("scroll_right", scroll_right)?;
+    layout_table.set("scroll_column_grow", scroll_column_grow)?;
+    layout_table.set("scroll_column_shrink", scroll_column_shrink)?;
+    layout_table.set("set_scroll_column_factor", set_scroll_column_factor)?;
     parent.set("layout", layout_table)?;
     Ok(())
 }
@@ -326,6 +561,10 @@ fn register_tag_module(
         create_action_table(lua, "ViewTag", Value::Integer(idx as i64))
     })?;
 
+    let view_all = lua.create_function(|lua, idx: i32| {
+        create_action_table(lua, "ViewTagAllMonitors", Value::Integer(idx as i64))
+    })?;
+
     let view_next =
         lua.create_function(|lua, ()| create_action_table(lua, "ViewNextTag", Value::Nil))?;
 
@@ -339,6 +578,10 @@ fn register_tag_module(
         create_action_table(lua, "ViewPreviousNonEmptyTag", Value::Nil)
     })?;
 
+    let move_tag = lua.create_function(|lua, direction: i32| {
+        create_action_table(lua, "MoveTag", Value::Integer(direction as i64))
+    })?;
+
     let toggleview = lua.create_function(|lua, idx: i32| {
         create_action_table(lua, "ToggleView", Value::Integer(idx as i64))
     })?;
@@ -356,20 +599,41 @@ fn register_tag_module(
         Ok(())
     })?;
 
+    let builder_clone = builder.clone();
+    let set_default_layout = lua.create_function(move |_, (idx, name): (usize, String)| {
+        let idx = check_limit(&limits::TAG_INDEX, idx)?;
+        name.parse::<crate::layout::LayoutType>()
+            .map_err(mlua::Error::RuntimeError)?;
+
+        let tag_layouts = &mut builder_clone.borrow_mut().tag_layouts;
+        if tag_layouts.len() <= idx {
+            tag_layouts.resize(idx + 1, None);
+        }
+        tag_layouts[idx] = Some(name);
+        Ok(())
+    })?;
+
     tag_table.set("view", view)?;
+    tag_table.set("view_all", view_all)?;
     tag_table.set("view_next", view_next)?;
     tag_table.set("view_previous", view_previous)?;
     tag_table.set("view_next_nonempty", view_next_nonempty)?;
     tag_table.set("view_previous_nonempty", view_previous_nonempty)?;
     tag_table.set("toggleview", toggleview)?;
+    tag_table.set("move", move_tag)?;
     tag_table.set("move_to", move_to)?;
     tag_table.set("toggletag", toggletag)?;
     tag_table.set("set_back_and_forth", set_back_and_forth)?;
+    tag_table.set("set_default_layout", set_default_layout)?;
     parent.set("tag", tag_table)?;
     Ok(())
 }
 
-fn register_monitor_module(lua: &Lua, parent: &Table) -> Result<(), ConfigError> {
+fn register_monitor_module(
+    lua: &Lua,
+    parent: &Table,
+    builder: SharedBuilder,
+) -> Result<(), ConfigError> {
     let monitor_table = lua.create_table()?;
 
     let focus = lua.create_function(|lua, direction: i64| {
@@ -380,8 +644,34 @@ fn register_monitor_module(lua: &Lua, parent: &Table) -> Result<(), ConfigError>
         create_action_table(lua, "TagMonitor", Value::Integer(direction))
     })?;
 
+    let send_keep_tag = lua.create_function(|lua, direction: i64| {
+        create_action_table(lua, "TagMonitorKeepTag", Value::Integer(direction))
+    })?;
+
+    let configure = lua.create_function(move |_, (idx, config): (usize, Table)| {
+        let idx = check_limit(&limits::MONITOR_INDEX, idx)?;
+        let default_layout: Option<String> = config.get("default_layout")?;
+        if let Some(name) = &default_layout {
+            name.parse::<crate::layout::LayoutType>()
+                .map_err(mlua::Error::RuntimeError)?;
+        }
+
+        let monitor_configs = &mut builder.borrow_mut().monitor_configs;
+        if monitor_configs.len() <= idx {
+            monitor_configs.resize(idx + 1, crate::MonitorConfig::default());
+        }
+        monitor_configs[idx] = crate::MonitorConfig {
+            tags: config.get("tags")?,
+            default_layout,
+            start_tag: config.get("tag")?,
+        };
+        Ok(())
+    })?;
+
     monitor_table.set("focus", focus)?;
     monitor_table.set("tag", tag)?;
+    monitor_table.set("send_keep_tag", send_keep_tag)?;
+    monitor_table.set("configure", configure)?;
     parent.set("monitor", monitor_table)?;
     Ok(())
 }
@@ -398,9 +688,25 @@ fn register_rule_module(
         let class: Option<String> = config.get("class").ok();
         let instance: Option<String> = config.get("instance").ok();
         let title: Option<String> = config.get("title").ok();
+        let role: Option<String> = config.get("role").ok();
         let is_floating: Option<bool> = config.get("floating").ok();
         let monitor: Option<usize> = config.get("monitor").ok();
         let focus: Option<bool> = config.get("focus").ok();
+        let scratchpad: Option<String> = config.get("scratchpad").ok();
+        let click_through: Option<bool> = config.get("click_through").ok();
+        let swallow: Option<bool> = config.get("swallow").ok();
+        let center: Option<bool> = config.get("center").ok();
+        let fake_fullscreen: Option<bool> = config.get("fake_fullscreen").ok();
+        let border: Option<bool> = config.get("border").ok();
+        let x: Option<i32> = config.get("x").ok();
+        let y: Option<i32> = config.get("y").ok();
+        let width: Option<i32> = config.get("width").ok();
+        let height: Option<i32> = config.get("height").ok();
+        if width.is_some_and(|w| w <= 0) || height.is_some_and(|h| h <= 0) {
+            return Err(mlua::Error::RuntimeError(
+                "rule width and height must be positive".into(),
+            ));
+        }
 
         let tags: Option<u32> = if let Ok(tag_index) = config.get::<i32>("tag") {
             if tag_index > 0 {
@@ -416,10 +722,21 @@ fn register_rule_module(
             class,
             instance,
             title,
+            role,
             tags,
             focus,
             is_floating,
             monitor,
+            scratchpad,
+            click_through,
+            swallow,
+            center,
+            fake_fullscreen,
+            border,
+            x,
+            y,
+            width,
+            height,
         };
 
         builder_clone.borrow_mut().window_rules.push(rule);
@@ -440,7 +757,18 @@ fn register_bar_module(
 
     let builder_clone = builder.clone();
     let set_font = lua.create_function(move |_, font: String| {
-        builder_clone.borrow_mut().font = font;
+        builder_clone.borrow_mut().fonts = vec![font];
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_fonts = lua.create_function(move |_, fonts: Vec<String>| {
+        if fonts.is_empty() {
+            return Err(mlua::Error::RuntimeError(
+                "oxwm.bar.set_fonts: at least one font is required".into(),
+            ));
+        }
+        builder_clone.borrow_mut().fonts = fonts;
         Ok(())
     })?;
 
@@ -449,6 +777,24 @@ fn register_bar_module(
     let ram =
         lua.create_function(|lua, config: Table| create_block_config(lua, config, "Ram", None))?;
 
+    let brightness = lua.create_function(|lua, config: Table| {
+        let device: Option<String> = config.get("device")?;
+        let set_command: Option<String> = config.get("set_command")?;
+        let step: Option<u32> = config.get("step")?;
+
+        let options_table = lua.create_table()?;
+        options_table.set("device", device)?;
+        options_table.set("set_command", set_command)?;
+        options_table.set("step", step.unwrap_or(5))?;
+
+        create_block_config(lua, config, "Brightness", Some(Value::Table(options_table)))
+    })?;
+
+    let temperature = lua.create_function(|lua, config: Table| {
+        let zone: Option<mlua::String> = config.get("zone")?;
+        create_block_config(lua, config, "Temperature", zone.map(Value::String))
+    })?;
+
     let datetime = lua.create_function(|lua, config: Table| {
         let date_format: String = config.get("date_format").map_err(|_| {
             mlua::Error::RuntimeError(
@@ -523,17 +869,42 @@ fn register_bar_module(
         )
     })?;
 
+    let custom_block = lua.create_function(|lua, config: Table| {
+        let function: mlua::Function = config.get("fn").map_err(|_| {
+            mlua::Error::RuntimeError("oxwm.bar.block.custom: 'fn' field must be a function".into())
+        })?;
+        create_block_config(lua, config, "Lua", Some(Value::Function(function)))
+    })?;
+
+    let window_title = lua.create_function(|lua, config: Table| {
+        let max_len: Option<i64> = config.get("max_len")?;
+        if config.get::<Value>("interval")?.is_nil() {
+            config.set("interval", 1)?;
+        }
+        create_block_config(
+            lua,
+            config,
+            "WindowTitle",
+            Some(Value::Integer(max_len.unwrap_or(50).max(1))),
+        )
+    })?;
+
     block_table.set("ram", ram)?;
+    block_table.set("temperature", temperature)?;
+    block_table.set("brightness", brightness)?;
     block_table.set("datetime", datetime)?;
     block_table.set("shell", shell)?;
     block_table.set("static", static_block)?;
     block_table.set("battery", battery)?;
     block_table.set("button", button_block)?;
+    block_table.set("custom", custom_block)?;
+    block_table.set("window_title", window_title)?;
 
     // Deprecated add_block() function for backwards compatibility
     // This allows old configs to still work, but users should migrate to set_blocks()
     let builder_clone = builder.clone();
-    let add_block = lua.create_function(move |_, (format, block_type, arg, interval, color, underline): (String, String, Value, u64, Value, Option<bool>)| -> mlua::Result<()> {
+    let add_block = lua.create_function(move |_, (format, block_type, arg, interval, color, underline): (String, String, Value, i64, Value, Option<bool>)| -> mlua::Result<()> {
+        let interval = check_limit(&limits::BLOCK_INTERVAL, interval)? as u64;
         eprintln!("WARNING: oxwm.bar.add_block() is deprecated. Please migrate to oxwm.bar.set_blocks() with block constructors.");
         eprintln!("See the migration guide for details.");
 
@@ -584,6 +955,8 @@ fn register_bar_module(
             interval_secs: interval,
             color: color_u32,
             underline: underline.unwrap_or(false),
+            min_width: None,
+            align: crate::bar::BlockAlign::default(),
         };
 
         builder_clone.borrow_mut().status_blocks.push(block);
@@ -591,7 +964,7 @@ fn register_bar_module(
     })?;
 
     let builder_clone = builder.clone();
-    let set_blocks = lua.create_function(move |_, blocks: Table| {
+    let set_blocks = lua.create_function(move |lua, blocks: Table| {
         use crate::bar::BlockCommand;
 
         let mut block_configs = Vec::new();
@@ -603,6 +976,8 @@ fn register_bar_module(
             let interval: u64 = block_table.get("interval")?;
             let color_val: Value = block_table.get("color")?;
             let underline: bool = block_table.get("underline").unwrap_or(false);
+            let min_width = parse_block_width(block_table.get("min_width")?)?;
+            let align = parse_block_align(block_table.get("align")?)?;
             let arg: Option<Value> = block_table.get("__arg").ok();
 
             let cmd = match block_type.as_str() {
@@ -634,6 +1009,25 @@ fn register_bar_module(
                     )),
                 }?,
                 "Ram" => BlockCommand::Ram,
+                "Brightness" => match arg {
+                    Some(Value::Table(options)) => BlockCommand::Brightness {
+                        device: options.get("device")?,
+                        set_command: options.get("set_command")?,
+                        step: options.get("step")?,
+                    },
+                    _ => {
+                        return Err(mlua::Error::RuntimeError(
+                            "Brightness block missing options".into(),
+                        ));
+                    }
+                },
+                "Temperature" => {
+                    let zone = match arg {
+                        Some(Value::String(s)) => Some(s.to_str()?.to_string()),
+                        _ => None,
+                    };
+                    BlockCommand::Temperature { zone }
+                }
                 "Static" => {
                     let text = arg
                         .and_then(|v| {
@@ -686,6 +1080,21 @@ fn register_bar_module(
 
                     BlockCommand::Button(command)
                 }
+                "WindowTitle" => {
+                    let max_len = match arg {
+                        Some(Value::Integer(max_len)) => max_len.max(1) as usize,
+                        _ => 50,
+                    };
+                    BlockCommand::WindowTitle { max_len }
+                }
+                "Lua" => match arg {
+                    Some(Value::Function(function)) => {
+                        BlockCommand::Lua(crate::bar::LuaFunction::new(lua, function)?)
+                    }
+                    _ => {
+                        return Err(mlua::Error::RuntimeError("Custom block missing fn".into()));
+                    }
+                },
                 _ => {
                     return Err(mlua::Error::RuntimeError(format!(
                         "Unknown block type '{}'",
@@ -702,6 +1111,8 @@ fn register_bar_module(
                 interval_secs: interval,
                 color: color_u32,
                 underline,
+                min_width,
+                align,
             };
 
             block_configs.push(block);
@@ -777,7 +1188,17 @@ fn register_bar_module(
         Ok(())
     })?;
 
+    let builder_clone = builder.clone();
+    let set_visible = lua.create_function(move |_, visible: bool| {
+        builder_clone.borrow_mut().show_bar = visible;
+        Ok(())
+    })?;
+
+    let toggle =
+        lua.create_function(|lua, ()| create_action_table(lua, "ToggleBar", Value::Nil))?;
+
     bar_table.set("set_font", set_font)?;
+    bar_table.set("set_fonts", set_fonts)?;
     bar_table.set("block", block_table)?;
     bar_table.set("add_block", add_block)?; // Deprecated, for backwards compatibility
     bar_table.set("set_blocks", set_blocks)?;
@@ -786,10 +1207,252 @@ fn register_bar_module(
     bar_table.set("set_scheme_selected", set_scheme_selected)?;
     bar_table.set("set_scheme_urgent", set_scheme_urgent)?;
     bar_table.set("set_hide_vacant_tags", set_hide_vacant_tags)?;
+    bar_table.set("set_visible", set_visible)?;
+    bar_table.set("toggle", toggle)?;
     parent.set("bar", bar_table)?;
     Ok(())
 }
 
+fn register_scratchpad_module(
+    lua: &Lua,
+    parent: &Table,
+    builder: SharedBuilder,
+) -> Result<(), ConfigError> {
+    let scratchpad_table = lua.create_table()?;
+
+    let define = lua.create_function(move |_, (name, config): (String, Table)| {
+        let size = |key: &str| -> mlua::Result<Option<f32>> {
+            config
+                .get::<Option<f32>>(key)?
+                .map(|value| check_limit(&limits::SCRATCHPAD_SIZE, value))
+                .transpose()
+        };
+        let offset = |key: &str| -> mlua::Result<Option<f32>> {
+            match config.get::<Value>(key)? {
+                Value::Nil => Ok(None),
+                Value::String(s) if s.to_str()? == "center" => Ok(None),
+                Value::Integer(n) => check_limit(&limits::SCRATCHPAD_OFFSET, n as f32).map(Some),
+                Value::Number(n) => check_limit(&limits::SCRATCHPAD_OFFSET, n as f32).map(Some),
+                _ => Err(mlua::Error::RuntimeError(format!(
+                    "oxwm.scratchpad.define: {} must be \"center\" or a fraction between 0 and 1",
+                    key
+                ))),
+            }
+        };
+
+        let scratchpad = crate::Scratchpad {
+            name: name.clone(),
+            command: config.get("command")?,
+            width: size("width")?,
+            height: size("height")?,
+            x: offset("x")?,
+            y: offset("y")?,
+            exclusive: config.get::<Option<bool>>("exclusive")?.unwrap_or(false),
+        };
+
+        let scratchpads = &mut builder.borrow_mut().scratchpads;
+        match scratchpads
+            .iter_mut()
+            .find(|existing| existing.name == name)
+        {
+            Some(existing) => *existing = scratchpad,
+            None => scratchpads.push(scratchpad),
+        }
+        Ok(())
+    })?;
+
+    let toggle = lua.create_function(|lua, (name, command): (String, Option<String>)| {
+        let arg = match command {
+            Some(command) => Value::Table(
+                lua.create_table_from([("name", name.as_str()), ("command", command.as_str())])?,
+            ),
+            None => Value::String(lua.create_string(&name)?),
+        };
+        create_action_table(lua, "ToggleScratchpad", arg)
+    })?;
+
+    scratchpad_table.set("define", define)?;
+    scratchpad_table.set("toggle", toggle)?;
+    parent.set("scratchpad", scratchpad_table)?;
+    Ok(())
+}
+
+fn register_system_module(
+    lua: &Lua,
+    parent: &Table,
+    builder: SharedBuilder,
+) -> Result<(), ConfigError> {
+    let system_table = lua.create_table()?;
+
+    let set_gamma = lua.create_function(|lua, temperature: u32| {
+        create_action_table(lua, "SetGamma", Value::Integer(temperature as i64))
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_night_light = lua.create_function(move |_, config: Table| {
+        let day: u32 = config
+            .get::<Option<u32>>("day")?
+            .unwrap_or(crate::gamma::NEUTRAL_TEMPERATURE);
+        let night: u32 = config.get("night")?;
+        let start: String = config.get("start")?;
+        let stop: String = config.get("stop")?;
+
+        let parse_time = |time: &str| {
+            crate::gamma::parse_time_of_day(time).ok_or_else(|| {
+                mlua::Error::RuntimeError(format!(
+                    "oxwm.system.set_night_light: invalid time '{}'. use format like \"21:00\"",
+                    time
+                ))
+            })
+        };
+
+        builder_clone.borrow_mut().night_light = Some(crate::gamma::NightLight {
+            day,
+            night,
+            start_minute: parse_time(&start)?,
+            stop_minute: parse_time(&stop)?,
+        });
+        Ok(())
+    })?;
+
+    system_table.set("set_gamma", set_gamma)?;
+    system_table.set("set_night_light", set_night_light)?;
+    parent.set("system", system_table)?;
+    Ok(())
+}
+
+fn register_animations_module(
+    lua: &Lua,
+    parent: &Table,
+    builder: SharedBuilder,
+) -> Result<(), ConfigError> {
+    let animations_table = lua.create_table()?;
+
+    let builder_clone = builder.clone();
+    let set_enabled = lua.create_function(move |_, enabled: bool| {
+        builder_clone.borrow_mut().animations_enabled = enabled;
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_tag_switch = lua.create_function(move |_, config: Option<Table>| {
+        builder_clone.borrow_mut().tag_switch_animation = parse_animation(config)?;
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_layout = lua.create_function(move |_, config: Option<Table>| {
+        builder_clone.borrow_mut().layout_animation = parse_animation(config)?;
+        Ok(())
+    })?;
+
+    animations_table.set("set_enabled", set_enabled)?;
+    animations_table.set("set_tag_switch", set_tag_switch)?;
+    animations_table.set("set_layout", set_layout)?;
+    parent.set("animations", animations_table)?;
+    Ok(())
+}
+
+fn parse_animation(
+    config: Option<Table>,
+) -> mlua::Result<Option<crate::animations::AnimationConfig>> {
+    let Some(config) = config else {
+        return Ok(None);
+    };
+
+    let defaults = crate::animations::AnimationConfig::default();
+    let duration = match config.get::<Option<u64>>("duration_ms")? {
+        Some(ms) => std::time::Duration::from_millis(ms),
+        None => defaults.duration,
+    };
+    let easing = match config.get::<Option<String>>("easing")? {
+        Some(name) => name.parse().map_err(mlua::Error::RuntimeError)?,
+        None => defaults.easing,
+    };
+
+    Ok(Some(crate::animations::AnimationConfig {
+        duration,
+        easing,
+    }))
+}
+
+fn register_theme_module(
+    lua: &Lua,
+    parent: &Table,
+    builder: SharedBuilder,
+) -> Result<(), ConfigError> {
+    let theme_table = lua.create_table()?;
+
+    let builder_clone = builder.clone();
+    let define = lua.create_function(move |_, (name, config): (String, Table)| {
+        let border_color = |key: &str| -> mlua::Result<Option<u32>> {
+            match config.get::<Value>(key)? {
+                Value::Nil => Ok(None),
+                value => parse_color_value(value).map(Some),
+            }
+        };
+        let scheme = |key: &str| -> mlua::Result<Option<ColorScheme>> {
+            let Some(colors) = config.get::<Option<Table>>(key)? else {
+                return Ok(None);
+            };
+            Ok(Some(ColorScheme {
+                foreground: parse_color_value(colors.get(1)?)?,
+                background: parse_color_value(colors.get(2)?)?,
+                underline: parse_color_value(colors.get(3)?)?,
+            }))
+        };
+
+        let theme = crate::Theme {
+            name: name.clone(),
+            border_focused: border_color("border_focused")?,
+            border_unfocused: border_color("border_unfocused")?,
+            border_urgent: border_color("border_urgent")?,
+            scheme_normal: scheme("scheme_normal")?,
+            scheme_occupied: scheme("scheme_occupied")?,
+            scheme_selected: scheme("scheme_selected")?,
+            scheme_urgent: scheme("scheme_urgent")?,
+        };
+
+        let themes = &mut builder_clone.borrow_mut().themes;
+        match themes.iter_mut().find(|existing| existing.name == name) {
+            Some(existing) => *existing = theme,
+            None => themes.push(theme),
+        }
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_schedule = lua.create_function(
+        move |_, (first, first_start, second, second_start): (String, String, String, String)| {
+            let parse_time = |time: &str| {
+                crate::gamma::parse_time_of_day(time).ok_or_else(|| {
+                    mlua::Error::RuntimeError(format!(
+                        "oxwm.theme.set_schedule: invalid time '{}'. use format like \"08:00\"",
+                        time
+                    ))
+                })
+            };
+
+            builder_clone.borrow_mut().theme_schedule = Some(crate::ThemeSchedule {
+                first,
+                first_start: parse_time(&first_start)?,
+                second,
+                second_start: parse_time(&second_start)?,
+            });
+            Ok(())
+        },
+    )?;
+
+    let cycle =
+        lua.create_function(|lua, ()| create_action_table(lua, "CycleTheme", Value::Nil))?;
+
+    theme_table.set("define", define)?;
+    theme_table.set("set_schedule", set_schedule)?;
+    theme_table.set("cycle", cycle)?;
+    parent.set("theme", theme_table)?;
+    Ok(())
+}
+
 fn register_misc(lua: &Lua, parent: &Table, builder: SharedBuilder) -> Result<(), ConfigError> {
     let builder_clone = builder.clone();
     let set_terminal = lua.create_function(move |_, term: String| {
@@ -797,6 +1460,12 @@ fn register_misc(lua: &Lua, parent: &Table, builder: SharedBuilder) -> Result<()
         Ok(())
     })?;
 
+    let builder_clone = builder.clone();
+    let set_terminal_follows_cwd = lua.create_function(move |_, enabled: bool| {
+        builder_clone.borrow_mut().terminal_follows_cwd = enabled;
+        Ok(())
+    })?;
+
     let builder_clone = builder.clone();
     let set_modkey = lua.create_function(move |_, modkey_str: String| {
         let modkey = parse_modkey_string(&modkey_str)
@@ -807,20 +1476,39 @@ fn register_misc(lua: &Lua, parent: &Table, builder: SharedBuilder) -> Result<()
 
     let builder_clone = builder.clone();
     let set_tags = lua.create_function(move |_, tags: Vec<String>| {
+        // An empty list falls back to a single tag with a warning after parsing.
+        if !tags.is_empty() {
+            check_limit(&limits::TAG_COUNT, tags.len())?;
+        }
         builder_clone.borrow_mut().tags = tags;
         Ok(())
     })?;
 
+    let builder_clone = builder.clone();
+    let set_tag_labels = lua.create_function(move |_, labels: Table| {
+        let occupied: Option<Vec<String>> = labels.get("occupied")?;
+        let empty: Option<Vec<String>> = labels.get("empty")?;
+        let mut builder = builder_clone.borrow_mut();
+        builder.tag_labels_occupied = occupied.unwrap_or_default();
+        builder.tag_labels_empty = empty.unwrap_or_default();
+        Ok(())
+    })?;
+
     let quit = lua.create_function(|lua, ()| create_action_table(lua, "Quit", Value::Nil))?;
 
     let restart = lua.create_function(|lua, ()| create_action_table(lua, "Restart", Value::Nil))?;
 
+    let rescan =
+        lua.create_function(|lua, ()| create_action_table(lua, "RescanWindows", Value::Nil))?;
+
+    let undo =
+        lua.create_function(|lua, ()| create_action_table(lua, "UndoWindowOp", Value::Nil))?;
+
     let toggle_gaps =
         lua.create_function(|lua, ()| create_action_table(lua, "ToggleGaps", Value::Nil))?;
 
-    let set_master_factor = lua.create_function(|lua, delta: i32| {
-        create_action_table(lua, "SetMasterFactor", Value::Integer(delta as i64))
-    })?;
+    let set_master_factor = lua
+        .create_function(|lua, delta: Value| create_action_table(lua, "SetMasterFactor", delta))?;
 
     let inc_num_master = lua.create_function(|lua, delta: i32| {
         create_action_table(lua, "IncNumMaster", Value::Integer(delta as i64))
@@ -843,8 +1531,41 @@ fn register_misc(lua: &Lua, parent: &Table, builder: SharedBuilder) -> Result<()
     })?;
 
     let builder_clone = builder.clone();
-    let autostart = lua.create_function(move |_, cmd: String| {
-        builder_clone.borrow_mut().autostart.push(cmd);
+    let autostart = lua.create_function(move |_, value: Value| {
+        let command = match value {
+            Value::String(cmd) => crate::AutostartCommand::new(cmd.to_str()?.to_string()),
+            Value::Table(options) => {
+                let cmd: Option<String> = options.get("cmd")?;
+                let Some(cmd) = cmd else {
+                    return Err(mlua::Error::RuntimeError(
+                        "oxwm.autostart: table form requires a 'cmd' field".into(),
+                    ));
+                };
+                crate::AutostartCommand {
+                    command: cmd,
+                    once: options.get::<Option<bool>>("once")?.unwrap_or(false),
+                    delay_ms: options.get::<Option<u64>>("delay_ms")?.unwrap_or(0),
+                }
+            }
+            _ => {
+                return Err(mlua::Error::RuntimeError(
+                    "oxwm.autostart: expected a command string or a table like { cmd = \"picom\", once = true }".into(),
+                ));
+            }
+        };
+        builder_clone.borrow_mut().autostart.push(command);
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let on_tag_change = lua.create_function(move |_, cmd: String| {
+        builder_clone.borrow_mut().tag_change_hook = Some(cmd);
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let on_layout_change = lua.create_function(move |_, cmd: String| {
+        builder_clone.borrow_mut().layout_change_hook = Some(cmd);
         Ok(())
     })?;
 
@@ -854,19 +1575,129 @@ fn register_misc(lua: &Lua, parent: &Table, builder: SharedBuilder) -> Result<()
         Ok(())
     })?;
 
+    let toggle_presentation_mode = lua.create_function(|lua, ()| {
+        create_action_table(lua, "TogglePresentationMode", Value::Nil)
+    })?;
+
+    let dump_state =
+        lua.create_function(|lua, ()| create_action_table(lua, "DumpState", Value::Nil))?;
+
+    let notify = lua.create_function(|lua, (text, duration_ms): (String, Option<u64>)| {
+        let arg = match duration_ms {
+            Some(ms) => Value::Table(
+                lua.create_table_from([("text", text), ("duration_ms", ms.to_string())])?,
+            ),
+            None => Value::String(lua.create_string(&text)?),
+        };
+        create_action_table(lua, "Notify", arg)
+    })?;
+
+    let osd = lua.create_function(|lua, (label, value): (String, f64)| {
+        let parts = lua.create_sequence_from([label, value.to_string()])?;
+        create_action_table(lua, "ShowOsd", Value::Table(parts))
+    })?;
+
+    let run_or_raise = lua.create_function(|lua, (command, class): (String, String)| {
+        let parts = lua.create_sequence_from([command, class])?;
+        create_action_table(lua, "RunOrRaise", Value::Table(parts))
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_presentation_blank_titles = lua.create_function(move |_, enabled: bool| {
+        builder_clone.borrow_mut().presentation_blank_titles = enabled;
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_presentation_indicator = lua.create_function(move |_, text: String| {
+        builder_clone.borrow_mut().presentation_indicator = text;
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_auto_reload = lua.create_function(move |_, enabled: bool| {
+        builder_clone.borrow_mut().auto_reload = enabled;
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_focus_new_windows = lua.create_function(move |_, enabled: bool| {
+        builder_clone.borrow_mut().focus_new_windows = enabled;
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_steal_monitor_focus = lua.create_function(move |_, enabled: bool| {
+        builder_clone.borrow_mut().steal_monitor_focus = enabled;
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_focus_on_activate = lua.create_function(move |_, enabled: bool| {
+        builder_clone.borrow_mut().focus_on_activate = enabled;
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_focus_follows_mouse = lua.create_function(move |_, enabled: bool| {
+        builder_clone.borrow_mut().focus_follows_mouse = enabled;
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_warp_cursor = lua.create_function(move |_, enabled: bool| {
+        builder_clone.borrow_mut().warp_cursor = enabled;
+        Ok(())
+    })?;
+
+    let builder_clone = builder.clone();
+    let set_snap_distance = lua.create_function(move |_, px: i64| {
+        builder_clone.borrow_mut().snap_distance = check_limit(&limits::SNAP_DISTANCE, px)? as u32;
+        Ok(())
+    })?;
+
+    let toggle_focus_follows_mouse = lua.create_function(|lua, ()| {
+        create_action_table(lua, "ToggleFocusFollowsMouse", Value::Nil)
+    })?;
+
     parent.set("set_terminal", set_terminal)?;
+    parent.set("set_terminal_follows_cwd", set_terminal_follows_cwd)?;
     parent.set("set_modkey", set_modkey)?;
     parent.set("set_tags", set_tags)?;
+    parent.set("set_tag_labels", set_tag_labels)?;
     parent.set("set_layout_symbol", set_layout_symbol)?;
     parent.set("autostart", autostart)?;
+    parent.set("on_tag_change", on_tag_change)?;
+    parent.set("on_layout_change", on_layout_change)?;
     parent.set("quit", quit)?;
     parent.set("restart", restart)?;
+    parent.set("set_auto_reload", set_auto_reload)?;
+    parent.set("undo", undo)?;
+    parent.set("rescan", rescan)?;
     parent.set("toggle_gaps", toggle_gaps)?;
     parent.set("set_master_factor", set_master_factor)?;
     parent.set("inc_num_master", inc_num_master)?;
     parent.set("show_keybinds", show_keybinds)?;
     parent.set("focus_monitor", focus_monitor)?;
     parent.set("auto_tile", auto_tile)?;
+    parent.set("set_focus_new_windows", set_focus_new_windows)?;
+    parent.set("set_steal_monitor_focus", set_steal_monitor_focus)?;
+    parent.set("set_focus_on_activate", set_focus_on_activate.clone())?;
+    parent.set("set_focus_on_activation", set_focus_on_activate)?;
+    parent.set("set_focus_follows_mouse", set_focus_follows_mouse)?;
+    parent.set("toggle_focus_follows_mouse", toggle_focus_follows_mouse)?;
+    parent.set("set_warp_cursor", set_warp_cursor)?;
+    parent.set("set_snap_distance", set_snap_distance)?;
+    parent.set("toggle_presentation_mode", toggle_presentation_mode)?;
+    parent.set("dump_state", dump_state)?;
+    parent.set("notify", notify)?;
+    parent.set("osd", osd)?;
+    parent.set("run_or_raise", run_or_raise)?;
+    parent.set(
+        "set_presentation_blank_titles",
+        set_presentation_blank_titles,
+    )?;
+    parent.set("set_presentation_indicator", set_presentation_indicator)?;
     Ok(())
 }
 
@@ -925,11 +1756,20 @@ fn parse_action_value(_lua: &Lua, value: Value) -> mlua::Result<(KeyAction, Arg)
         Value::Table(t) => {
             if let Ok(action_name) = t.get::<String>("__action") {
                 let action = string_to_action(&action_name)?;
-                let arg = if let Ok(arg_val) = t.get::<Value>("__arg") {
-                    value_to_arg(arg_val)?
+                let mut arg = if let Ok(arg_val) = t.get::<Value>("__arg") {
+                    value_to_arg(&action, arg_val)?
                 } else {
                     Arg::None
                 };
+                if let Ok(env) = t.get::<Table>("__env") {
+                    let mut vars = Vec::new();
+                    for pair in env.pairs::<String, Value>() {
+                        let (key, value) = pair?;
+                        vars.push((key, value_to_field(value)?));
+                    }
+                    vars.sort_by(|a, b| a.0.cmp(&b.0));
+                    arg = Arg::WithEnv(Box::new(arg), vars);
+                }
                 return Ok((action, arg));
             }
 
@@ -943,35 +1783,69 @@ fn parse_action_value(_lua: &Lua, value: Value) -> mlua::Result<(KeyAction, Arg)
     }
 }
 
-fn string_to_action(s: &str) -> mlua::Result<KeyAction> {
+pub(crate) fn string_to_action(s: &str) -> mlua::Result<KeyAction> {
     match s {
         "Spawn" => Ok(KeyAction::Spawn),
         "SpawnTerminal" => Ok(KeyAction::SpawnTerminal),
         "KillClient" => Ok(KeyAction::KillClient),
+        "KillOthers" => Ok(KeyAction::KillOthers),
+        "KillAll" => Ok(KeyAction::KillAll),
         "FocusStack" => Ok(KeyAction::FocusStack),
+        "FocusFloating" => Ok(KeyAction::FocusFloating),
         "MoveStack" => Ok(KeyAction::MoveStack),
+        "RotateStack" => Ok(KeyAction::RotateStack),
+        "CycleSameClass" => Ok(KeyAction::CycleSameClass),
+        "FocusUrgent" => Ok(KeyAction::FocusUrgent),
+        "CycleRecent" => Ok(KeyAction::CycleRecent),
         "Quit" => Ok(KeyAction::Quit),
         "Restart" => Ok(KeyAction::Restart),
         "ViewTag" => Ok(KeyAction::ViewTag),
+        "ViewTagAllMonitors" => Ok(KeyAction::ViewTagAllMonitors),
         "ViewNextTag" => Ok(KeyAction::ViewNextTag),
         "ViewPreviousTag" => Ok(KeyAction::ViewPreviousTag),
         "ViewNextNonEmptyTag" => Ok(KeyAction::ViewNextNonEmptyTag),
         "ViewPreviousNonEmptyTag" => Ok(KeyAction::ViewPreviousNonEmptyTag),
         "ToggleView" => Ok(KeyAction::ToggleView),
+        "MoveTag" => Ok(KeyAction::MoveTag),
         "MoveToTag" => Ok(KeyAction::MoveToTag),
         "ToggleTag" => Ok(KeyAction::ToggleTag),
         "ToggleGaps" => Ok(KeyAction::ToggleGaps),
+        "ToggleBar" => Ok(KeyAction::ToggleBar),
         "SetMasterFactor" => Ok(KeyAction::SetMasterFactor),
         "IncNumMaster" => Ok(KeyAction::IncNumMaster),
+        "SetClientFactor" => Ok(KeyAction::SetClientFactor),
         "ToggleFullScreen" => Ok(KeyAction::ToggleFullScreen),
         "ToggleFloating" => Ok(KeyAction::ToggleFloating),
+        "ToggleAlwaysOnTop" => Ok(KeyAction::ToggleAlwaysOnTop),
+        "ToggleFakeFullscreen" => Ok(KeyAction::ToggleFakeFullscreen),
         "ChangeLayout" => Ok(KeyAction::ChangeLayout),
         "CycleLayout" => Ok(KeyAction::CycleLayout),
+        "MirrorLayout" => Ok(KeyAction::MirrorLayout),
         "FocusMonitor" => Ok(KeyAction::FocusMonitor),
         "TagMonitor" => Ok(KeyAction::TagMonitor),
+        "TagMonitorKeepTag" => Ok(KeyAction::TagMonitorKeepTag),
         "ShowKeybindOverlay" => Ok(KeyAction::ShowKeybindOverlay),
         "ScrollLeft" => Ok(KeyAction::ScrollLeft),
         "ScrollRight" => Ok(KeyAction::ScrollRight),
+        "ScrollColumnGrow" => Ok(KeyAction::ScrollColumnGrow),
+        "ScrollColumnShrink" => Ok(KeyAction::ScrollColumnShrink),
+        "SetGamma" => Ok(KeyAction::SetGamma),
+        "MoveFloating" => Ok(KeyAction::MoveFloating),
+        "ResizeFloating" => Ok(KeyAction::ResizeFloating),
+        "ToggleScratchpad" => Ok(KeyAction::ToggleScratchpad),
+        "CenterFloating" => Ok(KeyAction::CenterFloating),
+        "SnapFloating" => Ok(KeyAction::SnapFloating),
+        "UndoWindowOp" => Ok(KeyAction::UndoWindowOp),
+        "ToggleClickThrough" => Ok(KeyAction::ToggleClickThrough),
+        "Zoom" => Ok(KeyAction::Zoom),
+        "RescanWindows" => Ok(KeyAction::RescanWindows),
+        "TogglePresentationMode" => Ok(KeyAction::TogglePresentationMode),
+        "ToggleFocusFollowsMouse" => Ok(KeyAction::ToggleFocusFollowsMouse),
+        "CycleTheme" => Ok(KeyAction::CycleTheme),
+        "DumpState" => Ok(KeyAction::DumpState),
+        "Notify" => Ok(KeyAction::Notify),
+        "ShowOsd" => Ok(KeyAction::ShowOsd),
+        "RunOrRaise" => Ok(KeyAction::RunOrRaise),
         _ => Err(mlua::Error::RuntimeError(format!(
             "unknown action '{}'. this is an internal error, please report it",
             s
@@ -979,15 +1853,39 @@ fn string_to_action(s: &str) -> mlua::Result<KeyAction> {
     }
 }
 
-fn value_to_arg(value: Value) -> mlua::Result<Arg> {
+/// Actions whose numeric argument is a float, in the same unit as their IPC command.
+fn takes_float_arg(action: &KeyAction) -> bool {
+    matches!(action, KeyAction::SetMasterFactor)
+}
+
+fn value_to_arg(action: &KeyAction, value: Value) -> mlua::Result<Arg> {
     match value {
         Value::Nil => Ok(Arg::None),
         Value::String(s) => Ok(Arg::Str(s.to_str()?.to_string())),
+        Value::Integer(i) if takes_float_arg(action) => Ok(Arg::Float(i as f32)),
+        Value::Number(n) if takes_float_arg(action) => Ok(Arg::Float(n as f32)),
         Value::Integer(i) => Ok(Arg::Int(i as i32)),
-        Value::Number(n) => Ok(Arg::Int(n as i32)),
+        Value::Number(n) if n.fract() == 0.0 => Ok(Arg::Int(n as i32)),
+        Value::Number(n) => Err(mlua::Error::RuntimeError(format!(
+            "{:?} expects a whole number, got {}",
+            action, n
+        ))),
         Value::Table(t) => {
+            let len = t.len()?;
+            if len == 0 {
+                let mut fields = Vec::new();
+                for pair in t.pairs::<String, Value>() {
+                    let (key, value) = pair?;
+                    fields.push((key, value_to_field(value)?));
+                }
+                if !fields.is_empty() {
+                    fields.sort_by(|a, b| a.0.cmp(&b.0));
+                    return Ok(Arg::Table(fields));
+                }
+            }
+
             let mut arr = Vec::new();
-            for i in 1..=t.len()? {
+            for i in 1..=len {
                 let item: String = t.get(i)?;
                 arr.push(item);
             }
@@ -997,6 +1895,19 @@ fn value_to_arg(value: Value) -> mlua::Result<Arg> {
     }
 }
 
+fn value_to_field(value: Value) -> mlua::Result<String> {
+    match value {
+        Value::String(s) => Ok(s.to_str()?.to_string()),
+        Value::Integer(i) => Ok(i.to_string()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Boolean(b) => Ok(b.to_string()),
+        other => Err(mlua::Error::RuntimeError(format!(
+            "action argument fields must be strings, numbers or booleans, got {}",
+            other.type_name()
+        ))),
+    }
+}
+
 fn create_action_table(lua: &Lua, action_name: &str, arg: Value) -> mlua::Result<Table> {
     let table = lua.create_table()?;
     table.set("__action", action_name)?;
@@ -1004,6 +1915,13 @@ fn create_action_table(lua: &Lua, action_name: &str, arg: Value) -> mlua::Result
     Ok(table)
 }
 
+fn check_limit<T: PartialOrd + Copy + std::fmt::Display>(
+    limit: &Limit<T>,
+    value: T,
+) -> mlua::Result<T> {
+    limit.check(value).map_err(mlua::Error::RuntimeError)
+}
+
 fn parse_color_value(value: Value) -> mlua::Result<u32> {
     match value {
         Value::Integer(i) => Ok(i as u32),
@@ -1049,14 +1967,18 @@ fn create_block_config(
     table.set("__block_type", block_type)?;
 
     let format: String = config.get("format").unwrap_or_default();
-    let interval: u64 = config.get("interval")?;
+    let interval = check_limit(&limits::BLOCK_INTERVAL, config.get("interval")?)?;
     let color: Value = config.get("color")?;
     let underline: bool = config.get("underline").unwrap_or(false);
+    let min_width: Value = config.get("min_width")?;
+    let align: Value = config.get("align")?;
 
     table.set("format", format)?;
     table.set("interval", interval)?;
     table.set("color", color)?;
     table.set("underline", underline)?;
+    table.set("min_width", min_width)?;
+    table.set("align", align)?;
 
     if let Some(arg_val) = arg {
         table.set("__arg", arg_val)?;
@@ -1064,3 +1986,274 @@ fn create_block_config(
 
     Ok(table)
 }
+
+fn parse_block_width(value: Value) -> mlua::Result<Option<crate::bar::BlockWidth>> {
+    use crate::bar::BlockWidth;
+
+    match value {
+        Value::Nil => Ok(None),
+        Value::Integer(pixels) => Ok(Some(BlockWidth::Pixels(
+            pixels.clamp(0, u16::MAX as i64) as u16
+        ))),
+        Value::Number(pixels) => Ok(Some(BlockWidth::Pixels(
+            pixels.clamp(0.0, u16::MAX as f64) as u16
+        ))),
+        Value::String(sample) => Ok(Some(BlockWidth::Sample(sample.to_str()?.to_string()))),
+        _ => Err(mlua::Error::RuntimeError(
+            "min_width must be a number of pixels or a sample string".into(),
+        )),
+    }
+}
+
+fn parse_block_align(value: Option<String>) -> mlua::Result<crate::bar::BlockAlign> {
+    use crate::bar::BlockAlign;
+
+    match value.as_deref() {
+        None | Some("right") => Ok(BlockAlign::Right),
+        Some("center") => Ok(BlockAlign::Center),
+        Some("left") => Ok(BlockAlign::Left),
+        Some(other) => Err(mlua::Error::RuntimeError(format!(
+            "invalid block align '{}'. expected 'left', 'center' or 'right'",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(source: &str) -> mlua::Result<(KeyAction, Arg)> {
+        let lua = Lua::new();
+        register_api(&lua).unwrap();
+        let value: Value = lua.load(source).eval()?;
+        parse_action_value(&lua, value)
+    }
+
+    fn arg(source: &str) -> Arg {
+        action(source).unwrap().1
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn fields(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn converts_missing_arguments_to_none() {
+        assert_eq!(arg("oxwm.spawn_terminal()"), Arg::None);
+        assert_eq!(arg("oxwm.client.kill()"), Arg::None);
+    }
+
+    #[test]
+    fn converts_integers_like_before() {
+        assert_eq!(
+            action("oxwm.tag.view(3)").unwrap(),
+            (KeyAction::ViewTag, Arg::Int(3))
+        );
+        assert_eq!(arg("oxwm.client.focus_stack(-1)"), Arg::Int(-1));
+        assert_eq!(arg("{ __action = 'FocusStack', __arg = 3.0 }"), Arg::Int(3));
+    }
+
+    #[test]
+    fn rejects_fractions_for_integer_actions() {
+        assert!(action("{ __action = 'FocusStack', __arg = 1.5 }").is_err());
+    }
+
+    #[test]
+    fn converts_master_factor_deltas_to_percent_floats() {
+        assert_eq!(
+            action("oxwm.set_master_factor(5)").unwrap(),
+            (KeyAction::SetMasterFactor, Arg::Float(5.0))
+        );
+        assert_eq!(arg("oxwm.set_master_factor(-2.5)"), Arg::Float(-2.5));
+    }
+
+    #[test]
+    fn converts_strings_and_string_arrays() {
+        assert_eq!(arg("oxwm.spawn('st')"), Arg::Str("st".to_string()));
+        assert_eq!(
+            arg("oxwm.spawn({ 'st', '-e', 'htop' })"),
+            Arg::Array(strings(&["st", "-e", "htop"]))
+        );
+    }
+
+    #[test]
+    fn converts_named_fields_to_a_sorted_table() {
+        assert_eq!(
+            arg("oxwm.client.move(-10, 20)"),
+            Arg::Table(fields(&[("x", "-10"), ("y", "20")]))
+        );
+        assert_eq!(
+            arg("oxwm.client.move('left', 2.5)"),
+            Arg::Table(fields(&[("x", "left"), ("y", "2.5")]))
+        );
+    }
+
+    #[test]
+    fn rejects_nested_tables_in_fields() {
+        assert!(action("oxwm.spawn({ cmd = { 'st' } })").is_err());
+    }
+
+    #[test]
+    fn wraps_spawn_environment() {
+        assert_eq!(
+            arg("oxwm.spawn('st', { TERM = 'xterm', LINES = 40 })"),
+            Arg::WithEnv(
+                Box::new(Arg::Str("st".to_string())),
+                fields(&[("LINES", "40"), ("TERM", "xterm")])
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_function_references() {
+        assert!(action("oxwm.spawn_terminal").is_err());
+    }
+
+    fn run(source: &str) -> mlua::Result<()> {
+        let lua = Lua::new();
+        register_api(&lua).unwrap();
+        lua.load(source).exec()
+    }
+
+    fn assert_limited<T: std::fmt::Display>(setter: &str, limit: &Limit<T>, below: T, above: T) {
+        let call = |value: &dyn std::fmt::Display| setter.replace("{}", &value.to_string());
+
+        for value in [&limit.min, &limit.max] {
+            if let Err(err) = run(&call(value)) {
+                panic!("{} rejected {}: {}", setter, value, err);
+            }
+        }
+
+        let allowed = format!("(allowed {}..={})", limit.min, limit.max);
+        for value in [below, above] {
+            let source = call(&value);
+            let err = run(&source).expect_err(&source).to_string();
+            assert!(err.contains(limit.name), "{}: {}", source, err);
+            assert!(err.contains(&value.to_string()), "{}: {}", source, err);
+            assert!(err.contains(&allowed), "{}: {}", source, err);
+        }
+    }
+
+    #[test]
+    fn integer_setters_enforce_their_limits() {
+        let setters: [(&str, &Limit<i64>); 9] = [
+            ("oxwm.border.set_width({})", &limits::BORDER_WIDTH),
+            ("oxwm.gaps.set_inner({}, 0)", &limits::GAP),
+            ("oxwm.gaps.set_inner(0, {})", &limits::GAP),
+            ("oxwm.gaps.set_outer({}, 0)", &limits::GAP),
+            ("oxwm.gaps.set_outer(0, {})", &limits::GAP),
+            ("oxwm.gaps.set_outer_sides(0, 0, 0, {})", &limits::GAP),
+            ("oxwm.key.set_chord_timeout({})", &limits::KEYCHORD_TIMEOUT),
+            ("oxwm.set_snap_distance({})", &limits::SNAP_DISTANCE),
+            (
+                "oxwm.bar.block.ram({ format = '', interval = {} })",
+                &limits::BLOCK_INTERVAL,
+            ),
+        ];
+
+        for (setter, limit) in setters {
+            assert_limited(setter, limit, limit.min - 1, limit.max + 1);
+        }
+    }
+
+    #[test]
+    fn float_setters_enforce_their_limits() {
+        let setters: [(&str, &Limit<f32>); 5] = [
+            (
+                "oxwm.layout.set_scroll_column_factor({})",
+                &limits::SCROLL_COLUMN_FACTOR,
+            ),
+            (
+                "oxwm.scratchpad.define('s', { command = 'st', width = {} })",
+                &limits::SCRATCHPAD_SIZE,
+            ),
+            (
+                "oxwm.scratchpad.define('s', { command = 'st', height = {} })",
+                &limits::SCRATCHPAD_SIZE,
+            ),
+            (
+                "oxwm.scratchpad.define('s', { command = 'st', x = {} })",
+                &limits::SCRATCHPAD_OFFSET,
+            ),
+            (
+                "oxwm.scratchpad.define('s', { command = 'st', y = {} })",
+                &limits::SCRATCHPAD_OFFSET,
+            ),
+        ];
+
+        for (setter, limit) in setters {
+            assert_limited(setter, limit, limit.min - 0.5, limit.max + 0.5);
+        }
+    }
+
+    #[test]
+    fn theme_define_reads_every_border_color() {
+        let lua = Lua::new();
+        let builder = register_api(&lua).unwrap();
+        lua.load(
+            "oxwm.theme.define('dark', { border_focused = 1, border_unfocused = 2, border_urgent = 3 })",
+        )
+        .exec()
+        .unwrap();
+
+        let builder = builder.borrow();
+        let theme = &builder.themes[0];
+        assert_eq!(theme.border_focused, Some(1));
+        assert_eq!(theme.border_unfocused, Some(2));
+        assert_eq!(theme.border_urgent, Some(3));
+        assert!(theme.scheme_normal.is_none());
+    }
+
+    #[test]
+    fn snap_rejects_unknown_regions() {
+        assert!(run("oxwm.client.snap('top-left')").is_ok());
+        let err = run("oxwm.client.snap('middle')").unwrap_err().to_string();
+        assert!(err.contains("unknown region 'middle'"), "{}", err);
+    }
+
+    #[test]
+    fn index_setters_enforce_their_limits() {
+        let setters: [(fn(usize) -> String, &Limit<usize>); 2] = [
+            (
+                |index| format!("oxwm.tag.set_default_layout({}, 'tiling')", index),
+                &limits::TAG_INDEX,
+            ),
+            (
+                |index| format!("oxwm.monitor.configure({}, {{}})", index),
+                &limits::MONITOR_INDEX,
+            ),
+        ];
+
+        for (call, limit) in setters {
+            assert!(run(&call(limit.max)).is_ok());
+            let source = call(limit.max + 1);
+            let err = run(&source).expect_err(&source).to_string();
+            assert!(err.contains(limit.name), "{}: {}", source, err);
+        }
+    }
+
+    #[test]
+    fn set_tags_enforces_the_tag_count() {
+        let set_tags = |count: usize| {
+            let names: Vec<String> = (1..=count).map(|n| format!("'{}'", n)).collect();
+            run(&format!("oxwm.set_tags({{ {} }})", names.join(", ")))
+        };
+
+        // An empty list is accepted and falls back to a single tag after parsing.
+        assert!(set_tags(0).is_ok());
+        assert!(set_tags(limits::TAG_COUNT.min).is_ok());
+        assert!(set_tags(limits::TAG_COUNT.max).is_ok());
+
+        let err = set_tags(limits::TAG_COUNT.max + 1).unwrap_err().to_string();
+        assert!(err.contains("tag count"), "{}", err);
+        assert!(err.contains("(allowed 1..=32)"), "{}", err);
+    }
+}
@@ -1,4 +1,10 @@
+pub mod limits;
 mod lua;
 mod lua_api;
+mod watch;
 
 pub use lua::parse_lua_config;
+pub use watch::ConfigWatcher;
+
+#[cfg(test)]
+pub(crate) use lua_api::string_to_action;
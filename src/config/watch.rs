@@ -0,0 +1,193 @@
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use inotify::{Inotify, WatchDescriptor, WatchMask};
+
+use super::lua::CONF_D_DIR;
+
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+/// Editors replace the file on save, so the directories are watched rather than the
+/// files, and a rename into place counts as a write.
+fn watch_mask() -> WatchMask {
+    WatchMask::CLOSE_WRITE
+        | WatchMask::MOVED_TO
+        | WatchMask::MOVED_FROM
+        | WatchMask::CREATE
+        | WatchMask::DELETE
+}
+
+/// Watches the config file and its conf.d snippets through inotify.
+pub struct ConfigWatcher {
+    inotify: Inotify,
+    config_dir: PathBuf,
+    config_name: OsString,
+    config_dir_watch: WatchDescriptor,
+    conf_d_watch: Option<WatchDescriptor>,
+    changed_at: Option<Instant>,
+}
+
+impl ConfigWatcher {
+    /// Returns None when inotify is unavailable or the config directory cannot be watched.
+    pub fn new(config_path: &Path) -> Option<Self> {
+        let config_dir = config_path.parent()?.to_path_buf();
+        let config_name = config_path.file_name()?.to_os_string();
+
+        let inotify = match Inotify::init() {
+            Ok(inotify) => inotify,
+            Err(error) => {
+                eprintln!("Config auto-reload disabled: {}", error);
+                return None;
+            }
+        };
+
+        let config_dir_watch = match inotify.watches().add(&config_dir, watch_mask()) {
+            Ok(watch) => watch,
+            Err(error) => {
+                eprintln!(
+                    "Config auto-reload disabled: cannot watch {}: {}",
+                    config_dir.display(),
+                    error
+                );
+                return None;
+            }
+        };
+
+        let mut watcher = Self {
+            inotify,
+            config_dir,
+            config_name,
+            config_dir_watch,
+            conf_d_watch: None,
+            changed_at: None,
+        };
+        watcher.watch_conf_d();
+        Some(watcher)
+    }
+
+    /// Returns true once the files changed and then stayed untouched for the settle time.
+    pub fn poll(&mut self) -> bool {
+        let now = Instant::now();
+        if self.read_events() {
+            self.changed_at = Some(now);
+            return false;
+        }
+
+        match self.changed_at {
+            Some(changed_at) if now.duration_since(changed_at) >= SETTLE_TIME => {
+                self.changed_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Drains the pending events and reports whether any touched the config.
+    fn read_events(&mut self) -> bool {
+        let mut changed = false;
+        let mut conf_d_created = false;
+        let mut buffer = [0u8; 4096];
+
+        while let Ok(events) = self.inotify.read_events(&mut buffer) {
+            for event in events {
+                let Some(name) = event.name else {
+                    continue;
+                };
+                if event.wd == self.config_dir_watch {
+                    if name == CONF_D_DIR {
+                        conf_d_created = true;
+                        changed = true;
+                    } else if name == self.config_name {
+                        changed = true;
+                    }
+                } else if Some(&event.wd) == self.conf_d_watch.as_ref() && is_lua_file(name) {
+                    changed = true;
+                }
+            }
+        }
+
+        if conf_d_created {
+            self.watch_conf_d();
+        }
+        changed
+    }
+
+    /// Starts watching conf.d, which may only appear after the watcher was created.
+    fn watch_conf_d(&mut self) {
+        let conf_d = self.config_dir.join(CONF_D_DIR);
+        if conf_d.is_dir() {
+            self.conf_d_watch = self.inotify.watches().add(&conf_d, watch_mask()).ok();
+        }
+    }
+}
+
+fn is_lua_file(name: &OsStr) -> bool {
+    Path::new(name).extension().is_some_and(|ext| ext == "lua")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scratch config directory that is removed even when an assertion fails.
+    struct ScratchDir(PathBuf);
+
+    impl ScratchDir {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("oxwm-watch-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for ScratchDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn settles(watcher: &mut ConfigWatcher) -> bool {
+        if watcher.poll() {
+            return true;
+        }
+        std::thread::sleep(SETTLE_TIME);
+        watcher.poll()
+    }
+
+    #[test]
+    fn poll_reports_a_write_after_it_settles() {
+        let dir = ScratchDir::new("write");
+        let config_path = dir.0.join("config.lua");
+        std::fs::write(&config_path, "-- v1").unwrap();
+
+        let mut watcher = ConfigWatcher::new(&config_path).unwrap();
+        std::fs::write(dir.0.join("unrelated.txt"), "x").unwrap();
+        assert!(!settles(&mut watcher));
+
+        std::fs::write(&config_path, "-- v2").unwrap();
+        assert!(!watcher.poll());
+        std::thread::sleep(SETTLE_TIME);
+        assert!(watcher.poll());
+        assert!(!watcher.poll());
+    }
+
+    #[test]
+    fn poll_picks_up_snippets_in_a_new_conf_d() {
+        let dir = ScratchDir::new("conf-d");
+        let config_path = dir.0.join("config.lua");
+        std::fs::write(&config_path, "-- v1").unwrap();
+
+        let mut watcher = ConfigWatcher::new(&config_path).unwrap();
+        std::fs::create_dir(dir.0.join(CONF_D_DIR)).unwrap();
+        assert!(settles(&mut watcher));
+
+        std::fs::write(dir.0.join(CONF_D_DIR).join("notes.txt"), "x").unwrap();
+        assert!(!settles(&mut watcher));
+
+        std::fs::write(dir.0.join(CONF_D_DIR).join("50-keys.lua"), "-- keys").unwrap();
+        assert!(settles(&mut watcher));
+    }
+}
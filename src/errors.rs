@@ -49,6 +49,7 @@ pub enum MainError {
     FailedCheckExist(std::io::Error),
     FailedReadConfig(std::io::Error),
     FailedReadConfigTemplate(ConfigError),
+    InvalidConfig(ConfigError),
     CouldNotStartWm(WmError),
     WmError(WmError),
     BadConfigPath,
@@ -210,6 +211,7 @@ impl std::fmt::Debug for MainError {
                 write!(f, "{e}")
             }
             FailedReadConfigTemplate(e) => write!(f, "{e}"),
+            InvalidConfig(e) => write!(f, "Config is invalid: {e}"),
             CouldNotStartWm(e) | WmError(e) => write!(f, "{e}"),
             BadConfigPath => write!(f, "Given config path does not exist"),
             NoConfigPath => write!(f, "The --config switch requires a path value"),
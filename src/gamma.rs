@@ -0,0 +1,275 @@
+use x11rb::connection::Connection;
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::xproto::Window;
+
+use crate::errors::X11Error;
+
+pub const NEUTRAL_TEMPERATURE: u32 = 6500;
+const MIN_TEMPERATURE: u32 = 1000;
+const MAX_TEMPERATURE: u32 = 25000;
+
+#[derive(Debug, Clone, Copy)]
+pub struct NightLight {
+    pub day: u32,
+    pub night: u32,
+    pub start_minute: u32,
+    pub stop_minute: u32,
+}
+
+impl NightLight {
+    pub fn temperature_at(&self, minute_of_day: u32) -> u32 {
+        let is_night = if self.start_minute <= self.stop_minute {
+            minute_of_day >= self.start_minute && minute_of_day < self.stop_minute
+        } else {
+            minute_of_day >= self.start_minute || minute_of_day < self.stop_minute
+        };
+
+        if is_night { self.night } else { self.day }
+    }
+}
+
+pub fn parse_time_of_day(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.split_once(':')?;
+    let hours: u32 = hours.trim().parse().ok()?;
+    let minutes: u32 = minutes.trim().parse().ok()?;
+
+    if hours < 24 && minutes < 60 {
+        Some(hours * 60 + minutes)
+    } else {
+        None
+    }
+}
+
+pub struct Gamma {
+    temperature: u32,
+    scheduled_temperature: Option<u32>,
+    /// Temperature last written to the CRTCs, or None while oxwm never touched them.
+    applied: Option<u32>,
+    warned: bool,
+}
+
+impl Gamma {
+    pub fn new() -> Self {
+        Self {
+            temperature: NEUTRAL_TEMPERATURE,
+            scheduled_temperature: None,
+            applied: None,
+            warned: false,
+        }
+    }
+
+    pub fn set(&mut self, connection: &impl Connection, root: Window, temperature: u32) {
+        self.set_with(temperature, |value| {
+            apply_temperature(connection, root, value)
+        });
+    }
+
+    /// Writes the current ramps again, e.g. after outputs changed, if oxwm ever set them.
+    pub fn reapply(&mut self, connection: &impl Connection, root: Window) {
+        if self.applied.is_some() {
+            self.write(|value| apply_temperature(connection, root, value));
+        }
+    }
+
+    pub fn restore(&mut self, connection: &impl Connection, root: Window) {
+        self.restore_with(|value| apply_temperature(connection, root, value));
+    }
+
+    pub fn update_schedule(
+        &mut self,
+        connection: &impl Connection,
+        root: Window,
+        night_light: &NightLight,
+        minute_of_day: u32,
+    ) {
+        self.update_schedule_with(night_light, minute_of_day, |value| {
+            apply_temperature(connection, root, value)
+        });
+    }
+
+    fn set_with(&mut self, temperature: u32, apply: impl FnOnce(u32) -> Result<(), X11Error>) {
+        self.temperature = temperature.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE);
+        // A first set writes even the neutral value, which also clears ramps left
+        // behind by an instance that did not exit cleanly.
+        if self.applied != Some(self.temperature) {
+            self.write(apply);
+        }
+    }
+
+    fn restore_with(&mut self, apply: impl FnOnce(u32) -> Result<(), X11Error>) {
+        self.temperature = NEUTRAL_TEMPERATURE;
+        if self.applied.is_some() {
+            self.write(apply);
+        }
+    }
+
+    fn update_schedule_with(
+        &mut self,
+        night_light: &NightLight,
+        minute_of_day: u32,
+        apply: impl FnOnce(u32) -> Result<(), X11Error>,
+    ) {
+        let temperature = night_light.temperature_at(minute_of_day);
+
+        // Only react to schedule transitions so a manual set_gamma sticks until the next one.
+        if self.scheduled_temperature != Some(temperature) {
+            self.scheduled_temperature = Some(temperature);
+            self.set_with(temperature, apply);
+        }
+    }
+
+    fn write(&mut self, apply: impl FnOnce(u32) -> Result<(), X11Error>) {
+        match apply(self.temperature) {
+            Ok(()) => self.applied = Some(self.temperature),
+            Err(error) if !self.warned => {
+                eprintln!(
+                    "Failed to set gamma via RandR, gamma control disabled: {}",
+                    error
+                );
+                self.warned = true;
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+impl Default for Gamma {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn apply_temperature(
+    connection: &impl Connection,
+    root: Window,
+    temperature: u32,
+) -> Result<(), X11Error> {
+    let (red, green, blue) = temperature_to_rgb(temperature);
+    let resources = connection
+        .randr_get_screen_resources_current(root)?
+        .reply()?;
+
+    for &crtc in &resources.crtcs {
+        let size = connection.randr_get_crtc_gamma_size(crtc)?.reply()?.size;
+        if size == 0 {
+            continue;
+        }
+
+        let ramp = |factor: f64| -> Vec<u16> {
+            (0..size)
+                .map(|i| {
+                    let value = i as f64 / (size - 1).max(1) as f64;
+                    (value * factor * u16::MAX as f64) as u16
+                })
+                .collect()
+        };
+
+        connection.randr_set_crtc_gamma(crtc, &ramp(red), &ramp(green), &ramp(blue))?;
+    }
+
+    connection.flush()?;
+    Ok(())
+}
+
+fn temperature_to_rgb(temperature: u32) -> (f64, f64, f64) {
+    let (red, green, blue) = blackbody(temperature);
+    let (neutral_red, neutral_green, neutral_blue) = blackbody(NEUTRAL_TEMPERATURE);
+
+    (
+        (red / neutral_red).min(1.0),
+        (green / neutral_green).min(1.0),
+        (blue / neutral_blue).min(1.0),
+    )
+}
+
+fn blackbody(temperature: u32) -> (f64, f64, f64) {
+    let t = temperature as f64 / 100.0;
+
+    let red = if t <= 66.0 {
+        255.0
+    } else {
+        329.698727446 * (t - 60.0).powf(-0.1332047592)
+    };
+
+    let green = if t <= 66.0 {
+        99.4708025861 * t.ln() - 161.1195681661
+    } else {
+        288.1221695283 * (t - 60.0).powf(-0.0755148492)
+    };
+
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.5177312231 * (t - 10.0).ln() - 305.0447927307
+    };
+
+    (
+        red.clamp(0.0, 255.0) / 255.0,
+        green.clamp(0.0, 255.0) / 255.0,
+        blue.clamp(0.0, 255.0) / 255.0,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(writes: &mut Vec<u32>) -> impl FnOnce(u32) -> Result<(), X11Error> + '_ {
+        |value| {
+            writes.push(value);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn returning_to_neutral_writes_neutral_ramps() {
+        let mut gamma = Gamma::new();
+        let mut writes = Vec::new();
+
+        gamma.set_with(4500, record(&mut writes));
+        gamma.set_with(NEUTRAL_TEMPERATURE, record(&mut writes));
+        gamma.set_with(NEUTRAL_TEMPERATURE, record(&mut writes));
+
+        assert_eq!(writes, vec![4500, NEUTRAL_TEMPERATURE]);
+    }
+
+    #[test]
+    fn restore_resets_ramps_applied_by_a_schedule() {
+        let night_light = NightLight {
+            day: NEUTRAL_TEMPERATURE,
+            night: 3500,
+            start_minute: 20 * 60,
+            stop_minute: 7 * 60,
+        };
+        let mut gamma = Gamma::new();
+        let mut writes = Vec::new();
+
+        gamma.update_schedule_with(&night_light, 12 * 60, record(&mut writes));
+        gamma.restore_with(record(&mut writes));
+
+        assert_eq!(writes, vec![NEUTRAL_TEMPERATURE, NEUTRAL_TEMPERATURE]);
+    }
+
+    #[test]
+    fn restore_leaves_untouched_ramps_alone() {
+        let mut gamma = Gamma::new();
+        let mut writes = Vec::new();
+
+        gamma.restore_with(record(&mut writes));
+
+        assert!(writes.is_empty());
+    }
+
+    #[test]
+    fn failed_write_is_retried_on_the_next_set() {
+        let mut gamma = Gamma::new();
+        let mut writes = Vec::new();
+
+        gamma.set_with(4500, |_| Err(X11Error::DisplayOpenFailed));
+        gamma.set_with(4500, record(&mut writes));
+
+        assert_eq!(writes, vec![4500]);
+    }
+}
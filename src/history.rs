@@ -0,0 +1,114 @@
+use crate::client::{Client, TagMask};
+use std::collections::VecDeque;
+use x11rb::protocol::xproto::Window;
+
+const MAX_ENTRIES: usize = 20;
+
+#[derive(Debug, Clone)]
+pub enum WindowOp {
+    Tags {
+        window: Window,
+        tags: TagMask,
+    },
+    Monitor {
+        window: Window,
+        monitor_index: usize,
+        tags: TagMask,
+    },
+    Floating {
+        window: Window,
+        was_floating: bool,
+        x_position: i16,
+        y_position: i16,
+        width: u16,
+        height: u16,
+    },
+    Fullscreen {
+        window: Window,
+        was_fullscreen: bool,
+    },
+}
+
+impl WindowOp {
+    pub fn tags(client: &Client) -> Self {
+        Self::Tags {
+            window: client.window,
+            tags: client.tags,
+        }
+    }
+
+    pub fn monitor(client: &Client) -> Self {
+        Self::Monitor {
+            window: client.window,
+            monitor_index: client.monitor_index,
+            tags: client.tags,
+        }
+    }
+
+    pub fn floating(client: &Client) -> Self {
+        Self::Floating {
+            window: client.window,
+            was_floating: client.is_floating,
+            x_position: client.x_position,
+            y_position: client.y_position,
+            width: client.width,
+            height: client.height,
+        }
+    }
+
+    pub fn fullscreen(client: &Client) -> Self {
+        Self::Fullscreen {
+            window: client.window,
+            was_fullscreen: client.is_fullscreen,
+        }
+    }
+
+    pub fn window(&self) -> Window {
+        match self {
+            Self::Tags { window, .. }
+            | Self::Monitor { window, .. }
+            | Self::Floating { window, .. }
+            | Self::Fullscreen { window, .. } => *window,
+        }
+    }
+
+    pub fn changed(&self, client: &Client) -> bool {
+        match self {
+            Self::Tags { tags, .. } => client.tags != *tags,
+            Self::Monitor { monitor_index, .. } => client.monitor_index != *monitor_index,
+            Self::Floating { was_floating, .. } => client.is_floating != *was_floating,
+            Self::Fullscreen { was_fullscreen, .. } => client.is_fullscreen != *was_fullscreen,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Tags { .. } => "tag change",
+            Self::Monitor { .. } => "monitor change",
+            Self::Floating { .. } => "floating toggle",
+            Self::Fullscreen { .. } => "fullscreen toggle",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct WindowHistory {
+    entries: VecDeque<WindowOp>,
+}
+
+impl WindowHistory {
+    pub fn push(&mut self, op: WindowOp) {
+        if self.entries.len() == MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(op);
+    }
+
+    pub fn pop(&mut self) -> Option<WindowOp> {
+        self.entries.pop_back()
+    }
+
+    pub fn forget(&mut self, window: Window) {
+        self.entries.retain(|op| op.window() != window);
+    }
+}
@@ -0,0 +1,779 @@
+use crate::bar::{Segment, SegmentPosition};
+use crate::client::Client;
+use crate::keyboard::{Arg, KeyAction};
+use std::io::{self, Read, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
+const WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+const MAX_REQUEST_LEN: usize = 64 * 1024;
+
+/// The socket lives in `$XDG_RUNTIME_DIR`, which is private to the user, or
+/// otherwise in a per-user directory under the temp dir that `bind` creates 0700.
+pub fn socket_path() -> PathBuf {
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(fallback_runtime_dir);
+    let display: String = std::env::var("DISPLAY")
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '.')
+        .collect();
+    runtime_dir.join(format!("oxwm-{}.sock", display))
+}
+
+fn fallback_runtime_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("oxwm-{}", current_uid()))
+}
+
+fn current_uid() -> u32 {
+    unsafe { libc::geteuid() }
+}
+
+fn ensure_private_dir(dir: &Path) -> io::Result<()> {
+    match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(e),
+    }
+
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != current_uid() || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} is not a private directory owned by the current user",
+                dir.display()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn peer_uid(stream: &UnixStream) -> io::Result<u32> {
+    let mut credentials = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut length = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut credentials as *mut libc::ucred as *mut libc::c_void,
+            &mut length,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(credentials.uid)
+}
+
+pub struct IpcServer {
+    listener: UnixListener,
+    path: PathBuf,
+    pending: Vec<PendingRequest>,
+}
+
+struct PendingRequest {
+    stream: UnixStream,
+    buffer: Vec<u8>,
+    accepted_at: Instant,
+}
+
+impl IpcServer {
+    pub fn bind() -> io::Result<Self> {
+        let path = socket_path();
+        if let Some(dir) = path.parent()
+            && std::env::var_os("XDG_RUNTIME_DIR").is_none()
+        {
+            ensure_private_dir(dir)?;
+        }
+
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("{} is in use by another oxwm instance", path.display()),
+                ));
+            }
+            std::fs::remove_file(&path)?;
+        }
+
+        let listener = UnixListener::bind(&path)?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            path,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Returns the next complete request without blocking. Connections from other
+    /// users are dropped, and slow clients are read a little more on every call.
+    pub fn accept(&mut self) -> Option<IpcRequest> {
+        while let Ok((stream, _)) = self.listener.accept() {
+            if peer_uid(&stream).ok() != Some(current_uid()) {
+                continue;
+            }
+            if stream.set_nonblocking(true).is_err() {
+                continue;
+            }
+            self.pending.push(PendingRequest {
+                stream,
+                buffer: Vec::new(),
+                accepted_at: Instant::now(),
+            });
+        }
+
+        let mut index = 0;
+        while index < self.pending.len() {
+            match self.pending[index].poll() {
+                Poll::Pending => index += 1,
+                Poll::Closed => {
+                    self.pending.swap_remove(index);
+                }
+                Poll::Ready(line) => {
+                    let pending = self.pending.swap_remove(index);
+                    return Some(IpcRequest {
+                        stream: pending.stream,
+                        args: split_args(line.trim_end()),
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+enum Poll {
+    Pending,
+    Closed,
+    Ready(String),
+}
+
+impl PendingRequest {
+    fn poll(&mut self) -> Poll {
+        let mut chunk = [0u8; 4096];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return self.finish(),
+                Ok(read) => {
+                    self.buffer.extend_from_slice(&chunk[..read]);
+                    if self.buffer.contains(&b'\n') {
+                        return self.finish();
+                    }
+                    if self.buffer.len() > MAX_REQUEST_LEN {
+                        return Poll::Closed;
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    return if self.accepted_at.elapsed() > REQUEST_TIMEOUT {
+                        Poll::Closed
+                    } else {
+                        Poll::Pending
+                    };
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => return Poll::Closed,
+            }
+        }
+    }
+
+    fn finish(&mut self) -> Poll {
+        let line = match self.buffer.iter().position(|&b| b == b'\n') {
+            Some(end) => &self.buffer[..end],
+            None if self.buffer.is_empty() => return Poll::Closed,
+            None => &self.buffer[..],
+        };
+        match String::from_utf8(line.to_vec()) {
+            Ok(line) => Poll::Ready(line),
+            Err(_) => Poll::Closed,
+        }
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+pub struct IpcRequest {
+    stream: UnixStream,
+    pub args: Vec<String>,
+}
+
+impl IpcRequest {
+    pub fn respond(mut self, result: Result<String, String>) {
+        let response = match result {
+            Ok(message) if message.is_empty() => "ok\n".to_string(),
+            Ok(message) => format!("ok\n{}\n", message),
+            Err(message) => format!("error: {}\n", message),
+        };
+        if self.stream.set_nonblocking(false).is_err()
+            || self.stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_err()
+        {
+            return;
+        }
+        let _ = self.stream.write_all(response.as_bytes());
+    }
+}
+
+pub fn split_args(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+                in_arg = true;
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+pub fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty()
+        && !arg.contains(|c: char| c.is_whitespace() || c == '\'' || c == '"' || c == '\\')
+    {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+#[derive(Debug)]
+pub enum IpcCommand {
+    SetSegment { name: String, segment: Segment },
+    ClearSegment { name: String },
+    Dump,
+    GetTagState { tag: usize },
+    FindClient(ClientQuery),
+    FocusClient { target: String },
+    Action { action: KeyAction, arg: Arg },
+}
+
+impl IpcCommand {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let Some((command, rest)) = args.split_first() else {
+            return Err("empty command".to_string());
+        };
+
+        match command.as_str() {
+            "set-segment" => parse_set_segment(rest),
+            "clear-segment" => match rest {
+                [name] => Ok(Self::ClearSegment { name: name.clone() }),
+                _ => Err("usage: clear-segment <name>".to_string()),
+            },
+            "find-client" => parse_find_client(rest),
+            "focus-client" => match rest {
+                [target] => Ok(Self::FocusClient {
+                    target: target.clone(),
+                }),
+                _ => Err("usage: focus-client <stable-id|window-id>".to_string()),
+            },
+            "dump" => match rest {
+                [] => Ok(Self::Dump),
+                _ => Err("usage: dump".to_string()),
+            },
+            "get-tag-state" => match rest {
+                [tag] => Ok(Self::GetTagState {
+                    tag: tag
+                        .parse()
+                        .map_err(|_| format!("invalid tag index '{}'", tag))?,
+                }),
+                _ => Err("usage: get-tag-state <tag>".to_string()),
+            },
+            _ => parse_action(command, rest),
+        }
+    }
+}
+
+fn parse_set_segment(args: &[String]) -> Result<IpcCommand, String> {
+    const USAGE: &str = "usage: set-segment <name> <text> [--color COLOR] [--position left|right] [--monitor N] [--ttl SECONDS]";
+
+    let [name, text, options @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+
+    let mut segment = Segment {
+        text: text.clone(),
+        color: None,
+        position: SegmentPosition::Right,
+        monitor: None,
+        expires_at: None,
+    };
+
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let value = options
+            .next()
+            .ok_or_else(|| format!("{} requires a value", option))?;
+
+        match option.as_str() {
+            "--color" => segment.color = Some(parse_color(value)?),
+            "--position" => {
+                segment.position = match value.as_str() {
+                    "left" => SegmentPosition::Left,
+                    "right" => SegmentPosition::Right,
+                    _ => return Err(format!("invalid position '{}'", value)),
+                }
+            }
+            "--monitor" => {
+                segment.monitor = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid monitor '{}'", value))?,
+                )
+            }
+            "--ttl" => {
+                let seconds: f64 = value
+                    .parse()
+                    .ok()
+                    .filter(|seconds: &f64| seconds.is_finite() && *seconds > 0.0)
+                    .ok_or_else(|| format!("invalid ttl '{}'", value))?;
+                segment.expires_at = Some(Instant::now() + Duration::from_secs_f64(seconds));
+            }
+            _ => return Err(format!("unknown option '{}'\n{}", option, USAGE)),
+        }
+    }
+
+    Ok(IpcCommand::SetSegment {
+        name: name.clone(),
+        segment,
+    })
+}
+
+#[derive(Debug, Default)]
+pub struct ClientQuery {
+    pub class: Option<String>,
+    pub instance: Option<String>,
+    pub stable_id: Option<String>,
+}
+
+impl ClientQuery {
+    pub fn matches(&self, client: &Client) -> bool {
+        self.class
+            .as_ref()
+            .is_none_or(|class| client.class == *class)
+            && self
+                .instance
+                .as_ref()
+                .is_none_or(|instance| client.instance == *instance)
+            && self
+                .stable_id
+                .as_ref()
+                .is_none_or(|stable_id| client.stable_id == *stable_id)
+    }
+}
+
+fn parse_find_client(args: &[String]) -> Result<IpcCommand, String> {
+    const USAGE: &str = "usage: find-client [--class CLASS] [--instance INSTANCE] [--id STABLE_ID]";
+
+    let mut query = ClientQuery::default();
+    let mut options = args.iter();
+    while let Some(option) = options.next() {
+        let value = options
+            .next()
+            .ok_or_else(|| format!("{} requires a value", option))?
+            .clone();
+
+        match option.as_str() {
+            "--class" => query.class = Some(value),
+            "--instance" => query.instance = Some(value),
+            "--id" => query.stable_id = Some(value),
+            _ => return Err(format!("unknown option '{}'\n{}", option, USAGE)),
+        }
+    }
+
+    Ok(IpcCommand::FindClient(query))
+}
+
+#[derive(Clone, Copy)]
+enum ArgKind {
+    None,
+    Int,
+    Float,
+    Str,
+    Text,
+    Pair,
+    Command,
+}
+
+/// IPC command names with the action they run and the argument they take.
+const ACTIONS: &[(&str, KeyAction, ArgKind)] = &[
+    ("spawn", KeyAction::Spawn, ArgKind::Command),
+    ("spawn-terminal", KeyAction::SpawnTerminal, ArgKind::None),
+    ("kill-client", KeyAction::KillClient, ArgKind::None),
+    ("kill-others", KeyAction::KillOthers, ArgKind::None),
+    ("kill-all", KeyAction::KillAll, ArgKind::None),
+    ("focus-stack", KeyAction::FocusStack, ArgKind::Int),
+    ("focus-floating", KeyAction::FocusFloating, ArgKind::Int),
+    ("move-stack", KeyAction::MoveStack, ArgKind::Int),
+    ("rotate-stack", KeyAction::RotateStack, ArgKind::Int),
+    ("cycle-class", KeyAction::CycleSameClass, ArgKind::Int),
+    ("focus-urgent", KeyAction::FocusUrgent, ArgKind::None),
+    ("quit", KeyAction::Quit, ArgKind::None),
+    ("view-tag", KeyAction::ViewTag, ArgKind::Int),
+    ("view-tag-all", KeyAction::ViewTagAllMonitors, ArgKind::Int),
+    ("view-next-tag", KeyAction::ViewNextTag, ArgKind::None),
+    (
+        "view-previous-tag",
+        KeyAction::ViewPreviousTag,
+        ArgKind::None,
+    ),
+    ("toggle-view", KeyAction::ToggleView, ArgKind::Int),
+    ("move-tag", KeyAction::MoveTag, ArgKind::Int),
+    ("move-to-tag", KeyAction::MoveToTag, ArgKind::Int),
+    ("toggle-tag", KeyAction::ToggleTag, ArgKind::Int),
+    ("toggle-gaps", KeyAction::ToggleGaps, ArgKind::None),
+    ("toggle-bar", KeyAction::ToggleBar, ArgKind::None),
+    (
+        "toggle-focus-follows-mouse",
+        KeyAction::ToggleFocusFollowsMouse,
+        ArgKind::None,
+    ),
+    ("cycle-theme", KeyAction::CycleTheme, ArgKind::None),
+    (
+        "toggle-fullscreen",
+        KeyAction::ToggleFullScreen,
+        ArgKind::None,
+    ),
+    (
+        "toggle-fake-fullscreen",
+        KeyAction::ToggleFakeFullscreen,
+        ArgKind::None,
+    ),
+    ("toggle-floating", KeyAction::ToggleFloating, ArgKind::None),
+    ("toggle-above", KeyAction::ToggleAlwaysOnTop, ArgKind::None),
+    ("set-layout", KeyAction::ChangeLayout, ArgKind::Str),
+    ("cycle-layout", KeyAction::CycleLayout, ArgKind::None),
+    ("mirror-layout", KeyAction::MirrorLayout, ArgKind::None),
+    ("focus-monitor", KeyAction::FocusMonitor, ArgKind::Int),
+    ("tag-monitor", KeyAction::TagMonitor, ArgKind::Int),
+    (
+        "tag-monitor-keep-tag",
+        KeyAction::TagMonitorKeepTag,
+        ArgKind::Int,
+    ),
+    (
+        "set-master-factor",
+        KeyAction::SetMasterFactor,
+        ArgKind::Float,
+    ),
+    ("inc-num-master", KeyAction::IncNumMaster, ArgKind::Int),
+    (
+        "set-client-factor",
+        KeyAction::SetClientFactor,
+        ArgKind::Int,
+    ),
+    (
+        "toggle-scratchpad",
+        KeyAction::ToggleScratchpad,
+        ArgKind::Str,
+    ),
+    ("notify", KeyAction::Notify, ArgKind::Text),
+    ("run-or-raise", KeyAction::RunOrRaise, ArgKind::Pair),
+    ("osd", KeyAction::ShowOsd, ArgKind::Pair),
+    ("zoom", KeyAction::Zoom, ArgKind::None),
+];
+
+fn parse_action(command: &str, args: &[String]) -> Result<IpcCommand, String> {
+    let Some(&(_, action, kind)) = ACTIONS.iter().find(|(name, _, _)| *name == command) else {
+        return Err(format!("unknown command '{}'", command));
+    };
+
+    let arg = match (kind, args) {
+        (ArgKind::None, []) => Arg::None,
+        (ArgKind::Int, [value]) => Arg::Int(
+            value
+                .parse()
+                .map_err(|_| format!("invalid integer '{}'", value))?,
+        ),
+        (ArgKind::Float, [value]) => Arg::Float(
+            value
+                .parse()
+                .map_err(|_| format!("invalid number '{}'", value))?,
+        ),
+        (ArgKind::Str, [value]) => Arg::Str(value.clone()),
+        (ArgKind::Text, [_, ..]) => Arg::Str(args.join(" ")),
+        (ArgKind::Pair, [_, _]) => Arg::Array(args.to_vec()),
+        (ArgKind::Command, [_, ..]) => Arg::Array(args.to_vec()),
+        (ArgKind::None, _) => return Err(format!("usage: {}", command)),
+        (ArgKind::Int, _) => return Err(format!("usage: {} <integer>", command)),
+        (ArgKind::Float, _) => return Err(format!("usage: {} <number>", command)),
+        (ArgKind::Str, _) => return Err(format!("usage: {} <name>", command)),
+        (ArgKind::Text, _) => return Err(format!("usage: {} <text>", command)),
+        (ArgKind::Pair, _) => return Err(format!("usage: {} <label> <value>", command)),
+        (ArgKind::Command, _) => return Err(format!("usage: {} <program> [args...]", command)),
+    };
+
+    Ok(IpcCommand::Action { action, arg })
+}
+
+fn parse_color(value: &str) -> Result<u32, String> {
+    let hex = value
+        .strip_prefix('#')
+        .or_else(|| value.strip_prefix("0x"))
+        .unwrap_or(value);
+    u32::from_str_radix(hex, 16).map_err(|_| format!("invalid color '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    fn parse_error(words: &[&str]) -> String {
+        IpcCommand::parse(&args(words)).unwrap_err()
+    }
+
+    #[test]
+    fn quoted_args_round_trip_through_split_args() {
+        let original = args(&[
+            "plain",
+            "two words",
+            "it's",
+            "say \"hi\"",
+            r"C:\path\",
+            "",
+            "tab\tand\nnewline",
+        ]);
+        let line: Vec<String> = original.iter().map(|arg| quote_arg(arg)).collect();
+
+        assert_eq!(split_args(&line.join(" ")), original);
+    }
+
+    #[test]
+    fn split_args_handles_double_quotes_and_escapes() {
+        assert_eq!(
+            split_args(r#"notify "a \"b\" c" d\ e  'f\g'"#),
+            args(&["notify", "a \"b\" c", "d e", r"f\g"])
+        );
+    }
+
+    #[test]
+    fn set_segment_parses_every_option() {
+        let command = IpcCommand::parse(&args(&[
+            "set-segment",
+            "mail",
+            "3 new",
+            "--color",
+            "#ff0000",
+            "--position",
+            "left",
+            "--monitor",
+            "1",
+            "--ttl",
+            "2.5",
+        ]))
+        .unwrap();
+
+        let IpcCommand::SetSegment { name, segment } = command else {
+            panic!("expected set-segment, got {:?}", command);
+        };
+        assert_eq!(name, "mail");
+        assert_eq!(segment.text, "3 new");
+        assert_eq!(segment.color, Some(0xff0000));
+        assert_eq!(segment.position, SegmentPosition::Left);
+        assert_eq!(segment.monitor, Some(1));
+        assert!(segment.expires_at.is_some());
+    }
+
+    #[test]
+    fn set_segment_reports_bad_options() {
+        assert!(parse_error(&["set-segment", "mail"]).starts_with("usage: set-segment"));
+        assert_eq!(
+            parse_error(&["set-segment", "mail", "x", "--ttl"]),
+            "--ttl requires a value"
+        );
+        for ttl in ["0", "-1", "soon", "inf", "NaN"] {
+            assert_eq!(
+                parse_error(&["set-segment", "mail", "x", "--ttl", ttl]),
+                format!("invalid ttl '{}'", ttl)
+            );
+        }
+        assert_eq!(
+            parse_error(&["set-segment", "mail", "x", "--color", "#zzz"]),
+            "invalid color '#zzz'"
+        );
+        assert_eq!(
+            parse_error(&["set-segment", "mail", "x", "--position", "top"]),
+            "invalid position 'top'"
+        );
+        assert!(
+            parse_error(&["set-segment", "mail", "x", "--size", "2"])
+                .starts_with("unknown option '--size'")
+        );
+    }
+
+    #[test]
+    fn actions_reject_the_wrong_argument_kind() {
+        assert_eq!(parse_error(&["zoom", "1"]), "usage: zoom");
+        assert_eq!(parse_error(&["view-tag"]), "usage: view-tag <integer>");
+        assert_eq!(parse_error(&["view-tag", "two"]), "invalid integer 'two'");
+        assert_eq!(
+            parse_error(&["set-master-factor", "half"]),
+            "invalid number 'half'"
+        );
+        assert_eq!(
+            parse_error(&["set-layout", "a", "b"]),
+            "usage: set-layout <name>"
+        );
+        assert_eq!(parse_error(&["notify"]), "usage: notify <text>");
+        assert_eq!(
+            parse_error(&["osd", "volume"]),
+            "usage: osd <label> <value>"
+        );
+        assert_eq!(parse_error(&["spawn"]), "usage: spawn <program> [args...]");
+        assert_eq!(parse_error(&["frobnicate"]), "unknown command 'frobnicate'");
+        assert_eq!(parse_error(&[]), "empty command");
+    }
+
+    #[test]
+    fn every_command_runs_the_action_of_its_lua_name() {
+        let strings = |words: &[&str]| Arg::Array(args(words));
+        let cases: &[(&[&str], &str, Arg)] = &[
+            (
+                &["spawn", "st", "-e", "htop"],
+                "Spawn",
+                strings(&["st", "-e", "htop"]),
+            ),
+            (&["spawn-terminal"], "SpawnTerminal", Arg::None),
+            (&["kill-client"], "KillClient", Arg::None),
+            (&["kill-others"], "KillOthers", Arg::None),
+            (&["kill-all"], "KillAll", Arg::None),
+            (&["focus-stack", "1"], "FocusStack", Arg::Int(1)),
+            (&["focus-floating", "-1"], "FocusFloating", Arg::Int(-1)),
+            (&["move-stack", "1"], "MoveStack", Arg::Int(1)),
+            (&["rotate-stack", "-1"], "RotateStack", Arg::Int(-1)),
+            (&["cycle-class", "1"], "CycleSameClass", Arg::Int(1)),
+            (&["focus-urgent"], "FocusUrgent", Arg::None),
+            (&["quit"], "Quit", Arg::None),
+            (&["view-tag", "3"], "ViewTag", Arg::Int(3)),
+            (&["view-tag-all", "2"], "ViewTagAllMonitors", Arg::Int(2)),
+            (&["view-next-tag"], "ViewNextTag", Arg::None),
+            (&["view-previous-tag"], "ViewPreviousTag", Arg::None),
+            (&["toggle-view", "4"], "ToggleView", Arg::Int(4)),
+            (&["move-tag", "1"], "MoveTag", Arg::Int(1)),
+            (&["move-to-tag", "5"], "MoveToTag", Arg::Int(5)),
+            (&["toggle-tag", "6"], "ToggleTag", Arg::Int(6)),
+            (&["toggle-gaps"], "ToggleGaps", Arg::None),
+            (&["toggle-bar"], "ToggleBar", Arg::None),
+            (
+                &["toggle-focus-follows-mouse"],
+                "ToggleFocusFollowsMouse",
+                Arg::None,
+            ),
+            (&["cycle-theme"], "CycleTheme", Arg::None),
+            (&["toggle-fullscreen"], "ToggleFullScreen", Arg::None),
+            (
+                &["toggle-fake-fullscreen"],
+                "ToggleFakeFullscreen",
+                Arg::None,
+            ),
+            (&["toggle-floating"], "ToggleFloating", Arg::None),
+            (&["toggle-above"], "ToggleAlwaysOnTop", Arg::None),
+            (
+                &["set-layout", "grid"],
+                "ChangeLayout",
+                Arg::Str("grid".to_string()),
+            ),
+            (&["cycle-layout"], "CycleLayout", Arg::None),
+            (&["mirror-layout"], "MirrorLayout", Arg::None),
+            (&["focus-monitor", "1"], "FocusMonitor", Arg::Int(1)),
+            (&["tag-monitor", "-1"], "TagMonitor", Arg::Int(-1)),
+            (
+                &["tag-monitor-keep-tag", "1"],
+                "TagMonitorKeepTag",
+                Arg::Int(1),
+            ),
+            (
+                &["set-master-factor", "2.5"],
+                "SetMasterFactor",
+                Arg::Float(2.5),
+            ),
+            (&["inc-num-master", "1"], "IncNumMaster", Arg::Int(1)),
+            (&["set-client-factor", "5"], "SetClientFactor", Arg::Int(5)),
+            (
+                &["toggle-scratchpad", "term"],
+                "ToggleScratchpad",
+                Arg::Str("term".to_string()),
+            ),
+            (
+                &["notify", "build", "done"],
+                "Notify",
+                Arg::Str("build done".to_string()),
+            ),
+            (
+                &["run-or-raise", "Firefox", "firefox"],
+                "RunOrRaise",
+                strings(&["Firefox", "firefox"]),
+            ),
+            (
+                &["osd", "volume", "40"],
+                "ShowOsd",
+                strings(&["volume", "40"]),
+            ),
+            (&["zoom"], "Zoom", Arg::None),
+        ];
+
+        for (line, lua_name, expected_arg) in cases {
+            let lua_action = crate::config::string_to_action(lua_name).unwrap();
+            match IpcCommand::parse(&args(line)) {
+                Ok(IpcCommand::Action { action, arg }) => {
+                    assert_eq!(action, lua_action, "{}", line[0]);
+                    assert_eq!(&arg, expected_arg, "{}", line[0]);
+                }
+                other => panic!("{} parsed as {:?}", line[0], other),
+            }
+        }
+
+        let tested: Vec<&str> = cases.iter().map(|(line, _, _)| line[0]).collect();
+        for (name, _, _) in ACTIONS {
+            assert!(
+                tested.contains(name),
+                "IPC command '{}' is not tested",
+                name
+            );
+        }
+    }
+}
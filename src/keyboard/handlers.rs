@@ -19,45 +19,93 @@ pub enum KeyAction {
     Spawn,
     SpawnTerminal,
     KillClient,
+    KillOthers,
+    KillAll,
     FocusStack,
+    FocusFloating,
     MoveStack,
+    RotateStack,
     Quit,
     Restart,
     ViewTag,
+    ViewTagAllMonitors,
     ViewNextTag,
     ViewPreviousTag,
     ViewNextNonEmptyTag,
     ViewPreviousNonEmptyTag,
     ToggleView,
+    MoveTag,
     MoveToTag,
     ToggleTag,
     ToggleGaps,
+    ToggleBar,
     ToggleFullScreen,
+    ToggleFakeFullscreen,
     ToggleFloating,
+    ToggleAlwaysOnTop,
     ChangeLayout,
     CycleLayout,
+    MirrorLayout,
     FocusMonitor,
     TagMonitor,
+    TagMonitorKeepTag,
     ShowKeybindOverlay,
     SetMasterFactor,
     IncNumMaster,
+    SetClientFactor,
     ScrollLeft,
     ScrollRight,
+    ScrollColumnGrow,
+    ScrollColumnShrink,
+    SetGamma,
+    MoveFloating,
+    ResizeFloating,
+    ToggleScratchpad,
+    CenterFloating,
+    SnapFloating,
+    UndoWindowOp,
+    ToggleClickThrough,
+    Zoom,
+    RescanWindows,
+    TogglePresentationMode,
+    ToggleFocusFollowsMouse,
+    CycleTheme,
+    CycleSameClass,
+    FocusUrgent,
+    CycleRecent,
+    DumpState,
+    Notify,
+    ShowOsd,
+    RunOrRaise,
     None,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Arg {
     None,
     Int(i32),
+    Float(f32),
     Str(String),
     Array(Vec<String>),
+    Table(Vec<(String, String)>),
+    /// A spawn command with extra environment variables.
+    WithEnv(Box<Arg>, Vec<(String, String)>),
 }
 
 impl Arg {
     pub const fn none() -> Self {
         Arg::None
     }
+
+    pub fn field(&self, key: &str) -> Option<&str> {
+        match self {
+            Arg::Table(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -80,11 +128,17 @@ pub struct KeyBinding {
     pub(crate) keys: Vec<KeyPress>,
     pub(crate) func: KeyAction,
     pub(crate) arg: Arg,
+    pub(crate) on_release: Option<KeyAction>,
 }
 
 impl KeyBinding {
     pub fn new(keys: Vec<KeyPress>, func: KeyAction, arg: Arg) -> Self {
-        Self { keys, func, arg }
+        Self {
+            keys,
+            func,
+            arg,
+            on_release: None,
+        }
     }
 
     pub fn single_key(
@@ -97,8 +151,14 @@ impl KeyBinding {
             keys: vec![KeyPress { modifiers, keysym }],
             func,
             arg,
+            on_release: None,
         }
     }
+
+    pub fn with_release(mut self, action: KeyAction) -> Self {
+        self.on_release = Some(action);
+        self
+    }
 }
 
 pub type Key = KeyBinding;
@@ -109,6 +169,7 @@ pub enum KeychordState {
     InProgress {
         candidates: Vec<usize>,
         keys_pressed: usize,
+        started: std::time::Instant,
     },
 }
 
@@ -258,10 +319,40 @@ pub fn handle_key_press(
         KeychordState::InProgress {
             candidates,
             keys_pressed,
+            ..
         } => handle_next_key(event, keysym, keybindings, candidates, *keys_pressed),
     }
 }
 
+pub fn handle_key_release(
+    event: KeyReleaseEvent,
+    keybindings: &[KeyBinding],
+    mapping: &KeyboardMapping,
+) -> Option<(KeyAction, Arg)> {
+    let keysym = mapping.keycode_to_keysym(event.detail);
+    let clean_state = event.state & !(u16::from(ModMask::LOCK) | u16::from(ModMask::M2));
+
+    for keybinding in keybindings {
+        let Some(release_action) = keybinding.on_release else {
+            continue;
+        };
+        if keybinding.keys.len() != 1 {
+            continue;
+        }
+
+        let key = &keybinding.keys[0];
+        let modifier_mask = modifiers_to_mask(&key.modifiers);
+
+        // The state of a release event still contains the modifiers that were held, so a
+        // binding like Mod4+Tab matches on release of Tab while Mod4 is down.
+        if keysym == key.keysym && clean_state == modifier_mask.into() {
+            return Some((release_action, keybinding.arg.clone()));
+        }
+    }
+
+    None
+}
+
 fn handle_first_key(
     event: KeyPressEvent,
     event_keysym: Keysym,
@@ -272,7 +363,11 @@ fn handle_first_key(
     let clean_state = event.state & !(u16::from(ModMask::LOCK) | u16::from(ModMask::M2));
 
     for (keybinding_index, keybinding) in keybindings.iter().enumerate() {
-        if keybinding.keys.is_empty() {
+        // Release-only bindings must not swallow the press of a key bound to
+        // something else.
+        if keybinding.keys.is_empty()
+            || (keybinding.func == KeyAction::None && keybinding.on_release.is_some())
+        {
             continue;
         }
 
@@ -340,27 +435,30 @@ fn handle_next_key(
 
 pub fn handle_spawn_action(action: KeyAction, arg: &Arg, selected_monitor: usize) -> Result<()> {
     if let KeyAction::Spawn = action {
+        let (arg, env) = match arg {
+            Arg::WithEnv(command, env) => (command.as_ref(), env.as_slice()),
+            _ => (arg, &[][..]),
+        };
         match arg {
             Arg::Str(command) => {
-                crate::signal::spawn_detached(command);
+                crate::signal::spawn_detached_with_env(command, env);
             }
             Arg::Array(command) => {
                 let Some((cmd, args)) = command.split_first() else {
                     return Ok(());
                 };
 
-                let mut args_vec: Vec<String> = args.to_vec();
+                let mut argv: Vec<String> = command.to_vec();
 
                 let is_dmenu = cmd.contains("dmenu");
                 let has_monitor_flag = args.iter().any(|arg| arg == "-m");
 
                 if is_dmenu && !has_monitor_flag {
-                    args_vec.insert(0, selected_monitor.to_string());
-                    args_vec.insert(0, "-m".to_string());
+                    argv.insert(1, selected_monitor.to_string());
+                    argv.insert(1, "-m".to_string());
                 }
 
-                let args_str: Vec<&str> = args_vec.iter().map(|s| s.as_str()).collect();
-                crate::signal::spawn_detached_with_args(cmd, &args_str);
+                crate::signal::spawn_detached_args(&argv, env);
             }
             _ => {}
         }
@@ -368,3 +466,52 @@ pub fn handle_spawn_action(action: KeyAction, arg: &Arg, selected_monitor: usize
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(modifiers: KeyButMask) -> KeyPressEvent {
+        KeyPressEvent {
+            state: modifiers,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn release_only_binding_does_not_take_the_press() {
+        let keybindings = [
+            KeyBinding::single_key(
+                vec![KeyButMask::MOD4],
+                keysyms::XK_TAB,
+                KeyAction::None,
+                Arg::None,
+            )
+            .with_release(KeyAction::FocusStack),
+            KeyBinding::single_key(
+                vec![KeyButMask::MOD4],
+                keysyms::XK_TAB,
+                KeyAction::ViewNextTag,
+                Arg::None,
+            ),
+        ];
+        let result = handle_first_key(press(KeyButMask::MOD4), keysyms::XK_TAB, &keybindings);
+        assert!(matches!(
+            result,
+            KeychordResult::Completed(KeyAction::ViewNextTag, Arg::None)
+        ));
+    }
+
+    #[test]
+    fn release_only_binding_alone_matches_nothing_on_press() {
+        let keybindings = [KeyBinding::single_key(
+            vec![KeyButMask::MOD4],
+            keysyms::XK_TAB,
+            KeyAction::None,
+            Arg::None,
+        )
+        .with_release(KeyAction::FocusStack)];
+        let result = handle_first_key(press(KeyButMask::MOD4), keysyms::XK_TAB, &keybindings);
+        assert!(matches!(result, KeychordResult::None));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod handlers;
 pub mod keysyms;
 
-pub use handlers::{Arg, KeyAction, KeyboardMapping, grab_keys, handle_key_press};
+pub use handlers::{
+    Arg, KeyAction, KeyboardMapping, grab_keys, handle_key_press, handle_key_release,
+};
 pub use keysyms::*;
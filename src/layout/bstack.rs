@@ -0,0 +1,198 @@
+use super::tiling::TilingLayout;
+use super::{GapConfig, Layout, WindowGeometry};
+use x11rb::protocol::xproto::Window;
+
+pub struct BstackLayout;
+
+impl Layout for BstackLayout {
+    fn name(&self) -> &'static str {
+        super::LayoutType::Bstack.as_str()
+    }
+
+    fn symbol(&self) -> &'static str {
+        "TTT"
+    }
+
+    fn arrange(
+        &self,
+        windows: &[Window],
+        screen_width: u32,
+        screen_height: u32,
+        gaps: &GapConfig,
+        master_factor: f32,
+        num_master: i32,
+        smartgaps_enabled: bool,
+        factors: &[f32],
+    ) -> Vec<WindowGeometry> {
+        let window_count = windows.len();
+        if window_count == 0 {
+            return Vec::new();
+        }
+
+        let gap_values = TilingLayout::getgaps(gaps, window_count, smartgaps_enabled);
+
+        let inner_gap_horizontal = gap_values.inner_horizontal;
+        let inner_gap_vertical = gap_values.inner_vertical;
+
+        let mut master_x = gap_values.outer_left as i32;
+        let master_y = gap_values.outer_top as i32;
+
+        let outer_gaps_height = (gap_values.outer_top + gap_values.outer_bottom) as i32;
+        let outer_gaps_width = (gap_values.outer_left + gap_values.outer_right) as i32;
+        let mut stack_x = master_x;
+        let mut stack_y = master_y;
+
+        let num_master_usize = num_master.max(0) as usize;
+        let master_count = window_count.min(num_master_usize);
+        let stack_count = window_count.saturating_sub(num_master_usize);
+
+        let master_width = (screen_width as i32)
+            - outer_gaps_width
+            - (inner_gap_vertical as i32 * master_count.saturating_sub(1) as i32);
+        let stack_width = (screen_width as i32)
+            - outer_gaps_width
+            - (inner_gap_vertical as i32 * stack_count.saturating_sub(1) as i32);
+        let mut master_height = (screen_height as i32) - outer_gaps_height;
+        let mut stack_height = master_height;
+
+        if num_master > 0 && window_count > num_master_usize {
+            stack_height = ((master_height as f32 - inner_gap_horizontal as f32)
+                * (1.0 - master_factor)) as i32;
+            master_height = master_height - inner_gap_horizontal as i32 - stack_height;
+            stack_y = master_y + master_height + inner_gap_horizontal as i32;
+        }
+
+        let facts =
+            TilingLayout::getfacts(window_count, num_master, master_width, stack_width, factors);
+
+        let mut geometries = Vec::new();
+
+        for (i, _window) in windows.iter().enumerate() {
+            if i < num_master_usize {
+                let window_width = (master_width as f32 / facts.master_facts) as i32
+                    + if (i as i32) < facts.master_remainder {
+                        1
+                    } else {
+                        0
+                    };
+
+                geometries.push(WindowGeometry {
+                    x_coordinate: master_x,
+                    y_coordinate: master_y,
+                    width: window_width as u32,
+                    height: master_height as u32,
+                });
+
+                master_x += window_width + inner_gap_vertical as i32;
+            } else {
+                let window_width = if facts.stack_facts > 0.0 {
+                    (stack_width as f32 * TilingLayout::factor_at(factors, i) / facts.stack_facts)
+                        as i32
+                        + if ((i - num_master_usize) as i32) < facts.stack_remainder {
+                            1
+                        } else {
+                            0
+                        }
+                } else {
+                    stack_width
+                };
+
+                geometries.push(WindowGeometry {
+                    x_coordinate: stack_x,
+                    y_coordinate: stack_y,
+                    width: window_width as u32,
+                    height: stack_height as u32,
+                });
+
+                stack_x += window_width + inner_gap_vertical as i32;
+            }
+        }
+
+        geometries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO_GAPS: GapConfig = GapConfig {
+        inner_horizontal: 0,
+        inner_vertical: 0,
+        outer_top: 0,
+        outer_bottom: 0,
+        outer_left: 0,
+        outer_right: 0,
+    };
+
+    const GAPS: GapConfig = GapConfig {
+        inner_horizontal: 10,
+        inner_vertical: 10,
+        outer_top: 10,
+        outer_bottom: 10,
+        outer_left: 10,
+        outer_right: 10,
+    };
+
+    fn arrange(
+        count: u32,
+        num_master: i32,
+        gaps: &GapConfig,
+        smartgaps: bool,
+    ) -> Vec<(i32, i32, u32, u32)> {
+        let windows: Vec<Window> = (1..=count).collect();
+        BstackLayout
+            .arrange(&windows, 1000, 800, gaps, 0.5, num_master, smartgaps, &[])
+            .iter()
+            .map(|g| (g.x_coordinate, g.y_coordinate, g.width, g.height))
+            .collect()
+    }
+
+    #[test]
+    fn no_windows_give_no_geometries() {
+        assert!(arrange(0, 1, &GAPS, false).is_empty());
+    }
+
+    #[test]
+    fn single_window_fills_the_screen() {
+        assert_eq!(arrange(1, 1, &NO_GAPS, false), [(0, 0, 1000, 800)]);
+        assert_eq!(arrange(1, 1, &GAPS, false), [(10, 10, 980, 780)]);
+    }
+
+    #[test]
+    fn stack_spreads_across_the_bottom() {
+        assert_eq!(
+            arrange(3, 1, &NO_GAPS, false),
+            [(0, 0, 1000, 400), (0, 400, 500, 400), (500, 400, 500, 400)]
+        );
+        assert_eq!(
+            arrange(3, 1, &GAPS, false),
+            [
+                (10, 10, 980, 385),
+                (10, 405, 485, 385),
+                (505, 405, 485, 385)
+            ]
+        );
+    }
+
+    #[test]
+    fn masters_without_a_stack_get_the_full_height() {
+        let expected = [(0, 0, 500, 800), (500, 0, 500, 800)];
+        assert_eq!(arrange(2, 2, &NO_GAPS, false), expected);
+        assert_eq!(arrange(2, 5, &NO_GAPS, false), expected);
+    }
+
+    #[test]
+    fn no_masters_put_every_window_in_a_full_height_stack() {
+        assert_eq!(
+            arrange(2, 0, &NO_GAPS, false),
+            [(0, 0, 500, 800), (500, 0, 500, 800)]
+        );
+    }
+
+    #[test]
+    fn smartgaps_drop_outer_gaps_for_a_single_window() {
+        assert_eq!(arrange(1, 1, &GAPS, true), [(0, 0, 1000, 800)]);
+        assert_eq!(arrange(3, 1, &GAPS, true), arrange(3, 1, &GAPS, false));
+    }
+}
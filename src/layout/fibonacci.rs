@@ -0,0 +1,258 @@
+use super::tiling::TilingLayout;
+use super::{GapConfig, Layout, WindowGeometry};
+use x11rb::protocol::xproto::Window;
+
+const MIN_SPLIT_SIZE: i32 = 32;
+
+pub struct FibonacciLayout {
+    pub dwindle: bool,
+}
+
+impl Layout for FibonacciLayout {
+    fn name(&self) -> &'static str {
+        if self.dwindle {
+            super::LayoutType::Dwindle.as_str()
+        } else {
+            super::LayoutType::Spiral.as_str()
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        if self.dwindle { "[\\]" } else { "[@]" }
+    }
+
+    fn arrange(
+        &self,
+        windows: &[Window],
+        screen_width: u32,
+        screen_height: u32,
+        gaps: &GapConfig,
+        master_factor: f32,
+        _num_master: i32,
+        smartgaps_enabled: bool,
+        _factors: &[f32],
+    ) -> Vec<WindowGeometry> {
+        let window_count = windows.len();
+        if window_count == 0 {
+            return Vec::new();
+        }
+
+        let gap_values = TilingLayout::getgaps(gaps, window_count, smartgaps_enabled);
+
+        let outer_top = gap_values.outer_top as i32;
+        let outer_left = gap_values.outer_left as i32;
+        let inner_gap_horizontal = gap_values.inner_horizontal as i32;
+        let inner_gap_vertical = gap_values.inner_vertical as i32;
+
+        let full_width =
+            screen_width as i32 - (gap_values.outer_left + gap_values.outer_right) as i32;
+
+        let mut x = outer_left;
+        let mut y = outer_top;
+        let mut width = full_width;
+        let mut height =
+            screen_height as i32 - (gap_values.outer_top + gap_values.outer_bottom) as i32;
+        let mut width_rest = 0;
+        let mut height_rest = 0;
+        let mut splitting = true;
+
+        let mut geometries = Vec::with_capacity(window_count);
+
+        for i in 0..window_count {
+            if splitting {
+                let too_small = if i % 2 == 1 {
+                    (height - inner_gap_horizontal) / 2 <= MIN_SPLIT_SIZE
+                } else {
+                    (width - inner_gap_vertical) / 2 <= MIN_SPLIT_SIZE
+                };
+                if too_small {
+                    splitting = false;
+                }
+
+                if splitting && i < window_count - 1 {
+                    if i % 2 == 1 {
+                        let half = (height - inner_gap_horizontal) / 2;
+                        height_rest = height - 2 * half - inner_gap_horizontal;
+                        height = half;
+                    } else {
+                        let half = (width - inner_gap_vertical) / 2;
+                        width_rest = width - 2 * half - inner_gap_vertical;
+                        width = half;
+                    }
+
+                    if i % 4 == 2 && !self.dwindle {
+                        x += width + inner_gap_vertical;
+                    } else if i % 4 == 3 && !self.dwindle {
+                        y += height + inner_gap_horizontal;
+                    }
+                }
+
+                match i % 4 {
+                    0 => {
+                        if self.dwindle {
+                            y += height + inner_gap_horizontal;
+                            height += height_rest;
+                        } else {
+                            height -= height_rest;
+                            y -= height + inner_gap_horizontal;
+                        }
+                    }
+                    1 => {
+                        x += width + inner_gap_vertical;
+                        width += width_rest;
+                    }
+                    2 => {
+                        y += height + inner_gap_horizontal;
+                        height += height_rest;
+                        if i < window_count - 1 {
+                            width += width_rest;
+                        }
+                    }
+                    _ => {
+                        if self.dwindle {
+                            x += width + inner_gap_vertical;
+                            width -= width_rest;
+                        } else {
+                            width -= width_rest;
+                            x -= width + inner_gap_vertical;
+                            height += height_rest;
+                        }
+                    }
+                }
+
+                if i == 0 {
+                    if window_count != 1 {
+                        let splittable = full_width - inner_gap_vertical;
+                        width = splittable - (splittable as f32 * (1.0 - master_factor)) as i32;
+                        width_rest = 0;
+                    }
+                    y = outer_top;
+                } else if i == 1 {
+                    width = full_width - width - inner_gap_vertical;
+                }
+            }
+
+            geometries.push(WindowGeometry {
+                x_coordinate: x,
+                y_coordinate: y,
+                width: width.max(1) as u32,
+                height: height.max(1) as u32,
+            });
+        }
+
+        geometries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO_GAPS: GapConfig = GapConfig {
+        inner_horizontal: 0,
+        inner_vertical: 0,
+        outer_top: 0,
+        outer_bottom: 0,
+        outer_left: 0,
+        outer_right: 0,
+    };
+
+    const GAPS: GapConfig = GapConfig {
+        inner_horizontal: 10,
+        inner_vertical: 10,
+        outer_top: 10,
+        outer_bottom: 10,
+        outer_left: 10,
+        outer_right: 10,
+    };
+
+    fn arrange(dwindle: bool, count: u32, gaps: &GapConfig) -> Vec<(i32, i32, u32, u32)> {
+        let windows: Vec<Window> = (1..=count).collect();
+        FibonacciLayout { dwindle }
+            .arrange(&windows, 1000, 800, gaps, 0.5, 1, false, &[])
+            .iter()
+            .map(|g| (g.x_coordinate, g.y_coordinate, g.width, g.height))
+            .collect()
+    }
+
+    #[test]
+    fn single_window_fills_the_screen() {
+        for dwindle in [false, true] {
+            assert_eq!(arrange(dwindle, 1, &NO_GAPS), [(0, 0, 1000, 800)]);
+            assert_eq!(arrange(dwindle, 1, &GAPS), [(10, 10, 980, 780)]);
+        }
+    }
+
+    #[test]
+    fn two_windows_split_vertically() {
+        for dwindle in [false, true] {
+            assert_eq!(
+                arrange(dwindle, 2, &NO_GAPS),
+                [(0, 0, 500, 800), (500, 0, 500, 800)]
+            );
+        }
+    }
+
+    #[test]
+    fn three_windows_split_the_second_half() {
+        let expected = [(0, 0, 500, 800), (500, 0, 500, 400), (500, 400, 500, 400)];
+        assert_eq!(arrange(false, 3, &NO_GAPS), expected);
+        assert_eq!(arrange(true, 3, &NO_GAPS), expected);
+        assert_eq!(
+            arrange(false, 3, &GAPS),
+            [
+                (10, 10, 485, 780),
+                (505, 10, 485, 385),
+                (505, 405, 485, 385)
+            ]
+        );
+    }
+
+    #[test]
+    fn five_windows_spiral_inwards() {
+        assert_eq!(
+            arrange(false, 5, &NO_GAPS),
+            [
+                (0, 0, 500, 800),
+                (500, 0, 500, 400),
+                (750, 400, 250, 400),
+                (500, 600, 250, 200),
+                (500, 400, 250, 200),
+            ]
+        );
+        assert_eq!(
+            arrange(false, 5, &GAPS),
+            [
+                (10, 10, 485, 780),
+                (505, 10, 485, 385),
+                (752, 405, 238, 385),
+                (505, 602, 237, 188),
+                (505, 405, 237, 187),
+            ]
+        );
+    }
+
+    #[test]
+    fn five_windows_dwindle_to_the_bottom_right() {
+        assert_eq!(
+            arrange(true, 5, &NO_GAPS),
+            [
+                (0, 0, 500, 800),
+                (500, 0, 500, 400),
+                (500, 400, 250, 400),
+                (750, 400, 250, 200),
+                (750, 600, 250, 200),
+            ]
+        );
+        assert_eq!(
+            arrange(true, 5, &GAPS),
+            [
+                (10, 10, 485, 780),
+                (505, 10, 485, 385),
+                (505, 405, 238, 385),
+                (753, 405, 237, 187),
+                (753, 602, 237, 188),
+            ]
+        );
+    }
+}
@@ -21,6 +21,7 @@ impl Layout for GridLayout {
         _master_factor: f32,
         _num_master: i32,
         _smartgaps_enabled: bool,
+        _factors: &[f32],
     ) -> Vec<WindowGeometry> {
         let window_count = windows.len();
         if window_count == 0 {
@@ -28,10 +29,10 @@ impl Layout for GridLayout {
         }
 
         if window_count == 1 {
-            let x = gaps.outer_horizontal as i32;
-            let y = gaps.outer_vertical as i32;
-            let width = screen_width.saturating_sub(2 * gaps.outer_horizontal);
-            let height = screen_height.saturating_sub(2 * gaps.outer_vertical);
+            let x = gaps.outer_left as i32;
+            let y = gaps.outer_top as i32;
+            let width = screen_width.saturating_sub(gaps.outer_left + gaps.outer_right);
+            let height = screen_height.saturating_sub(gaps.outer_top + gaps.outer_bottom);
 
             return vec![WindowGeometry {
                 x_coordinate: x,
@@ -47,8 +48,9 @@ impl Layout for GridLayout {
         let mut geometries = Vec::new();
 
         let total_horizontal_gaps =
-            gaps.outer_horizontal * 2 + gaps.inner_horizontal * (cols as u32 - 1);
-        let total_vertical_gaps = gaps.outer_vertical * 2 + gaps.inner_vertical * (rows as u32 - 1);
+            gaps.outer_left + gaps.outer_right + gaps.inner_horizontal * (cols as u32 - 1);
+        let total_vertical_gaps =
+            gaps.outer_top + gaps.outer_bottom + gaps.inner_vertical * (rows as u32 - 1);
 
         let cell_width = screen_width.saturating_sub(total_horizontal_gaps) / cols as u32;
         let cell_height = screen_height.saturating_sub(total_vertical_gaps) / rows as u32;
@@ -67,14 +69,14 @@ impl Layout for GridLayout {
                         gaps.inner_horizontal * (cols as u32 - windows_in_last_row as u32),
                     )) / windows_in_last_row as u32;
 
-                let x = gaps.outer_horizontal
+                let x = gaps.outer_left
                     + last_row_col as u32 * (last_row_cell_width + gaps.inner_horizontal);
-                let y = gaps.outer_vertical + row as u32 * (cell_height + gaps.inner_vertical);
+                let y = gaps.outer_top + row as u32 * (cell_height + gaps.inner_vertical);
 
                 (x as i32, y as i32, last_row_cell_width, cell_height)
             } else {
-                let x = gaps.outer_horizontal + col as u32 * (cell_width + gaps.inner_horizontal);
-                let y = gaps.outer_vertical + row as u32 * (cell_height + gaps.inner_vertical);
+                let x = gaps.outer_left + col as u32 * (cell_width + gaps.inner_horizontal);
+                let y = gaps.outer_top + row as u32 * (cell_height + gaps.inner_vertical);
 
                 (x as i32, y as i32, cell_width, cell_height)
             };
@@ -1,3 +1,5 @@
+pub mod bstack;
+pub mod fibonacci;
 pub mod grid;
 pub mod monocle;
 pub mod normie;
@@ -14,10 +16,13 @@ pub type LayoutBox = Box<dyn Layout>;
 pub struct GapConfig {
     pub inner_horizontal: u32,
     pub inner_vertical: u32,
-    pub outer_horizontal: u32,
-    pub outer_vertical: u32,
+    pub outer_top: u32,
+    pub outer_bottom: u32,
+    pub outer_left: u32,
+    pub outer_right: u32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LayoutType {
     Tiling,
     Normie,
@@ -25,9 +30,25 @@ pub enum LayoutType {
     Monocle,
     Tabbed,
     Scrolling,
+    Bstack,
+    Spiral,
+    Dwindle,
 }
 
 impl LayoutType {
+    /// Every layout, in the order `next` cycles through them.
+    pub const ALL: [Self; 9] = [
+        Self::Tiling,
+        Self::Normie,
+        Self::Grid,
+        Self::Monocle,
+        Self::Tabbed,
+        Self::Scrolling,
+        Self::Bstack,
+        Self::Spiral,
+        Self::Dwindle,
+    ];
+
     pub fn to_boxed_layout(&self) -> LayoutBox {
         match self {
             Self::Tiling => Box::new(tiling::TilingLayout),
@@ -36,6 +57,9 @@ impl LayoutType {
             Self::Monocle => Box::new(monocle::MonocleLayout),
             Self::Tabbed => Box::new(tabbed::TabbedLayout),
             Self::Scrolling => Box::new(scrolling::ScrollingLayout),
+            Self::Bstack => Box::new(bstack::BstackLayout),
+            Self::Spiral => Box::new(fibonacci::FibonacciLayout { dwindle: false }),
+            Self::Dwindle => Box::new(fibonacci::FibonacciLayout { dwindle: true }),
         }
     }
 
@@ -46,7 +70,10 @@ impl LayoutType {
             Self::Grid => Self::Monocle,
             Self::Monocle => Self::Tabbed,
             Self::Tabbed => Self::Scrolling,
-            Self::Scrolling => Self::Tiling,
+            Self::Scrolling => Self::Bstack,
+            Self::Bstack => Self::Spiral,
+            Self::Spiral => Self::Dwindle,
+            Self::Dwindle => Self::Tiling,
         }
     }
 
@@ -58,6 +85,9 @@ impl LayoutType {
             Self::Monocle => "monocle",
             Self::Tabbed => "tabbed",
             Self::Scrolling => "scrolling",
+            Self::Bstack => "bstack",
+            Self::Spiral => "spiral",
+            Self::Dwindle => "dwindle",
         }
     }
 }
@@ -73,6 +103,9 @@ impl FromStr for LayoutType {
             "monocle" => Ok(Self::Monocle),
             "tabbed" => Ok(Self::Tabbed),
             "scrolling" => Ok(Self::Scrolling),
+            "bstack" => Ok(Self::Bstack),
+            "spiral" => Ok(Self::Spiral),
+            "dwindle" => Ok(Self::Dwindle),
             _ => Err(format!("Invalid Layout Type: {}", s)),
         }
     }
@@ -92,6 +125,8 @@ pub fn next_layout(current_name: &str) -> &'static str {
 }
 
 pub trait Layout {
+    /// `factors` runs parallel to `windows`: column widths for the scrolling
+    /// layout and each client's share of the stack for tiling and bstack.
     fn arrange(
         &self,
         windows: &[Window],
@@ -101,12 +136,18 @@ pub trait Layout {
         master_factor: f32,
         num_master: i32,
         smartgaps_enabled: bool,
+        factors: &[f32],
     ) -> Vec<WindowGeometry>;
     fn name(&self) -> &'static str;
     fn symbol(&self) -> &'static str;
+
+    /// Symbol shown in the bar given the number of visible tiled clients.
+    fn symbol_for(&self, _client_count: usize) -> String {
+        self.symbol().to_string()
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct WindowGeometry {
     pub x_coordinate: i32,
     pub y_coordinate: i32,
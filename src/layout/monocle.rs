@@ -12,6 +12,14 @@ impl Layout for MonocleLayout {
         "[M]"
     }
 
+    fn symbol_for(&self, client_count: usize) -> String {
+        if client_count > 0 {
+            format!("[{}]", client_count)
+        } else {
+            self.symbol().to_string()
+        }
+    }
+
     fn arrange(
         &self,
         windows: &[Window],
@@ -21,16 +29,17 @@ impl Layout for MonocleLayout {
         _master_factor: f32,
         _num_master: i32,
         _smartgaps_enabled: bool,
+        _factors: &[f32],
     ) -> Vec<WindowGeometry> {
         let window_count = windows.len();
         if window_count == 0 {
             return Vec::new();
         }
 
-        let x = gaps.outer_horizontal as i32;
-        let y = gaps.outer_vertical as i32;
-        let width = screen_width.saturating_sub(2 * gaps.outer_horizontal);
-        let height = screen_height.saturating_sub(2 * gaps.outer_vertical);
+        let x = gaps.outer_left as i32;
+        let y = gaps.outer_top as i32;
+        let width = screen_width.saturating_sub(gaps.outer_left + gaps.outer_right);
+        let height = screen_height.saturating_sub(gaps.outer_top + gaps.outer_bottom);
 
         let geometry = WindowGeometry {
             x_coordinate: x,
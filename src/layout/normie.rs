@@ -22,6 +22,7 @@ impl Layout for NormieLayout {
         _master_factor: f32,
         _num_master: i32,
         _smartgaps_enabled: bool,
+        _factors: &[f32],
     ) -> Vec<WindowGeometry> {
         Vec::new()
     }
@@ -4,12 +4,24 @@ use x11rb::protocol::xproto::Window;
 pub struct ScrollingLayout;
 
 struct GapValues {
-    outer_horizontal: u32,
-    outer_vertical: u32,
+    outer_top: u32,
+    outer_bottom: u32,
+    outer_left: u32,
+    outer_right: u32,
     inner_vertical: u32,
 }
 
 impl ScrollingLayout {
+    /// A column with factor `f` takes that share of the width including its
+    /// trailing gap, so factors summing to 1.0 exactly fill the screen.
+    pub fn column_widths(factors: &[f32], available_width: u32, inner_gap: u32) -> Vec<u32> {
+        let span = (available_width + inner_gap) as f32;
+        factors
+            .iter()
+            .map(|factor| ((factor * span) as u32).saturating_sub(inner_gap).max(1))
+            .collect()
+    }
+
     fn getgaps(gaps: &GapConfig, window_count: usize, smartgaps_enabled: bool) -> GapValues {
         let outer_enabled = if smartgaps_enabled && window_count == 1 {
             0
@@ -18,8 +30,10 @@ impl ScrollingLayout {
         };
 
         GapValues {
-            outer_horizontal: gaps.outer_horizontal * outer_enabled,
-            outer_vertical: gaps.outer_vertical * outer_enabled,
+            outer_top: gaps.outer_top * outer_enabled,
+            outer_bottom: gaps.outer_bottom * outer_enabled,
+            outer_left: gaps.outer_left * outer_enabled,
+            outer_right: gaps.outer_right * outer_enabled,
             inner_vertical: gaps.inner_vertical,
         }
     }
@@ -41,8 +55,9 @@ impl Layout for ScrollingLayout {
         screen_height: u32,
         gaps: &GapConfig,
         _master_factor: f32,
-        num_master: i32,
+        _num_master: i32,
         smartgaps_enabled: bool,
+        factors: &[f32],
     ) -> Vec<WindowGeometry> {
         let window_count = windows.len();
         if window_count == 0 {
@@ -51,43 +66,22 @@ impl Layout for ScrollingLayout {
 
         let gap_values = Self::getgaps(gaps, window_count, smartgaps_enabled);
 
-        let outer_horizontal = gap_values.outer_horizontal;
-        let outer_vertical = gap_values.outer_vertical;
         let inner_vertical = gap_values.inner_vertical;
 
-        let visible_count = if num_master > 0 {
-            num_master as usize
-        } else {
-            2
-        };
-
-        let available_width = screen_width.saturating_sub(2 * outer_vertical);
-        let available_height = screen_height.saturating_sub(2 * outer_horizontal);
+        let available_width =
+            screen_width.saturating_sub(gap_values.outer_left + gap_values.outer_right);
+        let available_height =
+            screen_height.saturating_sub(gap_values.outer_top + gap_values.outer_bottom);
 
-        let total_inner_gaps = if visible_count > 1 {
-            inner_vertical * (visible_count.min(window_count) - 1) as u32
-        } else {
-            0
-        };
-        let window_width = if window_count <= visible_count {
-            let num_windows = window_count as u32;
-            let total_gaps = if num_windows > 1 {
-                inner_vertical * (num_windows - 1)
-            } else {
-                0
-            };
-            (available_width.saturating_sub(total_gaps)) / num_windows
-        } else {
-            (available_width.saturating_sub(total_inner_gaps)) / visible_count as u32
-        };
+        let widths = Self::column_widths(factors, available_width, inner_vertical);
 
         let mut geometries = Vec::with_capacity(window_count);
-        let mut x = outer_vertical as i32;
+        let mut x = gap_values.outer_left as i32;
 
-        for _window in windows.iter() {
+        for window_width in widths {
             geometries.push(WindowGeometry {
                 x_coordinate: x,
-                y_coordinate: outer_horizontal as i32,
+                y_coordinate: gap_values.outer_top as i32,
                 width: window_width,
                 height: available_height,
             });
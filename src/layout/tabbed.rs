@@ -23,17 +23,18 @@ impl Layout for TabbedLayout {
         _master_factor: f32,
         _num_master: i32,
         _smartgaps_enabled: bool,
+        _factors: &[f32],
     ) -> Vec<WindowGeometry> {
         let window_count = windows.len();
         if window_count == 0 {
             return Vec::new();
         }
 
-        let x = gaps.outer_horizontal as i32;
-        let y = (gaps.outer_vertical + TAB_BAR_HEIGHT) as i32;
-        let width = screen_width.saturating_sub(2 * gaps.outer_horizontal);
+        let x = gaps.outer_left as i32;
+        let y = (gaps.outer_top + TAB_BAR_HEIGHT) as i32;
+        let width = screen_width.saturating_sub(gaps.outer_left + gaps.outer_right);
         let height = screen_height
-            .saturating_sub(2 * gaps.outer_vertical)
+            .saturating_sub(gaps.outer_top + gaps.outer_bottom)
             .saturating_sub(TAB_BAR_HEIGHT);
 
         let geometry = WindowGeometry {
@@ -3,22 +3,28 @@ use x11rb::protocol::xproto::Window;
 
 pub struct TilingLayout;
 
-struct GapValues {
-    outer_horizontal: u32,
-    outer_vertical: u32,
-    inner_horizontal: u32,
-    inner_vertical: u32,
+pub(super) struct GapValues {
+    pub(super) outer_top: u32,
+    pub(super) outer_bottom: u32,
+    pub(super) outer_left: u32,
+    pub(super) outer_right: u32,
+    pub(super) inner_horizontal: u32,
+    pub(super) inner_vertical: u32,
 }
 
-struct FactValues {
-    master_facts: f32,
-    stack_facts: f32,
-    master_remainder: i32,
-    stack_remainder: i32,
+pub(super) struct FactValues {
+    pub(super) master_facts: f32,
+    pub(super) stack_facts: f32,
+    pub(super) master_remainder: i32,
+    pub(super) stack_remainder: i32,
 }
 
 impl TilingLayout {
-    fn getgaps(gaps: &GapConfig, window_count: usize, smartgaps_enabled: bool) -> GapValues {
+    pub(super) fn getgaps(
+        gaps: &GapConfig,
+        window_count: usize,
+        smartgaps_enabled: bool,
+    ) -> GapValues {
         let outer_enabled = if smartgaps_enabled && window_count == 1 {
             0
         } else {
@@ -27,26 +33,31 @@ impl TilingLayout {
         let inner_enabled = 1;
 
         GapValues {
-            outer_horizontal: gaps.outer_horizontal * outer_enabled,
-            outer_vertical: gaps.outer_vertical * outer_enabled,
+            outer_top: gaps.outer_top * outer_enabled,
+            outer_bottom: gaps.outer_bottom * outer_enabled,
+            outer_left: gaps.outer_left * outer_enabled,
+            outer_right: gaps.outer_right * outer_enabled,
             inner_horizontal: gaps.inner_horizontal * inner_enabled,
             inner_vertical: gaps.inner_vertical * inner_enabled,
         }
     }
 
-    fn getfacts(
+    pub(super) fn factor_at(factors: &[f32], index: usize) -> f32 {
+        factors.get(index).copied().unwrap_or(1.0)
+    }
+
+    pub(super) fn getfacts(
         window_count: usize,
         num_master: i32,
         master_size: i32,
         stack_size: i32,
+        factors: &[f32],
     ) -> FactValues {
         let num_master = num_master.max(0) as usize;
         let master_facts = window_count.min(num_master) as f32;
-        let stack_facts = if window_count > num_master {
-            (window_count - num_master) as f32
-        } else {
-            0.0
-        };
+        let stack_facts: f32 = (num_master..window_count)
+            .map(|i| Self::factor_at(factors, i))
+            .sum();
 
         let mut master_total = 0;
         let mut stack_total = 0;
@@ -55,7 +66,8 @@ impl TilingLayout {
             if i < num_master {
                 master_total += (master_size as f32 / master_facts) as i32;
             } else if stack_facts > 0.0 {
-                stack_total += (stack_size as f32 / stack_facts) as i32;
+                stack_total +=
+                    (stack_size as f32 * Self::factor_at(factors, i) / stack_facts) as i32;
             }
         }
 
@@ -86,6 +98,7 @@ impl Layout for TilingLayout {
         master_factor: f32,
         num_master: i32,
         smartgaps_enabled: bool,
+        factors: &[f32],
     ) -> Vec<WindowGeometry> {
         let window_count = windows.len();
         if window_count == 0 {
@@ -94,27 +107,28 @@ impl Layout for TilingLayout {
 
         let gap_values = Self::getgaps(gaps, window_count, smartgaps_enabled);
 
-        let outer_gap_horizontal = gap_values.outer_horizontal;
-        let outer_gap_vertical = gap_values.outer_vertical;
         let inner_gap_horizontal = gap_values.inner_horizontal;
         let inner_gap_vertical = gap_values.inner_vertical;
 
-        let mut stack_x = outer_gap_vertical as i32;
-        let mut stack_y = outer_gap_horizontal as i32;
-        let master_x = outer_gap_vertical as i32;
-        let mut master_y = outer_gap_horizontal as i32;
+        let mut stack_x = gap_values.outer_left as i32;
+        let mut stack_y = gap_values.outer_top as i32;
+        let master_x = gap_values.outer_left as i32;
+        let mut master_y = gap_values.outer_top as i32;
+
+        let outer_gaps_height = (gap_values.outer_top + gap_values.outer_bottom) as i32;
+        let outer_gaps_width = (gap_values.outer_left + gap_values.outer_right) as i32;
 
         let num_master_usize = num_master.max(0) as usize;
         let master_count = window_count.min(num_master_usize);
         let stack_count = window_count.saturating_sub(num_master_usize);
 
         let master_height = (screen_height as i32)
-            - (2 * outer_gap_horizontal) as i32
+            - outer_gaps_height
             - (inner_gap_horizontal as i32 * (master_count.saturating_sub(1)) as i32);
         let stack_height = (screen_height as i32)
-            - (2 * outer_gap_horizontal) as i32
+            - outer_gaps_height
             - (inner_gap_horizontal as i32 * stack_count.saturating_sub(1) as i32);
-        let mut stack_width = (screen_width as i32) - (2 * outer_gap_vertical) as i32;
+        let mut stack_width = (screen_width as i32) - outer_gaps_width;
         let mut master_width = stack_width;
 
         if num_master > 0 && window_count > num_master_usize {
@@ -124,7 +138,13 @@ impl Layout for TilingLayout {
             stack_x = master_x + master_width + inner_gap_vertical as i32;
         }
 
-        let facts = Self::getfacts(window_count, num_master, master_height, stack_height);
+        let facts = Self::getfacts(
+            window_count,
+            num_master,
+            master_height,
+            stack_height,
+            factors,
+        );
 
         let mut geometries = Vec::new();
 
@@ -147,7 +167,7 @@ impl Layout for TilingLayout {
                 master_y += window_height + inner_gap_horizontal as i32;
             } else {
                 let window_height = if facts.stack_facts > 0.0 {
-                    (stack_height as f32 / facts.stack_facts) as i32
+                    (stack_height as f32 * Self::factor_at(factors, i) / facts.stack_facts) as i32
                         + if ((i - num_master_usize) as i32) < facts.stack_remainder {
                             1
                         } else {
@@ -171,3 +191,30 @@ impl Layout for TilingLayout {
         geometries
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uneven_outer_gaps_offset_each_edge() {
+        let gaps = GapConfig {
+            inner_horizontal: 0,
+            inner_vertical: 0,
+            outer_top: 30,
+            outer_bottom: 10,
+            outer_left: 5,
+            outer_right: 15,
+        };
+        let geometries: Vec<(i32, i32, u32, u32)> = TilingLayout
+            .arrange(&[1, 2, 3], 1000, 800, &gaps, 0.5, 1, false, &[])
+            .iter()
+            .map(|g| (g.x_coordinate, g.y_coordinate, g.width, g.height))
+            .collect();
+
+        assert_eq!(
+            geometries,
+            [(5, 30, 490, 760), (495, 30, 490, 380), (495, 410, 490, 380)]
+        );
+    }
+}
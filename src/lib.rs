@@ -3,15 +3,23 @@ use std::path::PathBuf;
 pub mod animations;
 pub mod bar;
 pub mod client;
+pub mod client_state;
 pub mod config;
 pub mod errors;
+pub mod gamma;
+pub mod history;
+pub mod ipc;
 pub mod keyboard;
 pub mod layout;
 pub mod monitor;
 pub mod overlay;
+pub mod overrides;
 pub mod signal;
 pub mod size_hints;
+pub mod snap;
+pub mod state;
 pub mod tab_bar;
+pub mod tag_state;
 pub mod window_manager;
 
 pub mod prelude {
@@ -23,6 +31,87 @@ pub mod prelude {
     pub use x11rb::protocol::xproto::KeyButMask;
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct MonitorConfig {
+    pub tags: Option<Vec<String>>,
+    pub default_layout: Option<String>,
+    pub start_tag: Option<usize>,
+}
+
+/// Named bundle of colors; unset fields fall back to the base config colors.
+#[derive(Debug, Clone, Default)]
+pub struct Theme {
+    pub name: String,
+    pub border_focused: Option<u32>,
+    pub border_unfocused: Option<u32>,
+    pub border_urgent: Option<u32>,
+    pub scheme_normal: Option<ColorScheme>,
+    pub scheme_occupied: Option<ColorScheme>,
+    pub scheme_selected: Option<ColorScheme>,
+    pub scheme_urgent: Option<ColorScheme>,
+}
+
+/// Switches between two themes at fixed times of day, in minutes since midnight.
+#[derive(Debug, Clone)]
+pub struct ThemeSchedule {
+    pub first: String,
+    pub first_start: u32,
+    pub second: String,
+    pub second_start: u32,
+}
+
+impl ThemeSchedule {
+    pub fn theme_at(&self, minute_of_day: u32) -> &str {
+        let in_first = if self.first_start <= self.second_start {
+            minute_of_day >= self.first_start && minute_of_day < self.second_start
+        } else {
+            minute_of_day >= self.first_start || minute_of_day < self.second_start
+        };
+
+        if in_first { &self.first } else { &self.second }
+    }
+}
+
+/// Scratchpad defined up front; sizes and offsets are fractions of the monitor's
+/// window area, and a missing size or offset keeps the window's own size or centers it.
+#[derive(Debug, Clone, Default)]
+pub struct Scratchpad {
+    pub name: String,
+    pub command: Option<String>,
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub x: Option<f32>,
+    pub y: Option<f32>,
+    pub exclusive: bool,
+}
+
+/// Command run at startup. `once` skips it while a process with the same
+/// program name is running, e.g. after restarting the window manager.
+#[derive(Debug, Clone)]
+pub struct AutostartCommand {
+    pub command: String,
+    pub once: bool,
+    pub delay_ms: u64,
+}
+
+impl AutostartCommand {
+    pub fn new(command: String) -> Self {
+        Self {
+            command,
+            once: false,
+            delay_ms: 0,
+        }
+    }
+
+    /// Program name as `pgrep` sees it: the basename of the first word,
+    /// cut to the 15 characters the kernel keeps.
+    pub fn program_name(&self) -> Option<String> {
+        let program = self.command.split_whitespace().next()?;
+        let name = program.rsplit('/').next().unwrap_or(program);
+        Some(name.chars().take(15).collect())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LayoutSymbolOverride {
     pub name: String,
@@ -34,14 +123,25 @@ pub struct WindowRule {
     pub class: Option<String>,
     pub instance: Option<String>,
     pub title: Option<String>,
+    pub role: Option<String>,
     pub tags: Option<u32>,
     pub focus: Option<bool>,
     pub is_floating: Option<bool>,
     pub monitor: Option<usize>,
+    pub scratchpad: Option<String>,
+    pub click_through: Option<bool>,
+    pub swallow: Option<bool>,
+    pub center: Option<bool>,
+    pub fake_fullscreen: Option<bool>,
+    pub border: Option<bool>,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
 }
 
 impl WindowRule {
-    pub fn matches(&self, class: &str, instance: &str, title: &str) -> bool {
+    pub fn matches(&self, class: &str, instance: &str, title: &str, role: &str) -> bool {
         let class_matches = self
             .class
             .as_ref()
@@ -54,35 +154,44 @@ impl WindowRule {
             .title
             .as_ref()
             .is_none_or(|t| title.contains(t.as_str()));
-        class_matches && instance_matches && title_matches
+        let role_matches = self.role.as_ref().is_none_or(|r| role == r);
+        class_matches && instance_matches && title_matches && role_matches
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Config {
     // Meta
+    pub warnings: Vec<String>,
     pub path: Option<PathBuf>,
 
     // Appearance
     pub border_width: u32,
     pub border_focused: u32,
     pub border_unfocused: u32,
-    pub font: String,
+    pub border_urgent: u32,
+    pub smartborders_enabled: bool,
+    pub fonts: Vec<String>,
 
     // Gaps
     pub gaps_enabled: bool,
     pub smartgaps_enabled: bool,
     pub gap_inner_horizontal: u32,
     pub gap_inner_vertical: u32,
-    pub gap_outer_horizontal: u32,
-    pub gap_outer_vertical: u32,
+    pub gap_outer_top: u32,
+    pub gap_outer_bottom: u32,
+    pub gap_outer_left: u32,
+    pub gap_outer_right: u32,
 
     // Basics
     pub terminal: String,
+    pub terminal_follows_cwd: bool,
     pub modkey: x11rb::protocol::xproto::KeyButMask,
 
     // Tags
     pub tags: Vec<String>,
+    pub tag_labels_occupied: Vec<String>,
+    pub tag_labels_empty: Vec<String>,
 
     // Layout symbol overrides
     pub layout_symbols: Vec<LayoutSymbolOverride>,
@@ -90,9 +199,12 @@ pub struct Config {
     // Keybindings
     pub keybindings: Vec<crate::keyboard::handlers::Key>,
     pub tag_back_and_forth: bool,
+    pub tag_layouts: Vec<Option<String>>,
+    pub monitor_configs: Vec<MonitorConfig>,
 
     // Window rules
     pub window_rules: Vec<WindowRule>,
+    pub scratchpads: Vec<Scratchpad>,
 
     // Status bar
     pub status_blocks: Vec<crate::bar::BlockConfig>,
@@ -103,9 +215,31 @@ pub struct Config {
     pub scheme_selected: ColorScheme,
     pub scheme_urgent: ColorScheme,
 
-    pub autostart: Vec<String>,
+    pub autostart: Vec<AutostartCommand>,
+    pub tag_change_hook: Option<String>,
+    pub layout_change_hook: Option<String>,
     pub auto_tile: bool,
+    pub focus_new_windows: bool,
+    pub steal_monitor_focus: bool,
+    pub focus_on_activate: bool,
+    pub focus_follows_mouse: bool,
+    pub warp_cursor: bool,
+    pub snap_distance: u32,
+    pub keychord_timeout_ms: u64,
+    pub animations_enabled: bool,
+    pub tag_switch_animation: Option<crate::animations::AnimationConfig>,
+    pub layout_animation: Option<crate::animations::AnimationConfig>,
+    pub presentation_blank_titles: bool,
+    pub presentation_indicator: String,
+    pub auto_reload: bool,
+    pub scroll_column_factor: Option<f32>,
     pub hide_vacant_tags: bool,
+    pub show_bar: bool,
+    pub themes: Vec<Theme>,
+    pub theme_schedule: Option<ThemeSchedule>,
+
+    // Night light
+    pub night_light: Option<crate::gamma::NightLight>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -115,6 +249,18 @@ pub struct ColorScheme {
     pub underline: u32,
 }
 
+impl Config {
+    pub fn monitor_config(&self, monitor_index: usize) -> Option<&MonitorConfig> {
+        self.monitor_configs.get(monitor_index)
+    }
+
+    pub fn monitor_tags(&self, monitor_index: usize) -> &[String] {
+        self.monitor_config(monitor_index)
+            .and_then(|monitor| monitor.tags.as_deref())
+            .unwrap_or(&self.tags)
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         use crate::keyboard::handlers::KeyBinding;
@@ -127,23 +273,31 @@ impl Default for Config {
         const TERMINAL: &str = "st";
 
         Self {
+            warnings: Vec::new(),
             path: None,
             border_width: 2,
             border_focused: 0x6dade3,
             border_unfocused: 0xbbbbbb,
-            font: "monospace:size=10".to_string(),
+            border_urgent: 0xff5555,
+            smartborders_enabled: false,
+            fonts: vec!["monospace:size=10".to_string()],
             gaps_enabled: false,
             smartgaps_enabled: true,
             gap_inner_horizontal: 0,
             gap_inner_vertical: 0,
-            gap_outer_horizontal: 0,
-            gap_outer_vertical: 0,
+            gap_outer_top: 0,
+            gap_outer_bottom: 0,
+            gap_outer_left: 0,
+            gap_outer_right: 0,
             terminal: TERMINAL.to_string(),
+            terminal_follows_cwd: true,
             modkey: MODKEY,
             tags: vec!["1", "2", "3", "4", "5", "6", "7", "8", "9"]
                 .into_iter()
                 .map(String::from)
                 .collect(),
+            tag_labels_occupied: vec![],
+            tag_labels_empty: vec![],
             layout_symbols: vec![],
             keybindings: vec![
                 KeyBinding::single_key(
@@ -326,13 +480,18 @@ impl Default for Config {
                 ),
             ],
             tag_back_and_forth: false,
+            tag_layouts: Vec::new(),
+            monitor_configs: Vec::new(),
             window_rules: vec![],
+            scratchpads: vec![],
             status_blocks: vec![crate::bar::BlockConfig {
                 format: "{}".to_string(),
                 command: crate::bar::BlockCommand::DateTime("%a, %b %d - %-I:%M %P".to_string()),
                 interval_secs: 1,
                 color: 0x0db9d7,
                 underline: true,
+                min_width: None,
+                align: crate::bar::BlockAlign::Right,
             }],
             scheme_normal: ColorScheme {
                 foreground: 0xbbbbbb,
@@ -355,8 +514,28 @@ impl Default for Config {
                 underline: 0xff5555,
             },
             autostart: vec![],
+            tag_change_hook: None,
+            layout_change_hook: None,
             auto_tile: false,
+            focus_new_windows: true,
+            steal_monitor_focus: true,
+            focus_on_activate: false,
+            focus_follows_mouse: true,
+            warp_cursor: false,
+            snap_distance: 32,
+            keychord_timeout_ms: 1500,
+            animations_enabled: true,
+            tag_switch_animation: None,
+            layout_animation: None,
+            presentation_blank_titles: true,
+            presentation_indicator: "[presenting]".to_string(),
+            auto_reload: false,
+            scroll_column_factor: None,
             hide_vacant_tags: false,
+            show_bar: true,
+            themes: Vec::new(),
+            theme_schedule: None,
+            night_light: None,
         }
     }
 }
@@ -1,11 +1,77 @@
 use crate::client::TagMask;
+use crate::config::limits;
 use crate::errors::WmError;
+use crate::tag_state::MonitorTagState;
+use crate::window_manager::all_tags_mask;
 use x11rb::protocol::xinerama::ConnectionExt as _;
 use x11rb::protocol::xproto::{Screen, Window};
 use x11rb::rust_connection::RustConnection;
 
 type WmResult<T> = Result<T, WmError>;
 
+/// `(x, y, width, height)`
+pub type Rect = (i32, i32, i32, i32);
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Strut {
+    pub left: i32,
+    pub right: i32,
+    pub top: i32,
+    pub bottom: i32,
+    pub left_start_y: i32,
+    pub left_end_y: i32,
+    pub right_start_y: i32,
+    pub right_end_y: i32,
+    pub top_start_x: i32,
+    pub top_end_x: i32,
+    pub bottom_start_x: i32,
+    pub bottom_end_x: i32,
+}
+
+impl Strut {
+    /// Builds a strut from the cardinals of `_NET_WM_STRUT_PARTIAL` (12 values)
+    /// or `_NET_WM_STRUT` (4 values, spanning the whole root edge).
+    pub fn from_cardinals(values: &[u32], root_width: i32, root_height: i32) -> Option<Self> {
+        let v = |i: usize| values[i] as i32;
+        let strut = match values.len() {
+            12.. => Self {
+                left: v(0),
+                right: v(1),
+                top: v(2),
+                bottom: v(3),
+                left_start_y: v(4),
+                left_end_y: v(5),
+                right_start_y: v(6),
+                right_end_y: v(7),
+                top_start_x: v(8),
+                top_end_x: v(9),
+                bottom_start_x: v(10),
+                bottom_end_x: v(11),
+            },
+            4..12 => Self {
+                left: v(0),
+                right: v(1),
+                top: v(2),
+                bottom: v(3),
+                left_start_y: 0,
+                left_end_y: root_height - 1,
+                right_start_y: 0,
+                right_end_y: root_height - 1,
+                top_start_x: 0,
+                top_end_x: root_width - 1,
+                bottom_start_x: 0,
+                bottom_end_x: root_width - 1,
+            },
+            _ => return None,
+        };
+
+        if strut.left == 0 && strut.right == 0 && strut.top == 0 && strut.bottom == 0 {
+            return None;
+        }
+        Some(strut)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Pertag {
     pub current_tag: usize,
@@ -17,6 +83,22 @@ pub struct Pertag {
 }
 
 impl Pertag {
+    /// Entry 0 holds the "all tags" view, so tag `n` lives at index `n + 1`.
+    pub fn swap_tags(&mut self, first: usize, second: usize) {
+        let (first, second) = (first + 1, second + 1);
+        self.num_masters.swap(first, second);
+        self.master_factors.swap(first, second);
+        self.layouts.swap(first, second);
+        self.show_bars.swap(first, second);
+        for tag in [&mut self.current_tag, &mut self.previous_tag] {
+            if *tag == first {
+                *tag = second;
+            } else if *tag == second {
+                *tag = first;
+            }
+        }
+    }
+
     pub fn new(
         num_tags: usize,
         default_num_master: i32,
@@ -66,6 +148,7 @@ pub struct Monitor {
     pub bar_window: Option<Window>,
     pub layout_indices: [usize; 2],
     pub scroll_offset: i32,
+    pub mirrored: bool,
     pub pertag: Option<Pertag>,
 }
 
@@ -100,18 +183,78 @@ impl Monitor {
             bar_window: None,
             layout_indices: [0, 1],
             scroll_offset: 0,
+            mirrored: false,
             pertag: None,
         }
     }
 
-    pub fn init_pertag(&mut self, num_tags: usize, default_layout: &str) {
-        self.pertag = Some(Pertag::new(
+    pub fn init_pertag(
+        &mut self,
+        num_tags: usize,
+        default_layout: &str,
+        tag_layouts: &[Option<String>],
+    ) {
+        let mut pertag = Pertag::new(
             num_tags,
             self.num_master,
             self.master_factor,
             self.show_bar,
             default_layout,
-        ));
+        );
+
+        for (tag_index, layout) in tag_layouts.iter().enumerate().take(num_tags) {
+            if let Some(layout) = layout {
+                pertag.layouts[tag_index + 1] = layout.clone();
+            }
+        }
+
+        self.pertag = Some(pertag);
+    }
+
+    pub fn tag_state(&self) -> Option<MonitorTagState> {
+        self.pertag.as_ref().map(|pertag| MonitorTagState {
+            tagset: self.tagset,
+            selected_tags_index: self.selected_tags_index,
+            current_tag: pertag.current_tag,
+            previous_tag: pertag.previous_tag,
+            num_masters: pertag.num_masters.clone(),
+            master_factors: pertag.master_factors.clone(),
+            layouts: pertag.layouts.clone(),
+        })
+    }
+
+    /// Restores saved tag settings, ignoring state saved for a different tag count.
+    /// Returns the layout of the restored current tag.
+    pub fn restore_tag_state(&mut self, state: &MonitorTagState) -> Option<String> {
+        let pertag = self.pertag.as_mut()?;
+        let slots = pertag.num_masters.len();
+        let all_tags = all_tags_mask(slots - 1);
+        let valid_tags = |tags: u32| tags != 0 && tags & !all_tags == 0;
+        if state.num_masters.len() != slots
+            || state.master_factors.len() != slots
+            || state.layouts.len() != slots
+            || state.current_tag >= slots
+            || state.previous_tag >= slots
+            || state.selected_tags_index > 1
+            || !state.tagset.iter().all(|&tags| valid_tags(tags))
+        {
+            return None;
+        }
+
+        pertag.num_masters = state.num_masters.clone();
+        pertag.master_factors = state
+            .master_factors
+            .iter()
+            .map(|&factor| limits::MASTER_FACTOR.clamp(factor))
+            .collect();
+        pertag.layouts = state.layouts.clone();
+        pertag.current_tag = state.current_tag;
+        pertag.previous_tag = state.previous_tag;
+        self.tagset = state.tagset;
+        self.selected_tags_index = state.selected_tags_index;
+        self.num_master = pertag.num_masters[pertag.current_tag];
+        self.master_factor = pertag.master_factors[pertag.current_tag];
+        Some(pertag.layouts[pertag.current_tag].clone())
     }
 
     pub fn contains_point(&self, x: i32, y: i32) -> bool {
@@ -121,11 +264,111 @@ impl Monitor {
             && y < self.screen_y + self.screen_height
     }
 
+    pub fn reserve_struts<'a>(
+        &mut self,
+        struts: impl IntoIterator<Item = &'a Strut>,
+        root_width: i32,
+        root_height: i32,
+    ) {
+        let (x, y) = (self.screen_x, self.screen_y);
+        let (width, height) = (self.screen_width, self.screen_height);
+        let overlaps =
+            |start: i32, end: i32, from: i32, len: i32| start < from + len && end >= from;
+
+        let (mut left, mut right, mut top, mut bottom) = (0, 0, 0, 0);
+        for strut in struts {
+            if strut.left > 0 && overlaps(strut.left_start_y, strut.left_end_y, y, height) {
+                left = left.max((strut.left - x).clamp(0, width));
+            }
+            if strut.right > 0 && overlaps(strut.right_start_y, strut.right_end_y, y, height) {
+                right = right.max((x + width - (root_width - strut.right)).clamp(0, width));
+            }
+            if strut.top > 0 && overlaps(strut.top_start_x, strut.top_end_x, x, width) {
+                top = top.max((strut.top - y).clamp(0, height));
+            }
+            if strut.bottom > 0 && overlaps(strut.bottom_start_x, strut.bottom_end_x, x, width) {
+                bottom = bottom.max((y + height - (root_height - strut.bottom)).clamp(0, height));
+            }
+        }
+
+        self.window_area_x = x + left;
+        self.window_area_y = y + top;
+        self.window_area_width = (width - left - right).max(1);
+        self.window_area_height = (height - top - bottom).max(1);
+    }
+
+    pub fn screen_rect(&self) -> Rect {
+        (
+            self.screen_x,
+            self.screen_y,
+            self.screen_width,
+            self.screen_height,
+        )
+    }
+
+    pub fn window_area_rect(&self) -> Rect {
+        (
+            self.window_area_x,
+            self.window_area_y,
+            self.window_area_width,
+            self.window_area_height,
+        )
+    }
+
+    pub fn set_geometry(&mut self, (x, y, width, height): Rect) {
+        self.screen_x = x;
+        self.screen_y = y;
+        self.screen_width = width;
+        self.screen_height = height;
+        self.window_area_x = x;
+        self.window_area_y = y;
+        self.window_area_width = width;
+        self.window_area_height = height;
+    }
+
     pub fn get_selected_tag(&self) -> TagMask {
         self.tagset[self.selected_tags_index]
     }
 }
 
+/// Copies the geometry of freshly detected monitors onto the existing ones, pairing them
+/// by index. Returns the indices of the monitors that changed along with their old window area.
+/// Monitors that appeared or disappeared are left alone.
+pub fn apply_detected_geometry(
+    monitors: &mut [Monitor],
+    detected: &[Monitor],
+) -> Vec<(usize, Rect)> {
+    let mut changed = Vec::new();
+
+    for (index, (monitor, detected)) in monitors.iter_mut().zip(detected).enumerate() {
+        if monitor.screen_rect() != detected.screen_rect() {
+            changed.push((index, monitor.window_area_rect()));
+            monitor.set_geometry(detected.screen_rect());
+        }
+    }
+
+    changed
+}
+
+/// Moves a rectangle from one area to another, keeping its offset proportional to the
+/// area size and clamping it so it stays fully inside the new area.
+pub fn rescale_rect((x, y, width, height): Rect, from: Rect, to: Rect) -> Rect {
+    let scale = |offset: i32, old_len: i32, new_len: i32| {
+        if old_len <= 0 {
+            0
+        } else {
+            (offset as i64 * new_len as i64 / old_len as i64) as i32
+        }
+    };
+
+    let width = width.min(to.2).max(1);
+    let height = height.min(to.3).max(1);
+    let x = (to.0 + scale(x - from.0, from.2, to.2)).clamp(to.0, (to.0 + to.2 - width).max(to.0));
+    let y = (to.1 + scale(y - from.1, from.3, to.3)).clamp(to.1, (to.1 + to.3 - height).max(to.1));
+
+    (x, y, width, height)
+}
+
 pub fn detect_monitors(
     connection: &RustConnection,
     screen: &Screen,
@@ -196,3 +439,113 @@ pub fn detect_monitors(
 
     Ok(monitors)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitors(rects: &[Rect]) -> Vec<Monitor> {
+        rects
+            .iter()
+            .map(|&(x, y, width, height)| Monitor::new(x, y, width as u32, height as u32))
+            .collect()
+    }
+
+    #[test]
+    fn unchanged_monitors_are_not_reported() {
+        let mut current = monitors(&[(0, 0, 1920, 1080), (1920, 0, 1280, 1024)]);
+        let detected = monitors(&[(0, 0, 1920, 1080), (1920, 0, 1280, 1024)]);
+
+        assert!(apply_detected_geometry(&mut current, &detected).is_empty());
+    }
+
+    #[test]
+    fn resolution_change_reports_the_old_window_area() {
+        let mut current = monitors(&[(0, 0, 1920, 1080), (1920, 0, 1280, 1024)]);
+        current[0].window_area_y = 20;
+        current[0].window_area_height = 1060;
+        let detected = monitors(&[(0, 0, 1280, 720), (1920, 0, 1280, 1024)]);
+
+        let changed = apply_detected_geometry(&mut current, &detected);
+
+        assert_eq!(changed, vec![(0, (0, 20, 1920, 1060))]);
+        assert_eq!(current[0].screen_rect(), (0, 0, 1280, 720));
+        assert_eq!(current[0].window_area_rect(), (0, 0, 1280, 720));
+        assert_eq!(current[1].screen_rect(), (1920, 0, 1280, 1024));
+    }
+
+    #[test]
+    fn added_and_removed_monitors_are_left_alone() {
+        let mut current = monitors(&[(0, 0, 1920, 1080)]);
+        let detected = monitors(&[(0, 0, 2560, 1440), (2560, 0, 1920, 1080)]);
+
+        let changed = apply_detected_geometry(&mut current, &detected);
+        assert_eq!(changed, vec![(0, (0, 0, 1920, 1080))]);
+        assert_eq!(current.len(), 1);
+
+        let mut current = monitors(&[(0, 0, 1920, 1080), (1920, 0, 1920, 1080)]);
+        let detected = monitors(&[(0, 0, 1920, 1080)]);
+        assert!(apply_detected_geometry(&mut current, &detected).is_empty());
+        assert_eq!(current[1].screen_rect(), (1920, 0, 1920, 1080));
+    }
+
+    #[test]
+    fn rescale_keeps_the_relative_position() {
+        let from = (0, 0, 1920, 1080);
+        let to = (0, 0, 960, 540);
+
+        assert_eq!(
+            rescale_rect((960, 540, 200, 100), from, to),
+            (480, 270, 200, 100)
+        );
+    }
+
+    #[test]
+    fn rescale_follows_a_moved_monitor() {
+        let from = (1920, 0, 1920, 1080);
+        let to = (2560, 0, 1920, 1080);
+
+        assert_eq!(
+            rescale_rect((2020, 100, 300, 200), from, to),
+            (2660, 100, 300, 200)
+        );
+    }
+
+    #[test]
+    fn rescale_clamps_windows_into_the_new_area() {
+        let from = (0, 0, 1920, 1080);
+        let to = (0, 0, 1280, 720);
+
+        assert_eq!(
+            rescale_rect((1800, 1000, 400, 300), from, to),
+            (880, 420, 400, 300)
+        );
+        assert_eq!(
+            rescale_rect((-50, -50, 400, 300), from, to),
+            (0, 0, 400, 300)
+        );
+    }
+
+    #[test]
+    fn rescale_shrinks_windows_larger_than_the_new_area() {
+        let from = (0, 0, 3840, 2160);
+        let to = (0, 0, 1280, 720);
+
+        assert_eq!(
+            rescale_rect((100, 100, 3000, 2000), from, to),
+            (0, 0, 1280, 720)
+        );
+    }
+
+    #[test]
+    fn rescale_handles_empty_areas() {
+        assert_eq!(
+            rescale_rect((100, 100, 200, 200), (0, 0, 0, 0), (0, 0, 800, 600)),
+            (0, 0, 200, 200)
+        );
+        assert_eq!(
+            rescale_rect((100, 100, 200, 200), (0, 0, 800, 600), (10, 10, 0, 0)),
+            (10, 10, 1, 1)
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use super::{
+    BACKGROUND_COLOR, BORDER_COLOR, BORDER_WIDTH, FOREGROUND_COLOR, HIGHLIGHT_COLOR,
+    KeybindOverlay, MonitorRect, Overlay, OverlayBase, PADDING,
+};
+use crate::bar::font::Font;
+use crate::errors::X11Error;
+use crate::keyboard::handlers::KeyBinding;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::*;
+use x11rb::rust_connection::RustConnection;
+
+const KEY_ACTION_SPACING: i16 = 20;
+const LINE_SPACING: i16 = 6;
+const TITLE_BOTTOM_MARGIN: i16 = 12;
+
+/// Lists the keys that can follow a half-entered keychord, like emacs which-key.
+pub struct ChordOverlay {
+    base: OverlayBase,
+    prefix: String,
+    continuations: Vec<(String, String)>,
+    modkey: KeyButMask,
+    max_key_width: u16,
+}
+
+impl ChordOverlay {
+    pub fn new(
+        connection: &RustConnection,
+        screen: &Screen,
+        screen_num: usize,
+        display: *mut x11::xlib::Display,
+        modkey: KeyButMask,
+    ) -> Result<Self, X11Error> {
+        let base = OverlayBase::new(
+            connection,
+            screen,
+            screen_num,
+            display,
+            400,
+            100,
+            BORDER_WIDTH,
+            BORDER_COLOR,
+            BACKGROUND_COLOR,
+            FOREGROUND_COLOR,
+        )?;
+
+        Ok(ChordOverlay {
+            base,
+            prefix: String::new(),
+            continuations: Vec::new(),
+            modkey,
+            max_key_width: 0,
+        })
+    }
+
+    /// Shows the next key of every candidate binding, centered at the bottom of the monitor.
+    pub fn show(
+        &mut self,
+        connection: &RustConnection,
+        font: &Font,
+        keybindings: &[KeyBinding],
+        candidates: &[usize],
+        keys_pressed: usize,
+        monitor: MonitorRect,
+    ) -> Result<(), X11Error> {
+        self.prefix = candidates
+            .first()
+            .and_then(|&index| keybindings.get(index))
+            .map(|binding| {
+                binding
+                    .keys
+                    .iter()
+                    .take(keys_pressed)
+                    .map(|key| KeybindOverlay::format_key_combo(key, self.modkey))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+
+        self.continuations.clear();
+        for binding in candidates
+            .iter()
+            .filter_map(|&index| keybindings.get(index))
+        {
+            let Some(next) = binding.keys.get(keys_pressed) else {
+                continue;
+            };
+            let key = KeybindOverlay::format_key_combo(next, self.modkey);
+            let action = if binding.keys.len() > keys_pressed + 1 {
+                "+ more".to_string()
+            } else {
+                KeybindOverlay::action_description(binding)
+            };
+            let entry = (key, action);
+            if !self.continuations.contains(&entry) {
+                self.continuations.push(entry);
+            }
+        }
+
+        if self.continuations.is_empty() {
+            return self.hide(connection);
+        }
+
+        self.max_key_width = self
+            .continuations
+            .iter()
+            .map(|(key, _)| font.text_width(key))
+            .max()
+            .unwrap_or(0);
+        let max_action_width = self
+            .continuations
+            .iter()
+            .map(|(_, action)| font.text_width(action))
+            .max()
+            .unwrap_or(0);
+
+        let content_width = self.max_key_width + KEY_ACTION_SPACING as u16 + max_action_width;
+        let width = font.text_width(&self.prefix).max(content_width) + (PADDING as u16 * 2);
+        let line_height = font.height() + LINE_SPACING as u16;
+        let title_height = font.height() + TITLE_BOTTOM_MARGIN as u16;
+        let height =
+            title_height + (self.continuations.len() as u16 * line_height) + (PADDING as u16 * 2);
+
+        let (x, y) = monitor.bottom(width, height, PADDING as u16 * 2);
+
+        self.base.configure(connection, x, y, width, height)?;
+        self.base.is_visible = true;
+        self.draw(connection, font)?;
+        self.base.show(connection)?;
+        Ok(())
+    }
+}
+
+impl Overlay for ChordOverlay {
+    fn window(&self) -> Window {
+        self.base.window
+    }
+
+    fn is_visible(&self) -> bool {
+        self.base.is_visible
+    }
+
+    fn hide(&mut self, connection: &RustConnection) -> Result<(), X11Error> {
+        self.base.hide(connection)?;
+        self.prefix.clear();
+        self.continuations.clear();
+        Ok(())
+    }
+
+    fn draw(&self, connection: &RustConnection, font: &Font) -> Result<(), X11Error> {
+        if !self.base.is_visible {
+            return Ok(());
+        }
+
+        self.base.draw_background(connection)?;
+
+        let title_y = PADDING + font.ascent();
+        self.base.font_draw.draw_text(
+            font,
+            self.base.foreground_color,
+            PADDING,
+            title_y,
+            &self.prefix,
+        );
+
+        let line_height = font.height() + LINE_SPACING as u16;
+        let mut y = PADDING + font.height() as i16 + TITLE_BOTTOM_MARGIN + font.ascent();
+
+        for (key, action) in &self.continuations {
+            let key_width = font.text_width(key);
+
+            connection.change_gc(
+                self.base.graphics_context,
+                &ChangeGCAux::new().foreground(HIGHLIGHT_COLOR),
+            )?;
+            connection.poly_fill_rectangle(
+                self.base.window,
+                self.base.graphics_context,
+                &[Rectangle {
+                    x: PADDING - 4,
+                    y: y - font.ascent() - 2,
+                    width: key_width + 8,
+                    height: font.height() + 4,
+                }],
+            )?;
+
+            self.base
+                .font_draw
+                .draw_text(font, self.base.foreground_color, PADDING, y, key);
+
+            let action_x = PADDING + self.max_key_width as i16 + KEY_ACTION_SPACING;
+            self.base
+                .font_draw
+                .draw_text(font, self.base.foreground_color, action_x, y, action);
+
+            y += line_height as i16;
+        }
+
+        connection.flush()?;
+        self.base.font_draw.sync();
+        Ok(())
+    }
+
+    fn set_colors(
+        &mut self,
+        connection: &RustConnection,
+        border_color: u32,
+        background_color: u32,
+        foreground_color: u32,
+    ) -> Result<(), X11Error> {
+        self.base
+            .set_colors(connection, border_color, background_color, foreground_color)
+    }
+}
@@ -1,4 +1,7 @@
-use super::{Overlay, OverlayBase};
+use super::{
+    BACKGROUND_COLOR, BORDER_COLOR, FOREGROUND_COLOR, HIGHLIGHT_COLOR, MonitorRect, Overlay,
+    OverlayBase,
+};
 use crate::bar::font::Font;
 use crate::errors::X11Error;
 use crate::keyboard::KeyAction;
@@ -12,7 +15,6 @@ const PADDING: i16 = 24;
 const KEY_ACTION_SPACING: i16 = 20;
 const LINE_SPACING: i16 = 8;
 const BORDER_WIDTH: u16 = 4;
-const BORDER_COLOR: u32 = 0x7fccff;
 const TITLE_BOTTOM_MARGIN: i16 = 20;
 const INPUT_SUPPRESS_MS: u128 = 200;
 
@@ -42,14 +44,14 @@ impl KeybindOverlay {
             600,
             BORDER_WIDTH,
             BORDER_COLOR,
-            0x1a1a1a,
-            0xffffff,
+            BACKGROUND_COLOR,
+            FOREGROUND_COLOR,
         )?;
 
         Ok(KeybindOverlay {
             base,
             keybindings: Vec::new(),
-            key_bg_color: 0x2a2a2a,
+            key_bg_color: HIGHLIGHT_COLOR,
             modkey,
             last_shown_at: None,
             max_key_width: 0,
@@ -61,10 +63,7 @@ impl KeybindOverlay {
         connection: &RustConnection,
         font: &Font,
         keybindings: &[KeyBinding],
-        monitor_x: i16,
-        monitor_y: i16,
-        screen_width: u16,
-        screen_height: u16,
+        monitor: MonitorRect,
     ) -> Result<(), X11Error> {
         self.keybindings = self.collect_keybindings(keybindings);
 
@@ -95,8 +94,7 @@ impl KeybindOverlay {
         let height =
             title_height + (self.keybindings.len() as u16 * line_height) + (PADDING as u16 * 2);
 
-        let x = monitor_x + ((screen_width - width) / 2) as i16;
-        let y = monitor_y + ((screen_height - height) / 2) as i16;
+        let (x, y) = monitor.centered(width, height);
 
         self.base.configure(connection, x, y, width, height)?;
 
@@ -116,23 +114,12 @@ impl KeybindOverlay {
         connection: &RustConnection,
         font: &Font,
         keybindings: &[KeyBinding],
-        monitor_x: i16,
-        monitor_y: i16,
-        screen_width: u16,
-        screen_height: u16,
+        monitor: MonitorRect,
     ) -> Result<(), X11Error> {
         if self.base.is_visible {
             self.hide(connection)?;
         } else {
-            self.show(
-                connection,
-                font,
-                keybindings,
-                monitor_x,
-                monitor_y,
-                screen_width,
-                screen_height,
-            )?;
+            self.show(connection, font, keybindings, monitor)?;
         }
         Ok(())
     }
@@ -157,8 +144,11 @@ impl KeybindOverlay {
             KeyAction::SpawnTerminal,
             KeyAction::ToggleFullScreen,
             KeyAction::ToggleFloating,
+            KeyAction::MoveFloating,
+            KeyAction::ResizeFloating,
             KeyAction::CycleLayout,
             KeyAction::FocusStack,
+            KeyAction::Zoom,
             KeyAction::ViewTag,
         ];
 
@@ -171,8 +161,8 @@ impl KeybindOverlay {
             if let Some(binding) = binding
                 && !binding.keys.is_empty()
             {
-                let key_str = self.format_key_combo(&binding.keys[0]);
-                let action_str = self.action_description(binding);
+                let key_str = Self::format_key_combo(&binding.keys[0], self.modkey);
+                let action_str = Self::action_description(binding);
                 result.push((key_str, action_str));
             }
         }
@@ -180,12 +170,12 @@ impl KeybindOverlay {
         result
     }
 
-    fn format_key_combo(&self, key: &KeyPress) -> String {
+    pub(super) fn format_key_combo(key: &KeyPress, modkey: KeyButMask) -> String {
         let mut parts = Vec::new();
 
         for modifier in &key.modifiers {
             let mod_str = match *modifier {
-                m if m == self.modkey => "Mod",
+                m if m == modkey => "Mod",
                 KeyButMask::SHIFT => "Shift",
                 KeyButMask::CONTROL => "Ctrl",
                 KeyButMask::MOD1 => "Alt",
@@ -200,7 +190,7 @@ impl KeybindOverlay {
         parts.join(" + ")
     }
 
-    fn action_description(&self, binding: &KeyBinding) -> String {
+    pub(super) fn action_description(binding: &KeyBinding) -> String {
         use crate::keyboard::Arg;
 
         match binding.func {
@@ -208,7 +198,14 @@ impl KeybindOverlay {
             KeyAction::Quit => "Quit Window Manager".to_string(),
             KeyAction::Restart => "Restart Window Manager".to_string(),
             KeyAction::KillClient => "Close Focused Window".to_string(),
+            KeyAction::KillOthers => "Close Other Windows On Tag".to_string(),
+            KeyAction::KillAll => "Close All Windows On Tag".to_string(),
             KeyAction::Spawn => match &binding.arg {
+                Arg::WithEnv(command, _) => Self::action_description(&KeyBinding::new(
+                    binding.keys.clone(),
+                    KeyAction::Spawn,
+                    command.as_ref().clone(),
+                )),
                 Arg::Str(cmd) => format!("Launch: {}", cmd),
                 Arg::Array(arr) if !arr.is_empty() => format!("Launch: {}", arr[0]),
                 _ => "Launch Program".to_string(),
@@ -216,10 +213,19 @@ impl KeybindOverlay {
             KeyAction::SpawnTerminal => "Launch Terminal".to_string(),
             KeyAction::FocusStack => "Focus Next/Previous Window".to_string(),
             KeyAction::MoveStack => "Move Window Up/Down Stack".to_string(),
+            KeyAction::RotateStack => "Rotate Tiled Windows".to_string(),
+            KeyAction::FocusFloating => "Cycle Floating Windows".to_string(),
+            KeyAction::CycleSameClass => "Cycle Windows Of Same Application".to_string(),
+            KeyAction::FocusUrgent => "Focus Urgent Window".to_string(),
+            KeyAction::CycleRecent => "Switch To Recent Window".to_string(),
             KeyAction::ViewTag => match &binding.arg {
                 Arg::Int(n) => format!("View Workspace {}", n),
                 _ => "View Workspace".to_string(),
             },
+            KeyAction::ViewTagAllMonitors => match &binding.arg {
+                Arg::Int(n) => format!("View Workspace {} On All Monitors", n),
+                _ => "View Workspace On All Monitors".to_string(),
+            },
             KeyAction::ViewNextTag => "View Next Workspace".to_string(),
             KeyAction::ViewPreviousTag => "View Previous Workspace".to_string(),
             KeyAction::ViewNextNonEmptyTag => "View Next Non-Empty Workspace".to_string(),
@@ -229,18 +235,65 @@ impl KeybindOverlay {
                 _ => "Toggle View Workspace".to_string(),
             },
             KeyAction::MoveToTag => "Move Window to Workspace".to_string(),
+            KeyAction::MoveTag => "Reorder Workspace".to_string(),
             KeyAction::ToggleTag => "Toggle Window on Workspace".to_string(),
             KeyAction::ToggleGaps => "Toggle Window Gaps".to_string(),
+            KeyAction::ToggleBar => "Toggle Status Bar".to_string(),
             KeyAction::ToggleFullScreen => "Toggle Fullscreen Mode".to_string(),
+            KeyAction::ToggleFakeFullscreen => "Toggle Fake Fullscreen".to_string(),
             KeyAction::ToggleFloating => "Toggle Floating Mode".to_string(),
+            KeyAction::ToggleAlwaysOnTop => "Toggle Always on Top".to_string(),
             KeyAction::ChangeLayout => "Change Layout".to_string(),
             KeyAction::CycleLayout => "Cycle Through Layouts".to_string(),
+            KeyAction::MirrorLayout => "Mirror Layout Horizontally".to_string(),
             KeyAction::FocusMonitor => "Focus Next Monitor".to_string(),
             KeyAction::TagMonitor => "Send Window to Monitor".to_string(),
+            KeyAction::TagMonitorKeepTag => "Send Window to Monitor, Keeping Its Tags".to_string(),
             KeyAction::SetMasterFactor => "Adjust Master Area Size".to_string(),
             KeyAction::IncNumMaster => "Adjust Number of Master Windows".to_string(),
+            KeyAction::SetClientFactor => "Adjust Window Stack Share".to_string(),
             KeyAction::ScrollLeft => "Scroll Layout Left".to_string(),
             KeyAction::ScrollRight => "Scroll Layout Right".to_string(),
+            KeyAction::ScrollColumnGrow => "Widen Scrolling Column".to_string(),
+            KeyAction::ScrollColumnShrink => "Narrow Scrolling Column".to_string(),
+            KeyAction::SetGamma => match &binding.arg {
+                Arg::Int(temperature) => format!("Set Screen Temperature to {}K", temperature),
+                _ => "Set Screen Temperature".to_string(),
+            },
+            KeyAction::MoveFloating => "Move Floating Window".to_string(),
+            KeyAction::ResizeFloating => "Resize Floating Window".to_string(),
+            KeyAction::ToggleScratchpad => match &binding.arg {
+                Arg::Str(name) => format!("Toggle Scratchpad: {}", name),
+                _ => match binding.arg.field("name") {
+                    Some(name) => format!("Toggle Scratchpad: {}", name),
+                    None => "Toggle Scratchpad".to_string(),
+                },
+            },
+            KeyAction::CenterFloating => "Center Floating Window".to_string(),
+            KeyAction::SnapFloating => match &binding.arg {
+                Arg::Str(region) => format!("Snap Floating Window: {}", region),
+                _ => "Snap Floating Window".to_string(),
+            },
+            KeyAction::UndoWindowOp => "Undo Last Window Change".to_string(),
+            KeyAction::ToggleClickThrough => "Toggle Click-Through".to_string(),
+            KeyAction::Zoom => "Swap with Master".to_string(),
+            KeyAction::RescanWindows => "Manage Unmanaged Windows".to_string(),
+            KeyAction::TogglePresentationMode => "Toggle Presentation Mode".to_string(),
+            KeyAction::ToggleFocusFollowsMouse => "Toggle Focus Follows Mouse".to_string(),
+            KeyAction::CycleTheme => "Switch To Next Theme".to_string(),
+            KeyAction::DumpState => "Print Window Manager State".to_string(),
+            KeyAction::ShowOsd => match &binding.arg {
+                Arg::Array(parts) if !parts.is_empty() => format!("Show OSD: {}", parts[0]),
+                _ => "Show OSD".to_string(),
+            },
+            KeyAction::RunOrRaise => match &binding.arg {
+                Arg::Array(parts) if !parts.is_empty() => format!("Run Or Raise: {}", parts[0]),
+                _ => "Run Or Raise".to_string(),
+            },
+            KeyAction::Notify => match &binding.arg {
+                Arg::Str(text) => format!("Show Message: {}", text),
+                _ => "Show Message".to_string(),
+            },
             KeyAction::None => "No Action".to_string(),
         }
     }
@@ -317,4 +370,15 @@ impl Overlay for KeybindOverlay {
 
         Ok(())
     }
+
+    fn set_colors(
+        &mut self,
+        connection: &RustConnection,
+        border_color: u32,
+        background_color: u32,
+        foreground_color: u32,
+    ) -> Result<(), X11Error> {
+        self.base
+            .set_colors(connection, border_color, background_color, foreground_color)
+    }
 }
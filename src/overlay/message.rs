@@ -1,28 +1,28 @@
-use super::{Overlay, OverlayBase};
+use super::{BACKGROUND_COLOR, BORDER_WIDTH, FOREGROUND_COLOR, MonitorRect, Overlay, OverlayBase};
 use crate::bar::font::Font;
 use crate::errors::{ConfigError, X11Error};
+use std::time::{Duration, Instant};
 use x11rb::connection::Connection;
 use x11rb::protocol::xproto::*;
 use x11rb::rust_connection::RustConnection;
 
 const PADDING: i16 = 20;
 const LINE_SPACING: i16 = 5;
-const BORDER_WIDTH: u16 = 2;
-const BORDER_COLOR: u32 = 0xff5555;
 
-pub struct ErrorOverlay {
+/// Centered multi-line text, either until dismissed or for a fixed duration.
+pub struct MessageOverlay {
     base: OverlayBase,
     lines: Vec<String>,
+    expires_at: Option<Instant>,
 }
 
-impl ErrorOverlay {
+impl MessageOverlay {
     pub fn new(
         connection: &RustConnection,
         screen: &Screen,
         screen_num: usize,
         display: *mut x11::xlib::Display,
-        _font: &Font,
-        _max_width: u16,
+        border_color: u32,
     ) -> Result<Self, X11Error> {
         let base = OverlayBase::new(
             connection,
@@ -32,14 +32,15 @@ impl ErrorOverlay {
             400,
             200,
             BORDER_WIDTH,
-            BORDER_COLOR,
-            0x1a1a1a,
-            0xffffff,
+            border_color,
+            BACKGROUND_COLOR,
+            FOREGROUND_COLOR,
         )?;
 
-        Ok(ErrorOverlay {
+        Ok(MessageOverlay {
             base,
             lines: Vec::new(),
+            expires_at: None,
         })
     }
 
@@ -48,15 +49,39 @@ impl ErrorOverlay {
         connection: &RustConnection,
         font: &Font,
         error: ConfigError,
-        monitor_x: i16,
-        monitor_y: i16,
-        screen_width: u16,
-        screen_height: u16,
+        monitor: MonitorRect,
     ) -> Result<(), X11Error> {
-        let max_line_width = (screen_width as i16 / 2 - PADDING * 4).max(300) as u16;
         let error_with_instruction = format!("{}\n\nFix the config file and reload.", error);
-        self.lines = self.wrap_text(&error_with_instruction, font, max_line_width);
+        self.expires_at = None;
+        self.lines = self.wrap_text(&error_with_instruction, font, monitor.width);
+        self.show_lines(connection, font, monitor)
+    }
+
+    /// Shows `text` until `duration` has passed; see `is_expired`.
+    pub fn show_message(
+        &mut self,
+        connection: &RustConnection,
+        font: &Font,
+        text: &str,
+        duration: Duration,
+        monitor: MonitorRect,
+    ) -> Result<(), X11Error> {
+        self.expires_at = Some(Instant::now() + duration);
+        self.lines = self.wrap_text(text, font, monitor.width);
+        self.show_lines(connection, font, monitor)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| Instant::now() >= expires_at)
+    }
 
+    fn show_lines(
+        &mut self,
+        connection: &RustConnection,
+        font: &Font,
+        monitor: MonitorRect,
+    ) -> Result<(), X11Error> {
         let mut content_width = 0u16;
         for line in &self.lines {
             let line_width = font.text_width(line);
@@ -69,8 +94,7 @@ impl ErrorOverlay {
         let line_height = font.height() + LINE_SPACING as u16;
         let height = (self.lines.len() as u16 * line_height) + (PADDING as u16 * 2);
 
-        let x = monitor_x + ((screen_width - width) / 2) as i16;
-        let y = monitor_y + ((screen_height - height) / 2) as i16;
+        let (x, y) = monitor.centered(width, height);
 
         self.base.configure(connection, x, y, width, height)?;
         self.base.is_visible = true;
@@ -79,7 +103,8 @@ impl ErrorOverlay {
         Ok(())
     }
 
-    fn wrap_text(&self, text: &str, font: &Font, max_width: u16) -> Vec<String> {
+    fn wrap_text(&self, text: &str, font: &Font, screen_width: u16) -> Vec<String> {
+        let max_width = (screen_width as i16 / 2 - PADDING * 4).max(300) as u16;
         let mut lines = Vec::new();
         for paragraph in text.lines() {
             if paragraph.trim().is_empty() {
@@ -113,7 +138,7 @@ impl ErrorOverlay {
     }
 }
 
-impl Overlay for ErrorOverlay {
+impl Overlay for MessageOverlay {
     fn window(&self) -> Window {
         self.base.window
     }
@@ -125,6 +150,7 @@ impl Overlay for ErrorOverlay {
     fn hide(&mut self, connection: &RustConnection) -> Result<(), X11Error> {
         self.base.hide(connection)?;
         self.lines.clear();
+        self.expires_at = None;
         Ok(())
     }
 
@@ -145,4 +171,15 @@ impl Overlay for ErrorOverlay {
         self.base.font_draw.sync();
         Ok(())
     }
+
+    fn set_colors(
+        &mut self,
+        connection: &RustConnection,
+        border_color: u32,
+        background_color: u32,
+        foreground_color: u32,
+    ) -> Result<(), X11Error> {
+        self.base
+            .set_colors(connection, border_color, background_color, foreground_color)
+    }
 }
@@ -5,17 +5,77 @@ use x11rb::connection::Connection;
 use x11rb::protocol::xproto::*;
 use x11rb::rust_connection::RustConnection;
 
-pub mod error;
+pub mod chord;
 pub mod keybind;
+pub mod message;
+pub mod osd;
+pub mod switcher;
 
-pub use error::ErrorOverlay;
+/// Spacing between an overlay's border and its content.
+pub const PADDING: i16 = 16;
+pub const BORDER_WIDTH: u16 = 2;
+pub const BORDER_COLOR: u32 = 0x7fccff;
+pub const BACKGROUND_COLOR: u32 = 0x1a1a1a;
+pub const FOREGROUND_COLOR: u32 = 0xffffff;
+/// Background behind keys and the selected entry.
+pub const HIGHLIGHT_COLOR: u32 = 0x2a2a2a;
+
+pub use chord::ChordOverlay;
 pub use keybind::KeybindOverlay;
+pub use message::MessageOverlay;
+pub use osd::OsdOverlay;
+pub use switcher::SwitcherOverlay;
+
+/// Screen area of the monitor an overlay is placed on.
+#[derive(Debug, Clone, Copy)]
+pub struct MonitorRect {
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl MonitorRect {
+    /// Top-left corner that centers a `width` by `height` box on the monitor.
+    pub fn centered(&self, width: u16, height: u16) -> (i16, i16) {
+        (
+            self.x + (self.width.saturating_sub(width) / 2) as i16,
+            self.y + (self.height.saturating_sub(height) / 2) as i16,
+        )
+    }
+
+    /// Top-left corner of a box centered horizontally, `margin` above the bottom edge.
+    pub fn bottom(&self, width: u16, height: u16, margin: u16) -> (i16, i16) {
+        (
+            self.x + (self.width.saturating_sub(width) / 2) as i16,
+            self.y + self.height.saturating_sub(height + margin) as i16,
+        )
+    }
+}
+
+impl From<crate::monitor::Rect> for MonitorRect {
+    fn from((x, y, width, height): crate::monitor::Rect) -> Self {
+        Self {
+            x: x as i16,
+            y: y as i16,
+            width: width as u16,
+            height: height as u16,
+        }
+    }
+}
 
 pub trait Overlay {
     fn window(&self) -> Window;
     fn is_visible(&self) -> bool;
     fn hide(&mut self, connection: &RustConnection) -> Result<(), X11Error>;
     fn draw(&self, connection: &RustConnection, font: &Font) -> Result<(), X11Error>;
+    fn set_colors(
+        &mut self,
+        connection: &RustConnection,
+        border_color: u32,
+        background_color: u32,
+        foreground_color: u32,
+    ) -> Result<(), X11Error>;
 }
 
 pub struct OverlayBase {
@@ -113,6 +173,25 @@ impl OverlayBase {
         Ok(())
     }
 
+    /// Recolors the window; visible overlays pick the colors up on their next draw.
+    pub fn set_colors(
+        &mut self,
+        connection: &RustConnection,
+        border_color: u32,
+        background_color: u32,
+        foreground_color: u32,
+    ) -> Result<(), X11Error> {
+        connection.change_window_attributes(
+            self.window,
+            &ChangeWindowAttributesAux::new()
+                .background_pixel(background_color)
+                .border_pixel(border_color),
+        )?;
+        self.background_color = background_color;
+        self.foreground_color = foreground_color;
+        Ok(())
+    }
+
     pub fn show(&mut self, connection: &RustConnection) -> Result<(), X11Error> {
         connection.configure_window(
             self.window,
@@ -154,3 +233,37 @@ impl OverlayBase {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: MonitorRect = MonitorRect {
+        x: 1920,
+        y: 0,
+        width: 1280,
+        height: 1024,
+    };
+
+    #[test]
+    fn centers_on_the_monitor() {
+        assert_eq!(MONITOR.centered(400, 200), (1920 + 440, 412));
+    }
+
+    #[test]
+    fn sits_above_the_bottom_edge() {
+        assert_eq!(MONITOR.bottom(400, 50, 80), (1920 + 440, 1024 - 130));
+    }
+
+    #[test]
+    fn oversized_boxes_stick_to_the_top_left() {
+        assert_eq!(MONITOR.centered(2000, 2000), (1920, 0));
+        assert_eq!(MONITOR.bottom(2000, 2000, 80), (1920, 0));
+    }
+
+    #[test]
+    fn converts_from_a_monitor_rect() {
+        let rect = MonitorRect::from((-1280, 0, 1280, 720));
+        assert_eq!((rect.x, rect.y, rect.width, rect.height), (-1280, 0, 1280, 720));
+    }
+}
@@ -0,0 +1,158 @@
+use super::{
+    BACKGROUND_COLOR, BORDER_COLOR, BORDER_WIDTH, FOREGROUND_COLOR, HIGHLIGHT_COLOR, MonitorRect,
+    Overlay, OverlayBase, PADDING,
+};
+use crate::bar::font::Font;
+use crate::errors::X11Error;
+use std::time::{Duration, Instant};
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::*;
+use x11rb::rust_connection::RustConnection;
+
+const LABEL_BAR_SPACING: i16 = 16;
+const BAR_WIDTH: u16 = 240;
+const BAR_HEIGHT: u16 = 8;
+const BOTTOM_MARGIN: u16 = 80;
+const DISPLAY_DURATION: Duration = Duration::from_millis(800);
+
+/// Labeled progress bar for volume or brightness changes, shown near the bottom of the monitor.
+pub struct OsdOverlay {
+    base: OverlayBase,
+    label: String,
+    percent: u8,
+    shown_at: Option<Instant>,
+}
+
+impl OsdOverlay {
+    pub fn new(
+        connection: &RustConnection,
+        screen: &Screen,
+        screen_num: usize,
+        display: *mut x11::xlib::Display,
+    ) -> Result<Self, X11Error> {
+        let base = OverlayBase::new(
+            connection,
+            screen,
+            screen_num,
+            display,
+            300,
+            50,
+            BORDER_WIDTH,
+            BORDER_COLOR,
+            BACKGROUND_COLOR,
+            FOREGROUND_COLOR,
+        )?;
+
+        Ok(OsdOverlay {
+            base,
+            label: String::new(),
+            percent: 0,
+            shown_at: None,
+        })
+    }
+
+    pub fn show(
+        &mut self,
+        connection: &RustConnection,
+        font: &Font,
+        label: &str,
+        percent: u8,
+        monitor: MonitorRect,
+    ) -> Result<(), X11Error> {
+        self.percent = percent.min(100);
+        self.label = format!("{} {}%", label, self.percent);
+
+        let width = font.text_width(&self.label)
+            + LABEL_BAR_SPACING as u16
+            + BAR_WIDTH
+            + (PADDING as u16 * 2);
+        let height = font.height() + (PADDING as u16 * 2);
+
+        let (x, y) = monitor.bottom(width, height, BOTTOM_MARGIN);
+
+        self.base.configure(connection, x, y, width, height)?;
+        self.shown_at = Some(Instant::now());
+        self.base.is_visible = true;
+        self.draw(connection, font)?;
+        self.base.show(connection)?;
+        Ok(())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.shown_at
+            .is_some_and(|shown_at| shown_at.elapsed() >= DISPLAY_DURATION)
+    }
+}
+
+impl Overlay for OsdOverlay {
+    fn window(&self) -> Window {
+        self.base.window
+    }
+
+    fn is_visible(&self) -> bool {
+        self.base.is_visible
+    }
+
+    fn hide(&mut self, connection: &RustConnection) -> Result<(), X11Error> {
+        self.base.hide(connection)?;
+        self.shown_at = None;
+        self.label.clear();
+        Ok(())
+    }
+
+    fn draw(&self, connection: &RustConnection, font: &Font) -> Result<(), X11Error> {
+        if !self.base.is_visible {
+            return Ok(());
+        }
+
+        self.base.draw_background(connection)?;
+
+        let text_y = PADDING + font.ascent();
+        self.base.font_draw.draw_text(
+            font,
+            self.base.foreground_color,
+            PADDING,
+            text_y,
+            &self.label,
+        );
+
+        let bar_x = self.base.width as i16 - PADDING - BAR_WIDTH as i16;
+        let bar_y = (self.base.height as i16 - BAR_HEIGHT as i16) / 2;
+        let fill_width = (BAR_WIDTH as u32 * self.percent as u32 / 100) as u16;
+
+        for (color, width) in [(HIGHLIGHT_COLOR, BAR_WIDTH), (BORDER_COLOR, fill_width)] {
+            if width == 0 {
+                continue;
+            }
+            connection.change_gc(
+                self.base.graphics_context,
+                &ChangeGCAux::new().foreground(color),
+            )?;
+            connection.poly_fill_rectangle(
+                self.base.window,
+                self.base.graphics_context,
+                &[Rectangle {
+                    x: bar_x,
+                    y: bar_y,
+                    width,
+                    height: BAR_HEIGHT,
+                }],
+            )?;
+        }
+
+        connection.flush()?;
+        self.base.font_draw.sync();
+        Ok(())
+    }
+
+    fn set_colors(
+        &mut self,
+        connection: &RustConnection,
+        border_color: u32,
+        background_color: u32,
+        foreground_color: u32,
+    ) -> Result<(), X11Error> {
+        self.base
+            .set_colors(connection, border_color, background_color, foreground_color)
+    }
+}
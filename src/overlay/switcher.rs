@@ -0,0 +1,168 @@
+use super::{
+    BACKGROUND_COLOR, BORDER_COLOR, BORDER_WIDTH, FOREGROUND_COLOR, HIGHLIGHT_COLOR, MonitorRect,
+    Overlay, OverlayBase, PADDING,
+};
+use crate::bar::font::Font;
+use crate::errors::X11Error;
+use std::time::{Duration, Instant};
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::*;
+use x11rb::rust_connection::RustConnection;
+
+const LINE_SPACING: i16 = 8;
+const MAX_TITLE_WIDTH: u16 = 600;
+const DISPLAY_DURATION: Duration = Duration::from_millis(1200);
+
+/// Short-lived list of window titles with the selected one highlighted.
+pub struct SwitcherOverlay {
+    base: OverlayBase,
+    titles: Vec<String>,
+    selected: usize,
+    shown_at: Option<Instant>,
+}
+
+impl SwitcherOverlay {
+    pub fn new(
+        connection: &RustConnection,
+        screen: &Screen,
+        screen_num: usize,
+        display: *mut x11::xlib::Display,
+    ) -> Result<Self, X11Error> {
+        let base = OverlayBase::new(
+            connection,
+            screen,
+            screen_num,
+            display,
+            400,
+            100,
+            BORDER_WIDTH,
+            BORDER_COLOR,
+            BACKGROUND_COLOR,
+            FOREGROUND_COLOR,
+        )?;
+
+        Ok(SwitcherOverlay {
+            base,
+            titles: Vec::new(),
+            selected: 0,
+            shown_at: None,
+        })
+    }
+
+    pub fn show(
+        &mut self,
+        connection: &RustConnection,
+        font: &Font,
+        titles: Vec<String>,
+        selected: usize,
+        monitor: MonitorRect,
+    ) -> Result<(), X11Error> {
+        self.titles = titles
+            .into_iter()
+            .map(|title| truncate_to_width(font, &title, MAX_TITLE_WIDTH))
+            .collect();
+        self.selected = selected;
+
+        let content_width = self
+            .titles
+            .iter()
+            .map(|title| font.text_width(title))
+            .max()
+            .unwrap_or(0);
+        let line_height = font.height() + LINE_SPACING as u16;
+        let width = content_width + (PADDING as u16 * 2);
+        let height = (self.titles.len() as u16 * line_height) + (PADDING as u16 * 2);
+
+        let (x, y) = monitor.centered(width, height);
+
+        self.base.configure(connection, x, y, width, height)?;
+        self.shown_at = Some(Instant::now());
+        self.base.is_visible = true;
+        self.draw(connection, font)?;
+        self.base.show(connection)?;
+        Ok(())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.shown_at
+            .is_some_and(|shown_at| shown_at.elapsed() >= DISPLAY_DURATION)
+    }
+}
+
+fn truncate_to_width(font: &Font, text: &str, max_width: u16) -> String {
+    if font.text_width(text) <= max_width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.to_string();
+    while !truncated.is_empty() && font.text_width(&format!("{}…", truncated)) > max_width {
+        truncated.pop();
+    }
+    format!("{}…", truncated)
+}
+
+impl Overlay for SwitcherOverlay {
+    fn window(&self) -> Window {
+        self.base.window
+    }
+
+    fn is_visible(&self) -> bool {
+        self.base.is_visible
+    }
+
+    fn hide(&mut self, connection: &RustConnection) -> Result<(), X11Error> {
+        self.base.hide(connection)?;
+        self.shown_at = None;
+        self.titles.clear();
+        Ok(())
+    }
+
+    fn draw(&self, connection: &RustConnection, font: &Font) -> Result<(), X11Error> {
+        if !self.base.is_visible {
+            return Ok(());
+        }
+
+        self.base.draw_background(connection)?;
+
+        let line_height = font.height() + LINE_SPACING as u16;
+        let mut y = PADDING + font.ascent();
+
+        for (index, title) in self.titles.iter().enumerate() {
+            if index == self.selected {
+                connection.change_gc(
+                    self.base.graphics_context,
+                    &ChangeGCAux::new().foreground(HIGHLIGHT_COLOR),
+                )?;
+                connection.poly_fill_rectangle(
+                    self.base.window,
+                    self.base.graphics_context,
+                    &[Rectangle {
+                        x: PADDING / 2,
+                        y: y - font.ascent() - LINE_SPACING / 2,
+                        width: self.base.width - PADDING as u16,
+                        height: line_height,
+                    }],
+                )?;
+            }
+
+            self.base
+                .font_draw
+                .draw_text(font, self.base.foreground_color, PADDING, y, title);
+            y += line_height as i16;
+        }
+
+        connection.flush()?;
+        self.base.font_draw.sync();
+        Ok(())
+    }
+
+    fn set_colors(
+        &mut self,
+        connection: &RustConnection,
+        border_color: u32,
+        background_color: u32,
+        foreground_color: u32,
+    ) -> Result<(), X11Error> {
+        self.base
+            .set_colors(connection, border_color, background_color, foreground_color)
+    }
+}
@@ -0,0 +1,109 @@
+/// Runtime overrides layered over the loaded config. `None` defers to the config value,
+/// so switching a mode off restores the configured behaviour without touching `Config`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConfigOverrides {
+    pub focus_new_windows: Option<bool>,
+    pub steal_monitor_focus: Option<bool>,
+    pub focus_on_activate: Option<bool>,
+    pub notifications: Option<bool>,
+    pub monitor_follows_mouse: Option<bool>,
+    pub focus_follows_mouse: Option<bool>,
+    pub hide_window_titles: Option<bool>,
+}
+
+impl ConfigOverrides {
+    pub fn presentation(blank_titles: bool) -> Self {
+        Self {
+            focus_new_windows: Some(false),
+            steal_monitor_focus: Some(false),
+            focus_on_activate: Some(false),
+            notifications: Some(false),
+            monitor_follows_mouse: Some(false),
+            focus_follows_mouse: None,
+            hide_window_titles: Some(blank_titles),
+        }
+    }
+
+    fn fields(&mut self) -> [&mut Option<bool>; 7] {
+        [
+            &mut self.focus_new_windows,
+            &mut self.steal_monitor_focus,
+            &mut self.focus_on_activate,
+            &mut self.notifications,
+            &mut self.monitor_follows_mouse,
+            &mut self.focus_follows_mouse,
+            &mut self.hide_window_titles,
+        ]
+    }
+
+    /// Whether every override `mode` sets is in effect.
+    pub fn contains(&self, mode: &Self) -> bool {
+        let (mut current, mut mode) = (*self, *mode);
+        current
+            .fields()
+            .into_iter()
+            .zip(mode.fields())
+            .all(|(current, mode)| mode.is_none() || current == mode)
+    }
+
+    /// Switches `mode` off when it is in effect and on otherwise. Overrides `mode`
+    /// doesn't set are kept either way.
+    pub fn toggle(mut self, mode: &Self) -> Self {
+        let active = self.contains(mode);
+        let mut mode = *mode;
+        for (current, mode) in self.fields().into_iter().zip(mode.fields()) {
+            if mode.is_some() {
+                *current = if active { None } else { *mode };
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_is_overridden_by_default() {
+        let overrides = ConfigOverrides::default();
+        assert!(!overrides.contains(&ConfigOverrides::presentation(true)));
+        assert!(overrides.contains(&ConfigOverrides::default()));
+    }
+
+    #[test]
+    fn toggling_a_mode_twice_restores_the_config() {
+        let presentation = ConfigOverrides::presentation(true);
+        let on = ConfigOverrides::default().toggle(&presentation);
+        assert_eq!(on, presentation);
+        assert!(on.contains(&presentation));
+
+        let off = on.toggle(&presentation);
+        assert_eq!(off, ConfigOverrides::default());
+    }
+
+    #[test]
+    fn toggling_a_mode_keeps_unrelated_overrides() {
+        let presentation = ConfigOverrides::presentation(false);
+        let focus_follows_mouse = ConfigOverrides {
+            focus_follows_mouse: Some(false),
+            ..Default::default()
+        };
+
+        let on = focus_follows_mouse.toggle(&presentation);
+        assert_eq!(on.focus_follows_mouse, Some(false));
+        assert_eq!(on.hide_window_titles, Some(false));
+
+        let off = on.toggle(&presentation);
+        assert_eq!(off, focus_follows_mouse);
+    }
+
+    #[test]
+    fn a_mode_is_not_in_effect_when_one_of_its_overrides_differs() {
+        let presentation = ConfigOverrides::presentation(true);
+        let mut overrides = presentation;
+        overrides.notifications = Some(true);
+        assert!(!overrides.contains(&presentation));
+        assert_eq!(overrides.toggle(&presentation), presentation);
+    }
+}
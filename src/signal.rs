@@ -1,14 +1,54 @@
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 pub fn spawn_detached(cmd: &str) {
-    if let Ok(mut child) = Command::new("sh")
+    spawn_shell(cmd, None, &[]);
+}
+
+/// Like `spawn_detached`, but runs `cmd` in `dir`.
+pub fn spawn_detached_in(cmd: &str, dir: &Path) {
+    spawn_shell(cmd, Some(dir), &[]);
+}
+
+/// Like `spawn_detached`, with `env` added to the environment of `cmd`.
+pub fn spawn_detached_with_env(cmd: &str, env: &[(String, String)]) {
+    spawn_shell(cmd, None, env);
+}
+
+fn spawn_shell(cmd: &str, dir: Option<&Path>, env: &[(String, String)]) {
+    let mut command = Command::new("sh");
+    command
         .arg("-c")
         .arg(format!("({}) &", cmd))
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    if let Ok(mut child) = command.spawn() {
+        let _ = child.wait();
+    }
+}
+
+/// Runs `argv[0]` with the remaining arguments directly, so arguments never
+/// need quoting. The shell only backgrounds it, exits, and is reaped here.
+pub fn spawn_detached_args(argv: &[String], env: &[(String, String)]) {
+    if argv.is_empty() {
+        return;
+    }
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg("exec \"$@\" &")
+        .arg("sh")
+        .args(argv)
+        .envs(env.iter().map(|(key, value)| (key, value)))
         .stdin(Stdio::null())
         .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-    {
+        .stderr(Stdio::null());
+    if let Ok(mut child) = command.spawn() {
         let _ = child.wait();
     }
 }
@@ -0,0 +1,140 @@
+/// Outer rectangle of a window or monitor area, borders included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Positions a dragged window of a fixed size may snap to, computed once per drag.
+///
+/// The window aligns with the inside of the monitor area, and with other windows
+/// both edge to edge (placed next to them) and edge aligned (flush with them).
+#[derive(Debug, Clone, Default)]
+pub struct SnapTargets {
+    x: Vec<i32>,
+    y: Vec<i32>,
+}
+
+impl SnapTargets {
+    pub fn new(area: Rect, others: &[Rect], width: i32, height: i32) -> Self {
+        // A window larger than the area only snaps to the top left, keeping that corner
+        // in view instead of flushing the far edge and pushing the rest off screen.
+        let mut x = vec![area.x];
+        if width <= area.width {
+            x.push(area.x + area.width - width);
+        }
+        let mut y = vec![area.y];
+        if height <= area.height {
+            y.push(area.y + area.height - height);
+        }
+
+        for other in others {
+            for edge in [other.x, other.x + other.width] {
+                x.push(edge);
+                x.push(edge - width);
+            }
+            for edge in [other.y, other.y + other.height] {
+                y.push(edge);
+                y.push(edge - height);
+            }
+        }
+
+        Self { x, y }
+    }
+
+    /// Returns the position snapped to the nearest target within `distance` on each axis.
+    pub fn snap(&self, x: i32, y: i32, distance: i32) -> (i32, i32) {
+        (nearest(x, &self.x, distance), nearest(y, &self.y, distance))
+    }
+}
+
+fn nearest(position: i32, targets: &[i32], distance: i32) -> i32 {
+    targets
+        .iter()
+        .copied()
+        .filter(|target| (target - position).abs() < distance)
+        .min_by_key(|target| (target - position).abs())
+        .unwrap_or(position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect {
+        x: 0,
+        y: 20,
+        width: 1920,
+        height: 1060,
+    };
+
+    #[test]
+    fn snaps_to_the_area_edges() {
+        let targets = SnapTargets::new(AREA, &[], 400, 300);
+        assert_eq!(targets.snap(8, 25, 10), (0, 20));
+        assert_eq!(targets.snap(1515, 775, 10), (1520, 780));
+    }
+
+    #[test]
+    fn leaves_windows_outside_the_distance_alone() {
+        let targets = SnapTargets::new(AREA, &[], 400, 300);
+        assert_eq!(targets.snap(10, 30, 10), (10, 30));
+        assert_eq!(targets.snap(500, 500, 10), (500, 500));
+    }
+
+    #[test]
+    fn snaps_next_to_and_flush_with_other_windows() {
+        let other = Rect {
+            x: 600,
+            y: 200,
+            width: 500,
+            height: 400,
+        };
+        let targets = SnapTargets::new(AREA, &[other], 400, 300);
+        // Right edge of the window against the left edge of the other one.
+        assert_eq!(targets.snap(195, 500, 10), (200, 500));
+        // Left edge against the other window's right edge.
+        assert_eq!(targets.snap(1104, 500, 10), (1100, 500));
+        // Top edges flush.
+        assert_eq!(targets.snap(100, 203, 10), (100, 200));
+        // Bottom edges flush.
+        assert_eq!(targets.snap(100, 297, 10), (100, 300));
+    }
+
+    #[test]
+    fn picks_the_nearest_target() {
+        let other = Rect {
+            x: 406,
+            y: 500,
+            width: 100,
+            height: 100,
+        };
+        let targets = SnapTargets::new(AREA, &[other], 400, 300);
+        // Both the area edge at 0 and the other window at 6 are in range.
+        assert_eq!(targets.snap(4, 500, 10), (6, 500));
+        assert_eq!(targets.snap(2, 500, 10), (0, 500));
+    }
+
+    #[test]
+    fn distance_is_exclusive() {
+        let targets = SnapTargets::new(AREA, &[], 400, 300);
+        assert_eq!(targets.snap(10, 500, 10), (10, 500));
+        assert_eq!(targets.snap(9, 500, 10), (0, 500));
+    }
+
+    #[test]
+    fn windows_larger_than_the_area_only_snap_to_the_top_left() {
+        let targets = SnapTargets::new(AREA, &[], 2000, 1200);
+        assert_eq!(targets.snap(-5, 25, 10), (0, 20));
+        // The far edges would be at -80 and -120; they are not targets.
+        assert_eq!(targets.snap(-78, -118, 10), (-78, -118));
+    }
+
+    #[test]
+    fn windows_exactly_the_area_size_snap_to_the_area() {
+        let targets = SnapTargets::new(AREA, &[], AREA.width, AREA.height);
+        assert_eq!(targets.snap(3, 17, 10), (0, 20));
+    }
+}
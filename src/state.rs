@@ -0,0 +1,117 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct WmState {
+    pub selected_monitor: usize,
+    pub monitors: Vec<MonitorState>,
+    pub clients: Vec<ClientState>,
+}
+
+#[derive(Serialize)]
+pub struct MonitorState {
+    pub index: usize,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub selected_tags: u32,
+    pub occupied_tags: u32,
+    pub urgent_tags: u32,
+    pub layout: &'static str,
+    pub layout_symbol: String,
+    pub focused_window: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct ClientState {
+    pub window: u32,
+    pub stable_id: String,
+    pub class: String,
+    pub title: String,
+    pub monitor: usize,
+    pub tags: u32,
+    #[serde(rename = "floating")]
+    pub is_floating: bool,
+    #[serde(rename = "fullscreen")]
+    pub is_fullscreen: bool,
+    #[serde(rename = "urgent")]
+    pub is_urgent: bool,
+}
+
+#[derive(Serialize)]
+pub struct TagSettingsState {
+    pub monitor: usize,
+    pub tag: usize,
+    pub num_master: i32,
+    pub master_factor: f32,
+    pub layout: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(title: &str) -> ClientState {
+        ClientState {
+            window: 42,
+            stable_id: "oxwm-1".to_string(),
+            class: "Alacritty".to_string(),
+            title: title.to_string(),
+            monitor: 0,
+            tags: 1,
+            is_floating: true,
+            is_fullscreen: false,
+            is_urgent: false,
+        }
+    }
+
+    #[test]
+    fn state_keeps_the_documented_field_names() {
+        let state = WmState {
+            selected_monitor: 0,
+            monitors: vec![MonitorState {
+                index: 0,
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 1080,
+                selected_tags: 1,
+                occupied_tags: 3,
+                urgent_tags: 0,
+                layout: "tiling",
+                layout_symbol: "[]=".to_string(),
+                focused_window: None,
+            }],
+            clients: vec![client("shell")],
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&state).unwrap();
+        assert_eq!(json["monitors"][0]["layout_symbol"], "[]=");
+        assert!(json["monitors"][0]["focused_window"].is_null());
+        assert_eq!(json["clients"][0]["floating"], true);
+        assert_eq!(json["clients"][0]["fullscreen"], false);
+        assert_eq!(json["clients"][0]["urgent"], false);
+    }
+
+    #[test]
+    fn titles_are_escaped() {
+        let json = serde_json::to_string(&client("say \"hi\"\n\u{1}")).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["title"], "say \"hi\"\n\u{1}");
+    }
+
+    #[test]
+    fn non_finite_master_factor_stays_valid_json() {
+        let settings = TagSettingsState {
+            monitor: 0,
+            tag: 2,
+            num_master: 1,
+            master_factor: f32::NAN,
+            layout: "tiling".to_string(),
+        };
+
+        let json = serde_json::to_string(&settings).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(parsed["master_factor"].is_null());
+    }
+}
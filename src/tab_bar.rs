@@ -101,6 +101,11 @@ impl TabBar {
         self.window
     }
 
+    pub fn set_schemes(&mut self, scheme_normal: ColorScheme, scheme_selected: ColorScheme) {
+        self.scheme_normal = scheme_normal;
+        self.scheme_selected = scheme_selected;
+    }
+
     pub fn draw(
         &mut self,
         connection: &RustConnection,
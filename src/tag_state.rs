@@ -0,0 +1,191 @@
+use crate::layout::LayoutType;
+use std::str::FromStr;
+
+/// Per-monitor tag settings stored as CARDINALs on the root window so they
+/// survive both config reloads and restarting the window manager.
+///
+/// Layout: `[VERSION, tag_count, tag_order*, monitor_count, (tagset0, tagset1,
+/// selected_tags_index, current_tag, previous_tag, slot_count, (num_master, factor,
+/// layout)*)*]` where factors are scaled by `FACTOR_SCALE` and layouts are indices
+/// into `LayoutType::ALL`. Slot 0 is the "all tags" view. `tag_order` maps each tag
+/// position to the configured tag shown there after tags were moved.
+pub const VERSION: u32 = 3;
+const FACTOR_SCALE: f32 = 10000.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagState {
+    pub tag_order: Vec<usize>,
+    pub monitors: Vec<MonitorTagState>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorTagState {
+    pub tagset: [u32; 2],
+    pub selected_tags_index: usize,
+    pub current_tag: usize,
+    pub previous_tag: usize,
+    pub num_masters: Vec<i32>,
+    pub master_factors: Vec<f32>,
+    pub layouts: Vec<String>,
+}
+
+impl TagState {
+    pub fn to_cardinals(&self) -> Vec<u32> {
+        let mut values = vec![VERSION, self.tag_order.len() as u32];
+        values.extend(self.tag_order.iter().map(|&tag| tag as u32));
+        values.push(self.monitors.len() as u32);
+        for monitor in &self.monitors {
+            values.extend([
+                monitor.tagset[0],
+                monitor.tagset[1],
+                monitor.selected_tags_index as u32,
+                monitor.current_tag as u32,
+                monitor.previous_tag as u32,
+                monitor.num_masters.len() as u32,
+            ]);
+            for ((&num_master, &factor), layout) in monitor
+                .num_masters
+                .iter()
+                .zip(&monitor.master_factors)
+                .zip(&monitor.layouts)
+            {
+                let layout = LayoutType::from_str(layout)
+                    .ok()
+                    .and_then(|layout| LayoutType::ALL.iter().position(|&l| l == layout))
+                    .unwrap_or(0);
+                values.push(num_master.max(0) as u32);
+                values.push((factor * FACTOR_SCALE).round() as u32);
+                values.push(layout as u32);
+            }
+        }
+        values
+    }
+
+    /// Returns `None` for data written by an unknown version or truncated data.
+    pub fn from_cardinals(values: &[u32]) -> Option<Self> {
+        let mut values = values.iter().copied();
+        if values.next()? != VERSION {
+            return None;
+        }
+
+        let tag_count = values.next()?;
+        let mut tag_order = Vec::new();
+        for _ in 0..tag_count {
+            tag_order.push(values.next()? as usize);
+        }
+
+        let monitor_count = values.next()?;
+        let mut monitors = Vec::new();
+        for _ in 0..monitor_count {
+            let tagset = [values.next()?, values.next()?];
+            let selected_tags_index = values.next()? as usize;
+            let current_tag = values.next()? as usize;
+            let previous_tag = values.next()? as usize;
+            let slot_count = values.next()?;
+            let mut num_masters = Vec::new();
+            let mut master_factors = Vec::new();
+            let mut layouts = Vec::new();
+            for _ in 0..slot_count {
+                num_masters.push(values.next()? as i32);
+                master_factors.push(values.next()? as f32 / FACTOR_SCALE);
+                let layout = LayoutType::ALL.get(values.next()? as usize)?;
+                layouts.push(layout.as_str().to_string());
+            }
+            monitors.push(MonitorTagState {
+                tagset,
+                selected_tags_index,
+                current_tag,
+                previous_tag,
+                num_masters,
+                master_factors,
+                layouts,
+            });
+        }
+
+        Some(Self {
+            tag_order,
+            monitors,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> TagState {
+        TagState {
+            tag_order: vec![1, 0, 2, 3],
+            monitors: vec![
+                MonitorTagState {
+                    tagset: [1 << 2, 1],
+                    selected_tags_index: 1,
+                    current_tag: 3,
+                    previous_tag: 1,
+                    num_masters: vec![1, 2, 1, 3],
+                    master_factors: vec![0.55, 0.5, 0.7, 0.3],
+                    layouts: vec![
+                        "tiling".to_string(),
+                        "monocle".to_string(),
+                        "grid".to_string(),
+                        "tiling".to_string(),
+                    ],
+                },
+                MonitorTagState {
+                    tagset: [1, 1 << 31],
+                    selected_tags_index: 0,
+                    current_tag: 0,
+                    previous_tag: 0,
+                    num_masters: vec![1],
+                    master_factors: vec![0.55],
+                    layouts: vec!["tiling".to_string()],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn round_trips_through_cardinals() {
+        let state = sample();
+        assert_eq!(TagState::from_cardinals(&state.to_cardinals()), Some(state));
+    }
+
+    #[test]
+    fn round_trips_without_monitors() {
+        let state = TagState {
+            tag_order: Vec::new(),
+            monitors: Vec::new(),
+        };
+        assert_eq!(TagState::from_cardinals(&state.to_cardinals()), Some(state));
+    }
+
+    #[test]
+    fn starts_with_the_version() {
+        assert_eq!(sample().to_cardinals()[0], VERSION);
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut values = sample().to_cardinals();
+        values[0] = VERSION - 1;
+        assert_eq!(TagState::from_cardinals(&values), None);
+        values[0] = VERSION + 1;
+        assert_eq!(TagState::from_cardinals(&values), None);
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let values = sample().to_cardinals();
+        for length in 0..values.len() {
+            assert_eq!(TagState::from_cardinals(&values[..length]), None);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_layout_indices() {
+        let mut values = sample().to_cardinals();
+        let last = values.len() - 1;
+        values[last] = LayoutType::ALL.len() as u32;
+        assert_eq!(TagState::from_cardinals(&values), None);
+    }
+}
@@ -1,52 +1,609 @@
 use crate::Config;
-use crate::animations::{AnimationConfig, ScrollAnimation};
-use crate::bar::Bar;
+use crate::animations::{AnimationConfig, LayoutAnimation, ScrollAnimation, WindowTransition};
+use crate::bar::{Bar, Segment, SegmentPosition};
 use crate::client::{Client, TagMask};
+use crate::client_state::SavedClientState;
+use crate::config::{ConfigWatcher, limits};
 use crate::errors::{ConfigError, WmError};
+use crate::gamma::Gamma;
+use crate::history::{WindowHistory, WindowOp};
+use crate::ipc::{IpcCommand, IpcServer};
 use crate::keyboard::{self, Arg, KeyAction, handlers};
-use crate::layout::GapConfig;
+use crate::layout::scrolling::ScrollingLayout;
 use crate::layout::tiling::TilingLayout;
+use crate::layout::{GapConfig, WindowGeometry};
 use crate::layout::{Layout, LayoutBox, LayoutType, layout_from_str, next_layout};
-use crate::monitor::{Monitor, detect_monitors};
-use crate::overlay::{ErrorOverlay, KeybindOverlay, Overlay};
-use std::collections::{HashMap, HashSet};
+use crate::monitor::{
+    Monitor, Rect, Strut, apply_detected_geometry, detect_monitors, rescale_rect,
+};
+use crate::overlay::{
+    BORDER_COLOR, ChordOverlay, KeybindOverlay, MessageOverlay, OsdOverlay, Overlay,
+    SwitcherOverlay,
+};
+use crate::overrides::ConfigOverrides;
+use crate::state::{ClientState, MonitorState, TagSettingsState, WmState};
+use crate::tag_state::TagState;
+use chrono::Timelike;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
 use x11rb::connection::Connection;
 use x11rb::protocol::Event;
+use x11rb::protocol::shape::{self, ConnectionExt as _};
 use x11rb::protocol::xproto::*;
 use x11rb::rust_connection::RustConnection;
 
+const SCROLL_COLUMN_STEP: f32 = 0.1;
+/// ICCCM WM_STATE value of an iconified client.
+const ICONIC_STATE: u32 = 3;
+/// How long a spawned scratchpad may take to map before its command is run again.
+const SCRATCHPAD_SPAWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// Upper bound on IPC requests served per pass, so a busy script cannot starve X events.
+const IPC_REQUESTS_PER_PASS: usize = 16;
+
 enum Control {
     Continue,
     Quit,
 }
 
+fn swap_tag_bits(mask: TagMask, first: usize, second: usize) -> TagMask {
+    let first_bit = mask & tag_mask(first) != 0;
+    let second_bit = mask & tag_mask(second) != 0;
+    if first_bit == second_bit {
+        return mask;
+    }
+    mask ^ (tag_mask(first) | tag_mask(second))
+}
+
+/// Rotates the tiled windows of a stack by one slot, towards the end for a
+/// positive direction. Floating and hidden windows keep their slots. Returns
+/// whether anything moved.
+fn rotate_tiled(order: &mut [Window], is_tiled: &[bool], direction: i32) -> bool {
+    let tiled_slots: Vec<usize> = (0..order.len()).filter(|&slot| is_tiled[slot]).collect();
+    if tiled_slots.len() < 2 || direction == 0 {
+        return false;
+    }
+
+    let mut tiled: Vec<Window> = tiled_slots.iter().map(|&slot| order[slot]).collect();
+    if direction > 0 {
+        tiled.rotate_right(1);
+    } else {
+        tiled.rotate_left(1);
+    }
+    for (&slot, window) in tiled_slots.iter().zip(tiled) {
+        order[slot] = window;
+    }
+    true
+}
+
+/// Flips a layout symbol left to right. Brackets and arrows turn around and
+/// swap sides, while runs of letters and digits keep their reading order.
+fn mirror_symbol(symbol: &str) -> String {
+    let mut tokens: Vec<String> = Vec::new();
+    for c in symbol.chars() {
+        let mirrored = match c {
+            '[' => ']',
+            ']' => '[',
+            '(' => ')',
+            ')' => '(',
+            '<' => '>',
+            '>' => '<',
+            '{' => '}',
+            '}' => '{',
+            c => c,
+        };
+        match tokens.last_mut() {
+            Some(word) if c.is_alphanumeric() && word.chars().all(char::is_alphanumeric) => {
+                word.push(c)
+            }
+            _ => tokens.push(mirrored.to_string()),
+        }
+    }
+    tokens.into_iter().rev().collect()
+}
+
+/// Swaps two entries of a per-tag list. A partial list is first filled up to both
+/// positions so its entries keep following their tag; an empty list is left alone.
+fn swap_tag_entries<T>(
+    entries: &mut Vec<T>,
+    first: usize,
+    second: usize,
+    fill: impl Fn(usize) -> T,
+) {
+    if entries.is_empty() {
+        return;
+    }
+    while entries.len() <= first.max(second) {
+        entries.push(fill(entries.len()));
+    }
+    entries.swap(first, second);
+}
+
+/// Swaps the names, labels and default layouts of two tags. Missing labels are
+/// filled with the tag names they would have fallen back to.
+fn swap_config_tags(config: &mut Config, first: usize, second: usize) {
+    let names = config.tags.clone();
+    let name = |index: usize| names.get(index).cloned().unwrap_or_default();
+
+    swap_tag_entries(&mut config.tags, first, second, name);
+    swap_tag_entries(&mut config.tag_labels_occupied, first, second, name);
+    swap_tag_entries(&mut config.tag_labels_empty, first, second, name);
+    swap_tag_entries(&mut config.tag_layouts, first, second, |_| None);
+    for monitor_config in &mut config.monitor_configs {
+        if let Some(tags) = monitor_config.tags.as_mut() {
+            swap_tag_entries(tags, first, second, name);
+        }
+    }
+}
+
+/// The swaps that turn the configured tag order into `order`, where `order[position]`
+/// is the configured tag shown at that position.
+fn tag_order_swaps(order: &[usize]) -> Vec<(usize, usize)> {
+    let mut current: Vec<usize> = (0..order.len()).collect();
+    let mut swaps = Vec::new();
+    for (position, tag) in order.iter().enumerate() {
+        let Some(from) = current.iter().position(|t| t == tag) else {
+            continue;
+        };
+        if from != position {
+            current.swap(position, from);
+            swaps.push((position, from));
+        }
+    }
+    swaps
+}
+
+/// Whether `order` is a reordering of `tag_count` tags.
+fn is_tag_order(order: &[usize], tag_count: usize) -> bool {
+    let mut sorted = order.to_vec();
+    sorted.sort_unstable();
+    sorted.len() == tag_count && sorted.iter().enumerate().all(|(index, &tag)| index == tag)
+}
+
+fn max_scroll_offset(columns: &[(i32, i32)], available_width: i32) -> i32 {
+    columns
+        .last()
+        .map_or(0, |&(x, width)| x + width - available_width)
+        .max(0)
+}
+
+fn animation_config(config: &Config) -> AnimationConfig {
+    if config.animations_enabled {
+        AnimationConfig::default()
+    } else {
+        AnimationConfig::disabled()
+    }
+}
+
+fn layout_animation(config: &Config) -> Option<AnimationConfig> {
+    config
+        .layout_animation
+        .filter(|animation| config.animations_enabled && !animation.duration.is_zero())
+}
+
+/// Instance name a scratchpad command gives its window, read from the usual
+/// `-n`/`-name`/`--name` terminal flags and falling back to the scratchpad name.
+fn scratchpad_instance(name: &str, command: &str) -> String {
+    let mut words = command.split_whitespace();
+    while let Some(word) = words.next() {
+        if let Some(instance) = word.strip_prefix("--name=") {
+            return instance.to_string();
+        }
+        if matches!(word, "-n" | "-name" | "--name")
+            && let Some(instance) = words.next()
+        {
+            return instance.to_string();
+        }
+    }
+    name.to_string()
+}
+
+/// Resolves a `_NET_WM_STATE` client message action (remove, add, toggle)
+/// against the current state.
+fn net_wm_state_action(action: u32, current: bool) -> Option<bool> {
+    match action {
+        0 => Some(false),
+        1 => Some(true),
+        2 => Some(!current),
+        _ => None,
+    }
+}
+
+fn run_hook(hook: Option<&str>, arg: &str) {
+    if let Some(command) = hook {
+        crate::signal::spawn_detached_with_args(command, &[arg]);
+    }
+}
+
+fn run_autostart_command(autostart: &crate::AutostartCommand) {
+    if autostart.once
+        && let Some(name) = autostart.program_name()
+        && std::process::Command::new("pgrep")
+            .args(["-x", "--", &name])
+            .stdout(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    {
+        eprintln!("[autostart] Already running: {}", autostart.command);
+        return;
+    }
+    crate::signal::spawn_detached(&autostart.command);
+    eprintln!("[autostart] Spawned: {}", autostart.command);
+}
+
+/// Picks the edges a mouse resize moves from where the press landed in the
+/// window: -1 for the left/top edge, 1 for the right/bottom edge and 0 to keep
+/// that axis fixed. The middle third of an edge resizes only across that edge,
+/// and the very center falls back to the nearest corner.
+fn resize_handles(x: i32, y: i32, width: i32, height: i32) -> (i32, i32) {
+    let third = |offset: i32, size: i32| {
+        if offset < size / 3 {
+            -1
+        } else if offset >= size - size / 3 {
+            1
+        } else {
+            0
+        }
+    };
+    let half = |offset: i32, size: i32| if offset < size / 2 { -1 } else { 1 };
+
+    match (third(x, width), third(y, height)) {
+        (0, 0) => (half(x, width), half(y, height)),
+        handles => handles,
+    }
+}
+
+/// Encodes a window list the way _NET_CLIENT_LIST and _NET_CLIENT_LIST_STACKING
+/// expect it: one 32-bit value per window in native byte order.
+fn window_list_bytes(windows: &[Window]) -> Vec<u8> {
+    windows
+        .iter()
+        .flat_map(|window| window.to_ne_bytes())
+        .collect()
+}
+
+fn event_time(event: &Event) -> Option<Timestamp> {
+    match event {
+        Event::KeyPress(e) | Event::KeyRelease(e) => Some(e.time),
+        Event::ButtonPress(e) | Event::ButtonRelease(e) => Some(e.time),
+        Event::MotionNotify(e) => Some(e.time),
+        Event::EnterNotify(e) | Event::LeaveNotify(e) => Some(e.time),
+        Event::PropertyNotify(e) => Some(e.time),
+        _ => None,
+    }
+}
+
+fn local_hostname() -> Vec<u8> {
+    let mut buffer = [0u8; 256];
+    let result = unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) };
+    if result != 0 {
+        return Vec::new();
+    }
+    let length = buffer
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(buffer.len());
+    buffer[..length].to_vec()
+}
+
+/// Whether a client's `WM_CLIENT_MACHINE` names this host, so its `_NET_WM_PID`
+/// refers to a local process. Clients that don't set it are taken as local, and
+/// a short name matches the same host's fully qualified one.
+fn is_local_machine(machine: &[u8], hostname: &[u8]) -> bool {
+    let short = |name: &[u8]| -> Vec<u8> {
+        name.split(|&byte| byte == b'.')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+    machine.is_empty() || short(machine) == short(hostname)
+}
+
+fn parent_pid(pid: u32) -> Option<u32> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    stat_parent_pid(&stat)
+}
+
+fn stat_parent_pid(stat: &str) -> Option<u32> {
+    // The command name may contain spaces or parentheses, so skip past its closing paren.
+    let (_, rest) = stat.rsplit_once(')')?;
+    rest.split_whitespace().nth(1)?.parse().ok()
+}
+
+fn is_descendant(mut pid: u32, ancestor: u32, parent_of: &impl Fn(u32) -> Option<u32>) -> bool {
+    while pid > 1 {
+        match parent_of(pid) {
+            Some(parent) if parent == ancestor => return true,
+            Some(parent) => pid = parent,
+            None => return false,
+        }
+    }
+    false
+}
+
+/// The terminal `window` was started from, found by walking up its process tree.
+/// Floating windows and terminals themselves never swallow, and terminals that
+/// already swallowed a window are hidden on no tags.
+fn swallowing_terminal(
+    window: Window,
+    windows: &[Window],
+    clients: &HashMap<Window, Client>,
+    parent_of: impl Fn(u32) -> Option<u32>,
+) -> Option<Window> {
+    let client = clients.get(&window)?;
+    if client.pid == 0 || client.is_terminal || client.is_floating {
+        return None;
+    }
+
+    windows.iter().copied().find(|terminal| {
+        clients.get(terminal).is_some_and(|candidate| {
+            candidate.is_terminal
+                && candidate.pid != 0
+                && candidate.tags != 0
+                && is_descendant(client.pid, candidate.pid, &parent_of)
+        })
+    })
+}
+
+/// Whether an event's 16-bit sequence number was generated at or after a request.
+fn is_at_or_after(event_sequence: u16, request_sequence: u64) -> bool {
+    event_sequence.wrapping_sub(request_sequence as u16) < 0x8000
+}
+
+fn config_watcher(config: &Config) -> Option<ConfigWatcher> {
+    config
+        .path
+        .as_deref()
+        .filter(|_| config.auto_reload)
+        .and_then(ConfigWatcher::new)
+}
+
+fn tag_masks<'a>(
+    clients: impl Iterator<Item = &'a Client>,
+    monitor_index: usize,
+) -> (TagMask, TagMask) {
+    let mut occupied_tags: TagMask = 0;
+    let mut urgent_tags: TagMask = 0;
+    for client in clients.filter(|client| client.monitor_index == monitor_index && !client.is_popup)
+    {
+        occupied_tags |= client.tags;
+        if client.is_urgent {
+            urgent_tags |= client.tags;
+        }
+    }
+    (occupied_tags, urgent_tags)
+}
+
+/// Index of the next entry when cycling `direction` steps through `len` entries. Starts
+/// from the first or last entry when the current one is not part of the cycle.
+fn cycle_index(len: usize, current: Option<usize>, direction: i32) -> usize {
+    match current {
+        Some(index) => (index as i32 + direction).rem_euclid(len as i32) as usize,
+        None if direction > 0 => 0,
+        None => len - 1,
+    }
+}
+
+/// Window rule settings that only apply once it is known whether the window floats.
+#[derive(Debug, Clone, Copy, Default)]
+struct RulePlacement {
+    /// `x`, `y`, `width` and `height` relative to the monitor's window area.
+    geometry: [Option<i32>; 4],
+    center: bool,
+}
+
+/// Geometry a rule gives a floating window with border width `border`. Unset values keep
+/// the `current` geometry, and the result fits inside the window `area`.
+fn placed_geometry(
+    [x, y, width, height]: [Option<i32>; 4],
+    current: Rect,
+    area: Rect,
+    border: i32,
+) -> Rect {
+    let (area_x, area_y, area_width, area_height) = area;
+    let width = width
+        .unwrap_or(current.2)
+        .clamp(1, (area_width - 2 * border).max(1));
+    let height = height
+        .unwrap_or(current.3)
+        .clamp(1, (area_height - 2 * border).max(1));
+    let x = x.map_or(current.0, |x| area_x.saturating_add(x));
+    let y = y.map_or(current.1, |y| area_y.saturating_add(y));
+    let x = x.clamp(
+        area_x,
+        (area_x + area_width - width - 2 * border).max(area_x),
+    );
+    let y = y.clamp(
+        area_y,
+        (area_y + area_height - height - 2 * border).max(area_y),
+    );
+    (x, y, width, height)
+}
+
+/// What managing a new window does to focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NewWindowFocus {
+    /// Focus the window, selecting its monitor.
+    Focus,
+    /// Keep focus where it is and mark the window urgent instead.
+    MarkUrgent,
+    /// Keep focus where it is; used for notifications, splash screens and utility windows.
+    Ignore,
+}
+
+fn new_window_focus(
+    focus_new_windows: bool,
+    steal_monitor_focus: bool,
+    is_popup: bool,
+    client_monitor: usize,
+    selected_monitor: usize,
+) -> NewWindowFocus {
+    if is_popup {
+        NewWindowFocus::Ignore
+    } else if !focus_new_windows || (!steal_monitor_focus && client_monitor != selected_monitor) {
+        NewWindowFocus::MarkUrgent
+    } else {
+        NewWindowFocus::Focus
+    }
+}
+
+/// Enforces the flag invariants on a client and mirrors its flags into the floating and
+/// fullscreen sets. A window without a client is dropped from both sets.
+fn sync_window_sets(
+    window: Window,
+    client: Option<&mut Client>,
+    floating_windows: &mut HashSet<Window>,
+    fullscreen_windows: &mut HashSet<Window>,
+) {
+    let (is_floating, is_fullscreen) = match client {
+        Some(client) => {
+            client.is_floating |= client.is_fullscreen;
+            client.is_above &= client.is_floating;
+            client.is_sticky &= client.is_floating;
+            (client.is_floating, client.is_fullscreen)
+        }
+        None => (false, false),
+    };
+
+    if is_floating {
+        floating_windows.insert(window);
+    } else {
+        floating_windows.remove(&window);
+    }
+    if is_fullscreen {
+        fullscreen_windows.insert(window);
+    } else {
+        fullscreen_windows.remove(&window);
+    }
+}
+
+/// The full `_NET_WM_STATE` list for a client's flags, given the fullscreen, above,
+/// below and sticky atoms. Fake fullscreen is reported as fullscreen.
+fn net_wm_state_atoms(client: &Client, [fullscreen, above, below, sticky]: [Atom; 4]) -> Vec<Atom> {
+    [
+        (client.reports_fullscreen(), fullscreen),
+        (client.is_above, above),
+        (client.is_below, below),
+        (client.is_sticky, sticky),
+    ]
+    .into_iter()
+    .filter_map(|(set, atom)| set.then_some(atom))
+    .collect()
+}
+
 pub fn tag_mask(tag: usize) -> TagMask {
     1 << tag
 }
 
+/// The mask with the first `count` tags set, saturating at all 32 tags.
+pub fn all_tags_mask(count: usize) -> TagMask {
+    match count {
+        0 => 0,
+        count => TagMask::MAX >> (32 - count.min(32)),
+    }
+}
+
 pub fn unmask_tag(mask: TagMask) -> usize {
     mask.trailing_zeros() as usize
 }
 
+fn parse_delta_arg(arg: &Arg) -> Option<(i32, i32)> {
+    let (first, second) = match arg {
+        Arg::Table(_) => (arg.field("x")?, arg.field("y")?),
+        Arg::Array(values) => match values.as_slice() {
+            [first, second] => (first.as_str(), second.as_str()),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let parse_number = |value: &str| value.trim().parse::<f64>().ok().map(|n| n as i32);
+
+    if let (Some(x), Some(y)) = (parse_number(first), parse_number(second)) {
+        return Some((x, y));
+    }
+
+    let step = parse_number(second)?;
+    match first {
+        "left" => Some((-step, 0)),
+        "right" => Some((step, 0)),
+        "up" => Some((0, -step)),
+        "down" => Some((0, step)),
+        _ => None,
+    }
+}
+
+/// Alt-Tab style switch in progress: candidates in most-recently-focused
+/// order and the modifier keys whose release commits the selection.
+struct RecentCycle {
+    windows: Vec<Window>,
+    selected: usize,
+    original: Option<Window>,
+    modifier_keycodes: Vec<Keycode>,
+}
+
+#[derive(Clone, Copy)]
+enum SnapSpan {
+    Whole,
+    First,
+    Second,
+}
+
+fn snap_region(region: &str) -> Option<(SnapSpan, SnapSpan)> {
+    match region {
+        "left" => Some((SnapSpan::First, SnapSpan::Whole)),
+        "right" => Some((SnapSpan::Second, SnapSpan::Whole)),
+        "top" => Some((SnapSpan::Whole, SnapSpan::First)),
+        "bottom" => Some((SnapSpan::Whole, SnapSpan::Second)),
+        "top-left" => Some((SnapSpan::First, SnapSpan::First)),
+        "top-right" => Some((SnapSpan::Second, SnapSpan::First)),
+        "bottom-left" => Some((SnapSpan::First, SnapSpan::Second)),
+        "bottom-right" => Some((SnapSpan::Second, SnapSpan::Second)),
+        "full" => Some((SnapSpan::Whole, SnapSpan::Whole)),
+        _ => None,
+    }
+}
+
+/// Whether `region` names a region `oxwm.client.snap` accepts.
+pub(crate) fn is_snap_region(region: &str) -> bool {
+    snap_region(region).is_some()
+}
+
 struct AtomCache {
     net_supported: Atom,
     net_supporting_wm_check: Atom,
     net_current_desktop: Atom,
-    net_client_info: Atom,
+    net_number_of_desktops: Atom,
+    net_wm_desktop: Atom,
     wm_state: Atom,
     wm_protocols: Atom,
     wm_delete_window: Atom,
     net_wm_state: Atom,
     net_wm_state_fullscreen: Atom,
+    net_wm_state_above: Atom,
+    net_wm_state_below: Atom,
+    net_wm_state_sticky: Atom,
     net_wm_window_type: Atom,
     net_wm_window_type_dialog: Atom,
+    net_wm_window_type_desktop: Atom,
+    net_wm_window_type_dock: Atom,
+    net_wm_window_type_notification: Atom,
+    net_wm_window_type_utility: Atom,
+    net_wm_window_type_splash: Atom,
     wm_name: Atom,
     net_wm_name: Atom,
     utf8_string: Atom,
     net_active_window: Atom,
     wm_take_focus: Atom,
     net_client_list: Atom,
+    net_client_list_stacking: Atom,
+    net_wm_strut: Atom,
+    net_wm_strut_partial: Atom,
+    net_wm_pid: Atom,
+    motif_wm_hints: Atom,
+    wm_window_role: Atom,
+    net_desktop_names: Atom,
+    oxwm_tag_state: Atom,
+    oxwm_client_state: Atom,
 }
 
 impl AtomCache {
@@ -61,13 +618,18 @@ impl AtomCache {
             .reply()?
             .atom;
 
-        let net_current_desktop = connection
-            .intern_atom(false, b"_NET_CURRENT_DESKTOP")?
+        let net_number_of_desktops = connection
+            .intern_atom(false, b"_NET_NUMBER_OF_DESKTOPS")?
+            .reply()?
+            .atom;
+
+        let net_wm_desktop = connection
+            .intern_atom(false, b"_NET_WM_DESKTOP")?
             .reply()?
             .atom;
 
-        let net_client_info = connection
-            .intern_atom(false, b"_NET_CLIENT_INFO")?
+        let net_current_desktop = connection
+            .intern_atom(false, b"_NET_CURRENT_DESKTOP")?
             .reply()?
             .atom;
 
@@ -93,6 +655,21 @@ impl AtomCache {
             .reply()?
             .atom;
 
+        let net_wm_state_above = connection
+            .intern_atom(false, b"_NET_WM_STATE_ABOVE")?
+            .reply()?
+            .atom;
+
+        let net_wm_state_below = connection
+            .intern_atom(false, b"_NET_WM_STATE_BELOW")?
+            .reply()?
+            .atom;
+
+        let net_wm_state_sticky = connection
+            .intern_atom(false, b"_NET_WM_STATE_STICKY")?
+            .reply()?
+            .atom;
+
         let net_wm_window_type = connection
             .intern_atom(false, b"_NET_WM_WINDOW_TYPE")?
             .reply()?
@@ -103,6 +680,31 @@ impl AtomCache {
             .reply()?
             .atom;
 
+        let net_wm_window_type_desktop = connection
+            .intern_atom(false, b"_NET_WM_WINDOW_TYPE_DESKTOP")?
+            .reply()?
+            .atom;
+
+        let net_wm_window_type_dock = connection
+            .intern_atom(false, b"_NET_WM_WINDOW_TYPE_DOCK")?
+            .reply()?
+            .atom;
+
+        let net_wm_window_type_notification = connection
+            .intern_atom(false, b"_NET_WM_WINDOW_TYPE_NOTIFICATION")?
+            .reply()?
+            .atom;
+
+        let net_wm_window_type_utility = connection
+            .intern_atom(false, b"_NET_WM_WINDOW_TYPE_UTILITY")?
+            .reply()?
+            .atom;
+
+        let net_wm_window_type_splash = connection
+            .intern_atom(false, b"_NET_WM_WINDOW_TYPE_SPLASH")?
+            .reply()?
+            .atom;
+
         let wm_name = AtomEnum::WM_NAME.into();
         let net_wm_name = connection
             .intern_atom(false, b"_NET_WM_NAME")?
@@ -124,26 +726,126 @@ impl AtomCache {
             .reply()?
             .atom;
 
+        let net_client_list_stacking = connection
+            .intern_atom(false, b"_NET_CLIENT_LIST_STACKING")?
+            .reply()?
+            .atom;
+
+        let net_wm_strut = connection
+            .intern_atom(false, b"_NET_WM_STRUT")?
+            .reply()?
+            .atom;
+
+        let net_wm_strut_partial = connection
+            .intern_atom(false, b"_NET_WM_STRUT_PARTIAL")?
+            .reply()?
+            .atom;
+
+        let net_wm_pid = connection.intern_atom(false, b"_NET_WM_PID")?.reply()?.atom;
+
+        let motif_wm_hints = connection
+            .intern_atom(false, b"_MOTIF_WM_HINTS")?
+            .reply()?
+            .atom;
+
+        let wm_window_role = connection
+            .intern_atom(false, b"WM_WINDOW_ROLE")?
+            .reply()?
+            .atom;
+
+        let net_desktop_names = connection
+            .intern_atom(false, b"_NET_DESKTOP_NAMES")?
+            .reply()?
+            .atom;
+
+        let oxwm_tag_state = connection
+            .intern_atom(false, b"_OXWM_TAG_STATE")?
+            .reply()?
+            .atom;
+
+        let oxwm_client_state = connection
+            .intern_atom(false, b"_OXWM_CLIENT_STATE")?
+            .reply()?
+            .atom;
+
         Ok(Self {
             net_supported,
             net_supporting_wm_check,
             net_current_desktop,
-            net_client_info,
+            net_number_of_desktops,
+            net_wm_desktop,
             wm_state,
             wm_protocols,
             wm_delete_window,
             net_wm_state,
             net_wm_state_fullscreen,
+            net_wm_state_above,
+            net_wm_state_below,
+            net_wm_state_sticky,
             net_wm_window_type,
             net_wm_window_type_dialog,
+            net_wm_window_type_desktop,
+            net_wm_window_type_dock,
+            net_wm_window_type_notification,
+            net_wm_window_type_utility,
+            net_wm_window_type_splash,
             wm_name,
             net_wm_name,
             utf8_string,
             net_active_window,
             wm_take_focus,
             net_client_list,
+            net_client_list_stacking,
+            net_wm_strut,
+            net_wm_strut_partial,
+            net_wm_pid,
+            motif_wm_hints,
+            wm_window_role,
+            net_desktop_names,
+            oxwm_tag_state,
+            oxwm_client_state,
         })
     }
+
+    /// The `_NET_WM_STATE` atoms for fullscreen, above, below and sticky, in that order.
+    fn net_wm_states(&self) -> [Atom; 4] {
+        [
+            self.net_wm_state_fullscreen,
+            self.net_wm_state_above,
+            self.net_wm_state_below,
+            self.net_wm_state_sticky,
+        ]
+    }
+
+    /// The EWMH hints oxwm implements, published as `_NET_SUPPORTED`.
+    fn supported(&self) -> Vec<Atom> {
+        vec![
+            self.net_supported,
+            self.net_supporting_wm_check,
+            self.net_wm_state,
+            self.net_wm_state_fullscreen,
+            self.net_wm_state_above,
+            self.net_wm_state_below,
+            self.net_wm_state_sticky,
+            self.net_wm_window_type,
+            self.net_wm_window_type_dialog,
+            self.net_wm_window_type_desktop,
+            self.net_wm_window_type_dock,
+            self.net_wm_window_type_notification,
+            self.net_wm_window_type_utility,
+            self.net_wm_window_type_splash,
+            self.net_active_window,
+            self.net_wm_name,
+            self.net_current_desktop,
+            self.net_number_of_desktops,
+            self.net_wm_desktop,
+            self.net_desktop_names,
+            self.net_client_list,
+            self.net_client_list_stacking,
+            self.net_wm_strut,
+            self.net_wm_strut_partial,
+        ]
+    }
 }
 
 pub struct WindowManager {
@@ -151,14 +853,22 @@ pub struct WindowManager {
     connection: RustConnection,
     screen_number: usize,
     root: Window,
-    _wm_check_window: Window,
+    wm_check_window: Window,
     screen: Screen,
     windows: Vec<Window>,
     clients: HashMap<Window, Client>,
-    layout: LayoutBox,
+    layouts: Vec<LayoutBox>,
     gaps_enabled: bool,
+    /// Window the pointer was last warped into, with the sequence number of the warp.
+    expected_warp: Option<(Window, u64)>,
+    active_theme: Option<usize>,
+    scheduled_theme: Option<usize>,
     floating_windows: HashSet<Window>,
     fullscreen_windows: HashSet<Window>,
+    /// The `_NET_WM_STATE` last published for each client.
+    net_wm_states: HashMap<Window, Vec<Atom>>,
+    /// This host's name, checked against `WM_CLIENT_MACHINE` before trusting a PID.
+    hostname: Vec<u8>,
     bars: Vec<Bar>,
     tab_bars: Vec<crate::tab_bar::TabBar>,
     show_bar: bool,
@@ -166,16 +876,49 @@ pub struct WindowManager {
     selected_monitor: usize,
     atoms: AtomCache,
     previous_focused: Option<Window>,
+    last_event_time: Timestamp,
+    config_watcher: Option<ConfigWatcher>,
+    focus_serial: u64,
     display: *mut x11::xlib::Display,
     font: crate::bar::font::Font,
     keychord_state: keyboard::handlers::KeychordState,
     current_key: usize,
     keyboard_mapping: Option<keyboard::KeyboardMapping>,
     error_message: Option<String>,
-    overlay: ErrorOverlay,
+    overlay: MessageOverlay,
+    message_overlay: MessageOverlay,
     keybind_overlay: KeybindOverlay,
+    switcher_overlay: SwitcherOverlay,
+    chord_overlay: ChordOverlay,
+    osd_overlay: OsdOverlay,
+    class_cycle: Option<(Vec<Window>, usize)>,
+    recent_cycle: Option<RecentCycle>,
     scroll_animation: ScrollAnimation,
     animation_config: AnimationConfig,
+    layout_animation: Option<AnimationConfig>,
+    /// Horizontal offset for the next tag switch's tiled windows on a monitor,
+    /// so they are mapped off to the side and slide in instead of jumping.
+    slide_offset: Option<(usize, i32)>,
+    deferred_events: VecDeque<Event>,
+    gamma: Gamma,
+    scratchpads: HashMap<String, Window>,
+    /// Spawned scratchpads waiting for their window, keyed by expected instance,
+    /// with the time their command was run.
+    pending_scratchpads: HashMap<String, (String, std::time::Instant)>,
+    history: WindowHistory,
+    shape_supported: bool,
+    segments: BTreeMap<String, Segment>,
+    ipc: Option<IpcServer>,
+    struts: HashMap<Window, Strut>,
+    desktops: HashMap<Window, usize>,
+    /// Managed windows from bottom to top, for _NET_CLIENT_LIST_STACKING.
+    stacking_order: Vec<Window>,
+    overrides: ConfigOverrides,
+    /// State last written to the root and client windows, so only changes are written.
+    saved_tag_state: Option<TagState>,
+    /// The configured tag shown at each position after tags were moved.
+    tag_order: Vec<usize>,
+    saved_client_states: HashMap<Window, SavedClientState>,
 }
 
 type WmResult<T> = Result<T, WmError>;
@@ -194,6 +937,7 @@ impl WindowManager {
                         | EventMask::SUBSTRUCTURE_NOTIFY
                         | EventMask::PROPERTY_CHANGE
                         | EventMask::KEY_PRESS
+                        | EventMask::KEY_RELEASE
                         | EventMask::BUTTON_PRESS
                         | EventMask::POINTER_MOTION,
                 ),
@@ -236,10 +980,33 @@ impl WindowManager {
         }
 
         let mut monitors = detect_monitors(&connection, &screen, root)?;
-        for monitor in monitors.iter_mut() {
-            monitor.init_pertag(config.tags.len(), "tiling");
+        for (monitor_index, monitor) in monitors.iter_mut().enumerate() {
+            let monitor_config = config.monitor_config(monitor_index);
+            let default_layout = monitor_config
+                .and_then(|monitor_config| monitor_config.default_layout.as_deref())
+                .unwrap_or("tiling");
+            monitor.show_bar = config.show_bar;
+            monitor.init_pertag(config.tags.len(), default_layout, &config.tag_layouts);
+
+            if let Some(tag) = monitor_config.and_then(|monitor_config| monitor_config.start_tag) {
+                monitor.tagset[monitor.selected_tags_index] = tag_mask(tag);
+                if let Some(ref mut pertag) = monitor.pertag {
+                    pertag.current_tag = tag + 1;
+                }
+            }
         }
 
+        let layouts: Vec<LayoutBox> = monitors
+            .iter()
+            .map(|monitor| {
+                monitor
+                    .pertag
+                    .as_ref()
+                    .and_then(|pertag| layout_from_str(&pertag.layouts[pertag.current_tag]).ok())
+                    .unwrap_or_else(|| Box::new(TilingLayout))
+            })
+            .collect();
+
         let display = unsafe { x11::xlib::XOpenDisplay(std::ptr::null()) };
         if display.is_null() {
             return Err(WmError::X11(crate::errors::X11Error::DisplayOpenFailed));
@@ -252,11 +1019,11 @@ impl WindowManager {
             x11::xlib::XDefineCursor(display, root as u64, normal_cursor);
         }
 
-        let font = crate::bar::font::Font::new(display, screen_number as i32, &config.font)?;
+        let font = crate::bar::font::Font::new(display, screen_number as i32, &config.fonts)?;
 
         let mut bars = Vec::new();
-        for monitor in monitors.iter() {
-            let bar = Bar::new(
+        for (monitor_index, monitor) in monitors.iter().enumerate() {
+            let mut bar = Bar::new(
                 &connection,
                 &screen,
                 screen_number,
@@ -268,6 +1035,7 @@ impl WindowManager {
                 monitor.screen_width as u16,
                 normal_cursor as u32,
             )?;
+            bar.update_tags(&config, monitor_index);
             bars.push(bar);
         }
 
@@ -280,11 +1048,12 @@ impl WindowManager {
                 screen_number,
                 display,
                 &font,
-                (monitor.screen_x + config.gap_outer_horizontal as i32) as i16,
-                (monitor.screen_y as f32 + bar_height + config.gap_outer_vertical as f32) as i16,
+                (monitor.screen_x + config.gap_outer_left as i32) as i16,
+                (monitor.screen_y as f32 + bar_height + config.gap_outer_top as f32) as i16,
                 monitor
                     .screen_width
-                    .saturating_sub(2 * config.gap_outer_horizontal as i32) as u16,
+                    .saturating_sub((config.gap_outer_left + config.gap_outer_right) as i32)
+                    as u16,
                 config.scheme_occupied,
                 config.scheme_selected,
                 normal_cursor as u32,
@@ -293,22 +1062,28 @@ impl WindowManager {
         }
 
         let gaps_enabled = config.gaps_enabled;
+        let animation_config = animation_config(&config);
+        let layout_animation = layout_animation(&config);
+        let show_bar = config.show_bar;
+        let config_watcher = config_watcher(&config);
+
+        let shape_supported = connection
+            .shape_query_version()
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some();
+
+        let ipc = match IpcServer::bind() {
+            Ok(server) => Some(server),
+            Err(e) => {
+                eprintln!("Failed to start IPC server: {}", e);
+                None
+            }
+        };
 
         let atoms = AtomCache::new(&connection)?;
 
-        let supported_atoms: Vec<Atom> = vec![
-            atoms.net_supported,
-            atoms.net_supporting_wm_check,
-            atoms.net_wm_state,
-            atoms.net_wm_state_fullscreen,
-            atoms.net_wm_window_type,
-            atoms.net_wm_window_type_dialog,
-            atoms.net_active_window,
-            atoms.net_wm_name,
-            atoms.net_current_desktop,
-            atoms.net_client_info,
-            atoms.net_client_list,
-        ];
+        let supported_atoms = atoms.supported();
         let supported_bytes: Vec<u8> = supported_atoms
             .iter()
             .flat_map(|a| a.to_ne_bytes())
@@ -328,14 +1103,14 @@ impl WindowManager {
             screen.root_depth,
             wm_check_window,
             root,
-            0,
-            0,
+            -1,
+            -1,
             1,
             1,
             0,
             WindowClass::INPUT_OUTPUT,
             0,
-            &CreateWindowAux::new(),
+            &CreateWindowAux::new().override_redirect(1),
         )?;
 
         connection.change_property(
@@ -368,38 +1143,46 @@ impl WindowManager {
             &wm_check_window.to_ne_bytes(),
         )?;
 
-        let overlay = ErrorOverlay::new(
-            &connection,
-            &screen,
-            screen_number,
-            display,
-            &font,
-            screen.width_in_pixels,
-        )?;
+        let overlay = MessageOverlay::new(&connection, &screen, screen_number, display, 0xff5555)?;
+        let message_overlay =
+            MessageOverlay::new(&connection, &screen, screen_number, display, BORDER_COLOR)?;
 
         let keybind_overlay =
             KeybindOverlay::new(&connection, &screen, screen_number, display, config.modkey)?;
+        let switcher_overlay = SwitcherOverlay::new(&connection, &screen, screen_number, display)?;
+        let chord_overlay =
+            ChordOverlay::new(&connection, &screen, screen_number, display, config.modkey)?;
+        let osd_overlay = OsdOverlay::new(&connection, &screen, screen_number, display)?;
+        let tag_order = (0..config.tags.len()).collect();
 
         let mut window_manager = Self {
             config,
             connection,
             screen_number,
             root,
-            _wm_check_window: wm_check_window,
+            wm_check_window,
             screen,
             windows: Vec::new(),
             clients: HashMap::new(),
-            layout: Box::new(TilingLayout),
+            layouts,
             gaps_enabled,
+            expected_warp: None,
+            active_theme: None,
+            scheduled_theme: None,
             floating_windows: HashSet::new(),
             fullscreen_windows: HashSet::new(),
+            net_wm_states: HashMap::new(),
+            hostname: local_hostname(),
             bars,
             tab_bars,
-            show_bar: true,
+            show_bar,
             monitors,
             selected_monitor: 0,
             atoms,
             previous_focused: None,
+            last_event_time: x11rb::CURRENT_TIME,
+            config_watcher,
+            focus_serial: 0,
             display,
             font,
             keychord_state: keyboard::handlers::KeychordState::Idle,
@@ -407,42 +1190,105 @@ impl WindowManager {
             keyboard_mapping: None,
             error_message: None,
             overlay,
+            message_overlay,
             keybind_overlay,
+            switcher_overlay,
+            chord_overlay,
+            osd_overlay,
+            class_cycle: None,
+            recent_cycle: None,
             scroll_animation: ScrollAnimation::new(),
-            animation_config: AnimationConfig::default(),
+            layout_animation,
+            slide_offset: None,
+            animation_config,
+            deferred_events: VecDeque::new(),
+            gamma: Gamma::new(),
+            scratchpads: HashMap::new(),
+            pending_scratchpads: HashMap::new(),
+            history: WindowHistory::default(),
+            shape_supported,
+            segments: BTreeMap::new(),
+            ipc,
+            struts: HashMap::new(),
+            desktops: HashMap::new(),
+            stacking_order: Vec::new(),
+            overrides: ConfigOverrides::default(),
+            saved_tag_state: None,
+            tag_order,
+            saved_client_states: HashMap::new(),
         };
 
         for tab_bar in &window_manager.tab_bars {
             tab_bar.hide(&window_manager.connection)?;
         }
 
+        for property in [
+            window_manager.atoms.net_client_list,
+            window_manager.atoms.net_client_list_stacking,
+        ] {
+            window_manager
+                .connection
+                .delete_property(window_manager.root, property)?;
+        }
+        window_manager.apply_overlay_colors()?;
         window_manager.scan_existing_windows()?;
         window_manager.update_bar()?;
-        window_manager.run_autostart_commands();
 
         Ok(window_manager)
     }
 
     pub fn show_startup_config_error(&mut self, error: ConfigError) {
-        let monitor = &self.monitors[self.selected_monitor];
-        let monitor_x = monitor.screen_x as i16;
-        let monitor_y = monitor.screen_y as i16;
-        let screen_width = monitor.screen_width as u16;
-        let screen_height = monitor.screen_height as u16;
-
-        if let Err(e) = self.overlay.show_error(
-            &self.connection,
-            &self.font,
-            error,
-            monitor_x,
-            monitor_y,
-            screen_width,
-            screen_height,
-        ) {
+        let monitor = self.monitors[self.selected_monitor].screen_rect().into();
+        if let Err(e) = self
+            .overlay
+            .show_error(&self.connection, &self.font, error, monitor)
+        {
             eprintln!("Failed to show config error overlay: {:?}", e);
         }
     }
 
+    fn reload_config(&mut self) -> WmResult<()> {
+        match self.try_reload_config() {
+            Ok(()) => {
+                self.gaps_enabled = self.config.gaps_enabled;
+                self.reset_tag_order()?;
+                self.apply_overlay_colors()?;
+                let now = chrono::Local::now();
+                self.apply_scheduled_theme(now.hour() * 60 + now.minute())?;
+                self.error_message = None;
+                self.update_desktop_names()?;
+                self.place_visible_scratchpads()?;
+                if self.config.warnings.is_empty() {
+                    if let Err(error) = self.overlay.hide(&self.connection) {
+                        eprintln!("Failed to hide overlay after config reload: {:?}", error);
+                    }
+                } else {
+                    for warning in &self.config.warnings {
+                        eprintln!("Config warning: {}", warning);
+                    }
+                    let warnings = self.config.warnings.join("\n");
+                    self.show_startup_config_error(ConfigError::ValidationError(warnings));
+                }
+                self.refresh_borders()?;
+                self.apply_layout()?;
+                self.update_bar()?;
+            }
+            Err(err) => {
+                eprintln!("Config reload error: {}", err);
+                self.error_message = Some(err.to_string());
+                let monitor = self.monitors[self.selected_monitor].screen_rect().into();
+                match self
+                    .overlay
+                    .show_error(&self.connection, &self.font, err, monitor)
+                {
+                    Ok(()) => eprintln!("Error modal displayed"),
+                    Err(e) => eprintln!("Failed to show error modal: {:?}", e),
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn try_reload_config(&mut self) -> Result<(), ConfigError> {
         let lua_path = self
             .config
@@ -457,18 +1303,28 @@ impl WindowManager {
         let config_str =
             std::fs::read_to_string(lua_path).map_err(|e| ConfigError::CouldNotReadConfig(e))?;
 
-        let config_dir = lua_path.parent();
-
-        let new_config = crate::config::parse_lua_config(&config_str, config_dir)?;
+        let mut new_config = crate::config::parse_lua_config(&config_str, Some(lua_path))?;
+        if new_config.tags.len() == self.tag_order.len() {
+            for (first, second) in tag_order_swaps(&self.tag_order) {
+                swap_config_tags(&mut new_config, first, second);
+            }
+        }
 
         let lua_path = self.config.path.take();
 
         self.config = new_config;
         self.config.path = lua_path;
         self.error_message = None;
+        self.overrides = ConfigOverrides::default();
+        self.config_watcher = config_watcher(&self.config);
+        self.animation_config = animation_config(&self.config);
+        self.layout_animation = layout_animation(&self.config);
+        self.active_theme = None;
+        self.scheduled_theme = None;
+        self.pending_scratchpads.clear();
 
-        for bar in &mut self.bars {
-            bar.update_from_config(&self.config);
+        for (monitor_index, bar) in self.bars.iter_mut().enumerate() {
+            bar.update_from_config(&self.config, monitor_index);
         }
 
         Ok(())
@@ -476,11 +1332,12 @@ impl WindowManager {
 
     fn scan_existing_windows(&mut self) -> WmResult<()> {
         let tree = self.connection.query_tree(self.root)?.reply()?;
-        let net_client_info = self.atoms.net_client_info;
         let wm_state_atom = self.atoms.wm_state;
 
         for &window in &tree.children {
-            if self.bars.iter().any(|bar| bar.window() == window) {
+            if self.clients.contains_key(&window)
+                || self.bars.iter().any(|bar| bar.window() == window)
+            {
                 continue;
             }
 
@@ -493,19 +1350,23 @@ impl WindowManager {
             }
 
             if attrs.map_state == MapState::VIEWABLE {
-                let _tag = self.get_saved_tag(window, net_client_info)?;
-                self.windows.push(window);
+                let tag = self.get_saved_tag(window)?;
+                self.manage_existing_window(window, tag)?;
                 continue;
             }
 
             if attrs.map_state == MapState::UNMAPPED {
-                let has_wm_state = self
+                // Withdrawn windows keep a WM_STATE of 0 and must stay unmanaged; only
+                // iconified clients are adopted.
+                let is_iconic = self
                     .connection
-                    .get_property(false, window, wm_state_atom, AtomEnum::ANY, 0, 2)?
+                    .get_property(false, window, wm_state_atom, wm_state_atom, 0, 2)?
                     .reply()
-                    .is_ok_and(|prop| !prop.value.is_empty());
+                    .ok()
+                    .and_then(|prop| prop.value32().and_then(|mut values| values.next()))
+                    == Some(ICONIC_STATE);
 
-                if !has_wm_state {
+                if !is_iconic {
                     continue;
                 }
 
@@ -516,43 +1377,49 @@ impl WindowManager {
                     .is_ok_and(|prop| !prop.value.is_empty());
 
                 if has_wm_class {
-                    let _tag = self.get_saved_tag(window, net_client_info)?;
-                    self.connection.map_window(window)?;
-                    self.windows.push(window);
+                    let tag = self.get_saved_tag(window)?;
+                    self.manage_existing_window(window, tag)?;
                 }
             }
         }
 
-        if let Some(&first) = self.windows.first() {
+        let has_focus = self
+            .monitors
+            .get(self.selected_monitor)
+            .is_some_and(|m| m.selected_client.is_some());
+
+        if !has_focus && let Some(&first) = self.windows.first() {
             self.focus(Some(first))?;
         }
 
+        // Also replaces a list left on the root window by a previous session.
+        self.update_client_list()?;
         self.apply_layout()?;
         Ok(())
     }
 
-    fn get_saved_tag(&self, window: Window, net_client_info: Atom) -> WmResult<TagMask> {
-        match self
-            .connection
-            .get_property(false, window, net_client_info, AtomEnum::CARDINAL, 0, 2)?
-            .reply()
-        {
-            Ok(prop) if prop.value.len() >= 4 => {
-                let tags = u32::from_ne_bytes([
-                    prop.value[0],
-                    prop.value[1],
-                    prop.value[2],
-                    prop.value[3],
-                ]);
+    fn manage_existing_window(&mut self, window: Window, tag: TagMask) -> WmResult<()> {
+        self.manage_window(window)?;
+        if self.struts.contains_key(&window) || self.desktops.contains_key(&window) {
+            return Ok(());
+        }
 
-                if tags != 0 && tags < (1 << self.config.tags.len()) {
-                    return Ok(tags);
-                }
-            }
-            Ok(_) => {}
-            Err(e) => {
-                eprintln!("No _NET_CLIENT_INFO property ({})", e);
-            }
+        // Scratchpads are re-associated through their rules and start out hidden again.
+        let tags = if self.is_scratchpad(window) { 0 } else { tag };
+        if let Some(client) = self.clients.get_mut(&window) {
+            client.tags = tags;
+        }
+        self.update_client_desktop(window)?;
+        Ok(())
+    }
+
+    fn get_saved_tag(&self, window: Window) -> WmResult<TagMask> {
+        let saved = self
+            .read_saved_client_state(window)?
+            .map(|state| state.tags)
+            .filter(|&tags| tags != 0 && tags & !all_tags_mask(self.config.tags.len()) == 0);
+        if let Some(tags) = saved {
+            return Ok(tags);
         }
 
         Ok(self
@@ -562,22 +1429,50 @@ impl WindowManager {
             .unwrap_or(tag_mask(0)))
     }
 
-    fn save_client_tag(&self, window: Window, tag: TagMask) -> WmResult<()> {
-        let net_client_info = self.atoms.net_client_info;
+    fn read_saved_client_state(&self, window: Window) -> WmResult<Option<SavedClientState>> {
+        let values: Option<Vec<u32>> = self
+            .connection
+            .get_property(
+                false,
+                window,
+                self.atoms.oxwm_client_state,
+                AtomEnum::CARDINAL,
+                0,
+                16,
+            )?
+            .reply()
+            .ok()
+            .and_then(|reply| reply.value32().map(|values| values.collect()));
+        Ok(values.and_then(|values| SavedClientState::from_cardinals(&values)))
+    }
 
-        let bytes = tag.to_ne_bytes().to_vec();
+    /// Publishes the client's tags as _NET_WM_DESKTOP: the lowest tag, or all
+    /// desktops for clients on several tags or sticky ones. Hidden scratchpads
+    /// have no tag and lose the property.
+    fn update_client_desktop(&self, window: Window) -> WmResult<()> {
+        let Some(client) = self.clients.get(&window) else {
+            return Ok(());
+        };
+        if client.tags == 0 {
+            self.connection
+                .delete_property(window, self.atoms.net_wm_desktop)?;
+            return Ok(());
+        }
 
+        let desktop = if client.is_sticky || !client.tags.is_power_of_two() {
+            u32::MAX
+        } else {
+            client.tags.trailing_zeros()
+        };
         self.connection.change_property(
             PropMode::REPLACE,
             window,
-            net_client_info,
+            self.atoms.net_wm_desktop,
             AtomEnum::CARDINAL,
             32,
             1,
-            &bytes,
+            &desktop.to_ne_bytes(),
         )?;
-
-        self.connection.flush()?;
         Ok(())
     }
 
@@ -601,13 +1496,7 @@ impl WindowManager {
         Ok(())
     }
 
-    fn update_client_list(&self) -> WmResult<()> {
-        let window_bytes: Vec<u8> = self
-            .windows
-            .iter()
-            .flat_map(|window| window.to_ne_bytes())
-            .collect();
-
+    fn update_client_list(&mut self) -> WmResult<()> {
         self.connection.change_property(
             PropMode::REPLACE,
             self.root,
@@ -615,7 +1504,32 @@ impl WindowManager {
             AtomEnum::WINDOW,
             32,
             self.windows.len() as u32,
-            &window_bytes,
+            &window_list_bytes(&self.windows),
+        )?;
+
+        self.update_client_list_stacking()
+    }
+
+    /// Drops unmanaged windows from `stacking_order` and puts newly managed ones
+    /// on top until the next restack places them.
+    fn update_client_list_stacking(&mut self) -> WmResult<()> {
+        let windows = &self.windows;
+        self.stacking_order
+            .retain(|window| windows.contains(window));
+        for &window in &self.windows {
+            if !self.stacking_order.contains(&window) {
+                self.stacking_order.push(window);
+            }
+        }
+
+        self.connection.change_property(
+            PropMode::REPLACE,
+            self.root,
+            self.atoms.net_client_list_stacking,
+            AtomEnum::WINDOW,
+            32,
+            self.stacking_order.len() as u32,
+            &window_list_bytes(&self.stacking_order),
         )?;
 
         Ok(())
@@ -625,30 +1539,77 @@ impl WindowManager {
         println!("oxwm started on display {}", self.screen_number);
 
         self.grab_keys()?;
+        self.update_desktop_names()?;
         self.update_bar()?;
+        self.connection.flush()?;
+
+        // Spawned only once the root event mask, initial scan and grabs are in place,
+        // so fast clients are always seen as MapRequests by the loop below.
+        self.run_autostart_commands();
 
         let mut last_bar_update = std::time::Instant::now();
         const BAR_UPDATE_INTERVAL_MS: u64 = 100;
+        let mut last_night_light_minute = None;
+        let mut last_theme_minute = None;
 
         loop {
-            match self.connection.poll_for_event_with_sequence()? {
-                Some((event, _sequence)) => {
+            let event = match self.deferred_events.pop_front() {
+                Some(event) => Some(event),
+                None => self.connection.poll_for_event()?,
+            };
+            match event {
+                Some(event) => {
                     if matches!(self.handle_event(event)?, Control::Quit) {
-                        return Ok(());
+                        return self.shutdown();
                     }
                 }
                 None => {
+                    if matches!(self.process_ipc_requests()?, Control::Quit) {
+                        return self.shutdown();
+                    }
+
+                    if self
+                        .config_watcher
+                        .as_mut()
+                        .is_some_and(|watcher| watcher.poll())
+                    {
+                        self.reload_config()?;
+                    }
+
+                    self.expire_keychord()?;
+
                     if last_bar_update.elapsed().as_millis() >= BAR_UPDATE_INTERVAL_MS as u128 {
                         if let Some(bar) = self.bars.get_mut(self.selected_monitor) {
                             bar.update_blocks();
                         }
+                        self.expire_segments();
                         if self.bars.iter().any(|bar| bar.needs_redraw()) {
                             self.update_bar()?;
                         }
+                        self.save_state()?;
                         last_bar_update = std::time::Instant::now();
                     }
 
                     self.tick_animations()?;
+                    if self.recent_cycle.is_none()
+                        && self.switcher_overlay.is_visible()
+                        && self.switcher_overlay.is_expired()
+                        && let Err(error) = self.switcher_overlay.hide(&self.connection)
+                    {
+                        eprintln!("Failed to hide switcher overlay: {:?}", error);
+                    }
+                    if self.message_overlay.is_expired()
+                        && let Err(error) = self.message_overlay.hide(&self.connection)
+                    {
+                        eprintln!("Failed to hide message overlay: {:?}", error);
+                    }
+                    if self.osd_overlay.is_expired()
+                        && let Err(error) = self.osd_overlay.hide(&self.connection)
+                    {
+                        eprintln!("Failed to hide OSD overlay: {:?}", error);
+                    }
+                    self.update_night_light(&mut last_night_light_minute);
+                    self.update_theme_schedule(&mut last_theme_minute)?;
 
                     self.connection.flush()?;
                     std::thread::sleep(std::time::Duration::from_millis(16));
@@ -657,249 +1618,1312 @@ impl WindowManager {
         }
     }
 
-    fn toggle_floating(&mut self) -> WmResult<()> {
-        let focused = self
-            .monitors
-            .get(self.selected_monitor)
-            .and_then(|m| m.selected_client);
+    /// Saves state for a restarted oxwm and undoes what oxwm published on the root
+    /// window so the next window manager starts clean.
+    fn shutdown(&mut self) -> WmResult<()> {
+        self.gamma.restore(&self.connection, self.root);
+        self.save_state()?;
+        self.connection
+            .delete_property(self.root, self.atoms.net_supporting_wm_check)?;
+        self.connection
+            .delete_property(self.root, self.atoms.net_supported)?;
+        self.connection.destroy_window(self.wm_check_window)?;
+        self.connection.flush()?;
+        Ok(())
+    }
 
-        if focused.is_none() {
-            return Ok(());
+    fn process_ipc_requests(&mut self) -> WmResult<Control> {
+        for _ in 0..IPC_REQUESTS_PER_PASS {
+            let Some(request) = self.ipc.as_mut().and_then(|ipc| ipc.accept()) else {
+                break;
+            };
+            let result = match IpcCommand::parse(&request.args) {
+                Ok(IpcCommand::Action {
+                    action: KeyAction::Quit,
+                    ..
+                }) => {
+                    request.respond(Ok(String::new()));
+                    return Ok(Control::Quit);
+                }
+                Ok(command) => self.handle_ipc_command(command),
+                Err(message) => Err(message),
+            };
+            request.respond(result);
         }
-        let focused = focused.unwrap();
+        Ok(Control::Continue)
+    }
 
-        if let Some(client) = self.clients.get(&focused)
-            && client.is_fullscreen
+    fn handle_ipc_command(&mut self, command: IpcCommand) -> Result<String, String> {
+        match command {
+            IpcCommand::Dump => {
+                let state = serde_json::to_string(&self.state()).map_err(|e| e.to_string())?;
+                return Ok(format!("{}\n", state));
+            }
+            IpcCommand::GetTagState { tag } => {
+                if tag >= self.config.tags.len() {
+                    return Err(format!("tag {} is out of range", tag));
+                }
+                let monitors: Vec<TagSettingsState> = self
+                    .monitors
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, monitor)| {
+                        let pertag = monitor.pertag.as_ref()?;
+                        Some(TagSettingsState {
+                            monitor: index,
+                            tag,
+                            num_master: pertag.num_masters[tag + 1],
+                            master_factor: pertag.master_factors[tag + 1],
+                            layout: pertag.layouts[tag + 1].clone(),
+                        })
+                    })
+                    .collect();
+                let monitors = serde_json::to_string(&monitors).map_err(|e| e.to_string())?;
+                return Ok(format!("{}\n", monitors));
+            }
+            IpcCommand::FindClient(query) => {
+                let lines: String = self
+                    .clients_by_recency()
+                    .into_iter()
+                    .filter(|client| query.matches(client))
+                    .map(|client| {
+                        format!(
+                            "{}\t{}\t{}\t{}\n",
+                            client.window, client.stable_id, client.class, client.name
+                        )
+                    })
+                    .collect();
+                if lines.is_empty() {
+                    return Err("no matching client".to_string());
+                }
+                return Ok(lines);
+            }
+            IpcCommand::FocusClient { target } => {
+                let window = self
+                    .resolve_client(&target)
+                    .ok_or_else(|| format!("no client matching '{}'", target))?;
+                self.reveal_client(window).map_err(|e| e.to_string())?;
+            }
+            IpcCommand::Action { action, arg } => {
+                self.handle_key_action(action, &arg)
+                    .map_err(|e| e.to_string())?;
+                self.connection.flush().map_err(|e| e.to_string())?;
+                return Ok(String::new());
+            }
+            // Segment updates only mark the bars dirty; the bar tick redraws them,
+            // so a burst of updates costs one redraw instead of one per request.
+            IpcCommand::SetSegment { name, segment } => {
+                self.segments.insert(name, segment);
+                self.invalidate_bars();
+                return Ok(String::new());
+            }
+            IpcCommand::ClearSegment { name } => {
+                if self.segments.remove(&name).is_none() {
+                    return Err(format!("no segment named '{}'", name));
+                }
+                self.invalidate_bars();
+                return Ok(String::new());
+            }
+        }
+        self.update_bar().map_err(|e| e.to_string())?;
+        Ok(String::new())
+    }
+
+    fn clients_by_recency(&self) -> Vec<&Client> {
+        let mut clients: Vec<&Client> = self.clients.values().collect();
+        clients.sort_by_key(|client| (std::cmp::Reverse(client.last_focused), client.window));
+        clients
+    }
+
+    fn resolve_client(&self, target: &str) -> Option<Window> {
+        let window_id = match target.strip_prefix("0x") {
+            Some(hex) => Window::from_str_radix(hex, 16).ok(),
+            None => target.parse().ok(),
+        };
+        if let Some(window) = window_id
+            && self.clients.contains_key(&window)
         {
-            return Ok(());
+            return Some(window);
         }
 
-        let (is_fixed, x, y, w, h) = if let Some(client) = self.clients.get(&focused) {
-            (
-                client.is_fixed,
-                client.x_position as i32,
-                client.y_position as i32,
-                client.width as u32,
-                client.height as u32,
-            )
-        } else {
+        self.clients_by_recency()
+            .into_iter()
+            .find(|client| client.stable_id == target)
+            .map(|client| client.window)
+    }
+
+    fn reveal_client(&mut self, window: Window) -> WmResult<()> {
+        let Some((monitor_index, tags)) = self
+            .clients
+            .get(&window)
+            .map(|client| (client.monitor_index, client.tags))
+        else {
             return Ok(());
         };
 
-        let was_floating = self.floating_windows.contains(&focused);
-
-        if was_floating {
-            self.floating_windows.remove(&focused);
-            if let Some(client) = self.clients.get_mut(&focused) {
-                client.is_floating = false;
-            }
-        } else {
-            self.floating_windows.insert(focused);
-            if let Some(client) = self.clients.get_mut(&focused) {
-                client.is_floating = is_fixed || !client.is_floating;
+        if monitor_index != self.selected_monitor {
+            if let Some(old_selected) = self.monitors[self.selected_monitor].selected_client {
+                self.unfocus(old_selected, true)?;
             }
+            self.selected_monitor = monitor_index;
+        }
 
-            self.connection.configure_window(
-                focused,
-                &ConfigureWindowAux::new()
-                    .x(x)
-                    .y(y)
-                    .width(w)
-                    .height(h)
-                    .stack_mode(StackMode::ABOVE),
-            )?;
+        if !self.is_visible(window) {
+            self.view_tag(unmask_tag(tags))?;
         }
 
-        self.apply_layout()?;
+        self.focus(Some(window))?;
+        self.restack()?;
         Ok(())
     }
 
-    fn set_master_factor(&mut self, delta: f32) -> WmResult<()> {
-        if let Some(monitor) = self.monitors.get_mut(self.selected_monitor) {
-            let new_mfact = (monitor.master_factor + delta).clamp(0.05, 0.95);
-            monitor.master_factor = new_mfact;
-            if let Some(ref mut pertag) = monitor.pertag {
-                pertag.master_factors[pertag.current_tag] = new_mfact;
+    /// Focuses the most recently used client whose class contains `class`, switching
+    /// tag or monitor when needed, and spawns `command` when there is none.
+    fn run_or_raise(&mut self, command: &str, class: &str) -> WmResult<()> {
+        let target = self
+            .clients_by_recency()
+            .into_iter()
+            .find(|client| client.tags != 0 && client.class.contains(class))
+            .map(|client| client.window);
+
+        match target {
+            Some(window) => {
+                self.reveal_client(window)?;
+                self.update_tab_bars()?;
             }
-            self.apply_layout()?;
+            None => crate::signal::spawn_detached(command),
         }
         Ok(())
     }
 
-    fn inc_num_master(&mut self, delta: i32) -> WmResult<()> {
-        if let Some(monitor) = self.monitors.get_mut(self.selected_monitor) {
-            let new_nmaster = (monitor.num_master + delta).max(0);
-            monitor.num_master = new_nmaster;
-            if let Some(ref mut pertag) = monitor.pertag {
-                pertag.num_masters[pertag.current_tag] = new_nmaster;
-            }
-            self.apply_layout()?;
-        }
-        Ok(())
+    /// Reveals and focuses an urgent client, preferring ones on the selected
+    /// monitor. Focusing it clears the urgency hint.
+    fn focus_urgent(&mut self) -> WmResult<()> {
+        let selected_monitor = self.selected_monitor;
+        let Some(window) = self
+            .windows
+            .iter()
+            .copied()
+            .filter_map(|window| {
+                let client = self.clients.get(&window)?;
+                client
+                    .is_urgent
+                    .then_some((window, client.monitor_index != selected_monitor))
+            })
+            .min_by_key(|&(_, other_monitor)| other_monitor)
+            .map(|(window, _)| window)
+        else {
+            return Ok(());
+        };
+
+        self.reveal_client(window)?;
+        self.update_bar()
     }
 
-    fn tick_animations(&mut self) -> WmResult<()> {
-        if self.scroll_animation.is_active() {
-            if let Some(new_offset) = self.scroll_animation.update() {
-                if let Some(m) = self.monitors.get_mut(self.selected_monitor) {
-                    m.scroll_offset = new_offset;
-                }
-                self.apply_layout()?;
-                self.update_bar()?;
+    /// Cycles focus through the windows sharing the focused window's class in
+    /// most-recently-focused order. The order is kept while cycling so repeated
+    /// presses walk the whole group instead of bouncing between two windows.
+    fn cycle_same_class(&mut self, direction: i32) -> WmResult<()> {
+        let Some(focused) = self
+            .monitors
+            .get(self.selected_monitor)
+            .and_then(|monitor| monitor.selected_client)
+        else {
+            return Ok(());
+        };
+        let Some(class) = self
+            .clients
+            .get(&focused)
+            .map(|client| client.class.clone())
+            .filter(|class| !class.is_empty())
+        else {
+            return Ok(());
+        };
+
+        let (candidates, position) = match self.class_cycle.take() {
+            Some((candidates, position))
+                if candidates.get(position) == Some(&focused)
+                    && candidates
+                        .iter()
+                        .all(|window| self.clients.contains_key(window)) =>
+            {
+                (candidates, position)
+            }
+            _ => {
+                let candidates: Vec<Window> = self
+                    .clients_by_recency()
+                    .into_iter()
+                    .filter(|client| {
+                        client.class == class && client.tags != 0 && !client.click_through
+                    })
+                    .map(|client| client.window)
+                    .collect();
+                let position = candidates
+                    .iter()
+                    .position(|&window| window == focused)
+                    .unwrap_or(0);
+                (candidates, position)
             }
+        };
+
+        if candidates.len() < 2 {
+            return Ok(());
+        }
+
+        let next = (position as i32 + direction).rem_euclid(candidates.len() as i32) as usize;
+        self.reveal_client(candidates[next])?;
+        self.update_tab_bars()?;
+
+        let titles = candidates
+            .iter()
+            .filter_map(|window| self.clients.get(window))
+            .map(|client| client.name.clone())
+            .collect();
+        let monitor = &self.monitors[self.selected_monitor];
+        if let Err(error) = self.switcher_overlay.show(
+            &self.connection,
+            &self.font,
+            titles,
+            next,
+            monitor.screen_rect().into(),
+        ) {
+            eprintln!("Failed to show switcher overlay: {:?}", error);
         }
+
+        self.class_cycle = Some((candidates, next));
         Ok(())
     }
 
-    fn scroll_layout(&mut self, direction: i32) -> WmResult<()> {
-        if self.layout.name() != "scrolling" {
+    /// Starts or advances an MRU switch over all visible windows. The keyboard
+    /// stays grabbed until the held modifiers are released, which focuses the
+    /// selection, or Escape cancels.
+    fn cycle_recent(&mut self) -> WmResult<()> {
+        if let Some(cycle) = &mut self.recent_cycle {
+            cycle.selected = (cycle.selected + 1) % cycle.windows.len();
+            return self.show_recent_cycle();
+        }
+
+        let windows: Vec<Window> = self
+            .clients_by_recency()
+            .into_iter()
+            .filter(|client| !client.click_through)
+            .map(|client| client.window)
+            .filter(|&window| self.is_visible(window))
+            .collect();
+        if windows.len() < 2 {
             return Ok(());
         }
 
-        let monitor_index = self.selected_monitor;
-        let monitor = match self.monitors.get(monitor_index) {
-            Some(m) => m.clone(),
-            None => return Ok(()),
-        };
+        let grab = self
+            .connection
+            .grab_keyboard(
+                false,
+                self.root,
+                x11rb::CURRENT_TIME,
+                GrabMode::ASYNC,
+                GrabMode::ASYNC,
+            )?
+            .reply()?;
+        // Without the grab the modifier release would never reach us and the
+        // switcher would stay open.
+        if grab.status != GrabStatus::SUCCESS {
+            return Ok(());
+        }
 
-        let visible_count = if monitor.num_master > 0 {
-            monitor.num_master as usize
-        } else {
-            2
-        };
+        let held = u16::from(self.connection.query_pointer(self.root)?.reply()?.mask)
+            & !(u16::from(ModMask::LOCK) | u16::from(ModMask::M2));
+        let modifier_map = self.connection.get_modifier_mapping()?.reply()?;
+        let per_modifier = modifier_map.keycodes_per_modifier() as usize;
+        let modifier_keycodes = modifier_map
+            .keycodes
+            .chunks(per_modifier.max(1))
+            .enumerate()
+            .filter(|(bit, _)| held & (1 << bit) != 0)
+            .flat_map(|(_, keycodes)| keycodes.iter().copied().filter(|&keycode| keycode != 0))
+            .collect();
 
-        let mut tiled_count = 0;
-        let mut current = self.next_tiled(monitor.clients_head, &monitor);
-        while let Some(window) = current {
-            tiled_count += 1;
-            if let Some(client) = self.clients.get(&window) {
-                current = self.next_tiled(client.next, &monitor);
-            } else {
-                break;
-            }
+        self.recent_cycle = Some(RecentCycle {
+            windows,
+            selected: 1,
+            original: self.monitors[self.selected_monitor].selected_client,
+            modifier_keycodes,
+        });
+
+        if held == 0 {
+            return self.finish_recent_cycle(true);
         }
+        self.show_recent_cycle()
+    }
 
-        if tiled_count <= visible_count {
-            if let Some(m) = self.monitors.get_mut(monitor_index) {
-                m.scroll_offset = 0;
-            }
+    fn show_recent_cycle(&mut self) -> WmResult<()> {
+        let Some(cycle) = &self.recent_cycle else {
             return Ok(());
+        };
+        let titles = cycle
+            .windows
+            .iter()
+            .map(|window| {
+                self.clients
+                    .get(window)
+                    .map(|client| client.name.clone())
+                    .unwrap_or_default()
+            })
+            .collect();
+        let monitor = &self.monitors[self.selected_monitor];
+        if let Err(error) = self.switcher_overlay.show(
+            &self.connection,
+            &self.font,
+            titles,
+            cycle.selected,
+            monitor.screen_rect().into(),
+        ) {
+            eprintln!("Failed to show switcher overlay: {:?}", error);
         }
+        Ok(())
+    }
 
-        let outer_gap = if self.gaps_enabled {
-            self.config.gap_outer_vertical
-        } else {
-            0
-        };
-        let inner_gap = if self.gaps_enabled {
-            self.config.gap_inner_vertical
-        } else {
-            0
+    fn finish_recent_cycle(&mut self, commit: bool) -> WmResult<()> {
+        let Some(cycle) = self.recent_cycle.take() else {
+            return Ok(());
         };
+        self.connection.ungrab_keyboard(x11rb::CURRENT_TIME)?;
+        if let Err(error) = self.switcher_overlay.hide(&self.connection) {
+            eprintln!("Failed to hide switcher overlay: {:?}", error);
+        }
 
-        let available_width = monitor.screen_width - 2 * outer_gap as i32;
-        let total_inner_gaps = inner_gap as i32 * (visible_count - 1) as i32;
-        let window_width = (available_width - total_inner_gaps) / visible_count as i32;
-        let scroll_amount = window_width + inner_gap as i32;
-
-        let total_width =
-            tiled_count as i32 * window_width + (tiled_count - 1) as i32 * inner_gap as i32;
-        let max_scroll = (total_width - available_width).max(0);
-
-        let current_offset = monitor.scroll_offset;
-        let target_offset = if self.scroll_animation.is_active() {
-            self.scroll_animation.target() + direction * scroll_amount
+        let target = if commit {
+            Some(cycle.windows[cycle.selected])
         } else {
-            current_offset + direction * scroll_amount
+            cycle.original
         };
-        let target_offset = target_offset.clamp(0, max_scroll);
-
-        self.scroll_animation
-            .start(current_offset, target_offset, &self.animation_config);
-
+        if let Some(window) = target.filter(|window| self.clients.contains_key(window)) {
+            self.reveal_client(window)?;
+            self.update_tab_bars()?;
+        }
         Ok(())
     }
 
-    fn scroll_to_window(&mut self, target_window: Window, animate: bool) -> WmResult<()> {
-        if self.layout.name() != "scrolling" {
-            return Ok(());
+    fn expire_segments(&mut self) {
+        let now = std::time::Instant::now();
+        let count = self.segments.len();
+        self.segments.retain(|_, segment| !segment.is_expired(now));
+        if self.segments.len() != count {
+            self.invalidate_bars();
         }
+    }
 
-        let monitor_index = self.selected_monitor;
-        let monitor = match self.monitors.get(monitor_index) {
-            Some(m) => m.clone(),
-            None => return Ok(()),
+    fn invalidate_bars(&mut self) {
+        for bar in &mut self.bars {
+            bar.invalidate();
+        }
+    }
+
+    /// Applies the scheduled theme whenever the schedule crosses a boundary, so a
+    /// theme picked with CycleTheme stays until the next switch time.
+    fn update_theme_schedule(&mut self, last_minute: &mut Option<u32>) -> WmResult<()> {
+        if self.config.theme_schedule.is_none() {
+            return Ok(());
+        }
+
+        let now = chrono::Local::now();
+        let minute_of_day = now.hour() * 60 + now.minute();
+        if *last_minute == Some(minute_of_day) {
+            return Ok(());
+        }
+        *last_minute = Some(minute_of_day);
+        self.apply_scheduled_theme(minute_of_day)
+    }
+
+    fn apply_scheduled_theme(&mut self, minute_of_day: u32) -> WmResult<()> {
+        let Some(schedule) = &self.config.theme_schedule else {
+            return Ok(());
+        };
+
+        let name = schedule.theme_at(minute_of_day);
+        let scheduled = self
+            .config
+            .themes
+            .iter()
+            .position(|theme| theme.name == name);
+        if scheduled.is_some() && scheduled != self.scheduled_theme {
+            self.scheduled_theme = scheduled;
+            if let Some(index) = scheduled {
+                self.apply_theme(index)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn cycle_theme(&mut self) -> WmResult<()> {
+        if self.config.themes.is_empty() {
+            return Ok(());
+        }
+        let next = self
+            .active_theme
+            .map_or(0, |index| (index + 1) % self.config.themes.len());
+        self.apply_theme(next)
+    }
+
+    fn apply_theme(&mut self, index: usize) -> WmResult<()> {
+        let Some(theme) = self.config.themes.get(index).cloned() else {
+            return Ok(());
+        };
+
+        if let Some(color) = theme.border_focused {
+            self.config.border_focused = color;
+        }
+        if let Some(color) = theme.border_unfocused {
+            self.config.border_unfocused = color;
+        }
+        if let Some(color) = theme.border_urgent {
+            self.config.border_urgent = color;
+        }
+        if let Some(scheme) = theme.scheme_normal {
+            self.config.scheme_normal = scheme;
+        }
+        if let Some(scheme) = theme.scheme_occupied {
+            self.config.scheme_occupied = scheme;
+        }
+        if let Some(scheme) = theme.scheme_selected {
+            self.config.scheme_selected = scheme;
+        }
+        if let Some(scheme) = theme.scheme_urgent {
+            self.config.scheme_urgent = scheme;
+        }
+        self.active_theme = Some(index);
+
+        for bar in &mut self.bars {
+            bar.update_schemes(&self.config);
+        }
+        for tab_bar in &mut self.tab_bars {
+            tab_bar.set_schemes(self.config.scheme_occupied, self.config.scheme_selected);
+        }
+        self.apply_overlay_colors()?;
+        self.refresh_borders()?;
+        self.update_bar()?;
+        self.update_tab_bars()?;
+        Ok(())
+    }
+
+    /// Colors the popups after the bar's normal scheme; the error overlay keeps
+    /// the urgent border so it stands out from informational ones.
+    fn apply_overlay_colors(&mut self) -> WmResult<()> {
+        let border = self.config.border_focused;
+        let background = self.config.scheme_normal.background;
+        let foreground = self.config.scheme_normal.foreground;
+        let connection = &self.connection;
+        self.overlay.set_colors(
+            connection,
+            self.config.border_urgent,
+            background,
+            foreground,
+        )?;
+        let overlays: [&mut dyn Overlay; 5] = [
+            &mut self.message_overlay,
+            &mut self.keybind_overlay,
+            &mut self.switcher_overlay,
+            &mut self.chord_overlay,
+            &mut self.osd_overlay,
+        ];
+        for overlay in overlays {
+            overlay.set_colors(connection, border, background, foreground)?;
+        }
+        Ok(())
+    }
+
+    fn refresh_borders(&self) -> WmResult<()> {
+        for &window in self.clients.keys() {
+            self.refresh_border(window)?;
+        }
+        Ok(())
+    }
+
+    fn refresh_border(&self, window: Window) -> WmResult<()> {
+        if self.clients.get(&window).is_none_or(|c| c.is_borderless) {
+            return Ok(());
+        }
+        let focused = self
+            .monitors
+            .get(self.selected_monitor)
+            .and_then(|monitor| monitor.selected_client);
+        let color = if Some(window) == focused {
+            self.config.border_focused
+        } else {
+            self.unfocused_border_color(window)
+        };
+        self.connection.change_window_attributes(
+            window,
+            &ChangeWindowAttributesAux::new().border_pixel(color),
+        )?;
+        Ok(())
+    }
+
+    fn unfocused_border_color(&self, window: Window) -> u32 {
+        if self.clients.get(&window).is_some_and(|c| c.is_urgent) {
+            self.config.border_urgent
+        } else {
+            self.config.border_unfocused
+        }
+    }
+
+    fn update_night_light(&mut self, last_minute: &mut Option<u32>) {
+        let Some(night_light) = self.config.night_light else {
+            return;
+        };
+
+        let now = chrono::Local::now();
+        let minute_of_day = now.hour() * 60 + now.minute();
+
+        if *last_minute != Some(minute_of_day) {
+            *last_minute = Some(minute_of_day);
+            self.gamma
+                .update_schedule(&self.connection, self.root, &night_light, minute_of_day);
+        }
+    }
+
+    fn toggle_floating(&mut self) -> WmResult<()> {
+        let focused = self
+            .monitors
+            .get(self.selected_monitor)
+            .and_then(|m| m.selected_client);
+
+        if focused.is_none() {
+            return Ok(());
+        }
+        let focused = focused.unwrap();
+
+        if let Some(client) = self.clients.get(&focused)
+            && client.is_fullscreen
+        {
+            return Ok(());
+        }
+
+        let (was_floating, x, y, w, h) = if let Some(client) = self.clients.get(&focused) {
+            (
+                client.is_floating,
+                client.x_position as i32,
+                client.y_position as i32,
+                client.width as u32,
+                client.height as u32,
+            )
+        } else {
+            return Ok(());
+        };
+
+        if was_floating {
+            if let Some(client) = self.clients.get_mut(&focused) {
+                client.is_floating = false;
+                client.size_factor = 1.0;
+            }
+            self.sync_window_state(focused);
+        } else {
+            let border_width = self.client_border_width(focused);
+            if let Some(client) = self.clients.get_mut(&focused) {
+                client.is_floating = true;
+                client.border_width = border_width as u16;
+            }
+            self.sync_window_state(focused);
+
+            self.connection.configure_window(
+                focused,
+                &ConfigureWindowAux::new()
+                    .x(x)
+                    .y(y)
+                    .width(w)
+                    .height(h)
+                    .border_width(border_width)
+                    .stack_mode(StackMode::ABOVE),
+            )?;
+        }
+
+        self.apply_layout()?;
+        Ok(())
+    }
+
+    /// Brings `floating_windows` and `fullscreen_windows` in line with the
+    /// client's own flags, which are authoritative; fullscreen implies
+    /// floating. Call after every change to either flag, and after a client
+    /// is removed so both sets drop the window.
+    /// Also publishes the client's `_NET_WM_STATE` when its flags changed it.
+    fn sync_window_state(&mut self, window: Window) {
+        sync_window_sets(
+            window,
+            self.clients.get_mut(&window),
+            &mut self.floating_windows,
+            &mut self.fullscreen_windows,
+        );
+
+        let Some(client) = self.clients.get(&window) else {
+            self.net_wm_states.remove(&window);
+            return;
+        };
+        // Nothing is written for new clients without states, so the initial state a client
+        // sets before mapping is still there when manage_window reads it.
+        let states = net_wm_state_atoms(client, self.atoms.net_wm_states());
+        let published = self.net_wm_states.get(&window).map(Vec::as_slice);
+        if published.unwrap_or_default() == states.as_slice() {
+            return;
+        }
+        let bytes: Vec<u8> = states.iter().flat_map(|atom| atom.to_ne_bytes()).collect();
+        let _ = self.connection.change_property(
+            PropMode::REPLACE,
+            window,
+            self.atoms.net_wm_state,
+            AtomEnum::ATOM,
+            32,
+            states.len() as u32,
+            &bytes,
+        );
+        self.net_wm_states.insert(window, states);
+    }
+
+    fn debug_assert_window_state(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        for (window, client) in &self.clients {
+            debug_assert_eq!(
+                self.floating_windows.contains(window),
+                client.is_floating,
+                "floating_windows out of sync for window {}",
+                window
+            );
+            debug_assert_eq!(
+                self.fullscreen_windows.contains(window),
+                client.is_fullscreen,
+                "fullscreen_windows out of sync for window {}",
+                window
+            );
+        }
+        debug_assert!(
+            self.floating_windows
+                .iter()
+                .chain(&self.fullscreen_windows)
+                .all(|window| self.clients.contains_key(window)),
+            "window state sets hold unmanaged windows"
+        );
+    }
+
+    fn move_resize_floating(&mut self, dx: i32, dy: i32, dw: i32, dh: i32) -> WmResult<()> {
+        let Some(focused) = self
+            .monitors
+            .get(self.selected_monitor)
+            .and_then(|m| m.selected_client)
+        else {
+            return Ok(());
+        };
+
+        let Some(client) = self.clients.get(&focused) else {
+            return Ok(());
+        };
+
+        if !client.is_floating || client.is_fullscreen {
+            return Ok(());
+        }
+
+        let mut width = client.width as i32 + dw;
+        let mut height = client.height as i32 + dh;
+        if let Some(monitor) = self.monitors.get(client.monitor_index) {
+            let border = 2 * client.border_width as i32;
+            width = width.min(monitor.window_area_width - border);
+            height = height.min(monitor.window_area_height - border);
+        }
+
+        let (mut x, mut y, w, h, _) = self.apply_size_hints(
+            focused,
+            client.x_position as i32 + dx,
+            client.y_position as i32 + dy,
+            width,
+            height,
+        );
+
+        if let Some(client) = self.clients.get(&focused)
+            && let Some(monitor) = self.monitors.get(client.monitor_index)
+        {
+            let border = 2 * client.border_width as i32;
+            let max_x = monitor.window_area_x + monitor.window_area_width - w - border;
+            let max_y = monitor.window_area_y + monitor.window_area_height - h - border;
+            x = x.min(max_x).max(monitor.window_area_x);
+            y = y.min(max_y).max(monitor.window_area_y);
+        }
+
+        if let Some(client) = self.clients.get_mut(&focused) {
+            client.x_position = x as i16;
+            client.y_position = y as i16;
+            client.width = w as u16;
+            client.height = h as u16;
+        }
+
+        self.connection.configure_window(
+            focused,
+            &ConfigureWindowAux::new()
+                .x(x)
+                .y(y)
+                .width(w as u32)
+                .height(h as u32),
+        )?;
+        self.send_configure_notify(focused)?;
+        self.connection.flush()?;
+        Ok(())
+    }
+
+    fn center_floating(&mut self, float_tiled: bool) -> WmResult<()> {
+        let Some(focused) = self
+            .monitors
+            .get(self.selected_monitor)
+            .and_then(|m| m.selected_client)
+        else {
+            return Ok(());
+        };
+
+        let Some(client) = self.clients.get(&focused) else {
+            return Ok(());
+        };
+
+        if client.is_fullscreen {
+            return Ok(());
+        }
+
+        if !client.is_floating {
+            if !float_tiled {
+                return Ok(());
+            }
+            self.toggle_floating()?;
+        }
+
+        let Some((x, y)) = self.center_client(focused) else {
+            return Ok(());
+        };
+
+        self.connection
+            .configure_window(focused, &ConfigureWindowAux::new().x(x).y(y))?;
+        self.send_configure_notify(focused)?;
+        self.connection.flush()?;
+        Ok(())
+    }
+
+    /// Moves the client to the center of its monitor's window area, returning the new position.
+    fn center_client(&mut self, window: Window) -> Option<(i32, i32)> {
+        let client = self.clients.get_mut(&window)?;
+        let monitor = self.monitors.get(client.monitor_index)?;
+
+        let outer_width = client.width as i32 + 2 * client.border_width as i32;
+        let outer_height = client.height as i32 + 2 * client.border_width as i32;
+        let x = monitor.window_area_x + (monitor.window_area_width - outer_width) / 2;
+        let y = monitor.window_area_y + (monitor.window_area_height - outer_height) / 2;
+
+        client.x_position = x as i16;
+        client.y_position = y as i16;
+        Some((x, y))
+    }
+
+    fn gap_config(&self) -> GapConfig {
+        if self.gaps_enabled {
+            GapConfig {
+                inner_horizontal: self.config.gap_inner_horizontal,
+                inner_vertical: self.config.gap_inner_vertical,
+                outer_top: self.config.gap_outer_top,
+                outer_bottom: self.config.gap_outer_bottom,
+                outer_left: self.config.gap_outer_left,
+                outer_right: self.config.gap_outer_right,
+            }
+        } else {
+            GapConfig {
+                inner_horizontal: 0,
+                inner_vertical: 0,
+                outer_top: 0,
+                outer_bottom: 0,
+                outer_left: 0,
+                outer_right: 0,
+            }
+        }
+    }
+
+    fn zoom(&mut self) -> WmResult<()> {
+        let Some(monitor) = self.monitors.get(self.selected_monitor) else {
+            return Ok(());
+        };
+        let Some(focused) = monitor.selected_client else {
+            return Ok(());
         };
 
+        if self
+            .clients
+            .get(&focused)
+            .is_none_or(|client| client.is_floating || client.is_fullscreen)
+        {
+            return Ok(());
+        }
+
+        let mut window = focused;
+        if self.next_tiled(monitor.clients_head, monitor) == Some(focused) {
+            let next = self.clients.get(&focused).and_then(|client| client.next);
+            match self.next_tiled(next, monitor) {
+                Some(next_window) => window = next_window,
+                None => return Ok(()),
+            }
+        }
+
+        let monitor_index = self.selected_monitor;
+        self.detach(window);
+        self.attach(window, monitor_index);
+        self.focus(Some(window))?;
+        self.apply_layout()?;
+        self.restack()?;
+        Ok(())
+    }
+
+    fn set_click_through(&mut self, window: Window, enabled: bool) -> WmResult<()> {
+        if !self.shape_supported {
+            eprintln!("Click-through requires the X SHAPE extension");
+            return Ok(());
+        }
+
+        if enabled {
+            self.connection.shape_rectangles(
+                shape::SO::SET,
+                shape::SK::INPUT,
+                ClipOrdering::UNSORTED,
+                window,
+                0,
+                0,
+                &[],
+            )?;
+        } else {
+            self.connection.shape_mask(
+                shape::SO::SET,
+                shape::SK::INPUT,
+                window,
+                0,
+                0,
+                x11rb::NONE,
+            )?;
+        }
+
+        if let Some(client) = self.clients.get_mut(&window) {
+            client.click_through = enabled;
+        }
+
+        self.connection.flush()?;
+        Ok(())
+    }
+
+    fn focused_window_op(&self, capture: fn(&Client) -> WindowOp) -> Option<WindowOp> {
+        self.monitors
+            .get(self.selected_monitor)
+            .and_then(|m| m.selected_client)
+            .and_then(|window| self.clients.get(&window))
+            .map(capture)
+    }
+
+    fn record_window_op(&mut self, op: Option<WindowOp>) {
+        if let Some(op) = op
+            && self
+                .clients
+                .get(&op.window())
+                .is_some_and(|client| op.changed(client))
+        {
+            self.history.push(op);
+        }
+    }
+
+    fn undo_window_op(&mut self) -> WmResult<()> {
+        let Some(op) = self.history.pop() else {
+            return Ok(());
+        };
+
+        let window = op.window();
+        let Some(name) = self.clients.get(&window).map(|c| c.name.clone()) else {
+            return Ok(());
+        };
+
+        match op {
+            WindowOp::Tags { tags, .. } => {
+                self.restore_client_tags(window, tags)?;
+            }
+            WindowOp::Monitor {
+                monitor_index,
+                tags,
+                ..
+            } => {
+                if monitor_index < self.monitors.len() {
+                    self.move_window_to_monitor(window, monitor_index, false)?;
+                }
+                self.restore_client_tags(window, tags)?;
+            }
+            WindowOp::Floating {
+                was_floating,
+                x_position,
+                y_position,
+                width,
+                height,
+                ..
+            } => {
+                let is_floating = self
+                    .clients
+                    .get(&window)
+                    .is_some_and(|client| client.is_floating);
+
+                if is_floating != was_floating {
+                    self.focus(Some(window))?;
+                    self.toggle_floating()?;
+                }
+
+                if was_floating {
+                    if let Some(client) = self.clients.get_mut(&window) {
+                        client.x_position = x_position;
+                        client.y_position = y_position;
+                        client.width = width;
+                        client.height = height;
+                    }
+
+                    self.connection.configure_window(
+                        window,
+                        &ConfigureWindowAux::new()
+                            .x(x_position as i32)
+                            .y(y_position as i32)
+                            .width(width as u32)
+                            .height(height as u32),
+                    )?;
+                    self.send_configure_notify(window)?;
+                }
+
+                self.restack()?;
+            }
+            WindowOp::Fullscreen { was_fullscreen, .. } => {
+                self.set_window_fullscreen(window, was_fullscreen)?;
+                self.restack()?;
+            }
+        }
+
+        self.show_message(
+            &format!("Undid {} of {}", op.description(), name),
+            std::time::Duration::from_millis(2000),
+        );
+
+        self.connection.flush()?;
+        Ok(())
+    }
+
+    fn restore_client_tags(&mut self, window: Window, tags: u32) -> WmResult<()> {
+        if let Some(client) = self.clients.get_mut(&window) {
+            client.tags = tags;
+        }
+
+        if let Err(error) = self.update_client_desktop(window) {
+            eprintln!("Failed to update client desktop: {:?}", error);
+        }
+
+        self.focus(None)?;
+        self.apply_layout()?;
+        self.update_bar()?;
+        Ok(())
+    }
+
+    fn snap_floating(&mut self, region: &str) -> WmResult<()> {
+        let Some((columns, rows)) = snap_region(region) else {
+            eprintln!("Unknown snap region '{}'", region);
+            return Ok(());
+        };
+
+        let Some(focused) = self
+            .monitors
+            .get(self.selected_monitor)
+            .and_then(|m| m.selected_client)
+        else {
+            return Ok(());
+        };
+
+        let Some(client) = self.clients.get(&focused) else {
+            return Ok(());
+        };
+
+        if !client.is_floating || client.is_fullscreen {
+            return Ok(());
+        }
+
+        let Some(monitor) = self.monitors.get(client.monitor_index) else {
+            return Ok(());
+        };
+
+        let gaps = self.gap_config();
+        let inner_horizontal = gaps.inner_horizontal as i32;
+        let inner_vertical = gaps.inner_vertical as i32;
+
+        let area_x = monitor.window_area_x + gaps.outer_left as i32;
+        let area_y = monitor.window_area_y + gaps.outer_top as i32;
+        let area_width = monitor.window_area_width - (gaps.outer_left + gaps.outer_right) as i32;
+        let area_height = monitor.window_area_height - (gaps.outer_top + gaps.outer_bottom) as i32;
+
+        let (x, width) = match columns {
+            SnapSpan::Whole => (area_x, area_width),
+            SnapSpan::First => (area_x, (area_width - inner_vertical) / 2),
+            SnapSpan::Second => {
+                let half = (area_width - inner_vertical) / 2;
+                (area_x + area_width - half, half)
+            }
+        };
+        let (y, height) = match rows {
+            SnapSpan::Whole => (area_y, area_height),
+            SnapSpan::First => (area_y, (area_height - inner_horizontal) / 2),
+            SnapSpan::Second => {
+                let half = (area_height - inner_horizontal) / 2;
+                (area_y + area_height - half, half)
+            }
+        };
+
+        let border_width = client.border_width as i32;
+        let (x, y, w, h, _) = self.apply_size_hints(
+            focused,
+            x,
+            y,
+            (width - 2 * border_width).max(1),
+            (height - 2 * border_width).max(1),
+        );
+
+        if let Some(client) = self.clients.get_mut(&focused) {
+            client.x_position = x as i16;
+            client.y_position = y as i16;
+            client.width = w as u16;
+            client.height = h as u16;
+        }
+
+        self.connection.configure_window(
+            focused,
+            &ConfigureWindowAux::new()
+                .x(x)
+                .y(y)
+                .width(w as u32)
+                .height(h as u32),
+        )?;
+        self.send_configure_notify(focused)?;
+        self.connection.flush()?;
+        Ok(())
+    }
+
+    fn set_master_factor(&mut self, delta: f32) -> WmResult<()> {
+        if let Some(monitor) = self.monitors.get_mut(self.selected_monitor) {
+            let new_mfact = limits::MASTER_FACTOR.clamp(monitor.master_factor + delta);
+            monitor.master_factor = new_mfact;
+            if let Some(ref mut pertag) = monitor.pertag {
+                pertag.master_factors[pertag.current_tag] = new_mfact;
+            }
+            self.apply_layout()?;
+            self.save_tag_state()?;
+        }
+        Ok(())
+    }
+
+    fn set_client_factor(&mut self, delta: f32) -> WmResult<()> {
+        let Some(focused) = self
+            .monitors
+            .get(self.selected_monitor)
+            .and_then(|monitor| monitor.selected_client)
+        else {
+            return Ok(());
+        };
+        if self.floating_windows.contains(&focused) {
+            return Ok(());
+        }
+
+        if let Some(client) = self.clients.get_mut(&focused) {
+            client.size_factor = limits::CLIENT_FACTOR.clamp(client.size_factor + delta);
+        }
+        self.apply_layout()
+    }
+
+    fn inc_num_master(&mut self, delta: i32) -> WmResult<()> {
+        if let Some(monitor) = self.monitors.get_mut(self.selected_monitor) {
+            let new_nmaster = (monitor.num_master + delta).max(0);
+            monitor.num_master = new_nmaster;
+            if let Some(ref mut pertag) = monitor.pertag {
+                pertag.num_masters[pertag.current_tag] = new_nmaster;
+            }
+            self.apply_layout()?;
+            self.save_tag_state()?;
+        }
+        Ok(())
+    }
+
+    fn tick_animations(&mut self) -> WmResult<()> {
+        if self.scroll_animation.is_active() {
+            if let Some(new_offset) = self.scroll_animation.update() {
+                if let Some(m) = self.monitors.get_mut(self.selected_monitor) {
+                    m.scroll_offset = new_offset;
+                }
+                self.apply_layout()?;
+                self.update_bar()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn scroll_column_factors(&self, monitor: &Monitor, windows: &[Window]) -> Vec<f32> {
         let visible_count = if monitor.num_master > 0 {
             monitor.num_master as usize
         } else {
             2
         };
+        let default_factor = self
+            .config
+            .scroll_column_factor
+            .unwrap_or(1.0 / visible_count.min(windows.len()).max(1) as f32);
+
+        windows
+            .iter()
+            .map(|window| {
+                self.clients
+                    .get(window)
+                    .and_then(|client| client.scroll_column_factor)
+                    .unwrap_or(default_factor)
+            })
+            .collect()
+    }
+
+    /// Tiled windows of the scrolling layout with each column's x offset and
+    /// width relative to the scrolling area, plus the width of that area.
+    fn scroll_columns(&self, monitor_index: usize) -> (Vec<Window>, Vec<(i32, i32)>, i32) {
+        let Some(monitor) = self.monitors.get(monitor_index) else {
+            return (Vec::new(), Vec::new(), 0);
+        };
+        let gaps = self.gap_config();
+
+        let mut windows = Vec::new();
+        let mut current = self.next_tiled(monitor.clients_head, monitor);
+        while let Some(window) = current {
+            windows.push(window);
+            current = self
+                .clients
+                .get(&window)
+                .and_then(|client| self.next_tiled(client.next, monitor));
+        }
+
+        let available_width =
+            (monitor.window_area_width - (gaps.outer_left + gaps.outer_right) as i32).max(0);
+        let factors = self.scroll_column_factors(monitor, &windows);
+
+        let mut x = 0;
+        let columns =
+            ScrollingLayout::column_widths(&factors, available_width as u32, gaps.inner_vertical)
+                .into_iter()
+                .map(|width| {
+                    let column = (x, width as i32);
+                    x += width as i32 + gaps.inner_vertical as i32;
+                    column
+                })
+                .collect();
+
+        (windows, columns, available_width)
+    }
+
+    fn scroll_layout(&mut self, direction: i32) -> WmResult<()> {
+        if self.monitor_layout(self.selected_monitor).name() != "scrolling" {
+            return Ok(());
+        }
+
+        let monitor_index = self.selected_monitor;
+        let (windows, columns, available_width) = self.scroll_columns(monitor_index);
+        let max_scroll = max_scroll_offset(&columns, available_width);
+
+        if max_scroll == 0 {
+            if let Some(m) = self.monitors.get_mut(monitor_index) {
+                m.scroll_offset = 0;
+            }
+            return Ok(());
+        }
+
+        let current_offset = self.monitors[monitor_index].scroll_offset;
+        let from_offset = if self.scroll_animation.is_active() {
+            self.scroll_animation.target()
+        } else {
+            current_offset
+        };
+        let target_offset = if direction > 0 {
+            columns
+                .iter()
+                .map(|&(x, _)| x)
+                .find(|&x| x > from_offset)
+                .unwrap_or(max_scroll)
+        } else {
+            columns
+                .iter()
+                .rev()
+                .map(|&(x, _)| x)
+                .find(|&x| x < from_offset)
+                .unwrap_or(0)
+        };
+        let target_offset = target_offset.clamp(0, max_scroll);
+
+        // Focus follows the leftmost column that is fully in view.
+        let visible_window = columns
+            .iter()
+            .position(|&(x, _)| x >= target_offset)
+            .map(|index| windows[index]);
+        if let Some(window) = visible_window
+            && self.monitors[monitor_index].selected_client != Some(window)
+        {
+            self.focus(Some(window))?;
+            self.restack()?;
+            self.update_tab_bars()?;
+        }
+
+        self.scroll_animation
+            .start(current_offset, target_offset, &self.animation_config);
 
-        let outer_gap = if self.gaps_enabled {
-            self.config.gap_outer_vertical
-        } else {
-            0
-        };
-        let inner_gap = if self.gaps_enabled {
-            self.config.gap_inner_vertical
-        } else {
-            0
-        };
+        Ok(())
+    }
 
-        let mut tiled_windows = Vec::new();
-        let mut current = self.next_tiled(monitor.clients_head, &monitor);
-        while let Some(window) = current {
-            tiled_windows.push(window);
-            if let Some(client) = self.clients.get(&window) {
-                current = self.next_tiled(client.next, &monitor);
-            } else {
-                break;
-            }
+    fn scroll_to_window(&mut self, target_window: Window, animate: bool) -> WmResult<()> {
+        if self.monitor_layout(self.selected_monitor).name() != "scrolling" {
+            return Ok(());
         }
 
-        let target_idx = tiled_windows.iter().position(|&w| w == target_window);
-        let target_idx = match target_idx {
+        let monitor_index = self.selected_monitor;
+        let (windows, columns, available_width) = self.scroll_columns(monitor_index);
+
+        let target_idx = match windows.iter().position(|&w| w == target_window) {
             Some(idx) => idx,
             None => return Ok(()),
         };
 
-        let tiled_count = tiled_windows.len();
-        if tiled_count <= visible_count {
-            if animate && monitor.scroll_offset != 0 {
+        let current_offset = self.monitors[monitor_index].scroll_offset;
+        let max_scroll = max_scroll_offset(&columns, available_width);
+        let new_offset = columns[target_idx].0.clamp(0, max_scroll);
+
+        if current_offset != new_offset {
+            if animate {
                 self.scroll_animation
-                    .start(monitor.scroll_offset, 0, &self.animation_config);
+                    .start(current_offset, new_offset, &self.animation_config);
             } else if let Some(m) = self.monitors.get_mut(monitor_index) {
-                m.scroll_offset = 0;
+                m.scroll_offset = new_offset;
             }
-            return Ok(());
         }
 
-        let available_width = monitor.screen_width - 2 * outer_gap as i32;
-        let total_inner_gaps = inner_gap as i32 * (visible_count - 1) as i32;
-        let window_width = (available_width - total_inner_gaps) / visible_count as i32;
-        let scroll_step = window_width + inner_gap as i32;
+        Ok(())
+    }
 
-        let total_width =
-            tiled_count as i32 * window_width + (tiled_count - 1) as i32 * inner_gap as i32;
-        let max_scroll = (total_width - available_width).max(0);
+    fn resize_scroll_column(&mut self, delta: f32) -> WmResult<()> {
+        let monitor_index = self.selected_monitor;
+        if self.monitor_layout(monitor_index).name() != "scrolling" {
+            return Ok(());
+        }
 
-        let target_scroll = (target_idx as i32) * scroll_step;
-        let new_offset = target_scroll.clamp(0, max_scroll);
+        let Some(selected) = self.monitors[monitor_index].selected_client else {
+            return Ok(());
+        };
+        let (windows, _, _) = self.scroll_columns(monitor_index);
+        let Some(index) = windows.iter().position(|&window| window == selected) else {
+            return Ok(());
+        };
 
-        let current_offset = monitor.scroll_offset;
-        if current_offset != new_offset {
-            if animate {
-                self.scroll_animation
-                    .start(current_offset, new_offset, &self.animation_config);
-            } else {
-                if let Some(m) = self.monitors.get_mut(monitor_index) {
-                    m.scroll_offset = new_offset;
-                }
-            }
+        let factor = self.scroll_column_factors(&self.monitors[monitor_index], &windows)[index];
+        if let Some(client) = self.clients.get_mut(&selected) {
+            client.scroll_column_factor = Some(limits::SCROLL_COLUMN_FACTOR.clamp(factor + delta));
         }
 
+        self.apply_layout()?;
+        self.scroll_to_window(selected, true)?;
+        self.update_bar()?;
         Ok(())
     }
 
@@ -916,70 +2940,163 @@ impl WindowManager {
         Ok(())
     }
 
-    fn get_layout_symbol(&self) -> String {
-        let layout_name = self.layout.name();
+    fn focus_new_windows(&self, rule_focus: Option<bool>) -> bool {
+        self.overrides
+            .focus_new_windows
+            .or(rule_focus)
+            .unwrap_or(self.config.focus_new_windows)
+    }
 
-        if layout_name == "scrolling" {
-            if let Some(monitor) = self.monitors.get(self.selected_monitor) {
-                let visible_count = if monitor.num_master > 0 {
-                    monitor.num_master as usize
-                } else {
-                    2
-                };
+    fn steal_monitor_focus(&self) -> bool {
+        self.overrides
+            .steal_monitor_focus
+            .unwrap_or(self.config.steal_monitor_focus)
+    }
 
-                let mut tiled_count = 0;
-                let mut current = self.next_tiled(monitor.clients_head, monitor);
-                while let Some(window) = current {
-                    tiled_count += 1;
-                    if let Some(client) = self.clients.get(&window) {
-                        current = self.next_tiled(client.next, monitor);
-                    } else {
-                        break;
-                    }
-                }
+    fn focus_on_activate(&self) -> bool {
+        self.overrides
+            .focus_on_activate
+            .unwrap_or(self.config.focus_on_activate)
+    }
 
-                if tiled_count > 0 {
-                    let outer_gap = if self.gaps_enabled {
-                        self.config.gap_outer_vertical
-                    } else {
-                        0
-                    };
-                    let inner_gap = if self.gaps_enabled {
-                        self.config.gap_inner_vertical
-                    } else {
-                        0
-                    };
+    fn focus_follows_mouse(&self) -> bool {
+        self.overrides
+            .focus_follows_mouse
+            .unwrap_or(self.config.focus_follows_mouse)
+    }
 
-                    let available_width = monitor.screen_width - 2 * outer_gap as i32;
-                    let total_inner_gaps =
-                        inner_gap as i32 * (visible_count.min(tiled_count) - 1) as i32;
-                    let window_width = if tiled_count <= visible_count {
-                        (available_width - total_inner_gaps) / tiled_count as i32
-                    } else {
-                        (available_width - inner_gap as i32 * (visible_count - 1) as i32)
-                            / visible_count as i32
-                    };
+    fn presenting(&self) -> bool {
+        self.overrides.contains(&ConfigOverrides::presentation(
+            self.config.presentation_blank_titles,
+        ))
+    }
 
-                    let scroll_step = window_width + inner_gap as i32;
-                    let first_visible = if scroll_step > 0 {
-                        (monitor.scroll_offset / scroll_step) + 1
-                    } else {
-                        1
-                    };
-                    let last_visible =
-                        (first_visible + visible_count as i32 - 1).min(tiled_count as i32);
+    fn notifications_enabled(&self) -> bool {
+        self.overrides.notifications.unwrap_or(true)
+    }
 
-                    return format!("[{}-{}/{}]", first_visible, last_visible, tiled_count);
-                }
+    /// Shows `text` on the selected monitor for `duration`, unless
+    /// notifications are turned off.
+    fn show_message(&mut self, text: &str, duration: std::time::Duration) {
+        if !self.notifications_enabled() {
+            return;
+        }
+        let monitor = &self.monitors[self.selected_monitor];
+        if let Err(error) = self.message_overlay.show_message(
+            &self.connection,
+            &self.font,
+            text,
+            duration,
+            monitor.screen_rect().into(),
+        ) {
+            eprintln!("Failed to show message overlay: {:?}", error);
+        }
+    }
+
+    fn monitor_follows_mouse(&self) -> bool {
+        self.overrides.monitor_follows_mouse.unwrap_or(true)
+    }
+
+    fn hide_window_titles(&self) -> bool {
+        self.overrides.hide_window_titles.unwrap_or(false)
+    }
+
+    fn monitor_layout(&self, monitor_index: usize) -> &dyn Layout {
+        self.layouts[monitor_index].as_ref()
+    }
+
+    fn is_tabbed(&self, monitor_index: usize) -> bool {
+        self.monitor_layout(monitor_index).name() == LayoutType::Tabbed.as_str()
+    }
+
+    fn get_layout_symbol(&self, monitor_index: usize) -> String {
+        let layout = self.monitor_layout(monitor_index);
+        let layout_name = layout.name();
+
+        if layout_name == "scrolling" {
+            let (_, columns, available_width) = self.scroll_columns(monitor_index);
+            if !columns.is_empty() {
+                let offset = self.monitors[monitor_index].scroll_offset;
+                let first_visible = columns
+                    .iter()
+                    .position(|&(x, width)| x + width > offset)
+                    .unwrap_or(0)
+                    + 1;
+                let last_visible = columns
+                    .iter()
+                    .rposition(|&(x, _)| x < offset + available_width)
+                    .map_or(first_visible, |index| index + 1);
+
+                return format!("[{}-{}/{}]", first_visible, last_visible, columns.len());
             }
         }
 
-        self.config
+        let client_count = self.tiled_count(monitor_index);
+        let symbol = self
+            .config
             .layout_symbols
             .iter()
             .find(|l| l.name == layout_name)
-            .map(|l| l.symbol.clone())
-            .unwrap_or_else(|| self.layout.symbol().to_string())
+            .map(|l| l.symbol.replace("{n}", &client_count.to_string()))
+            .unwrap_or_else(|| layout.symbol_for(client_count));
+
+        if self.is_mirrored(monitor_index) {
+            mirror_symbol(&symbol)
+        } else {
+            symbol
+        }
+    }
+
+    fn is_mirrored(&self, monitor_index: usize) -> bool {
+        let layout_name = self.monitor_layout(monitor_index).name();
+        layout_name != LayoutType::Normie.as_str()
+            && layout_name != "scrolling"
+            && self
+                .monitors
+                .get(monitor_index)
+                .is_some_and(|monitor| monitor.mirrored)
+    }
+
+    fn expire_keychord(&mut self) -> WmResult<()> {
+        let keyboard::handlers::KeychordState::InProgress { started, .. } = &self.keychord_state
+        else {
+            return Ok(());
+        };
+        if started.elapsed().as_millis() < u128::from(self.config.keychord_timeout_ms) {
+            return Ok(());
+        }
+
+        self.keychord_state = keyboard::handlers::KeychordState::Idle;
+        self.current_key = 0;
+        self.grab_keys()?;
+        self.update_bar()?;
+        self.update_chord_overlay();
+        self.connection.flush()?;
+        Ok(())
+    }
+
+    fn update_chord_overlay(&mut self) {
+        let result = match &self.keychord_state {
+            keyboard::handlers::KeychordState::InProgress {
+                candidates,
+                keys_pressed,
+                ..
+            } => {
+                let monitor = &self.monitors[self.selected_monitor];
+                self.chord_overlay.show(
+                    &self.connection,
+                    &self.font,
+                    &self.config.keybindings,
+                    candidates,
+                    *keys_pressed,
+                    monitor.screen_rect().into(),
+                )
+            }
+            keyboard::handlers::KeychordState::Idle => self.chord_overlay.hide(&self.connection),
+        };
+        if let Err(error) = result {
+            eprintln!("Failed to update keychord overlay: {:?}", error);
+        }
     }
 
     fn get_keychord_indicator(&self) -> Option<String> {
@@ -988,6 +3105,7 @@ impl WindowManager {
             keyboard::handlers::KeychordState::InProgress {
                 candidates,
                 keys_pressed,
+                ..
             } => {
                 if candidates.is_empty() {
                     return None;
@@ -1025,22 +3143,98 @@ impl WindowManager {
         }
     }
 
+    fn state(&self) -> WmState {
+        let monitors = self
+            .monitors
+            .iter()
+            .enumerate()
+            .map(|(index, monitor)| {
+                let (occupied_tags, urgent_tags) = tag_masks(self.clients.values(), index);
+                MonitorState {
+                    index,
+                    x: monitor.screen_x,
+                    y: monitor.screen_y,
+                    width: monitor.screen_width,
+                    height: monitor.screen_height,
+                    selected_tags: monitor.tagset[monitor.selected_tags_index],
+                    occupied_tags,
+                    urgent_tags,
+                    layout: self.monitor_layout(index).name(),
+                    layout_symbol: self.get_layout_symbol(index),
+                    focused_window: monitor.selected_client,
+                }
+            })
+            .collect();
+
+        let mut clients: Vec<ClientState> = self
+            .clients
+            .values()
+            .map(|client| ClientState {
+                window: client.window,
+                stable_id: client.stable_id.clone(),
+                class: client.class.clone(),
+                title: client.name.clone(),
+                monitor: client.monitor_index,
+                tags: client.tags,
+                is_floating: client.is_floating,
+                is_fullscreen: client.is_fullscreen,
+                is_urgent: client.is_urgent,
+            })
+            .collect();
+        clients.sort_by_key(|client| client.window);
+
+        WmState {
+            selected_monitor: self.selected_monitor,
+            monitors,
+            clients,
+        }
+    }
+
     fn update_bar(&mut self) -> WmResult<()> {
-        let layout_symbol = self.get_layout_symbol();
+        let layout_symbols: Vec<String> = (0..self.monitors.len())
+            .map(|monitor_index| self.get_layout_symbol(monitor_index))
+            .collect();
         let keychord_indicator = self.get_keychord_indicator();
+        let hide_window_titles = self.hide_window_titles();
+        let mode_indicator = (self.presenting() && !self.config.presentation_indicator.is_empty())
+            .then(|| {
+                (
+                    SegmentPosition::Left,
+                    self.config.presentation_indicator.clone(),
+                    self.config.scheme_urgent.foreground,
+                )
+            });
 
         for (monitor_index, monitor) in self.monitors.iter().enumerate() {
             if let Some(bar) = self.bars.get_mut(monitor_index) {
-                let mut occupied_tags: TagMask = 0;
-                let mut urgent_tags: TagMask = 0;
-                for client in self.clients.values() {
-                    if client.monitor_index == monitor_index {
-                        occupied_tags |= client.tags;
-                        if client.is_urgent {
-                            urgent_tags |= client.tags;
-                        }
-                    }
-                }
+                let (occupied_tags, urgent_tags) = tag_masks(self.clients.values(), monitor_index);
+
+                let segments = mode_indicator
+                    .clone()
+                    .into_iter()
+                    .chain(
+                        self.segments
+                            .values()
+                            .filter(|segment| segment.shows_on(monitor_index))
+                            .map(|segment| {
+                                (
+                                    segment.position,
+                                    segment.text.clone(),
+                                    segment
+                                        .color
+                                        .unwrap_or(self.config.scheme_normal.foreground),
+                                )
+                            }),
+                    )
+                    .collect();
+                bar.set_segments(segments);
+
+                let focused_title = monitor
+                    .selected_client
+                    .and_then(|window| self.clients.get(&window))
+                    .map(|client| client.name.as_str())
+                    .filter(|_| !hide_window_titles);
+                bar.update_focused_title(focused_title);
 
                 let draw_blocks = monitor_index == self.selected_monitor;
                 bar.invalidate();
@@ -1052,7 +3246,7 @@ impl WindowManager {
                     occupied_tags,
                     urgent_tags,
                     draw_blocks,
-                    &layout_symbol,
+                    &layout_symbols[monitor_index],
                     keychord_indicator.as_deref(),
                 )?;
             }
@@ -1061,6 +3255,8 @@ impl WindowManager {
     }
 
     fn update_tab_bars(&mut self) -> WmResult<()> {
+        let hide_window_titles = self.hide_window_titles();
+
         for (monitor_index, monitor) in self.monitors.iter().enumerate() {
             if let Some(tab_bar) = self.tab_bars.get_mut(monitor_index) {
                 let visible_windows: Vec<(Window, String)> = self
@@ -1075,7 +3271,12 @@ impl WindowManager {
                                 return None;
                             }
                             if (client.tags & monitor.tagset[monitor.selected_tags_index]) != 0 {
-                                return Some((window, client.name.clone()));
+                                let name = if hide_window_titles {
+                                    String::new()
+                                } else {
+                                    client.name.clone()
+                                };
+                                return Some((window, name));
                             }
                         }
                         None
@@ -1098,9 +3299,10 @@ impl WindowManager {
     fn handle_key_action(&mut self, action: KeyAction, arg: &Arg) -> WmResult<()> {
         match action {
             KeyAction::Spawn => handlers::handle_spawn_action(action, arg, self.selected_monitor)?,
-            KeyAction::SpawnTerminal => {
-                crate::signal::spawn_detached(&self.config.terminal);
-            }
+            KeyAction::SpawnTerminal => match self.focused_cwd() {
+                Some(dir) => crate::signal::spawn_detached_in(&self.config.terminal, &dir),
+                None => crate::signal::spawn_detached(&self.config.terminal),
+            },
             KeyAction::KillClient => {
                 if let Some(focused) = self
                     .monitors
@@ -1110,56 +3312,93 @@ impl WindowManager {
                     self.kill_client(focused)?;
                 }
             }
+            KeyAction::KillOthers | KeyAction::KillAll => {
+                let focused = self
+                    .monitors
+                    .get(self.selected_monitor)
+                    .and_then(|m| m.selected_client);
+                for window in self.visible_windows_on_monitor(self.selected_monitor) {
+                    if action == KeyAction::KillAll || Some(window) != focused {
+                        self.kill_client(window)?;
+                    }
+                }
+            }
             KeyAction::ToggleFullScreen => {
+                let op = self.focused_window_op(WindowOp::fullscreen);
                 self.fullscreen()?;
+                self.record_window_op(op);
                 self.restack()?;
             }
             KeyAction::ChangeLayout => {
                 if let Arg::Str(layout_name) = arg {
                     match layout_from_str(layout_name) {
                         Ok(layout) => {
-                            self.layout = layout;
+                            self.layouts[self.selected_monitor] = layout;
                             if let Some(monitor) = self.monitors.get_mut(self.selected_monitor) {
                                 if let Some(ref mut pertag) = monitor.pertag {
                                     pertag.layouts[pertag.current_tag] = layout_name.to_string();
                                 }
                             }
                             if layout_name != "normie" && layout_name != "floating" {
-                                self.floating_windows.clear();
+                                self.clear_floating_on_monitor(self.selected_monitor);
                             }
                             self.apply_layout()?;
                             self.update_bar()?;
                             self.restack()?;
+                            run_hook(self.config.layout_change_hook.as_deref(), layout_name);
                         }
                         Err(e) => eprintln!("Failed to change layout: {}", e),
                     }
                 }
             }
+            KeyAction::MirrorLayout => {
+                if let Some(monitor) = self.monitors.get_mut(self.selected_monitor) {
+                    monitor.mirrored = !monitor.mirrored;
+                }
+                self.apply_layout()?;
+                self.update_bar()?;
+            }
             KeyAction::CycleLayout => {
-                let current_name = self.layout.name();
+                let current_name = self.monitor_layout(self.selected_monitor).name();
                 let next_name = next_layout(current_name);
                 match layout_from_str(next_name) {
                     Ok(layout) => {
-                        self.layout = layout;
+                        self.layouts[self.selected_monitor] = layout;
                         if let Some(monitor) = self.monitors.get_mut(self.selected_monitor) {
                             if let Some(ref mut pertag) = monitor.pertag {
                                 pertag.layouts[pertag.current_tag] = next_name.to_string();
                             }
                         }
                         if next_name != "normie" && next_name != "floating" {
-                            self.floating_windows.clear();
+                            self.clear_floating_on_monitor(self.selected_monitor);
                         }
                         self.apply_layout()?;
                         self.update_bar()?;
                         self.restack()?;
+                        run_hook(self.config.layout_change_hook.as_deref(), next_name);
                     }
                     Err(e) => eprintln!("Failed to cycle layout: {}", e),
                 }
             }
+            KeyAction::ToggleFakeFullscreen => {
+                self.toggle_fake_fullscreen()?;
+            }
             KeyAction::ToggleFloating => {
+                let op = self.focused_window_op(WindowOp::floating);
                 self.toggle_floating()?;
+                self.record_window_op(op);
                 self.restack()?;
             }
+            KeyAction::ToggleAlwaysOnTop => {
+                if let Some(focused) = self
+                    .monitors
+                    .get(self.selected_monitor)
+                    .and_then(|m| m.selected_client)
+                {
+                    let is_above = self.clients.get(&focused).is_some_and(|c| c.is_above);
+                    self.set_window_above(focused, !is_above)?;
+                }
+            }
 
             KeyAction::FocusStack => {
                 if let Arg::Int(direction) = arg {
@@ -1167,18 +3406,45 @@ impl WindowManager {
                     self.restack()?;
                 }
             }
+            KeyAction::FocusFloating => {
+                if let Arg::Int(direction) = arg {
+                    self.focus_floating(*direction)?;
+                }
+            }
             KeyAction::MoveStack => {
                 if let Arg::Int(direction) = arg {
                     self.move_stack(*direction)?;
                     self.restack()?;
                 }
             }
+            KeyAction::CycleSameClass => {
+                if let Arg::Int(direction) = arg {
+                    self.cycle_same_class(*direction)?;
+                }
+            }
+            KeyAction::FocusUrgent => {
+                self.focus_urgent()?;
+            }
+            KeyAction::CycleRecent => {
+                self.cycle_recent()?;
+            }
+            KeyAction::RotateStack => {
+                if let Arg::Int(direction) = arg {
+                    self.rotate_stack(*direction)?;
+                    self.restack()?;
+                }
+            }
             KeyAction::Quit | KeyAction::Restart => {}
             KeyAction::ViewTag => {
                 if let Arg::Int(tag_index) = arg {
                     self.view_tag(*tag_index as usize)?;
                 }
             }
+            KeyAction::ViewTagAllMonitors => {
+                if let Arg::Int(tag_index) = arg {
+                    self.view_tag_all_monitors(*tag_index as usize)?;
+                }
+            }
             KeyAction::ViewNextTag => {
                 let monitor = self.get_selected_monitor();
                 let current_tag_index = unmask_tag(monitor.get_selected_tag()) as i32;
@@ -1219,6 +3485,11 @@ impl WindowManager {
                     }
                 }
             }
+            KeyAction::MoveTag => {
+                if let Arg::Int(direction) = arg {
+                    self.move_tag(*direction)?;
+                }
+            }
             KeyAction::ToggleView => {
                 if let Arg::Int(tag_index) = arg {
                     self.toggleview(*tag_index as usize)?;
@@ -1226,56 +3497,204 @@ impl WindowManager {
             }
             KeyAction::MoveToTag => {
                 if let Arg::Int(tag_index) = arg {
+                    let op = self.focused_window_op(WindowOp::tags);
                     self.move_to_tag(*tag_index as usize)?;
+                    self.record_window_op(op);
+                }
+            }
+            KeyAction::ToggleTag => {
+                if let Arg::Int(tag_index) = arg {
+                    let op = self.focused_window_op(WindowOp::tags);
+                    self.toggletag(*tag_index as usize)?;
+                    self.record_window_op(op);
+                }
+            }
+            KeyAction::ToggleGaps => {
+                self.gaps_enabled = !self.gaps_enabled;
+                self.apply_layout()?;
+                self.restack()?;
+            }
+            KeyAction::ToggleBar => {
+                self.toggle_bar()?;
+            }
+            KeyAction::ToggleFocusFollowsMouse => {
+                self.overrides.focus_follows_mouse = Some(!self.focus_follows_mouse());
+            }
+            KeyAction::CycleTheme => {
+                self.cycle_theme()?;
+            }
+            KeyAction::FocusMonitor => {
+                if let Arg::Int(direction) = arg {
+                    self.focus_monitor(*direction)?;
+                }
+            }
+            KeyAction::TagMonitor | KeyAction::TagMonitorKeepTag => {
+                if let Arg::Int(direction) = arg {
+                    let op = self.focused_window_op(WindowOp::monitor);
+                    self.send_window_to_adjacent_monitor(
+                        *direction,
+                        action == KeyAction::TagMonitorKeepTag,
+                    )?;
+                    self.record_window_op(op);
+                }
+            }
+            KeyAction::ShowKeybindOverlay => {
+                let monitor = &self.monitors[self.selected_monitor];
+                self.keybind_overlay.toggle(
+                    &self.connection,
+                    &self.font,
+                    &self.config.keybindings,
+                    monitor.screen_rect().into(),
+                )?;
+            }
+            // Deltas are percentage points from both Lua and IPC.
+            KeyAction::SetMasterFactor => match arg {
+                Arg::Int(percent) => self.set_master_factor(*percent as f32 / 100.0)?,
+                Arg::Float(percent) => self.set_master_factor(*percent / 100.0)?,
+                _ => {}
+            },
+            KeyAction::IncNumMaster => {
+                if let Arg::Int(delta) = arg {
+                    self.inc_num_master(*delta)?;
+                }
+            }
+            KeyAction::SetClientFactor => {
+                if let Arg::Int(delta) = arg {
+                    self.set_client_factor(*delta as f32 / 100.0)?;
+                }
+            }
+            KeyAction::ScrollLeft => {
+                self.scroll_layout(-1)?;
+            }
+            KeyAction::ScrollRight => {
+                self.scroll_layout(1)?;
+            }
+            KeyAction::ScrollColumnGrow => {
+                self.resize_scroll_column(SCROLL_COLUMN_STEP)?;
+            }
+            KeyAction::ScrollColumnShrink => {
+                self.resize_scroll_column(-SCROLL_COLUMN_STEP)?;
+            }
+            KeyAction::MoveFloating => {
+                if let Some((dx, dy)) = parse_delta_arg(arg) {
+                    self.move_resize_floating(dx, dy, 0, 0)?;
+                }
+            }
+            KeyAction::ResizeFloating => {
+                if let Some((dw, dh)) = parse_delta_arg(arg) {
+                    self.move_resize_floating(0, 0, dw, dh)?;
+                }
+            }
+            KeyAction::CenterFloating => {
+                self.center_floating(!matches!(arg, Arg::Int(0)))?;
+                self.restack()?;
+            }
+            KeyAction::ToggleClickThrough => {
+                // Click-through windows can't be focused, so toggling turns click-through off
+                // for the ones on screen before it turns it on for the focused window.
+                let click_through: Vec<Window> = self
+                    .visible_windows_on_monitor(self.selected_monitor)
+                    .into_iter()
+                    .filter(|window| self.clients.get(window).is_some_and(|c| c.click_through))
+                    .collect();
+                if !click_through.is_empty() {
+                    for &window in &click_through {
+                        self.set_click_through(window, false)?;
+                    }
+                    if let Some(&window) = click_through.first() {
+                        self.focus(Some(window))?;
+                    }
+                } else if let Some(focused) = self
+                    .monitors
+                    .get(self.selected_monitor)
+                    .and_then(|m| m.selected_client)
+                {
+                    self.set_click_through(focused, true)?;
+                    self.focus(None)?;
+                }
+                self.restack()?;
+            }
+            KeyAction::TogglePresentationMode => {
+                self.overrides = self.overrides.toggle(&ConfigOverrides::presentation(
+                    self.config.presentation_blank_titles,
+                ));
+                self.update_bar()?;
+                self.update_tab_bars()?;
+            }
+            KeyAction::DumpState => match serde_json::to_string(&self.state()) {
+                Ok(state) => println!("{}", state),
+                Err(error) => eprintln!("Failed to serialize state: {}", error),
+            },
+            KeyAction::ShowOsd => {
+                if self.notifications_enabled()
+                    && let Arg::Array(parts) = arg
+                    && let [label, value] = parts.as_slice()
+                    && let Ok(value) = value.trim_end_matches('%').parse::<f32>()
+                {
+                    let monitor = &self.monitors[self.selected_monitor];
+                    if let Err(error) = self.osd_overlay.show(
+                        &self.connection,
+                        &self.font,
+                        label,
+                        value.clamp(0.0, 100.0).round() as u8,
+                        monitor.screen_rect().into(),
+                    ) {
+                        eprintln!("Failed to show OSD overlay: {:?}", error);
+                    }
                 }
             }
-            KeyAction::ToggleTag => {
-                if let Arg::Int(tag_index) = arg {
-                    self.toggletag(*tag_index as usize)?;
+            KeyAction::RunOrRaise => {
+                if let Arg::Array(parts) = arg
+                    && let [command, class] = parts.as_slice()
+                {
+                    self.run_or_raise(command, class)?;
                 }
             }
-            KeyAction::ToggleGaps => {
-                self.gaps_enabled = !self.gaps_enabled;
-                self.apply_layout()?;
-                self.restack()?;
-            }
-            KeyAction::FocusMonitor => {
-                if let Arg::Int(direction) = arg {
-                    self.focus_monitor(*direction)?;
+            KeyAction::Notify => {
+                let (text, duration_ms) = match arg {
+                    Arg::Str(text) => (Some(text.as_str()), None),
+                    Arg::Table(_) => (
+                        arg.field("text"),
+                        arg.field("duration_ms").and_then(|ms| ms.parse().ok()),
+                    ),
+                    _ => (None, None),
+                };
+                if let Some(text) = text {
+                    self.show_message(
+                        text,
+                        std::time::Duration::from_millis(duration_ms.unwrap_or(2000)),
+                    );
                 }
             }
-            KeyAction::TagMonitor => {
-                if let Arg::Int(direction) = arg {
-                    self.send_window_to_adjacent_monitor(*direction)?;
-                }
+            KeyAction::RescanWindows => {
+                self.scan_existing_windows()?;
+                self.update_bar()?;
             }
-            KeyAction::ShowKeybindOverlay => {
-                let monitor = &self.monitors[self.selected_monitor];
-                self.keybind_overlay.toggle(
-                    &self.connection,
-                    &self.font,
-                    &self.config.keybindings,
-                    monitor.screen_x as i16,
-                    monitor.screen_y as i16,
-                    monitor.screen_width as u16,
-                    monitor.screen_height as u16,
-                )?;
+            KeyAction::Zoom => {
+                self.zoom()?;
             }
-            KeyAction::SetMasterFactor => {
-                if let Arg::Int(delta) = arg {
-                    self.set_master_factor(*delta as f32 / 100.0)?;
-                }
+            KeyAction::UndoWindowOp => {
+                self.undo_window_op()?;
             }
-            KeyAction::IncNumMaster => {
-                if let Arg::Int(delta) = arg {
-                    self.inc_num_master(*delta)?;
+            KeyAction::SnapFloating => {
+                if let Arg::Str(region) = arg {
+                    self.snap_floating(region)?;
                 }
             }
-            KeyAction::ScrollLeft => {
-                self.scroll_layout(-1)?;
-            }
-            KeyAction::ScrollRight => {
-                self.scroll_layout(1)?;
+            KeyAction::ToggleScratchpad => match arg {
+                Arg::Str(name) => self.toggle_scratchpad(name, None)?,
+                Arg::Table(_) => {
+                    if let Some(name) = arg.field("name") {
+                        self.toggle_scratchpad(name, arg.field("command"))?;
+                    }
+                }
+                _ => {}
+            },
+            KeyAction::SetGamma => {
+                if let Arg::Int(temperature) = arg {
+                    self.gamma
+                        .set(&self.connection, self.root, (*temperature).max(0) as u32);
+                }
             }
             KeyAction::None => {}
         }
@@ -1362,10 +3781,13 @@ impl WindowManager {
         best_monitor
     }
 
+    /// Moves a window to another monitor, onto that monitor's visible tags
+    /// unless `keep_tags` is set.
     fn move_window_to_monitor(
         &mut self,
         window: Window,
         target_monitor_index: usize,
+        keep_tags: bool,
     ) -> WmResult<()> {
         let current_monitor_index = self.clients.get(&window).map(|c| c.monitor_index);
 
@@ -1381,10 +3803,11 @@ impl WindowManager {
 
         if let Some(client) = self.clients.get_mut(&window) {
             client.monitor_index = target_monitor_index;
-            if let Some(target_monitor) = self.monitors.get(target_monitor_index) {
+            if !keep_tags && let Some(target_monitor) = self.monitors.get(target_monitor_index) {
                 client.tags = target_monitor.tagset[target_monitor.selected_tags_index];
             }
         }
+        self.update_client_desktop(window)?;
 
         self.attach_aside(window, target_monitor_index);
         self.attach_stack(window, target_monitor_index);
@@ -1422,7 +3845,8 @@ impl WindowManager {
             return false;
         };
 
-        (client.tags & monitor.tagset[monitor.selected_tags_index]) != 0
+        (client.is_sticky && client.tags != 0)
+            || (client.tags & monitor.tagset[monitor.selected_tags_index]) != 0
     }
 
     fn showhide(&mut self, window: Option<Window>) -> WmResult<()> {
@@ -1439,19 +3863,22 @@ impl WindowManager {
             None => return Ok(()),
         };
 
-        let is_visible = (client.tags & monitor.tagset[monitor.selected_tags_index]) != 0;
+        let is_visible = (client.is_sticky && client.tags != 0)
+            || (client.tags & monitor.tagset[monitor.selected_tags_index]) != 0;
 
         if is_visible {
+            let slide = self.slide_offset_for(&client);
             self.connection.configure_window(
                 window,
                 &ConfigureWindowAux::new()
-                    .x(client.x_position as i32)
+                    .x(client.x_position as i32 + slide)
                     .y(client.y_position as i32),
             )?;
 
             let is_floating = client.is_floating;
             let is_fullscreen = client.is_fullscreen;
-            let has_no_layout = self.layout.name() == LayoutType::Normie.as_str();
+            let has_no_layout =
+                self.monitor_layout(client.monitor_index).name() == LayoutType::Normie.as_str();
 
             if (has_no_layout || is_floating) && !is_fullscreen {
                 let (x, y, w, h, changed) = self.apply_size_hints(
@@ -1479,7 +3906,7 @@ impl WindowManager {
                             .y(y)
                             .width(w as u32)
                             .height(h as u32)
-                            .border_width(self.config.border_width),
+                            .border_width(self.client_border_width(window)),
                     )?;
                     self.send_configure_notify(window)?;
                     self.connection.flush()?;
@@ -1508,8 +3935,11 @@ impl WindowManager {
         }
 
         let new_tagset = tag_mask(tag_index);
-        let mut layout_name: Option<String> = None;
-        let mut toggle_bar = false;
+        let old_layout = self.monitor_layout(self.selected_monitor).name();
+        let old_tagset = self
+            .monitors
+            .get(self.selected_monitor)
+            .map_or(0, |monitor| monitor.tagset[monitor.selected_tags_index]);
 
         if let Some(monitor) = self.monitors.get_mut(self.selected_monitor) {
             if new_tagset == monitor.tagset[monitor.selected_tags_index] {
@@ -1530,31 +3960,173 @@ impl WindowManager {
                     pertag.current_tag = tag_index + 1;
                 }
             }
+        }
 
-            if let Some(ref pertag) = monitor.pertag {
-                monitor.num_master = pertag.num_masters[pertag.current_tag];
-                monitor.master_factor = pertag.master_factors[pertag.current_tag];
-                layout_name = Some(pertag.layouts[pertag.current_tag].clone());
-                if monitor.show_bar != pertag.show_bars[pertag.current_tag] {
-                    toggle_bar = true;
-                }
-            }
+        if self.load_pertag(self.selected_monitor) {
+            self.toggle_bar()?;
         }
 
-        if let Some(name) = layout_name {
-            if let Ok(layout) = layout_from_str(&name) {
-                self.layout = layout;
-            }
+        let new_tagset = self
+            .monitors
+            .get(self.selected_monitor)
+            .map_or(0, |monitor| monitor.tagset[monitor.selected_tags_index]);
+        if new_tagset != old_tagset {
+            let direction = if new_tagset.trailing_zeros() > old_tagset.trailing_zeros() {
+                1
+            } else {
+                -1
+            };
+            self.prepare_slide(direction);
         }
 
-        if toggle_bar {
-            self.toggle_bar()?;
+        self.save_selected_tags()?;
+        self.focus(None)?;
+        self.apply_layout()?;
+        self.update_bar()?;
+
+        if new_tagset != old_tagset {
+            self.slide_in_tiled()?;
+            run_hook(
+                self.config.tag_change_hook.as_deref(),
+                &(unmask_tag(new_tagset) + 1).to_string(),
+            );
+        }
+
+        let new_layout = self.monitor_layout(self.selected_monitor).name();
+        if new_layout != old_layout {
+            run_hook(self.config.layout_change_hook.as_deref(), new_layout);
+        }
+
+        Ok(())
+    }
+
+    /// Restores the master count, master factor and layout the monitor's
+    /// current tag last used, returning whether its bar visibility differs.
+    fn load_pertag(&mut self, monitor_index: usize) -> bool {
+        let Some(monitor) = self.monitors.get_mut(monitor_index) else {
+            return false;
+        };
+        let Some(ref pertag) = monitor.pertag else {
+            return false;
+        };
+        monitor.num_master = pertag.num_masters[pertag.current_tag];
+        monitor.master_factor = pertag.master_factors[pertag.current_tag];
+        let toggle_bar = monitor.show_bar != pertag.show_bars[pertag.current_tag];
+        if let Ok(layout) = layout_from_str(&pertag.layouts[pertag.current_tag]) {
+            self.layouts[monitor_index] = layout;
+        }
+        toggle_bar
+    }
+
+    /// Switches every monitor to the same tag, keeping focus on the selected
+    /// monitor. All monitors are retagged first and laid out once, without the
+    /// slide, hooks or pointer warp of a single `view_tag`.
+    fn view_tag_all_monitors(&mut self, tag_index: usize) -> WmResult<()> {
+        if tag_index >= self.config.tags.len() {
+            return Ok(());
+        }
+
+        let new_tagset = tag_mask(tag_index);
+        for monitor_index in 0..self.monitors.len() {
+            let monitor = &mut self.monitors[monitor_index];
+            if monitor.tagset[monitor.selected_tags_index] == new_tagset {
+                continue;
+            }
+            monitor.selected_tags_index ^= 1;
+            monitor.tagset[monitor.selected_tags_index] = new_tagset;
+            if let Some(ref mut pertag) = monitor.pertag {
+                pertag.previous_tag = pertag.current_tag;
+                pertag.current_tag = tag_index + 1;
+            }
+
+            if self.load_pertag(monitor_index) {
+                let monitor = &mut self.monitors[monitor_index];
+                monitor.show_bar = !monitor.show_bar;
+            }
         }
 
+        if let Some(monitor) = self.monitors.get(self.selected_monitor) {
+            self.show_bar = monitor.show_bar;
+        }
         self.save_selected_tags()?;
         self.focus(None)?;
         self.apply_layout()?;
         self.update_bar()?;
+        Ok(())
+    }
+
+    /// Marks the selected monitor's next layout pass to place tiled windows
+    /// one screen width to the side, ready for `slide_in_tiled`.
+    fn prepare_slide(&mut self, direction: i32) {
+        let enabled = self.config.tag_switch_animation.is_some_and(|animation| {
+            self.config.animations_enabled && !animation.duration.is_zero()
+        });
+        self.slide_offset = self
+            .monitors
+            .get(self.selected_monitor)
+            .filter(|_| enabled)
+            .map(|monitor| (self.selected_monitor, monitor.screen_width * direction));
+    }
+
+    fn slide_offset_for(&self, client: &Client) -> i32 {
+        let Some((monitor_index, offset)) = self.slide_offset else {
+            return 0;
+        };
+        let on_tagset = self
+            .monitors
+            .get(monitor_index)
+            .is_some_and(|monitor| client.tags & monitor.tagset[monitor.selected_tags_index] != 0);
+        if client.monitor_index == monitor_index && on_tagset && !client.is_floating {
+            offset
+        } else {
+            0
+        }
+    }
+
+    /// Slides the tiled windows placed by `prepare_slide` back into view,
+    /// blocking for the configured duration.
+    fn slide_in_tiled(&mut self) -> WmResult<()> {
+        let Some((monitor_index, distance)) = self.slide_offset.take() else {
+            return Ok(());
+        };
+        let Some(animation) = self.config.tag_switch_animation else {
+            return Ok(());
+        };
+        let Some(monitor) = self.monitors.get(monitor_index) else {
+            return Ok(());
+        };
+
+        let mut windows = Vec::new();
+        let mut current = self.next_tiled(monitor.clients_head, monitor);
+        while let Some(window) = current {
+            if let Some(client) = self.clients.get(&window) {
+                windows.push((window, client.x_position as i32));
+            }
+            current = self
+                .clients
+                .get(&window)
+                .and_then(|client| self.next_tiled(client.next, monitor));
+        }
+        if windows.is_empty() {
+            return Ok(());
+        }
+
+        let slide = LayoutAnimation::new(&animation);
+        loop {
+            let progress = slide.progress();
+            let offset = (distance as f64 * (1.0 - progress)).round() as i32;
+
+            for &(window, x) in &windows {
+                self.connection
+                    .configure_window(window, &ConfigureWindowAux::new().x(x + offset))?;
+            }
+            self.connection.flush()?;
+
+            if progress >= 1.0 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(16));
+        }
 
         Ok(())
     }
@@ -1565,7 +4137,7 @@ impl WindowManager {
         }
 
         let num_tags = self.config.tags.len();
-        let all_tags_mask = (1u32 << num_tags) - 1;
+        let all_tags_mask = all_tags_mask(num_tags);
         let mut layout_name: Option<String> = None;
         let mut toggle_bar = false;
 
@@ -1585,35 +4157,291 @@ impl WindowManager {
                     pertag.current_tag = 0;
                 }
 
-                if pertag.current_tag > 0 && (new_tagset & (1 << (pertag.current_tag - 1))) == 0 {
-                    pertag.previous_tag = pertag.current_tag;
-                    pertag.current_tag = (new_tagset.trailing_zeros() as usize) + 1;
+                if pertag.current_tag > 0 && (new_tagset & (1 << (pertag.current_tag - 1))) == 0 {
+                    pertag.previous_tag = pertag.current_tag;
+                    pertag.current_tag = (new_tagset.trailing_zeros() as usize) + 1;
+                }
+
+                monitor.num_master = pertag.num_masters[pertag.current_tag];
+                monitor.master_factor = pertag.master_factors[pertag.current_tag];
+                layout_name = Some(pertag.layouts[pertag.current_tag].clone());
+                if monitor.show_bar != pertag.show_bars[pertag.current_tag] {
+                    toggle_bar = true;
+                }
+            }
+        }
+
+        if let Some(name) = layout_name {
+            if let Ok(layout) = layout_from_str(&name) {
+                self.layouts[self.selected_monitor] = layout;
+            }
+        }
+
+        if toggle_bar {
+            self.toggle_bar()?;
+        }
+
+        self.save_selected_tags()?;
+        self.focus(None)?;
+        self.apply_layout()?;
+        self.update_bar()?;
+
+        Ok(())
+    }
+
+    fn tag_state(&self) -> TagState {
+        TagState {
+            tag_order: self.tag_order.clone(),
+            monitors: self
+                .monitors
+                .iter()
+                .filter_map(|monitor| monitor.tag_state())
+                .collect(),
+        }
+    }
+
+    fn save_tag_state(&mut self) -> WmResult<()> {
+        let state = self.tag_state();
+        if self.saved_tag_state.as_ref() == Some(&state) {
+            return Ok(());
+        }
+        let values = state.to_cardinals();
+        let bytes: Vec<u8> = values
+            .iter()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect();
+        self.connection.change_property(
+            PropMode::REPLACE,
+            self.root,
+            self.atoms.oxwm_tag_state,
+            AtomEnum::CARDINAL,
+            32,
+            values.len() as u32,
+            &bytes,
+        )?;
+        self.saved_tag_state = Some(state);
+        Ok(())
+    }
+
+    /// Saves monitor and client state whenever it changed. Called from the idle loop's
+    /// bar tick, which only starts once `restore_saved_state` has read the previous state.
+    fn save_state(&mut self) -> WmResult<()> {
+        self.save_tag_state()?;
+        self.save_client_states()
+    }
+
+    /// Restores the monitor and client state saved by a previous instance, e.g. before
+    /// re-executing oxwm after an upgrade, and rearranges everything once.
+    pub fn restore_saved_state(&mut self) -> WmResult<()> {
+        self.restore_tag_state()?;
+        self.restore_client_state()?;
+        self.save_selected_tags()?;
+        self.focus(None)?;
+        self.apply_layout()?;
+        self.update_bar()?;
+        Ok(())
+    }
+
+    fn restore_tag_state(&mut self) -> WmResult<()> {
+        let reply = self
+            .connection
+            .get_property(
+                false,
+                self.root,
+                self.atoms.oxwm_tag_state,
+                AtomEnum::CARDINAL,
+                0,
+                u32::MAX,
+            )?
+            .reply()?;
+        let Some(values) = reply.value32() else {
+            return Ok(());
+        };
+        let values: Vec<u32> = values.collect();
+        let Some(state) = TagState::from_cardinals(&values) else {
+            eprintln!("Ignoring saved tag state from an unknown format version");
+            return Ok(());
+        };
+
+        if is_tag_order(&state.tag_order, self.config.tags.len()) {
+            for (first, second) in tag_order_swaps(&state.tag_order) {
+                swap_config_tags(&mut self.config, first, second);
+            }
+            self.tag_order = state.tag_order.clone();
+            for (monitor_index, bar) in self.bars.iter_mut().enumerate() {
+                bar.update_tags(&self.config, monitor_index);
+            }
+            self.update_desktop_names()?;
+        }
+
+        for (monitor_index, saved) in state.monitors.iter().enumerate() {
+            let Some(monitor) = self.monitors.get_mut(monitor_index) else {
+                break;
+            };
+            if let Some(name) = monitor.restore_tag_state(saved)
+                && let Ok(layout) = layout_from_str(&name)
+            {
+                self.layouts[monitor_index] = layout;
+            }
+        }
+        Ok(())
+    }
+
+    fn client_states(&self) -> Vec<(Window, SavedClientState)> {
+        let mut states = Vec::new();
+        for monitor in &self.monitors {
+            let mut stack = HashMap::new();
+            let mut current = monitor.stack_head;
+            while let Some(window) = current {
+                let position = stack.len();
+                stack.entry(window).or_insert(position);
+                current = self.clients.get(&window).and_then(|c| c.stack_next);
+            }
+
+            let mut order = 0;
+            let mut current = monitor.clients_head;
+            while let Some(window) = current {
+                let Some(client) = self.clients.get(&window) else {
+                    break;
+                };
+                // Fullscreen clients are saved with the state they return to.
+                let (is_floating, x, y, width, height) = if client.is_fullscreen {
+                    (
+                        client.old_state,
+                        client.old_x_position,
+                        client.old_y_position,
+                        client.old_width,
+                        client.old_height,
+                    )
+                } else {
+                    (
+                        client.is_floating,
+                        client.x_position,
+                        client.y_position,
+                        client.width,
+                        client.height,
+                    )
+                };
+                states.push((
+                    window,
+                    SavedClientState {
+                        tags: client.tags,
+                        monitor: client.monitor_index,
+                        is_floating,
+                        x: x as i32,
+                        y: y as i32,
+                        width: width as u32,
+                        height: height as u32,
+                        order,
+                        stack: stack.get(&window).copied().unwrap_or(stack.len()),
+                    },
+                ));
+
+                order += 1;
+                current = client.next;
+            }
+        }
+        states
+    }
+
+    /// Writes the state of every client that changed since the last call, so a crashed
+    /// or re-executed oxwm finds it on the windows without having to quit cleanly.
+    fn save_client_states(&mut self) -> WmResult<()> {
+        let states = self.client_states();
+        self.saved_client_states
+            .retain(|window, _| self.clients.contains_key(window));
+
+        for (window, state) in states {
+            if self.saved_client_states.get(&window) == Some(&state) {
+                continue;
+            }
+            let values = state.to_cardinals();
+            let bytes: Vec<u8> = values
+                .iter()
+                .flat_map(|value| value.to_ne_bytes())
+                .collect();
+            self.connection.change_property(
+                PropMode::REPLACE,
+                window,
+                self.atoms.oxwm_client_state,
+                AtomEnum::CARDINAL,
+                32,
+                values.len() as u32,
+                &bytes,
+            )?;
+            self.saved_client_states.insert(window, state);
+        }
+        Ok(())
+    }
+
+    /// Puts clients back on the monitor, tags, floating geometry and list positions saved
+    /// by `save_client_states`.
+    fn restore_client_state(&mut self) -> WmResult<()> {
+        let mut saved: Vec<(Window, SavedClientState)> = Vec::new();
+        let windows: Vec<Window> = self.clients.keys().copied().collect();
+        for window in windows {
+            if let Some(state) = self.read_saved_client_state(window)? {
+                saved.push((window, state));
+            }
+        }
+
+        let all_tags = all_tags_mask(self.config.tags.len());
+        for &(window, state) in &saved {
+            if self.is_scratchpad(window) {
+                continue;
+            }
+            if state.monitor < self.monitors.len() {
+                self.move_window_to_monitor(window, state.monitor, true)?;
+            }
+            if state.tags != 0 && state.tags & !all_tags == 0 {
+                if let Some(client) = self.clients.get_mut(&window) {
+                    client.tags = state.tags;
                 }
+                self.update_client_desktop(window)?;
+            }
 
-                monitor.num_master = pertag.num_masters[pertag.current_tag];
-                monitor.master_factor = pertag.master_factors[pertag.current_tag];
-                layout_name = Some(pertag.layouts[pertag.current_tag].clone());
-                if monitor.show_bar != pertag.show_bars[pertag.current_tag] {
-                    toggle_bar = true;
+            let Some(client) = self.clients.get_mut(&window) else {
+                continue;
+            };
+            let has_geometry = state.is_floating && state.width > 0 && state.height > 0;
+            if client.is_fullscreen {
+                client.old_state = state.is_floating;
+                if has_geometry {
+                    client.old_x_position = state.x as i16;
+                    client.old_y_position = state.y as i16;
+                    client.old_width = state.width as u16;
+                    client.old_height = state.height as u16;
                 }
+                continue;
+            }
+            client.is_floating = state.is_floating;
+            if has_geometry {
+                client.x_position = state.x as i16;
+                client.y_position = state.y as i16;
+                client.width = state.width as u16;
+                client.height = state.height as u16;
+            }
+            self.sync_window_state(window);
+            if has_geometry {
+                self.configure_client_geometry(window)?;
             }
         }
 
-        if let Some(name) = layout_name {
-            if let Ok(layout) = layout_from_str(&name) {
-                self.layout = layout;
+        // Prepending in reverse saved order rebuilds both lists in the saved order.
+        saved.sort_by_key(|&(_, state)| std::cmp::Reverse(state.order));
+        for &(window, _) in &saved {
+            if let Some(monitor_index) = self.clients.get(&window).map(|c| c.monitor_index) {
+                self.detach(window);
+                self.attach(window, monitor_index);
             }
         }
-
-        if toggle_bar {
-            self.toggle_bar()?;
+        saved.sort_by_key(|&(_, state)| std::cmp::Reverse(state.stack));
+        for &(window, _) in &saved {
+            if let Some(monitor_index) = self.clients.get(&window).map(|c| c.monitor_index) {
+                self.detach_stack(window);
+                self.attach_stack(window, monitor_index);
+            }
         }
-
-        self.save_selected_tags()?;
-        self.focus(None)?;
-        self.apply_layout()?;
-        self.update_bar()?;
-
         Ok(())
     }
 
@@ -1642,6 +4470,102 @@ impl WindowManager {
         Ok(())
     }
 
+    fn update_desktop_names(&self) -> WmResult<()> {
+        let names: Vec<u8> = self
+            .config
+            .tags
+            .iter()
+            .flat_map(|name| name.bytes().chain(std::iter::once(0)))
+            .collect();
+
+        self.connection.change_property(
+            PropMode::REPLACE,
+            self.root,
+            self.atoms.net_desktop_names,
+            self.atoms.utf8_string,
+            8,
+            names.len() as u32,
+            &names,
+        )?;
+
+        self.connection.change_property(
+            PropMode::REPLACE,
+            self.root,
+            self.atoms.net_number_of_desktops,
+            AtomEnum::CARDINAL,
+            32,
+            1,
+            &(self.config.tags.len() as u32).to_ne_bytes(),
+        )?;
+        Ok(())
+    }
+
+    /// Swaps the selected tag with its neighbour. Windows follow their tag,
+    /// while keybindings and rules keep referring to tag positions.
+    fn move_tag(&mut self, direction: i32) -> WmResult<()> {
+        let Some(monitor) = self.monitors.get(self.selected_monitor) else {
+            return Ok(());
+        };
+        let tag_index = unmask_tag(monitor.get_selected_tag());
+        let target_index = tag_index as i32 + direction.signum();
+        if tag_index >= self.config.tags.len()
+            || target_index < 0
+            || target_index as usize >= self.config.tags.len()
+        {
+            return Ok(());
+        }
+        let target_index = target_index as usize;
+
+        swap_config_tags(&mut self.config, tag_index, target_index);
+        self.tag_order.swap(tag_index, target_index);
+        self.swap_tag_contents(tag_index, target_index)?;
+
+        for (monitor_index, bar) in self.bars.iter_mut().enumerate() {
+            bar.update_tags(&self.config, monitor_index);
+        }
+
+        self.update_desktop_names()?;
+        self.save_selected_tags()?;
+        self.save_tag_state()?;
+        self.update_bar()?;
+        Ok(())
+    }
+
+    /// Swaps which windows, tagsets and per-tag settings belong to two tag positions.
+    fn swap_tag_contents(&mut self, first: usize, second: usize) -> WmResult<()> {
+        let remap = |mask: TagMask| swap_tag_bits(mask, first, second);
+
+        let windows: Vec<Window> = self.clients.keys().copied().collect();
+        for window in windows {
+            let Some(client) = self.clients.get_mut(&window) else {
+                continue;
+            };
+            client.tags = remap(client.tags);
+            self.update_client_desktop(window)?;
+        }
+
+        for monitor in &mut self.monitors {
+            monitor.tagset = monitor.tagset.map(remap);
+            if let Some(ref mut pertag) = monitor.pertag {
+                pertag.swap_tags(first, second);
+            }
+        }
+        Ok(())
+    }
+
+    /// A reload reads the tags in their configured order. Moved tags are reapplied when
+    /// the tag count is unchanged; otherwise the windows move back to their configured tags.
+    fn reset_tag_order(&mut self) -> WmResult<()> {
+        if self.tag_order.len() == self.config.tags.len() {
+            return Ok(());
+        }
+        for (first, second) in tag_order_swaps(&self.tag_order).into_iter().rev() {
+            self.swap_tag_contents(first, second)?;
+        }
+        self.tag_order = (0..self.config.tags.len()).collect();
+        Ok(())
+    }
+
     pub fn move_to_tag(&mut self, tag_index: usize) -> WmResult<()> {
         if tag_index >= self.config.tags.len() {
             return Ok(());
@@ -1662,8 +4586,8 @@ impl WindowManager {
             client.tags = mask;
         }
 
-        if let Err(error) = self.save_client_tag(focused, mask) {
-            eprintln!("Failed to save client tag: {:?}", error);
+        if let Err(error) = self.update_client_desktop(focused) {
+            eprintln!("Failed to update client desktop: {:?}", error);
         }
 
         self.focus(None)?;
@@ -1699,8 +4623,8 @@ impl WindowManager {
             client.tags = new_tags;
         }
 
-        if let Err(error) = self.save_client_tag(focused, new_tags) {
-            eprintln!("Failed to save client tag: {:?}", error);
+        if let Err(error) = self.update_client_desktop(focused) {
+            eprintln!("Failed to update client desktop: {:?}", error);
         }
 
         self.focus(None)?;
@@ -1737,7 +4661,7 @@ impl WindowManager {
             visible[0]
         };
 
-        let is_tabbed = self.layout.name() == "tabbed";
+        let is_tabbed = self.is_tabbed(self.selected_monitor);
         if is_tabbed {
             self.connection.configure_window(
                 next_window,
@@ -1814,7 +4738,7 @@ impl WindowManager {
             type_: self.atoms.wm_protocols,
             data: x11rb::protocol::xproto::ClientMessageData::from([
                 protocol,
-                x11rb::CURRENT_TIME,
+                self.last_event_time,
                 0,
                 0,
                 0,
@@ -1831,6 +4755,7 @@ impl WindowManager {
         if let Some(client) = self.clients.get_mut(&window) {
             client.is_urgent = urgent;
         }
+        self.refresh_border(window)?;
 
         let hints_reply = self
             .connection
@@ -1870,24 +4795,300 @@ impl WindowManager {
         Ok(())
     }
 
-    fn get_window_atom_property(&self, window: Window, property: Atom) -> WmResult<Option<Atom>> {
-        let reply = self
+    /// Whether the window asks for no decorations through `_MOTIF_WM_HINTS`.
+    fn wants_no_decorations(&self, window: Window) -> bool {
+        const MWM_HINTS_DECORATIONS: u32 = 1 << 1;
+
+        let hints: Vec<u32> = self
             .connection
-            .get_property(false, window, property, AtomEnum::ATOM, 0, 1)?
-            .reply();
+            .get_property(
+                false,
+                window,
+                self.atoms.motif_wm_hints,
+                AtomEnum::ANY,
+                0,
+                5,
+            )
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| reply.value32().map(|values| values.collect()))
+            .unwrap_or_default();
 
-        match reply {
-            Ok(prop) if !prop.value.is_empty() && prop.value.len() >= 4 => {
-                let atom = u32::from_ne_bytes([
-                    prop.value[0],
-                    prop.value[1],
-                    prop.value[2],
-                    prop.value[3],
-                ]);
-                Ok(Some(atom))
+        match hints.as_slice() {
+            [flags, _, decorations, ..] => flags & MWM_HINTS_DECORATIONS != 0 && *decorations == 0,
+            _ => false,
+        }
+    }
+
+    fn client_border_width(&self, window: Window) -> u32 {
+        if self.clients.get(&window).is_some_and(|c| c.is_borderless) {
+            0
+        } else {
+            self.config.border_width
+        }
+    }
+
+    fn update_motif_hints(&mut self, window: Window) -> WmResult<()> {
+        let is_borderless = self.wants_no_decorations(window);
+        let Some(client) = self.clients.get_mut(&window) else {
+            return Ok(());
+        };
+        if client.border_rule.is_some() || client.is_borderless == is_borderless {
+            return Ok(());
+        }
+        client.is_borderless = is_borderless;
+        if client.is_fullscreen {
+            return Ok(());
+        }
+
+        let border_width = self.client_border_width(window);
+        if let Some(client) = self.clients.get_mut(&window) {
+            client.border_width = border_width as u16;
+        }
+        self.connection.configure_window(
+            window,
+            &ConfigureWindowAux::new().border_width(border_width),
+        )?;
+        self.apply_layout()?;
+        Ok(())
+    }
+
+    fn get_window_strut(&self, window: Window) -> WmResult<Option<Strut>> {
+        let root_width = self.screen.width_in_pixels as i32;
+        let root_height = self.screen.height_in_pixels as i32;
+
+        for property in [self.atoms.net_wm_strut_partial, self.atoms.net_wm_strut] {
+            let reply = self
+                .connection
+                .get_property(false, window, property, AtomEnum::CARDINAL, 0, 12)?
+                .reply();
+
+            if let Ok(prop) = reply
+                && let Some(values) = prop.value32()
+            {
+                let values: Vec<u32> = values.collect();
+                if let Some(strut) = Strut::from_cardinals(&values, root_width, root_height) {
+                    return Ok(Some(strut));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn manage_dock(&mut self, window: Window, strut: Strut) -> WmResult<()> {
+        self.connection.change_window_attributes(
+            window,
+            &ChangeWindowAttributesAux::new()
+                .event_mask(EventMask::STRUCTURE_NOTIFY | EventMask::PROPERTY_CHANGE),
+        )?;
+        self.connection.map_window(window)?;
+        self.struts.insert(window, strut);
+        self.update_struts()
+    }
+
+    fn has_window_type(&self, window: Window, window_type: Atom) -> bool {
+        self.get_window_atom_list_property(window, self.atoms.net_wm_window_type)
+            .is_ok_and(|types| types.contains(&window_type))
+    }
+
+    /// Keeps docks above the clients of the selected monitor, unless one of
+    /// them is fullscreen there.
+    fn raise_docks(&self) -> WmResult<()> {
+        let covered = self
+            .monitors
+            .get(self.selected_monitor)
+            .is_some_and(|monitor| {
+                let tags = monitor.tagset[monitor.selected_tags_index];
+                self.fullscreen_windows.iter().any(|window| {
+                    self.clients.get(window).is_some_and(|client| {
+                        client.monitor_index == self.selected_monitor && client.tags & tags != 0
+                    })
+                })
+            });
+        if covered {
+            return Ok(());
+        }
+        for &window in self.struts.keys() {
+            self.connection.configure_window(
+                window,
+                &ConfigureWindowAux::new().stack_mode(StackMode::ABOVE),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Desktop windows (e.g. a file manager drawing icons) are left unmanaged,
+    /// cover their monitor exactly and stay below every client.
+    fn manage_desktop(&mut self, window: Window) -> WmResult<()> {
+        let geometry = self.connection.get_geometry(window)?.reply()?;
+        let monitor_index = self
+            .get_monitor_at_point(
+                geometry.x as i32 + geometry.width as i32 / 2,
+                geometry.y as i32 + geometry.height as i32 / 2,
+            )
+            .unwrap_or(self.selected_monitor);
+
+        self.connection.change_window_attributes(
+            window,
+            &ChangeWindowAttributesAux::new().event_mask(EventMask::STRUCTURE_NOTIFY),
+        )?;
+        self.desktops.insert(window, monitor_index);
+        self.span_desktop(window)?;
+        self.connection.map_window(window)?;
+        Ok(())
+    }
+
+    fn span_desktop(&self, window: Window) -> WmResult<()> {
+        let Some(monitor) = self
+            .desktops
+            .get(&window)
+            .and_then(|&monitor_index| self.monitors.get(monitor_index))
+        else {
+            return Ok(());
+        };
+
+        self.connection.configure_window(
+            window,
+            &ConfigureWindowAux::new()
+                .x(monitor.screen_x)
+                .y(monitor.screen_y)
+                .width(monitor.screen_width as u32)
+                .height(monitor.screen_height as u32)
+                .border_width(0)
+                .stack_mode(StackMode::BELOW),
+        )?;
+        Ok(())
+    }
+
+    fn lower_desktops(&self) -> WmResult<()> {
+        for &window in self.desktops.keys() {
+            self.connection.configure_window(
+                window,
+                &ConfigureWindowAux::new().stack_mode(StackMode::BELOW),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn update_struts(&mut self) -> WmResult<()> {
+        self.reserve_struts();
+        self.apply_layout()?;
+        self.update_bar()?;
+        Ok(())
+    }
+
+    fn reserve_struts(&mut self) {
+        let root_width = self.screen.width_in_pixels as i32;
+        let root_height = self.screen.height_in_pixels as i32;
+
+        for monitor in &mut self.monitors {
+            monitor.reserve_struts(self.struts.values(), root_width, root_height);
+        }
+    }
+
+    fn update_monitor_geometry(&mut self) -> WmResult<()> {
+        let detected = detect_monitors(&self.connection, &self.screen, self.root)?;
+        if detected.len() != self.monitors.len() {
+            eprintln!(
+                "Monitor count changed from {} to {}, only existing monitors are updated",
+                self.monitors.len(),
+                detected.len()
+            );
+        }
+
+        let changed = apply_detected_geometry(&mut self.monitors, &detected);
+        if changed.is_empty() {
+            return Ok(());
+        }
+        self.reserve_struts();
+        for &window in self.desktops.keys() {
+            self.span_desktop(window)?;
+        }
+
+        let windows: Vec<Window> = self.clients.keys().copied().collect();
+        for &window in &windows {
+            let Some(client) = self.clients.get_mut(&window) else {
+                continue;
+            };
+            let Some(&(_, old_area)) = changed
+                .iter()
+                .find(|(index, _)| *index == client.monitor_index)
+            else {
+                continue;
+            };
+
+            let new_area = self.monitors[client.monitor_index].window_area_rect();
+            let border = 2 * client.border_width as i32;
+            let (x, y, width, height) = rescale_rect(
+                (
+                    client.x_position as i32,
+                    client.y_position as i32,
+                    client.width as i32 + border,
+                    client.height as i32 + border,
+                ),
+                old_area,
+                new_area,
+            );
+
+            client.x_position = x as i16;
+            client.y_position = y as i16;
+
+            if client.is_floating && !client.is_fullscreen {
+                client.width = (width - border).max(1) as u16;
+                client.height = (height - border).max(1) as u16;
+                self.connection.configure_window(
+                    window,
+                    &ConfigureWindowAux::new()
+                        .x(x)
+                        .y(y)
+                        .width(client.width as u32)
+                        .height(client.height as u32),
+                )?;
+            }
+        }
+
+        for &window in &windows {
+            if !self.is_visible(window) {
+                continue;
+            }
+            let Some(client) = self.clients.get(&window) else {
+                continue;
+            };
+
+            let target = self.get_monitor_for_rect(
+                client.x_position as i32,
+                client.y_position as i32,
+                client.width as i32,
+                client.height as i32,
+            );
+            if target != client.monitor_index {
+                self.move_window_to_monitor(window, target, false)?;
+            }
+        }
+
+        let gaps = self.gap_config();
+        for (monitor_index, _) in changed {
+            let monitor = &self.monitors[monitor_index];
+            let (x, y, width, _) = monitor.screen_rect();
+
+            if let Some(bar) = self.bars.get_mut(monitor_index) {
+                bar.reposition(&self.connection, x as i16, y as i16, width as u16)?;
+                let bar_height = bar.height() as i32;
+
+                if let Some(tab_bar) = self.tab_bars.get_mut(monitor_index) {
+                    tab_bar.reposition(
+                        &self.connection,
+                        (x + gaps.outer_left as i32) as i16,
+                        (y + bar_height + gaps.outer_top as i32) as i16,
+                        width.saturating_sub((gaps.outer_left + gaps.outer_right) as i32) as u16,
+                    )?;
+                }
             }
-            _ => Ok(None),
         }
+
+        self.update_bar()?;
+        Ok(())
     }
 
     fn get_window_atom_list_property(&self, window: Window, property: Atom) -> WmResult<Vec<Atom>> {
@@ -1918,8 +5119,84 @@ impl WindowManager {
             return Ok(());
         };
 
-        let is_fullscreen = self.fullscreen_windows.contains(&focused_window);
-        self.set_window_fullscreen(focused_window, !is_fullscreen)?;
+        let is_fullscreen = self
+            .clients
+            .get(&focused_window)
+            .is_some_and(|client| client.reports_fullscreen());
+        self.set_window_fullscreen(focused_window, !is_fullscreen)?;
+        Ok(())
+    }
+
+    /// Switches the focused window between real and fake fullscreen while keeping
+    /// its fullscreen state, so a fullscreen window moves back into its tile and a
+    /// fake fullscreen one covers the monitor.
+    fn toggle_fake_fullscreen(&mut self) -> WmResult<()> {
+        let Some(window) = self
+            .monitors
+            .get(self.selected_monitor)
+            .and_then(|m| m.selected_client)
+        else {
+            return Ok(());
+        };
+        let Some(client) = self.clients.get(&window) else {
+            return Ok(());
+        };
+        let was_fullscreen = client.reports_fullscreen();
+
+        self.set_window_fullscreen(window, false)?;
+        if let Some(client) = self.clients.get_mut(&window) {
+            client.fake_fullscreen = !client.fake_fullscreen;
+        }
+        if was_fullscreen {
+            self.set_window_fullscreen(window, true)?;
+        }
+        self.restack()?;
+        Ok(())
+    }
+
+    /// Keeps `window` above everything else in restack; tiled windows are floated first.
+    fn set_window_above(&mut self, window: Window, above: bool) -> WmResult<()> {
+        let Some(client) = self.clients.get_mut(&window) else {
+            return Ok(());
+        };
+        let was_floating = client.is_floating;
+        client.is_above = above;
+        client.is_below &= !above;
+        client.is_floating |= above;
+        self.sync_window_state(window);
+
+        if above && !was_floating {
+            self.apply_layout()?;
+        }
+        self.restack()?;
+        Ok(())
+    }
+
+    /// Keeps `window` beneath the other windows of its monitor in restack.
+    fn set_window_below(&mut self, window: Window, below: bool) -> WmResult<()> {
+        let Some(client) = self.clients.get_mut(&window) else {
+            return Ok(());
+        };
+        client.is_below = below;
+        client.is_above &= !below;
+        self.sync_window_state(window);
+        self.restack()?;
+        Ok(())
+    }
+
+    /// Shows `window` on every tag of its monitor; like always-on-top it only
+    /// applies to floating windows, so tiled windows are floated first.
+    fn set_window_sticky(&mut self, window: Window, sticky: bool) -> WmResult<()> {
+        let Some(client) = self.clients.get_mut(&window) else {
+            return Ok(());
+        };
+        client.is_sticky = sticky;
+        client.is_floating |= sticky;
+        self.sync_window_state(window);
+        self.update_client_desktop(window)?;
+        self.apply_layout()?;
+        self.restack()?;
+        self.update_bar()?;
         Ok(())
     }
 
@@ -1931,18 +5208,20 @@ impl WindowManager {
             .unwrap_or(self.selected_monitor);
         let monitor = &self.monitors[monitor_idx];
 
-        if fullscreen && !self.fullscreen_windows.contains(&window) {
-            let bytes = self.atoms.net_wm_state_fullscreen.to_ne_bytes().to_vec();
-            self.connection.change_property(
-                PropMode::REPLACE,
-                window,
-                self.atoms.net_wm_state,
-                AtomEnum::ATOM,
-                32,
-                1,
-                &bytes,
-            )?;
+        if let Some(client) = self.clients.get(&window)
+            && (client.is_fake_fullscreen || (client.fake_fullscreen && !client.is_fullscreen))
+        {
+            if fullscreen == client.is_fake_fullscreen {
+                return Ok(());
+            }
+            if let Some(client) = self.clients.get_mut(&window) {
+                client.is_fake_fullscreen = fullscreen;
+            }
+            self.sync_window_state(window);
+            return Ok(());
+        }
 
+        if fullscreen && !self.fullscreen_windows.contains(&window) {
             if let Some(client) = self.clients.get_mut(&window) {
                 client.is_fullscreen = true;
                 client.old_state = client.is_floating;
@@ -1955,9 +5234,6 @@ impl WindowManager {
                 client.is_floating = true;
             }
 
-            self.fullscreen_windows.insert(window);
-            self.floating_windows.insert(window);
-
             self.connection.configure_window(
                 window,
                 &x11rb::protocol::xproto::ConfigureWindowAux::new()
@@ -1968,34 +5244,15 @@ impl WindowManager {
                     .height(monitor.screen_height as u32)
                     .stack_mode(x11rb::protocol::xproto::StackMode::ABOVE),
             )?;
+            self.sync_window_state(window);
 
             self.connection.flush()?;
         } else if !fullscreen && self.fullscreen_windows.contains(&window) {
-            self.connection.change_property(
-                PropMode::REPLACE,
-                window,
-                self.atoms.net_wm_state,
-                AtomEnum::ATOM,
-                32,
-                0,
-                &[],
-            )?;
-
-            self.fullscreen_windows.remove(&window);
-
-            let (
-                was_floating,
-                restored_x,
-                restored_y,
-                restored_width,
-                restored_height,
-                restored_border,
-            ) = self
+            let (restored_x, restored_y, restored_width, restored_height, restored_border) = self
                 .clients
                 .get(&window)
                 .map(|client| {
                     (
-                        client.old_state,
                         client.old_x_position,
                         client.old_y_position,
                         client.old_width,
@@ -2003,11 +5260,7 @@ impl WindowManager {
                         client.old_border_width,
                     )
                 })
-                .unwrap_or((false, 0, 0, 100, 100, 0));
-
-            if !was_floating {
-                self.floating_windows.remove(&window);
-            }
+                .unwrap_or((0, 0, 100, 100, 0));
 
             if let Some(client) = self.clients.get_mut(&window) {
                 client.is_fullscreen = false;
@@ -2018,6 +5271,7 @@ impl WindowManager {
                 client.width = client.old_width;
                 client.height = client.old_height;
             }
+            self.sync_window_state(window);
 
             self.connection.configure_window(
                 window,
@@ -2070,88 +5324,527 @@ impl WindowManager {
             .ok()
             .and_then(|cookie| cookie.reply().ok());
 
-        if let Some(reply) = reply
-            && !reply.value.is_empty()
-            && let Ok(text) = std::str::from_utf8(&reply.value)
-        {
-            let parts: Vec<&str> = text.split('\0').collect();
-            let instance = parts.first().unwrap_or(&"").to_string();
-            let class = parts.get(1).unwrap_or(&"").to_string();
-            return (instance, class);
+        if let Some(reply) = reply
+            && !reply.value.is_empty()
+            && let Ok(text) = std::str::from_utf8(&reply.value)
+        {
+            let parts: Vec<&str> = text.split('\0').collect();
+            let instance = parts.first().unwrap_or(&"").to_string();
+            let class = parts.get(1).unwrap_or(&"").to_string();
+            return (instance, class);
+        }
+
+        (String::new(), String::new())
+    }
+
+    fn get_window_role(&self, window: Window) -> String {
+        self.connection
+            .get_property(
+                false,
+                window,
+                self.atoms.wm_window_role,
+                AtomEnum::ANY,
+                0,
+                1024,
+            )
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| String::from_utf8(reply.value).ok())
+            .unwrap_or_default()
+    }
+
+    /// Working directory of the focused window's process, for spawning terminals.
+    fn focused_cwd(&self) -> Option<std::path::PathBuf> {
+        if !self.config.terminal_follows_cwd {
+            return None;
+        }
+        let window = self
+            .monitors
+            .get(self.selected_monitor)
+            .and_then(|m| m.selected_client)?;
+        let pid = match self.clients.get(&window).map(|c| c.pid) {
+            Some(pid) if pid != 0 => pid,
+            _ => self.get_window_pid(window)?,
+        };
+        std::fs::read_link(format!("/proc/{}/cwd", pid))
+            .ok()
+            .filter(|dir| dir.is_dir())
+    }
+
+    /// The PID in `_NET_WM_PID`, unless the window belongs to another host.
+    fn get_window_pid(&self, window: Window) -> Option<u32> {
+        let machine = self
+            .connection
+            .get_property(
+                false,
+                window,
+                AtomEnum::WM_CLIENT_MACHINE,
+                AtomEnum::STRING,
+                0,
+                256,
+            )
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| reply.value)
+            .unwrap_or_default();
+        if !is_local_machine(&machine, &self.hostname) {
+            return None;
+        }
+
+        self.connection
+            .get_property(
+                false,
+                window,
+                self.atoms.net_wm_pid,
+                AtomEnum::CARDINAL,
+                0,
+                1,
+            )
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| reply.value32().and_then(|mut values| values.next()))
+    }
+
+    fn get_window_command(&self, window: Window) -> String {
+        const COMMAND_CHARS: usize = 64;
+
+        let Some(cmdline) = self
+            .get_window_pid(window)
+            .and_then(|pid| std::fs::read(format!("/proc/{}/cmdline", pid)).ok())
+        else {
+            return String::new();
+        };
+
+        String::from_utf8_lossy(&cmdline)
+            .replace('\0', " ")
+            .trim_end()
+            .chars()
+            .take(COMMAND_CHARS)
+            .collect()
+    }
+
+    /// Applies matching window rules and returns the rule's focus preference, if any.
+    fn apply_rules(&mut self, window: Window) -> WmResult<(Option<bool>, RulePlacement)> {
+        let (instance, class) = self.get_window_class_instance(window);
+        let role = self.get_window_role(window);
+        let title = self
+            .clients
+            .get(&window)
+            .map(|c| c.name.clone())
+            .unwrap_or_default();
+
+        let mut rule_tags: Option<u32> = None;
+        let mut rule_floating: Option<bool> = None;
+        let mut rule_monitor: Option<usize> = None;
+        let mut rule_scratchpad: Option<String> = None;
+        let mut rule_click_through: Option<bool> = None;
+        let mut rule_swallow: Option<bool> = None;
+        let mut rule_center: Option<bool> = None;
+        let mut rule_fake_fullscreen: Option<bool> = None;
+        let mut rule_border: Option<bool> = None;
+        let mut rule_geometry: [Option<i32>; 4] = [None; 4];
+        let mut rule_focus: Option<bool> = None;
+
+        for rule in &self.config.window_rules {
+            if rule.matches(&class, &instance, &title, &role) {
+                if rule.tags.is_some() {
+                    rule_tags = rule.tags;
+                }
+                if rule.is_floating.is_some() {
+                    rule_floating = rule.is_floating;
+                }
+                if rule.monitor.is_some() {
+                    rule_monitor = rule.monitor;
+                }
+                if rule.scratchpad.is_some() {
+                    rule_scratchpad = rule.scratchpad.clone();
+                }
+                if rule.click_through.is_some() {
+                    rule_click_through = rule.click_through;
+                }
+                if rule.swallow.is_some() {
+                    rule_swallow = rule.swallow;
+                }
+                if rule.center.is_some() {
+                    rule_center = rule.center;
+                }
+                if rule.fake_fullscreen.is_some() {
+                    rule_fake_fullscreen = rule.fake_fullscreen;
+                }
+                if rule.border.is_some() {
+                    rule_border = rule.border;
+                }
+                for (slot, value) in
+                    rule_geometry
+                        .iter_mut()
+                        .zip([rule.x, rule.y, rule.width, rule.height])
+                {
+                    if value.is_some() {
+                        *slot = value;
+                    }
+                }
+                if rule.focus.is_some() {
+                    rule_focus = rule.focus;
+                }
+            }
+        }
+
+        let focus_new_windows = self.focus_new_windows(rule_focus);
+        let steal_monitor_focus = self.steal_monitor_focus();
+
+        if let Some(client) = self.clients.get_mut(&window) {
+            client.is_terminal = rule_swallow.unwrap_or(false);
+            client.fake_fullscreen = rule_fake_fullscreen.unwrap_or(false);
+            client.border_rule = rule_border;
+            if let Some(border) = rule_border {
+                client.is_borderless = !border;
+                client.border_width = if border {
+                    self.config.border_width as u16
+                } else {
+                    0
+                };
+            }
+        }
+
+        if let Some(is_floating) = rule_floating
+            && let Some(client) = self.clients.get_mut(&window)
+        {
+            client.is_floating = is_floating;
+            self.sync_window_state(window);
+        }
+
+        if let Some(client) = self.clients.get_mut(&window) {
+            if let Some(monitor_index) = rule_monitor
+                && monitor_index < self.monitors.len()
+            {
+                client.monitor_index = monitor_index;
+            }
+
+            if let Some(tags) = rule_tags {
+                client.tags = tags;
+
+                if rule_focus == Some(true)
+                    && focus_new_windows
+                    && (steal_monitor_focus || client.monitor_index == self.selected_monitor)
+                {
+                    let tag_index = unmask_tag(tags);
+                    let monitor_tagset = self
+                        .monitors
+                        .get(client.monitor_index)
+                        .map(|monitor| monitor.get_selected_tag())
+                        .unwrap_or(tag_mask(0));
+                    let is_tag_focused = monitor_tagset & tags == tags;
+                    let client_monitor = client.monitor_index;
+
+                    if !is_tag_focused {
+                        // The tag is viewed on the window's monitor, which it takes focus to.
+                        if client_monitor != self.selected_monitor {
+                            if let Some(focused) = self
+                                .monitors
+                                .get(self.selected_monitor)
+                                .and_then(|m| m.selected_client)
+                            {
+                                self.unfocus(focused, false)?;
+                            }
+                            self.selected_monitor = client_monitor;
+                        }
+                        self.view_tag(tag_index)?;
+                    }
+                }
+            }
+        }
+
+        if rule_scratchpad.is_none() {
+            rule_scratchpad = self
+                .pending_scratchpads
+                .remove(&instance)
+                .map(|(name, _)| name);
+        }
+        if let Some(name) = rule_scratchpad {
+            self.register_scratchpad(name, window);
+        }
+
+        if rule_click_through == Some(true) {
+            self.set_click_through(window, true)?;
+        }
+
+        Ok((
+            rule_focus,
+            RulePlacement {
+                geometry: rule_geometry,
+                center: rule_center == Some(true),
+            },
+        ))
+    }
+
+    /// Applies rule geometry and centering once it is known whether the window floats.
+    /// Positions are relative to the monitor's window area; only floating clients use them.
+    fn place_by_rules(&mut self, window: Window, placement: RulePlacement) {
+        let Some(client) = self.clients.get_mut(&window) else {
+            return;
+        };
+        if !client.is_floating {
+            return;
+        }
+        let Some(monitor) = self.monitors.get(client.monitor_index) else {
+            return;
+        };
+
+        if placement.geometry.iter().any(Option::is_some) {
+            let (x, y, width, height) = placed_geometry(
+                placement.geometry,
+                (
+                    client.x_position as i32,
+                    client.y_position as i32,
+                    client.width as i32,
+                    client.height as i32,
+                ),
+                monitor.window_area_rect(),
+                client.border_width as i32,
+            );
+            client.x_position = x as i16;
+            client.y_position = y as i16;
+            client.width = width as u16;
+            client.height = height as u16;
+        }
+
+        if placement.center {
+            self.center_client(window);
+        }
+    }
+
+    fn register_scratchpad(&mut self, name: String, window: Window) {
+        if let Some(previous) = self.scratchpads.insert(name, window)
+            && previous != window
+            && let Some(client) = self.clients.get_mut(&previous)
+            && client.tags == 0
+        {
+            let monitor = &self.monitors[client.monitor_index];
+            client.tags = monitor.tagset[monitor.selected_tags_index];
+        }
+
+        if let Some(client) = self.clients.get_mut(&window) {
+            client.is_floating = true;
+        }
+        self.sync_window_state(window);
+        self.place_scratchpad(window);
+    }
+
+    fn scratchpad_config(&self, name: &str) -> Option<&crate::Scratchpad> {
+        self.config
+            .scratchpads
+            .iter()
+            .find(|scratchpad| scratchpad.name == name)
+    }
+
+    /// Sizes and positions a scratchpad on its monitor from its definition,
+    /// centering it when nothing else is configured.
+    fn place_scratchpad(&mut self, window: Window) {
+        let definition = self
+            .scratchpads
+            .iter()
+            .find(|&(_, &w)| w == window)
+            .and_then(|(name, _)| self.scratchpad_config(name))
+            .cloned()
+            .unwrap_or_default();
+        let Some(client) = self.clients.get_mut(&window) else {
+            return;
+        };
+        let Some(monitor) = self.monitors.get(client.monitor_index) else {
+            return;
+        };
+
+        let border = 2 * client.border_width as i32;
+        let span = |fraction: Option<f32>, area: i32, current: u16| {
+            fraction.map_or(current as i32, |fraction| {
+                ((area as f32 * fraction) as i32 - border).max(1)
+            })
+        };
+        let width = span(definition.width, monitor.window_area_width, client.width);
+        let height = span(definition.height, monitor.window_area_height, client.height);
+        let offset = |fraction: Option<f32>, start: i32, area: i32, size: i32| match fraction {
+            Some(fraction) => start + (area as f32 * fraction) as i32,
+            None => start + (area - size - border) / 2,
+        };
+
+        client.x_position = offset(
+            definition.x,
+            monitor.window_area_x,
+            monitor.window_area_width,
+            width,
+        ) as i16;
+        client.y_position = offset(
+            definition.y,
+            monitor.window_area_y,
+            monitor.window_area_height,
+            height,
+        ) as i16;
+        client.width = width as u16;
+        client.height = height as u16;
+    }
+
+    fn configure_client_geometry(&self, window: Window) -> WmResult<()> {
+        let Some(client) = self.clients.get(&window) else {
+            return Ok(());
+        };
+        self.connection.configure_window(
+            window,
+            &ConfigureWindowAux::new()
+                .x(client.x_position as i32)
+                .y(client.y_position as i32)
+                .width(client.width as u32)
+                .height(client.height as u32),
+        )?;
+        self.send_configure_notify(window)?;
+        Ok(())
+    }
+
+    /// Reapplies definitions to the scratchpads on screen, e.g. after a config reload.
+    fn place_visible_scratchpads(&mut self) -> WmResult<()> {
+        let visible: Vec<Window> = self
+            .scratchpads
+            .values()
+            .copied()
+            .filter(|window| self.clients.get(window).is_some_and(|c| c.tags != 0))
+            .collect();
+        for window in visible {
+            self.place_scratchpad(window);
+            self.configure_client_geometry(window)?;
         }
+        Ok(())
+    }
 
-        (String::new(), String::new())
+    fn is_scratchpad(&self, window: Window) -> bool {
+        self.scratchpads.values().any(|&w| w == window)
     }
 
-    fn apply_rules(&mut self, window: Window) -> WmResult<()> {
-        let (instance, class) = self.get_window_class_instance(window);
-        let title = self
-            .clients
-            .get(&window)
-            .map(|c| c.name.clone())
-            .unwrap_or_default();
+    fn toggle_scratchpad(&mut self, name: &str, command: Option<&str>) -> WmResult<()> {
+        let command = command.map(str::to_string).or_else(|| {
+            self.scratchpad_config(name)
+                .and_then(|scratchpad| scratchpad.command.clone())
+        });
+        let command = command.as_deref();
 
-        let mut rule_tags: Option<u32> = None;
-        let mut rule_floating: Option<bool> = None;
-        let mut rule_monitor: Option<usize> = None;
-        let mut rule_focus = false;
+        if self
+            .scratchpads
+            .get(name)
+            .is_some_and(|window| !self.clients.contains_key(window))
+        {
+            self.scratchpads.remove(name);
+        }
 
-        for rule in &self.config.window_rules {
-            if rule.matches(&class, &instance, &title) {
-                if rule.tags.is_some() {
-                    rule_tags = rule.tags;
-                }
-                if rule.is_floating.is_some() {
-                    rule_floating = rule.is_floating;
+        if !self.scratchpads.contains_key(name)
+            && let Some(command) = command
+        {
+            let instance = scratchpad_instance(name, command);
+            // A window left over from before a restart is adopted instead of spawning a second one.
+            let existing = self.windows.iter().copied().find(|&window| {
+                !self.is_scratchpad(window)
+                    && self
+                        .clients
+                        .get(&window)
+                        .is_some_and(|client| client.instance == instance)
+            });
+            match existing {
+                Some(window) => {
+                    self.register_scratchpad(name.to_string(), window);
+                    if let Some(client) = self.clients.get_mut(&window) {
+                        client.tags = 0;
+                    }
+                    self.update_client_desktop(window)?;
                 }
-                if rule.monitor.is_some() {
-                    rule_monitor = rule.monitor;
+                None => {
+                    let now = std::time::Instant::now();
+                    let waiting =
+                        self.pending_scratchpads
+                            .get(&instance)
+                            .is_some_and(|(_, spawned)| {
+                                now.duration_since(*spawned) < SCRATCHPAD_SPAWN_TIMEOUT
+                            });
+                    if !waiting {
+                        self.pending_scratchpads
+                            .insert(instance, (name.to_string(), now));
+                        crate::signal::spawn_detached(command);
+                    }
+                    return Ok(());
                 }
-                rule_focus = rule.focus.unwrap_or(false);
             }
         }
 
-        if let Some(client) = self.clients.get_mut(&window) {
-            if let Some(is_floating) = rule_floating {
-                client.is_floating = is_floating;
-                if is_floating {
-                    self.floating_windows.insert(window);
-                } else {
-                    self.floating_windows.remove(&window);
-                }
-            }
-
-            if let Some(monitor_index) = rule_monitor
-                && monitor_index < self.monitors.len()
-            {
-                client.monitor_index = monitor_index;
-            }
-
-            if let Some(tags) = rule_tags {
-                client.tags = tags;
+        let Some(&window) = self.scratchpads.get(name) else {
+            return Ok(());
+        };
+        let Some(client) = self.clients.get(&window) else {
+            return Ok(());
+        };
 
-                if rule_focus {
-                    let tag_index = unmask_tag(tags);
-                    let monitor_tagset = self
-                        .monitors
-                        .get(client.monitor_index)
-                        .map(|monitor| monitor.get_selected_tag())
-                        .unwrap_or(tag_mask(0));
-                    let is_tag_focused = monitor_tagset & tags == tags;
+        let selected_tags = self.monitors[self.selected_monitor].get_selected_tag();
+        let is_shown =
+            client.monitor_index == self.selected_monitor && (client.tags & selected_tags) != 0;
 
-                    if !is_tag_focused {
-                        self.view_tag(tag_index)?;
+        if is_shown {
+            if let Some(client) = self.clients.get_mut(&window) {
+                client.tags = 0;
+            }
+            self.update_client_desktop(window)?;
+            self.focus(None)?;
+            self.apply_layout()?;
+        } else {
+            if client.monitor_index != self.selected_monitor {
+                self.move_window_to_monitor(window, self.selected_monitor, false)?;
+            }
+            if self
+                .scratchpad_config(name)
+                .is_some_and(|scratchpad| scratchpad.exclusive)
+            {
+                let others: Vec<Window> = self
+                    .scratchpads
+                    .values()
+                    .copied()
+                    .filter(|&other| other != window)
+                    .collect();
+                for other in others {
+                    if let Some(client) = self.clients.get_mut(&other)
+                        && client.monitor_index == self.selected_monitor
+                    {
+                        client.tags = 0;
+                        self.update_client_desktop(other)?;
                     }
                 }
             }
+            if let Some(client) = self.clients.get_mut(&window) {
+                client.tags = selected_tags;
+                client.is_floating = true;
+            }
+            self.sync_window_state(window);
+            self.update_client_desktop(window)?;
+            self.place_scratchpad(window);
+            self.configure_client_geometry(window)?;
+            self.focus(Some(window))?;
+            self.apply_layout()?;
+            self.restack()?;
         }
 
+        self.update_bar()?;
         Ok(())
     }
 
     fn manage_window(&mut self, window: Window) -> WmResult<()> {
+        let strut = self.get_window_strut(window)?;
+        if strut.is_some() || self.has_window_type(window, self.atoms.net_wm_window_type_dock) {
+            return self.manage_dock(window, strut.unwrap_or_default());
+        }
+        if self.has_window_type(window, self.atoms.net_wm_window_type_desktop) {
+            return self.manage_desktop(window);
+        }
+
         let geometry = self.connection.get_geometry(window)?.reply()?;
-        let border_width = self.config.border_width;
+        let is_borderless = self.wants_no_decorations(window);
+        let border_width = if is_borderless {
+            0
+        } else {
+            self.config.border_width
+        };
 
         let transient_parent = self.get_transient_parent(window);
         let is_transient = transient_parent.is_some();
@@ -2187,13 +5880,28 @@ impl WindowManager {
         client.old_height = geometry.height;
         client.old_border_width = geometry.border_width;
         client.border_width = border_width as u16;
+        client.is_borderless = is_borderless;
+
+        let (instance, class) = self.get_window_class_instance(window);
+        client.stable_id = crate::client::stable_id(&[
+            &class,
+            &instance,
+            &self.get_window_role(window),
+            &self.get_window_command(window),
+        ]);
+        client.class = class;
+        client.instance = instance;
+        client.pid = self.get_window_pid(window).unwrap_or(0);
 
         self.clients.insert(window, client);
         self.update_window_title(window)?;
 
-        if !is_transient {
-            self.apply_rules(window)?;
-        }
+        let (rule_focus, placement) = if is_transient {
+            (None, RulePlacement::default())
+        } else {
+            self.apply_rules(window)?
+        };
+        let border_width = self.client_border_width(window);
 
         let client_monitor = self
             .clients
@@ -2272,6 +5980,8 @@ impl WindowManager {
             c.is_floating = is_transient || is_fixed;
             c.old_state = c.is_floating;
         }
+        self.sync_window_state(window);
+        self.place_by_rules(window, placement);
 
         if self
             .clients
@@ -2279,14 +5989,13 @@ impl WindowManager {
             .map(|c| c.is_floating)
             .unwrap_or(false)
         {
-            self.floating_windows.insert(window);
             self.connection.configure_window(
                 window,
                 &ConfigureWindowAux::new().stack_mode(StackMode::ABOVE),
             )?;
         }
 
-        if self.layout.name() == "scrolling" {
+        if self.monitor_layout(client_monitor).name() == "scrolling" {
             if let Some(selected) = self
                 .monitors
                 .get(client_monitor)
@@ -2301,7 +6010,16 @@ impl WindowManager {
         }
         self.attach_stack(window, client_monitor);
         self.windows.push(window);
+        self.swallow_terminal(window);
 
+        let (x, y, w, h) = self.clients.get(&window).map_or((x, y, w, h), |c| {
+            (
+                c.x_position as i32,
+                c.y_position as i32,
+                c.width as i32,
+                c.height as i32,
+            )
+        });
         let off_screen_x = x + 2 * self.screen.width_in_pixels as i32;
         self.connection.configure_window(
             window,
@@ -2315,38 +6033,178 @@ impl WindowManager {
         self.set_wm_state(window, 1)?;
         self.update_client_list()?;
 
-        let final_tags = self.clients.get(&window).map(|c| c.tags).unwrap_or(tags);
-        let _ = self.save_client_tag(window, final_tags);
+        let _ = self.update_client_desktop(window);
+
+        // Notifications, splash screens and utility windows never take focus when they appear.
+        let is_popup = self.clients.get(&window).is_some_and(|c| c.is_popup);
+        let decision = new_window_focus(
+            self.focus_new_windows(rule_focus),
+            self.steal_monitor_focus(),
+            is_popup,
+            client_monitor,
+            self.selected_monitor,
+        );
+        let previous_focus = self
+            .monitors
+            .get(self.selected_monitor)
+            .and_then(|m| m.selected_client);
 
-        if client_monitor == self.selected_monitor
-            && let Some(old_sel) = self
-                .monitors
-                .get(self.selected_monitor)
-                .and_then(|m| m.selected_client)
-        {
-            self.unfocus(old_sel, false)?;
-        }
+        if decision == NewWindowFocus::Focus {
+            if let Some(old_sel) = previous_focus {
+                self.unfocus(old_sel, false)?;
+            }
+            self.selected_monitor = client_monitor;
 
-        if let Some(m) = self.monitors.get_mut(client_monitor) {
-            m.selected_client = Some(window);
-        }
+            if let Some(m) = self.monitors.get_mut(client_monitor) {
+                m.selected_client = Some(window);
+            }
 
-        if self.layout.name() == "scrolling" {
-            self.scroll_to_window(window, true)?;
+            if self.monitor_layout(client_monitor).name() == "scrolling" {
+                self.scroll_to_window(window, true)?;
+            }
         }
 
         self.apply_layout()?;
         self.connection.map_window(window)?;
-        self.focus(None)?;
+
+        if decision == NewWindowFocus::Focus {
+            self.focus(None)?;
+        } else if previous_focus.is_some() {
+            self.focus(previous_focus)?;
+        }
+
+        if decision == NewWindowFocus::MarkUrgent {
+            self.set_urgent(window, true)?;
+        }
+
         self.update_bar()?;
 
-        if self.layout.name() == "tabbed" {
+        if self.is_tabbed(client_monitor) {
             self.update_tab_bars()?;
         }
 
         Ok(())
     }
 
+    /// Hides the terminal `window` was started from, letting `window` take its
+    /// place in the layout when the terminal is on screen.
+    fn swallow_terminal(&mut self, window: Window) {
+        let Some(terminal) = swallowing_terminal(window, &self.windows, &self.clients, parent_pid)
+        else {
+            return;
+        };
+
+        let Some((terminal_monitor, terminal_tags)) = self
+            .clients
+            .get(&terminal)
+            .map(|terminal| (terminal.monitor_index, terminal.tags))
+        else {
+            return;
+        };
+        let same_monitor = self
+            .clients
+            .get(&window)
+            .is_some_and(|client| client.monitor_index == terminal_monitor);
+        if same_monitor && self.is_visible(terminal) {
+            self.detach(window);
+            self.insert_before(window, terminal, terminal_monitor);
+            if let Some(client) = self.clients.get_mut(&window) {
+                client.tags = terminal_tags;
+            }
+        }
+
+        if let Some(client) = self.clients.get_mut(&window) {
+            client.swallowing = Some(terminal);
+        }
+        if let Some(terminal) = self.clients.get_mut(&terminal) {
+            terminal.tags = 0;
+        }
+        let _ = self.update_client_desktop(terminal);
+    }
+
+    /// Brings back the terminal hidden by `window` in the slot, monitor and tags
+    /// `window` occupies now, since either may have moved while it was hidden.
+    fn restore_swallowed(&mut self, window: Window) -> Option<Window> {
+        let client = self.clients.get(&window)?;
+        let terminal = client.swallowing?;
+        let (monitor_index, tags) = (client.monitor_index, client.tags);
+        if !self.clients.contains_key(&terminal) {
+            return None;
+        }
+
+        self.detach(terminal);
+        self.detach_stack(terminal);
+        if let Some(terminal) = self.clients.get_mut(&terminal) {
+            terminal.monitor_index = monitor_index;
+            terminal.tags = tags;
+        }
+        self.insert_before(terminal, window, monitor_index);
+        self.attach_stack(terminal, monitor_index);
+        let _ = self.update_client_desktop(terminal);
+        Some(terminal)
+    }
+
+    /// Moves the pointer to the center of `window` unless it is already inside it.
+    /// The EnterNotify this causes is ignored so it can't re-focus another window.
+    fn warp_cursor(&mut self, window: Window) -> WmResult<()> {
+        let Some(client) = self.clients.get(&window) else {
+            return Ok(());
+        };
+        let x = client.x_position as i32;
+        let y = client.y_position as i32;
+        let width = client.width as i32 + 2 * client.border_width as i32;
+        let height = client.height as i32 + 2 * client.border_width as i32;
+
+        let pointer = self.connection.query_pointer(self.root)?.reply()?;
+        let pointer_x = pointer.root_x as i32;
+        let pointer_y = pointer.root_y as i32;
+        if pointer_x >= x && pointer_x < x + width && pointer_y >= y && pointer_y < y + height {
+            return Ok(());
+        }
+
+        let sequence = self
+            .connection
+            .warp_pointer(
+                x11rb::NONE,
+                self.root,
+                0,
+                0,
+                0,
+                0,
+                (x + width / 2) as i16,
+                (y + height / 2) as i16,
+            )?
+            .sequence_number();
+        self.expected_warp = Some((window, sequence));
+        Ok(())
+    }
+
+    /// Moves the pointer to the center of a monitor unless it is already on it.
+    fn warp_cursor_to_monitor(&self, monitor_index: usize) -> WmResult<()> {
+        let Some(monitor) = self.monitors.get(monitor_index) else {
+            return Ok(());
+        };
+
+        let pointer = self.connection.query_pointer(self.root)?.reply()?;
+        if monitor.contains_point(pointer.root_x as i32, pointer.root_y as i32) {
+            return Ok(());
+        }
+
+        self.connection.warp_pointer(
+            x11rb::NONE,
+            self.root,
+            0,
+            0,
+            0,
+            0,
+            (monitor.screen_x + monitor.screen_width / 2) as i16,
+            (monitor.screen_y + monitor.screen_height / 2) as i16,
+        )?;
+        Ok(())
+    }
+
+    /// Gives input focus following the ICCCM input models: windows whose WM_HINTS
+    /// input field is false are only sent WM_TAKE_FOCUS, if they support it.
     pub fn set_focus(&mut self, window: Window) -> WmResult<()> {
         let never_focus = self
             .clients
@@ -2441,10 +6299,12 @@ impl WindowManager {
 
         self.grabbuttons(window, false)?;
 
-        self.connection.change_window_attributes(
-            window,
-            &ChangeWindowAttributesAux::new().border_pixel(self.config.border_unfocused),
-        )?;
+        if !self.clients.get(&window).is_some_and(|c| c.is_borderless) {
+            self.connection.change_window_attributes(
+                window,
+                &ChangeWindowAttributesAux::new().border_pixel(self.unfocused_border_color(window)),
+            )?;
+        }
 
         if reset_input_focus {
             self.connection.set_input_focus(
@@ -2474,7 +6334,12 @@ impl WindowManager {
 
             focus_client = None;
             while let Some(w) = current {
-                if self.is_visible(w) {
+                if self.is_visible(w)
+                    && !self
+                        .clients
+                        .get(&w)
+                        .is_some_and(|c| c.is_popup || c.click_through)
+                {
                     focus_client = Some(w);
                     break;
                 }
@@ -2508,42 +6373,28 @@ impl WindowManager {
 
             self.grabbuttons(win, true)?;
 
-            self.connection.change_window_attributes(
-                win,
-                &ChangeWindowAttributesAux::new().border_pixel(self.config.border_focused),
-            )?;
-
-            let never_focus = self
-                .clients
-                .get(&win)
-                .map(|client| client.never_focus)
-                .unwrap_or(false);
-
-            if !never_focus {
-                self.connection.set_input_focus(
-                    InputFocus::POINTER_ROOT,
+            if !self.clients.get(&win).is_some_and(|c| c.is_borderless) {
+                self.connection.change_window_attributes(
                     win,
-                    x11rb::CURRENT_TIME,
-                )?;
-
-                self.connection.change_property(
-                    PropMode::REPLACE,
-                    self.root,
-                    self.atoms.net_active_window,
-                    AtomEnum::WINDOW,
-                    32,
-                    1,
-                    &win.to_ne_bytes(),
+                    &ChangeWindowAttributesAux::new().border_pixel(self.config.border_focused),
                 )?;
             }
 
-            let _ = self.send_event(win, self.atoms.wm_take_focus);
+            self.set_focus(win)?;
 
             if let Some(monitor) = self.monitors.get_mut(self.selected_monitor) {
                 monitor.selected_client = Some(win);
             }
 
             self.previous_focused = Some(win);
+            self.focus_serial += 1;
+            if let Some(client) = self.clients.get_mut(&win) {
+                client.last_focused = self.focus_serial;
+            }
+
+            if self.config.warp_cursor && old_selected != Some(win) {
+                self.warp_cursor(win)?;
+            }
         } else {
             self.connection.set_input_focus(
                 InputFocus::POINTER_ROOT,
@@ -2596,6 +6447,14 @@ impl WindowManager {
             }
             current = self.clients.get(&win).and_then(|c| c.stack_next);
         }
+        windows_to_restack.sort_by_key(|&win| {
+            let layer = match self.clients.get(&win) {
+                Some(client) if client.is_above => 0,
+                Some(client) if client.is_below => 2,
+                _ => 1,
+            };
+            (layer, !self.is_scratchpad(win))
+        });
 
         for (i, &win) in windows_to_restack.iter().enumerate() {
             if i == 0 {
@@ -2612,6 +6471,13 @@ impl WindowManager {
                 )?;
             }
         }
+        self.lower_desktops()?;
+        self.raise_docks()?;
+
+        self.stacking_order
+            .retain(|window| !windows_to_restack.contains(window));
+        self.stacking_order.extend(windows_to_restack.iter().rev());
+        self.update_client_list_stacking()?;
 
         Ok(())
     }
@@ -2643,7 +6509,8 @@ impl WindowManager {
         let mut current_window = monitor.clients_head;
         while let Some(window) = current_window {
             if let Some(client) = self.clients.get(&window) {
-                if client.tags & selected_tags != 0 && !client.is_floating {
+                if client.tags & selected_tags != 0 && !client.is_floating && !client.click_through
+                {
                     stack_windows.push(window);
                 }
                 current_window = client.next;
@@ -2659,26 +6526,11 @@ impl WindowManager {
         let current_index = stack_windows
             .iter()
             .position(|&window| window == selected_window);
-
-        let next_window = if let Some(index) = current_index {
-            if direction > 0 {
-                if index + 1 < stack_windows.len() {
-                    stack_windows[index + 1]
-                } else {
-                    stack_windows[0]
-                }
-            } else if index > 0 {
-                stack_windows[index - 1]
-            } else {
-                stack_windows[stack_windows.len() - 1]
-            }
-        } else {
-            return Ok(());
-        };
+        let next_window = stack_windows[cycle_index(stack_windows.len(), current_index, direction)];
 
         self.focus(Some(next_window))?;
 
-        if self.layout.name() == "scrolling" {
+        if self.monitor_layout(self.selected_monitor).name() == "scrolling" {
             self.scroll_to_window(next_window, true)?;
         }
 
@@ -2688,6 +6540,35 @@ impl WindowManager {
         Ok(())
     }
 
+    /// Cycles focus among the floating windows visible on the selected monitor, raising each.
+    fn focus_floating(&mut self, direction: i32) -> WmResult<()> {
+        let floating: Vec<Window> = self
+            .visible_windows_on_monitor(self.selected_monitor)
+            .into_iter()
+            .filter(|window| {
+                self.floating_windows.contains(window)
+                    && !self.fullscreen_windows.contains(window)
+                    && !self.clients.get(window).is_some_and(|c| c.click_through)
+            })
+            .collect();
+        if floating.is_empty() {
+            return Ok(());
+        }
+
+        let selected = self
+            .monitors
+            .get(self.selected_monitor)
+            .and_then(|monitor| monitor.selected_client);
+        let current = selected.and_then(|window| floating.iter().position(|&w| w == window));
+        let next = cycle_index(floating.len(), current, direction);
+
+        self.focus(Some(floating[next]))?;
+        self.restack()?;
+        self.update_tab_bars()?;
+
+        Ok(())
+    }
+
     pub fn move_stack(&mut self, direction: i32) -> WmResult<()> {
         let monitor_index = self.selected_monitor;
         let monitor = match self.monitors.get(monitor_index) {
@@ -2801,20 +6682,59 @@ impl WindowManager {
             client.next = Some(target);
         }
 
-        if let Some(prev) = prev_target
-            && prev != selected
-            && let Some(client) = self.clients.get_mut(&prev)
-        {
-            client.next = Some(selected);
+        if let Some(prev) = prev_target
+            && prev != selected
+            && let Some(client) = self.clients.get_mut(&prev)
+        {
+            client.next = Some(selected);
+        }
+
+        if let Some(monitor) = self.monitors.get_mut(monitor_index) {
+            if monitor.clients_head == Some(selected) {
+                monitor.clients_head = Some(target);
+            } else if monitor.clients_head == Some(target) {
+                monitor.clients_head = Some(selected);
+            }
+        }
+
+        self.apply_layout()?;
+        Ok(())
+    }
+
+    pub fn rotate_stack(&mut self, direction: i32) -> WmResult<()> {
+        let monitor_index = self.selected_monitor;
+        let Some(monitor) = self.monitors.get(monitor_index) else {
+            return Ok(());
+        };
+        let selected_tags = monitor.tagset[monitor.selected_tags_index];
+
+        let mut order = Vec::new();
+        let mut current = monitor.clients_head;
+        while let Some(window) = current {
+            order.push(window);
+            current = self.clients.get(&window).and_then(|client| client.next);
+        }
+
+        let is_tiled: Vec<bool> = order
+            .iter()
+            .map(|window| {
+                self.clients
+                    .get(window)
+                    .is_some_and(|client| client.tags & selected_tags != 0 && !client.is_floating)
+            })
+            .collect();
+        if !rotate_tiled(&mut order, &is_tiled, direction) {
+            return Ok(());
         }
 
-        if let Some(monitor) = self.monitors.get_mut(monitor_index) {
-            if monitor.clients_head == Some(selected) {
-                monitor.clients_head = Some(target);
-            } else if monitor.clients_head == Some(target) {
-                monitor.clients_head = Some(selected);
+        for (index, window) in order.iter().enumerate() {
+            if let Some(client) = self.clients.get_mut(window) {
+                client.next = order.get(index + 1).copied();
             }
         }
+        if let Some(monitor) = self.monitors.get_mut(monitor_index) {
+            monitor.clients_head = order.first().copied();
+        }
 
         self.apply_layout()?;
         Ok(())
@@ -2842,10 +6762,22 @@ impl WindowManager {
         self.selected_monitor = target_monitor;
         self.focus(None)?;
 
+        let is_empty = self
+            .monitors
+            .get(target_monitor)
+            .is_some_and(|monitor| monitor.selected_client.is_none());
+        if self.config.warp_cursor && is_empty {
+            self.warp_cursor_to_monitor(target_monitor)?;
+        }
+
         Ok(())
     }
 
-    pub fn send_window_to_adjacent_monitor(&mut self, direction: i32) -> WmResult<()> {
+    pub fn send_window_to_adjacent_monitor(
+        &mut self,
+        direction: i32,
+        keep_tags: bool,
+    ) -> WmResult<()> {
         if self.monitors.len() <= 1 {
             return Ok(());
         }
@@ -2865,7 +6797,7 @@ impl WindowManager {
             None => return Ok(()),
         };
 
-        self.move_window_to_monitor(window, target_monitor)?;
+        self.move_window_to_monitor(window, target_monitor, keep_tags)?;
 
         Ok(())
     }
@@ -2881,33 +6813,55 @@ impl WindowManager {
             return Ok(());
         }
 
-        let client_info = self.clients.get(&window).map(|c| {
-            (
-                c.x_position,
-                c.y_position,
-                c.width,
-                c.height,
-                c.is_floating,
-                c.monitor_index,
-            )
-        });
-
-        let Some((orig_x, orig_y, width, height, was_floating, monitor_idx)) = client_info else {
-            return Ok(());
-        };
+        let client_info = self
+            .clients
+            .get(&window)
+            .map(|c| (c.x_position, c.y_position, c.is_floating, c.monitor_index));
 
-        let monitor = self.monitors.get(monitor_idx).cloned();
-        let Some(monitor) = monitor else {
+        let Some((orig_x, orig_y, was_floating, monitor_idx)) = client_info else {
             return Ok(());
         };
 
-        let snap = 32;
-        let is_normie = self.layout.name() == "normie";
+        let is_normie = self.monitor_layout(monitor_idx).name() == "normie";
 
         if !was_floating && !is_normie {
             self.toggle_floating()?;
         }
 
+        let Some(monitor) = self.monitors.get(monitor_idx) else {
+            return Ok(());
+        };
+
+        let snap = self.config.snap_distance as i32;
+        let outer = |client: &Client| crate::snap::Rect {
+            x: client.x_position as i32,
+            y: client.y_position as i32,
+            width: client.width as i32 + 2 * client.border_width as i32,
+            height: client.height as i32 + 2 * client.border_width as i32,
+        };
+        let others: Vec<crate::snap::Rect> = self
+            .windows
+            .iter()
+            .filter(|&&other| other != window && self.is_visible(other))
+            .filter_map(|other| self.clients.get(other))
+            .filter(|client| client.monitor_index == monitor_idx && client.is_floating)
+            .map(outer)
+            .collect();
+        let Some(dragged) = self.clients.get(&window).map(outer) else {
+            return Ok(());
+        };
+        let snap_targets = crate::snap::SnapTargets::new(
+            crate::snap::Rect {
+                x: monitor.window_area_x,
+                y: monitor.window_area_y,
+                width: monitor.window_area_width,
+                height: monitor.window_area_height,
+            },
+            &others,
+            dragged.width,
+            dragged.height,
+        );
+
         self.connection
             .grab_pointer(
                 false,
@@ -2936,28 +6890,11 @@ impl WindowManager {
                     }
                     last_time = e.time;
 
-                    let mut new_x = orig_x as i32 + (e.root_x as i32 - start_x);
-                    let mut new_y = orig_y as i32 + (e.root_y as i32 - start_y);
-
-                    if (monitor.window_area_x - new_x).abs() < snap {
-                        new_x = monitor.window_area_x;
-                    } else if ((monitor.window_area_x + monitor.window_area_width)
-                        - (new_x + width as i32))
-                        .abs()
-                        < snap
-                    {
-                        new_x = monitor.window_area_x + monitor.window_area_width - width as i32;
-                    }
-
-                    if (monitor.window_area_y - new_y).abs() < snap {
-                        new_y = monitor.window_area_y;
-                    } else if ((monitor.window_area_y + monitor.window_area_height)
-                        - (new_y + height as i32))
-                        .abs()
-                        < snap
-                    {
-                        new_y = monitor.window_area_y + monitor.window_area_height - height as i32;
-                    }
+                    let (new_x, new_y) = snap_targets.snap(
+                        orig_x as i32 + (e.root_x as i32 - start_x),
+                        orig_y as i32 + (e.root_y as i32 - start_y),
+                        snap,
+                    );
 
                     let should_resize = is_normie
                         || self
@@ -2996,7 +6933,7 @@ impl WindowManager {
         if let Some((x, y, w, h)) = final_client {
             let new_monitor = self.get_monitor_for_rect(x as i32, y as i32, w as i32, h as i32);
             if new_monitor != monitor_idx {
-                self.move_window_to_monitor(window, new_monitor)?;
+                self.move_window_to_monitor(window, new_monitor, false)?;
                 self.selected_monitor = new_monitor;
                 self.focus(None)?;
             }
@@ -3017,10 +6954,10 @@ impl WindowManager {
                 }
             }
 
-            self.floating_windows.remove(&window);
             if let Some(client) = self.clients.get_mut(&window) {
                 client.is_floating = false;
             }
+            self.sync_window_state(window);
             self.apply_layout()?;
         }
 
@@ -3088,6 +7025,86 @@ impl WindowManager {
         }
     }
 
+    /// Mouse resizing of a tiled window moves the master/stack split instead of
+    /// floating the window: horizontally for tiling, vertically for bstack.
+    fn resize_master_with_mouse(&mut self, monitor_idx: usize, vertical: bool) -> WmResult<()> {
+        let Some(monitor) = self.monitors.get(monitor_idx) else {
+            return Ok(());
+        };
+        let orig_factor = monitor.master_factor;
+        let span = if vertical {
+            monitor.window_area_height
+        } else {
+            monitor.window_area_width
+        }
+        .max(1) as f32;
+        let direction = if !vertical && self.is_mirrored(monitor_idx) {
+            -1.0
+        } else {
+            1.0
+        };
+
+        let pointer = self.connection.query_pointer(self.root)?.reply()?;
+        let start = if vertical {
+            pointer.root_y
+        } else {
+            pointer.root_x
+        } as f32;
+
+        self.connection
+            .grab_pointer(
+                false,
+                self.root,
+                EventMask::POINTER_MOTION | EventMask::BUTTON_RELEASE | EventMask::BUTTON_PRESS,
+                GrabMode::ASYNC,
+                GrabMode::ASYNC,
+                x11rb::NONE,
+                x11rb::NONE,
+                x11rb::CURRENT_TIME,
+            )?
+            .reply()?;
+
+        let mut last_time = 0u32;
+        // Animating here would steal the motion and release events this loop waits on.
+        let layout_animation = self.layout_animation.take();
+
+        loop {
+            let event = self.connection.wait_for_event()?;
+            match event {
+                Event::ConfigureRequest(_) | Event::MapRequest(_) | Event::Expose(_) => {}
+                Event::MotionNotify(e) => {
+                    if e.time.wrapping_sub(last_time) <= 16 {
+                        continue;
+                    }
+                    last_time = e.time;
+
+                    let position = if vertical { e.root_y } else { e.root_x } as f32;
+                    let factor = limits::MASTER_FACTOR
+                        .clamp(orig_factor + direction * (position - start) / span);
+
+                    if let Some(monitor) = self.monitors.get_mut(monitor_idx) {
+                        monitor.master_factor = factor;
+                        if let Some(ref mut pertag) = monitor.pertag {
+                            pertag.master_factors[pertag.current_tag] = factor;
+                        }
+                    }
+                    self.apply_layout()?;
+                    self.connection.flush()?;
+                }
+                Event::ButtonRelease(_) => break,
+                _ => {}
+            }
+        }
+        self.layout_animation = layout_animation;
+
+        self.connection
+            .ungrab_pointer(x11rb::CURRENT_TIME)?
+            .check()?;
+        self.save_tag_state()?;
+        self.update_bar()?;
+        Ok(())
+    }
+
     fn resize_window_with_mouse(&mut self, window: Window) -> WmResult<()> {
         let is_fullscreen = self
             .clients
@@ -3099,37 +7116,26 @@ impl WindowManager {
             return Ok(());
         }
 
-        let client_info = self.clients.get(&window).map(|c| {
-            (
-                c.x_position,
-                c.y_position,
-                c.width,
-                c.height,
-                c.border_width,
-                c.is_floating,
-                c.monitor_index,
-            )
-        });
+        let client_info = self
+            .clients
+            .get(&window)
+            .map(|c| (c.is_floating, c.monitor_index));
 
-        let Some((
-            orig_x,
-            orig_y,
-            orig_width,
-            orig_height,
-            border_width,
-            was_floating,
-            monitor_idx,
-        )) = client_info
-        else {
+        let Some((was_floating, monitor_idx)) = client_info else {
             return Ok(());
         };
 
+        let layout_name = self.monitor_layout(monitor_idx).name();
+        if !was_floating && matches!(layout_name, "tiling" | "bstack") {
+            return self.resize_master_with_mouse(monitor_idx, layout_name == "bstack");
+        }
+
         let monitor = match self.monitors.get(monitor_idx) {
             Some(m) => m,
             None => return Ok(()),
         };
 
-        let is_normie = self.layout.name() == "normie";
+        let is_normie = self.monitor_layout(monitor_idx).name() == "normie";
 
         if self.config.auto_tile && !was_floating && !is_normie {
             let mut tiled_count = 0;
@@ -3154,16 +7160,50 @@ impl WindowManager {
             self.toggle_floating()?;
         }
 
-        self.connection.warp_pointer(
-            x11rb::NONE,
-            window,
-            0,
-            0,
-            0,
-            0,
-            (orig_width + border_width - 1) as i16,
-            (orig_height + border_width - 1) as i16,
-        )?;
+        // Floating may have restored an earlier geometry, so read it only now.
+        let Some((orig_x, orig_y, orig_width, orig_height, border_width)) =
+            self.clients.get(&window).map(|c| {
+                (
+                    c.x_position as i32,
+                    c.y_position as i32,
+                    c.width as i32,
+                    c.height as i32,
+                    c.border_width as i32,
+                )
+            })
+        else {
+            return Ok(());
+        };
+        let outer_width = orig_width + 2 * border_width;
+        let outer_height = orig_height + 2 * border_width;
+
+        let pointer = self.connection.query_pointer(self.root)?.reply()?;
+        let (mut start_x, mut start_y) = (pointer.root_x as i32, pointer.root_y as i32);
+        let pointer_inside = (orig_x..orig_x + outer_width).contains(&start_x)
+            && (orig_y..orig_y + outer_height).contains(&start_y);
+
+        let (horizontal, vertical) = if pointer_inside {
+            resize_handles(
+                start_x - orig_x,
+                start_y - orig_y,
+                outer_width,
+                outer_height,
+            )
+        } else {
+            self.connection.warp_pointer(
+                x11rb::NONE,
+                window,
+                0,
+                0,
+                0,
+                0,
+                (orig_width + border_width - 1) as i16,
+                (orig_height + border_width - 1) as i16,
+            )?;
+            start_x = orig_x + orig_width + 2 * border_width - 1;
+            start_y = orig_y + orig_height + 2 * border_width - 1;
+            (1, 1)
+        };
 
         self.connection
             .grab_pointer(
@@ -3190,12 +7230,10 @@ impl WindowManager {
                     }
                     last_time = e.time;
 
-                    let new_width = ((e.root_x as i32 - orig_x as i32 - 2 * border_width as i32
-                        + 1)
-                    .max(1)) as u32;
-                    let new_height = ((e.root_y as i32 - orig_y as i32 - 2 * border_width as i32
-                        + 1)
-                    .max(1)) as u32;
+                    let dx = e.root_x as i32 - start_x;
+                    let dy = e.root_y as i32 - start_y;
+                    let new_width = (orig_width + horizontal * dx).max(1);
+                    let new_height = (orig_height + vertical * dy).max(1);
 
                     let should_resize = is_normie
                         || self
@@ -3204,16 +7242,24 @@ impl WindowManager {
                             .map(|c| c.is_floating)
                             .unwrap_or(false);
 
-                    if should_resize && let Some(client) = self.clients.get(&window).cloned() {
-                        let (_, _, hint_width, hint_height, _) = self.apply_size_hints(
-                            window,
-                            client.x_position as i32,
-                            client.y_position as i32,
-                            new_width as i32,
-                            new_height as i32,
-                        );
+                    if should_resize {
+                        let (_, _, hint_width, hint_height, _) =
+                            self.apply_size_hints(window, orig_x, orig_y, new_width, new_height);
+                        // Dragging the left or top edge keeps the opposite edge in place.
+                        let new_x = if horizontal < 0 {
+                            orig_x + orig_width - hint_width
+                        } else {
+                            orig_x
+                        };
+                        let new_y = if vertical < 0 {
+                            orig_y + orig_height - hint_height
+                        } else {
+                            orig_y
+                        };
 
                         if let Some(client_mut) = self.clients.get_mut(&window) {
+                            client_mut.x_position = new_x as i16;
+                            client_mut.y_position = new_y as i16;
                             client_mut.width = hint_width as u16;
                             client_mut.height = hint_height as u16;
                         }
@@ -3221,6 +7267,8 @@ impl WindowManager {
                         self.connection.configure_window(
                             window,
                             &ConfigureWindowAux::new()
+                                .x(new_x)
+                                .y(new_y)
                                 .width(hint_width as u32)
                                 .height(hint_height as u32),
                         )?;
@@ -3232,9 +7280,12 @@ impl WindowManager {
             }
         }
 
-        let final_client = self.clients.get(&window).map(|c| (c.width, c.border_width));
-
-        if let Some((w, bw)) = final_client {
+        if !pointer_inside
+            && let Some((w, h, bw)) = self
+                .clients
+                .get(&window)
+                .map(|c| (c.width, c.height, c.border_width))
+        {
             self.connection.warp_pointer(
                 x11rb::NONE,
                 window,
@@ -3243,7 +7294,7 @@ impl WindowManager {
                 0,
                 0,
                 (w + bw - 1) as i16,
-                (w + bw - 1) as i16,
+                (h + bw - 1) as i16,
             )?;
         }
 
@@ -3259,17 +7310,17 @@ impl WindowManager {
         if let Some((x, y, w, h)) = final_client_pos {
             let new_monitor = self.get_monitor_for_rect(x as i32, y as i32, w as i32, h as i32);
             if new_monitor != monitor_idx {
-                self.move_window_to_monitor(window, new_monitor)?;
+                self.move_window_to_monitor(window, new_monitor, false)?;
                 self.selected_monitor = new_monitor;
                 self.focus(None)?;
             }
         }
 
         if self.config.auto_tile && !was_floating && !is_normie {
-            self.floating_windows.remove(&window);
             if let Some(client) = self.clients.get_mut(&window) {
                 client.is_floating = false;
             }
+            self.sync_window_state(window);
             self.apply_layout()?;
         }
 
@@ -3277,6 +7328,10 @@ impl WindowManager {
     }
 
     fn handle_event(&mut self, event: Event) -> WmResult<Control> {
+        if let Some(time) = event_time(&event) {
+            self.last_event_time = time;
+        }
+
         match event {
             Event::KeyPress(ref key_event) if key_event.event == self.overlay.window() => {
                 if self.overlay.is_visible()
@@ -3286,6 +7341,28 @@ impl WindowManager {
                 }
                 return Ok(Control::Continue);
             }
+            Event::ButtonPress(ref button_event)
+                if button_event.event == self.message_overlay.window() =>
+            {
+                if let Err(error) = self.message_overlay.hide(&self.connection) {
+                    eprintln!("Failed to hide message overlay: {:?}", error);
+                }
+                return Ok(Control::Continue);
+            }
+            Event::Expose(ref expose_event) if expose_event.window == self.osd_overlay.window() => {
+                if let Err(error) = self.osd_overlay.draw(&self.connection, &self.font) {
+                    eprintln!("Failed to draw OSD overlay: {:?}", error);
+                }
+                return Ok(Control::Continue);
+            }
+            Event::Expose(ref expose_event)
+                if expose_event.window == self.message_overlay.window() =>
+            {
+                if let Err(error) = self.message_overlay.draw(&self.connection, &self.font) {
+                    eprintln!("Failed to draw message overlay: {:?}", error);
+                }
+                return Ok(Control::Continue);
+            }
             Event::ButtonPress(ref button_event) if button_event.event == self.overlay.window() => {
                 if self.overlay.is_visible()
                     && let Err(error) = self.overlay.hide(&self.connection)
@@ -3325,6 +7402,22 @@ impl WindowManager {
                     .allow_events(Allow::REPLAY_POINTER, e.time)?;
                 return Ok(Control::Continue);
             }
+            Event::Expose(ref expose_event)
+                if expose_event.window == self.switcher_overlay.window() =>
+            {
+                if let Err(error) = self.switcher_overlay.draw(&self.connection, &self.font) {
+                    eprintln!("Failed to draw switcher overlay: {:?}", error);
+                }
+                return Ok(Control::Continue);
+            }
+            Event::Expose(ref expose_event)
+                if expose_event.window == self.chord_overlay.window() =>
+            {
+                if let Err(error) = self.chord_overlay.draw(&self.connection, &self.font) {
+                    eprintln!("Failed to draw keychord overlay: {:?}", error);
+                }
+                return Ok(Control::Continue);
+            }
             Event::Expose(ref expose_event)
                 if expose_event.window == self.keybind_overlay.window() =>
             {
@@ -3349,6 +7442,29 @@ impl WindowManager {
                     self.manage_window(event.window)?;
                 }
             }
+            Event::UnmapNotify(event) if self.desktops.contains_key(&event.window) => {
+                self.desktops.remove(&event.window);
+            }
+            Event::DestroyNotify(event) if self.desktops.contains_key(&event.window) => {
+                self.desktops.remove(&event.window);
+            }
+            Event::UnmapNotify(event) if self.struts.contains_key(&event.window) => {
+                self.struts.remove(&event.window);
+                self.update_struts()?;
+            }
+            Event::DestroyNotify(event) if self.struts.contains_key(&event.window) => {
+                self.struts.remove(&event.window);
+                self.update_struts()?;
+            }
+            Event::PropertyNotify(event)
+                if self.struts.contains_key(&event.window)
+                    && (event.atom == self.atoms.net_wm_strut
+                        || event.atom == self.atoms.net_wm_strut_partial) =>
+            {
+                let strut = self.get_window_strut(event.window)?.unwrap_or_default();
+                self.struts.insert(event.window, strut);
+                self.update_struts()?;
+            }
             Event::UnmapNotify(event) => {
                 if self.windows.contains(&event.window) && self.is_window_visible(event.window) {
                     self.remove_window(event.window, false)?;
@@ -3368,7 +7484,7 @@ impl WindowManager {
                     return Ok(Control::Continue);
                 }
 
-                if event.atom == AtomEnum::WM_TRANSIENT_FOR.into() {
+                if event.atom == Atom::from(AtomEnum::WM_TRANSIENT_FOR) {
                     let is_floating = self
                         .clients
                         .get(&event.window)
@@ -3381,23 +7497,36 @@ impl WindowManager {
                         if let Some(c) = self.clients.get_mut(&event.window) {
                             c.is_floating = true;
                         }
-                        self.floating_windows.insert(event.window);
+                        self.sync_window_state(event.window);
                         self.apply_layout()?;
                     }
-                } else if event.atom == AtomEnum::WM_NORMAL_HINTS.into() {
+                } else if event.atom == Atom::from(AtomEnum::WM_NORMAL_HINTS) {
                     if let Some(c) = self.clients.get_mut(&event.window) {
                         c.hints_valid = false;
                     }
-                } else if event.atom == AtomEnum::WM_HINTS.into() {
+                } else if event.atom == Atom::from(AtomEnum::WM_HINTS) {
                     self.update_window_hints(event.window)?;
                     self.update_bar()?;
+                } else if event.atom == self.atoms.motif_wm_hints {
+                    self.update_motif_hints(event.window)?;
                 }
 
                 if event.atom == self.atoms.wm_name || event.atom == self.atoms.net_wm_name {
                     let _ = self.update_window_title(event.window);
-                    if self.layout.name() == "tabbed" {
+                    if self
+                        .clients
+                        .get(&event.window)
+                        .is_some_and(|c| self.is_tabbed(c.monitor_index))
+                    {
                         self.update_tab_bars()?;
                     }
+                    if self
+                        .monitors
+                        .iter()
+                        .any(|monitor| monitor.selected_client == Some(event.window))
+                    {
+                        self.update_bar()?;
+                    }
                 }
 
                 if event.atom == self.atoms.net_wm_window_type {
@@ -3405,15 +7534,37 @@ impl WindowManager {
                 }
             }
             Event::EnterNotify(event) => {
-                if event.mode != x11rb::protocol::xproto::NotifyMode::NORMAL
+                // The first crossing generated after a warp settles it, even when the
+                // pointer ended up somewhere else, so a stale warp never swallows a
+                // later enter into the same window.
+                let warped_here = match self.expected_warp {
+                    Some((window, sequence)) if is_at_or_after(event.sequence, sequence) => {
+                        self.expected_warp = None;
+                        window == event.event
+                    }
+                    _ => false,
+                };
+                if !self.focus_follows_mouse()
+                    || event.mode != x11rb::protocol::xproto::NotifyMode::NORMAL
                     || event.detail == x11rb::protocol::xproto::NotifyDetail::INFERIOR
                 {
                     return Ok(Control::Continue);
                 }
-                if self.windows.contains(&event.event) {
+                if warped_here && self.windows.contains(&event.event) {
+                    return Ok(Control::Continue);
+                }
+                if self.windows.contains(&event.event)
+                    && !self
+                        .clients
+                        .get(&event.event)
+                        .is_some_and(|client| client.click_through)
+                {
                     if let Some(client) = self.clients.get(&event.event)
                         && client.monitor_index != self.selected_monitor
                     {
+                        if !self.monitor_follows_mouse() {
+                            return Ok(Control::Continue);
+                        }
                         if let Some(old_selected) = self
                             .monitors
                             .get(self.selected_monitor)
@@ -3429,7 +7580,10 @@ impl WindowManager {
                 }
             }
             Event::MotionNotify(event) => {
-                if event.event != self.root {
+                if event.event != self.root
+                    || !self.focus_follows_mouse()
+                    || !self.monitor_follows_mouse()
+                {
                     return Ok(Control::Continue);
                 }
 
@@ -3456,6 +7610,13 @@ impl WindowManager {
                     return Ok(Control::Continue);
                 };
 
+                if self.recent_cycle.is_some()
+                    && mapping.keycode_to_keysym(event.detail) == keyboard::keysyms::XK_ESCAPE
+                {
+                    self.finish_recent_cycle(false)?;
+                    return Ok(Control::Continue);
+                }
+
                 let result = keyboard::handle_key_press(
                     event,
                     &self.config.keybindings,
@@ -3469,44 +7630,11 @@ impl WindowManager {
                         self.current_key = 0;
                         self.grab_keys()?;
                         self.update_bar()?;
+                        self.update_chord_overlay();
 
                         match action {
                             KeyAction::Quit => return Ok(Control::Quit),
-                            KeyAction::Restart => match self.try_reload_config() {
-                                Ok(()) => {
-                                    self.gaps_enabled = self.config.gaps_enabled;
-                                    self.error_message = None;
-                                    if let Err(error) = self.overlay.hide(&self.connection) {
-                                        eprintln!(
-                                            "Failed to hide overlay after config reload: {:?}",
-                                            error
-                                        );
-                                    }
-                                    self.apply_layout()?;
-                                    self.update_bar()?;
-                                }
-                                Err(err) => {
-                                    eprintln!("Config reload error: {}", err);
-                                    self.error_message = Some(err.to_string());
-                                    let monitor = &self.monitors[self.selected_monitor];
-                                    let monitor_x = monitor.screen_x as i16;
-                                    let monitor_y = monitor.screen_y as i16;
-                                    let screen_width = monitor.screen_width as u16;
-                                    let screen_height = monitor.screen_height as u16;
-                                    match self.overlay.show_error(
-                                        &self.connection,
-                                        &self.font,
-                                        err,
-                                        monitor_x,
-                                        monitor_y,
-                                        screen_width,
-                                        screen_height,
-                                    ) {
-                                        Ok(()) => eprintln!("Error modal displayed"),
-                                        Err(e) => eprintln!("Failed to show error modal: {:?}", e),
-                                    }
-                                }
-                            },
+                            KeyAction::Restart => self.reload_config()?,
                             _ => self.handle_key_action(action, &arg)?,
                         }
                     }
@@ -3515,9 +7643,11 @@ impl WindowManager {
                         self.keychord_state = keyboard::handlers::KeychordState::InProgress {
                             candidates: candidates.clone(),
                             keys_pressed: self.current_key,
+                            started: std::time::Instant::now(),
                         };
                         self.grab_keys()?;
                         self.update_bar()?;
+                        self.update_chord_overlay();
                     }
                     keyboard::handlers::KeychordResult::Cancelled
                     | keyboard::handlers::KeychordResult::None => {
@@ -3525,6 +7655,30 @@ impl WindowManager {
                         self.current_key = 0;
                         self.grab_keys()?;
                         self.update_bar()?;
+                        self.update_chord_overlay();
+                    }
+                }
+            }
+            Event::KeyRelease(event) => {
+                if let Some(cycle) = &self.recent_cycle {
+                    if cycle.modifier_keycodes.contains(&event.detail) {
+                        self.finish_recent_cycle(true)?;
+                    }
+                    return Ok(Control::Continue);
+                }
+                if !matches!(self.keychord_state, keyboard::handlers::KeychordState::Idle) {
+                    return Ok(Control::Continue);
+                }
+                let Some(mapping) = &self.keyboard_mapping else {
+                    return Ok(Control::Continue);
+                };
+
+                if let Some((action, arg)) =
+                    keyboard::handle_key_release(event, &self.config.keybindings, mapping)
+                {
+                    match action {
+                        KeyAction::Quit => return Ok(Control::Quit),
+                        _ => self.handle_key_action(action, &arg)?,
                     }
                 }
             }
@@ -3543,7 +7697,10 @@ impl WindowManager {
                     .find(|(_, bar)| bar.window() == event.event);
 
                 if let Some((monitor_index, bar)) = is_bar_click {
-                    if let Some(tag_index) = bar.handle_click(event.event_x) {
+                    let scroll_up = event.detail == u8::from(ButtonIndex::M4);
+                    if scroll_up || event.detail == u8::from(ButtonIndex::M5) {
+                        bar.handle_scroll(event.event_x, scroll_up);
+                    } else if let Some(tag_index) = bar.handle_click(event.event_x) {
                         if monitor_index != self.selected_monitor {
                             self.selected_monitor = monitor_index;
                         }
@@ -3595,7 +7752,9 @@ impl WindowManager {
                             self.focus(Some(clicked_window))?;
                             self.update_tab_bars()?;
                         }
-                    } else if event.child != x11rb::NONE {
+                    } else if event.child != x11rb::NONE
+                        && !self.desktops.contains_key(&event.child)
+                    {
                         self.focus(Some(event.child))?;
                         self.restack()?;
                         self.update_tab_bars()?;
@@ -3604,13 +7763,13 @@ impl WindowManager {
                             & !(u16::from(ModMask::LOCK) | u16::from(ModMask::M2));
                         let modkey_held = state_clean & u16::from(self.config.modkey) != 0;
 
-                        if modkey_held && event.detail == ButtonIndex::M1.into() {
+                        if modkey_held && event.detail == u8::from(ButtonIndex::M1) {
                             if self.clients.contains_key(&event.child) {
                                 self.drag_window(event.child)?;
                             }
                             self.connection
                                 .allow_events(Allow::REPLAY_POINTER, event.time)?;
-                        } else if modkey_held && event.detail == ButtonIndex::M3.into() {
+                        } else if modkey_held && event.detail == u8::from(ButtonIndex::M3) {
                             if self.clients.contains_key(&event.child) {
                                 self.resize_window_with_mouse(event.child)?;
                             }
@@ -3629,11 +7788,11 @@ impl WindowManager {
                             & !(u16::from(ModMask::LOCK) | u16::from(ModMask::M2));
                         let modkey_held = state_clean & u16::from(self.config.modkey) != 0;
 
-                        if modkey_held && event.detail == ButtonIndex::M1.into() {
+                        if modkey_held && event.detail == u8::from(ButtonIndex::M1) {
                             self.drag_window(event.event)?;
                             self.connection
                                 .allow_events(Allow::REPLAY_POINTER, event.time)?;
-                        } else if modkey_held && event.detail == ButtonIndex::M3.into() {
+                        } else if modkey_held && event.detail == u8::from(ButtonIndex::M3) {
                             self.resize_window_with_mouse(event.event)?;
                             self.connection
                                 .allow_events(Allow::REPLAY_POINTER, event.time)?;
@@ -3662,12 +7821,15 @@ impl WindowManager {
                     }
                 }
             }
+            Event::ConfigureRequest(event) if self.desktops.contains_key(&event.window) => {
+                self.span_desktop(event.window)?;
+            }
             Event::ConfigureRequest(event) => {
                 if let Some(client) = self.clients.get(&event.window) {
                     let monitor = &self.monitors[client.monitor_index];
                     let is_floating = client.is_floating;
                     let is_fullscreen = client.is_fullscreen;
-                    let has_layout = self.layout.name() != "normie";
+                    let has_layout = self.monitor_layout(client.monitor_index).name() != "normie";
 
                     if event.value_mask.contains(ConfigWindow::BORDER_WIDTH) {
                         if let Some(c) = self.clients.get_mut(&event.window) {
@@ -3706,7 +7868,7 @@ impl WindowManager {
                             h = event.height as i32;
                         }
 
-                        let bw = self.config.border_width as i32;
+                        let bw = self.client_border_width(event.window) as i32;
                         let width_with_border = w + 2 * bw;
                         let height_with_border = h + 2 * bw;
 
@@ -3791,12 +7953,38 @@ impl WindowManager {
                         let fullscreen = match action {
                             1 => true,
                             0 => false,
-                            2 => !self.fullscreen_windows.contains(&event.window),
+                            2 => !self
+                                .clients
+                                .get(&event.window)
+                                .is_some_and(|client| client.reports_fullscreen()),
                             _ => return Ok(Control::Continue),
                         };
                         self.set_window_fullscreen(event.window, fullscreen)?;
                         self.restack()?;
                     }
+
+                    let Some(client) = self.clients.get(&event.window) else {
+                        return Ok(Control::Continue);
+                    };
+                    let (is_above, is_below, is_sticky) =
+                        (client.is_above, client.is_below, client.is_sticky);
+                    let requested = |atom: Atom| atom1 == atom || atom2 == atom;
+
+                    if requested(self.atoms.net_wm_state_above)
+                        && let Some(above) = net_wm_state_action(data[0], is_above)
+                    {
+                        self.set_window_above(event.window, above)?;
+                    }
+                    if requested(self.atoms.net_wm_state_below)
+                        && let Some(below) = net_wm_state_action(data[0], is_below)
+                    {
+                        self.set_window_below(event.window, below)?;
+                    }
+                    if requested(self.atoms.net_wm_state_sticky)
+                        && let Some(sticky) = net_wm_state_action(data[0], is_sticky)
+                    {
+                        self.set_window_sticky(event.window, sticky)?;
+                    }
                 } else if event.type_ == self.atoms.net_active_window {
                     let selected_window = self
                         .monitors
@@ -3809,7 +7997,15 @@ impl WindowManager {
                         .map(|c| c.is_urgent)
                         .unwrap_or(false);
 
-                    if Some(event.window) != selected_window && !is_urgent {
+                    if Some(event.window) == selected_window {
+                        return Ok(Control::Continue);
+                    }
+                    // Hidden scratchpads have no tag to switch to, so they are only marked urgent.
+                    let has_tags = self.clients.get(&event.window).is_some_and(|c| c.tags != 0);
+                    if self.focus_on_activate() && has_tags {
+                        self.reveal_client(event.window)?;
+                        self.update_bar()?;
+                    } else if !is_urgent {
                         self.set_urgent(event.window, true)?;
                     }
                 }
@@ -3848,6 +8044,9 @@ impl WindowManager {
 
                     if event.width != old_width || event.height != old_height {
                         self.screen = self.connection.setup().roots[self.screen_number].clone();
+                        self.screen.width_in_pixels = event.width;
+                        self.screen.height_in_pixels = event.height;
+                        self.update_monitor_geometry()?;
 
                         for monitor_index in 0..self.monitors.len() {
                             let monitor = &self.monitors[monitor_index];
@@ -3880,139 +8079,262 @@ impl WindowManager {
                             }
                         }
 
-                        self.apply_layout()?;
-                    }
-                }
+                        self.apply_layout()?;
+                    }
+
+                    self.gamma.reapply(&self.connection, self.root);
+                }
+            }
+            _ => {}
+        }
+        Ok(Control::Continue)
+    }
+
+    /// Plays relayout transitions to completion. Events arriving meanwhile are
+    /// queued for the main loop, and key or button presses end the animation
+    /// early so input never waits on it.
+    fn animate_layout(&mut self, transitions: &[WindowTransition]) -> WmResult<()> {
+        let Some(config) = self.layout_animation.filter(|_| !transitions.is_empty()) else {
+            return Ok(());
+        };
+
+        let animation = LayoutAnimation::new(&config);
+        loop {
+            let mut progress = animation.progress();
+            while let Some(event) = self.connection.poll_for_event()? {
+                let is_input = matches!(event, Event::KeyPress(_) | Event::ButtonPress(_));
+                self.deferred_events.push_back(event);
+                if is_input {
+                    progress = 1.0;
+                    break;
+                }
+            }
+
+            for transition in transitions {
+                let geometry = transition.at(progress);
+                self.connection.configure_window(
+                    transition.window,
+                    &ConfigureWindowAux::new()
+                        .x(geometry.x_coordinate)
+                        .y(geometry.y_coordinate)
+                        .width(geometry.width)
+                        .height(geometry.height),
+                )?;
+            }
+            self.connection.flush()?;
+
+            if progress >= 1.0 {
+                return Ok(());
             }
-            _ => {}
+            std::thread::sleep(std::time::Duration::from_millis(16));
         }
-        Ok(Control::Continue)
     }
 
     fn apply_layout(&mut self) -> WmResult<()> {
+        self.debug_assert_window_state();
+
         for monitor_index in 0..self.monitors.len() {
             let stack_head = self.monitors.get(monitor_index).and_then(|m| m.stack_head);
             self.showhide(stack_head)?;
         }
 
-        let is_normie = self.layout.name() == LayoutType::Normie.as_str();
+        let mut transitions = Vec::new();
+        let monitor_count = self.monitors.len();
+        for monitor_index in 0..monitor_count {
+            let layout = self.monitor_layout(monitor_index);
+            if layout.name() == LayoutType::Normie.as_str() {
+                continue;
+            }
 
-        if !is_normie {
-            let monitor_count = self.monitors.len();
-            for monitor_index in 0..monitor_count {
-                let monitor = &self.monitors[monitor_index];
-                let border_width = self.config.border_width;
+            let monitor = &self.monitors[monitor_index];
 
-                let gaps = if self.gaps_enabled {
-                    GapConfig {
-                        inner_horizontal: self.config.gap_inner_horizontal,
-                        inner_vertical: self.config.gap_inner_vertical,
-                        outer_horizontal: self.config.gap_outer_horizontal,
-                        outer_vertical: self.config.gap_outer_vertical,
-                    }
-                } else {
-                    GapConfig {
-                        inner_horizontal: 0,
-                        inner_vertical: 0,
-                        outer_horizontal: 0,
-                        outer_vertical: 0,
-                    }
-                };
+            let gaps = self.gap_config();
 
-                let monitor_x = monitor.screen_x;
-                let monitor_y = monitor.screen_y;
-                let monitor_width = monitor.screen_width;
-                let monitor_height = monitor.screen_height;
-                let scroll_offset = monitor.scroll_offset;
+            let monitor_x = monitor.window_area_x;
+            let monitor_y = monitor.window_area_y;
+            let monitor_width = monitor.window_area_width;
+            let monitor_height = monitor.window_area_height;
+            let scroll_offset = monitor.scroll_offset;
 
-                let mut visible: Vec<Window> = Vec::new();
-                let mut current = self.next_tiled(monitor.clients_head, monitor);
-                while let Some(window) = current {
+            // Fullscreen clients that were tiled keep their slot, so the other
+            // windows don't move when they enter or leave fullscreen.
+            let mut visible: Vec<Window> = Vec::new();
+            let mut current = monitor.clients_head;
+            while let Some(window) = current {
+                let Some(client) = self.clients.get(&window) else {
+                    break;
+                };
+                let visible_tags = client.tags & monitor.tagset[monitor.selected_tags_index];
+                if visible_tags != 0
+                    && (!client.is_floating || (client.is_fullscreen && !client.old_state))
+                {
                     visible.push(window);
-                    if let Some(client) = self.clients.get(&window) {
-                        current = self.next_tiled(client.next, monitor);
-                    } else {
-                        break;
-                    }
                 }
+                current = client.next;
+            }
 
-                let bar_height = if self.show_bar {
-                    self.bars
-                        .get(monitor_index)
-                        .map(|bar| bar.height() as u32)
-                        .unwrap_or(0)
-                } else {
+            let border_width = if self.config.smartborders_enabled && visible.len() == 1 {
+                0
+            } else {
+                self.config.border_width
+            };
+
+            let bar_height = if self.show_bar {
+                self.bars
+                    .get(monitor_index)
+                    .map(|bar| bar.height() as u32)
+                    .unwrap_or(0)
+            } else {
+                0
+            };
+            let usable_height = monitor_height.saturating_sub(bar_height as i32);
+            let master_factor = monitor.master_factor;
+            let num_master = monitor.num_master;
+            let smartgaps_enabled = self.config.smartgaps_enabled;
+
+            let is_scrolling = layout.name() == "scrolling";
+            let slide = self
+                .slide_offset
+                .filter(|(index, _)| *index == monitor_index)
+                .map_or(0, |(_, offset)| offset);
+            let animate = self.layout_animation.is_some()
+                && slide == 0
+                && !is_scrolling
+                && !self.scroll_animation.is_active();
+            let factors = if is_scrolling {
+                self.scroll_column_factors(monitor, &visible)
+            } else {
+                visible
+                    .iter()
+                    .map(|window| {
+                        self.clients
+                            .get(window)
+                            .map(|client| client.size_factor)
+                            .unwrap_or(1.0)
+                    })
+                    .collect()
+            };
+
+            let mut geometries = layout.arrange(
+                &visible,
+                monitor_width as u32,
+                usable_height as u32,
+                &gaps,
+                master_factor,
+                num_master,
+                smartgaps_enabled,
+                &factors,
+            );
+
+            // Reflect within the span the layout actually used, so uneven
+            // left/right outer gaps stay on their own side.
+            if self.is_mirrored(monitor_index) {
+                let left = geometries.iter().map(|g| g.x_coordinate).min().unwrap_or(0);
+                let right = geometries
+                    .iter()
+                    .map(|g| g.x_coordinate + g.width as i32)
+                    .max()
+                    .unwrap_or(0);
+                for geometry in &mut geometries {
+                    geometry.x_coordinate =
+                        left + right - geometry.x_coordinate - geometry.width as i32;
+                }
+            }
+
+            for (window, geometry) in visible.iter().zip(geometries.iter()) {
+                if self.fullscreen_windows.contains(window) {
+                    continue;
+                }
+                let border_width = if self.clients.get(window).is_some_and(|c| c.is_borderless) {
                     0
+                } else {
+                    border_width
                 };
-                let usable_height = monitor_height.saturating_sub(bar_height as i32);
-                let master_factor = monitor.master_factor;
-                let num_master = monitor.num_master;
-                let smartgaps_enabled = self.config.smartgaps_enabled;
-
-                let geometries = self.layout.arrange(
-                    &visible,
-                    monitor_width as u32,
-                    usable_height as u32,
-                    &gaps,
-                    master_factor,
-                    num_master,
-                    smartgaps_enabled,
-                );
+                let mut adjusted_width = geometry.width.saturating_sub(2 * border_width);
+                let mut adjusted_height = geometry.height.saturating_sub(2 * border_width);
 
-                for (window, geometry) in visible.iter().zip(geometries.iter()) {
-                    let mut adjusted_width = geometry.width.saturating_sub(2 * border_width);
-                    let mut adjusted_height = geometry.height.saturating_sub(2 * border_width);
+                if let Some(client) = self.clients.get(window).cloned()
+                    && !client.is_floating
+                {
+                    let (_, _, hint_width, hint_height, _) = self.apply_size_hints(
+                        *window,
+                        geometry.x_coordinate,
+                        geometry.y_coordinate,
+                        adjusted_width as i32,
+                        adjusted_height as i32,
+                    );
+                    adjusted_width = hint_width as u32;
+                    adjusted_height = hint_height as u32;
+                }
 
-                    if let Some(client) = self.clients.get(window).cloned()
-                        && !client.is_floating
-                    {
-                        let (_, _, hint_width, hint_height, _) = self.apply_size_hints(
-                            *window,
-                            geometry.x_coordinate,
-                            geometry.y_coordinate,
-                            adjusted_width as i32,
-                            adjusted_height as i32,
-                        );
-                        adjusted_width = hint_width as u32;
-                        adjusted_height = hint_height as u32;
-                    }
+                let adjusted_x = if is_scrolling {
+                    geometry.x_coordinate + monitor_x - scroll_offset
+                } else {
+                    geometry.x_coordinate + monitor_x
+                };
+                let adjusted_y = geometry.y_coordinate + monitor_y + bar_height as i32;
 
-                    let is_scrolling = self.layout.name() == "scrolling";
-                    let adjusted_x = if is_scrolling {
-                        geometry.x_coordinate + monitor_x - scroll_offset
-                    } else {
-                        geometry.x_coordinate + monitor_x
-                    };
-                    let adjusted_y = geometry.y_coordinate + monitor_y + bar_height as i32;
+                let previous = self
+                    .clients
+                    .get(window)
+                    .filter(|client| client.width > 0 && client.height > 0)
+                    .map(|client| WindowGeometry {
+                        x_coordinate: client.x_position as i32,
+                        y_coordinate: client.y_position as i32,
+                        width: client.width as u32,
+                        height: client.height as u32,
+                    });
+                let target = WindowGeometry {
+                    x_coordinate: adjusted_x,
+                    y_coordinate: adjusted_y,
+                    width: adjusted_width,
+                    height: adjusted_height,
+                };
 
-                    if let Some(client) = self.clients.get_mut(window) {
-                        client.x_position = adjusted_x as i16;
-                        client.y_position = adjusted_y as i16;
-                        client.width = adjusted_width as u16;
-                        client.height = adjusted_height as u16;
-                    }
+                if let Some(client) = self.clients.get_mut(window) {
+                    client.x_position = adjusted_x as i16;
+                    client.y_position = adjusted_y as i16;
+                    client.width = adjusted_width as u16;
+                    client.height = adjusted_height as u16;
+                }
 
+                if animate
+                    && let Some(from) = previous
+                    && from != target
+                {
+                    self.connection.configure_window(
+                        *window,
+                        &ConfigureWindowAux::new().border_width(border_width),
+                    )?;
+                    transitions.push(WindowTransition {
+                        window: *window,
+                        from,
+                        to: target,
+                    });
+                } else {
                     self.connection.configure_window(
                         *window,
                         &ConfigureWindowAux::new()
-                            .x(adjusted_x)
+                            .x(adjusted_x + slide)
                             .y(adjusted_y)
                             .width(adjusted_width)
                             .height(adjusted_height)
                             .border_width(border_width),
                     )?;
+                }
 
-                    if let Some(c) = self.clients.get_mut(window) {
-                        c.x_position = adjusted_x as i16;
-                        c.y_position = adjusted_y as i16;
-                        c.width = adjusted_width as u16;
-                        c.height = adjusted_height as u16;
-                        c.border_width = border_width as u16;
-                    }
+                if let Some(c) = self.clients.get_mut(window) {
+                    c.x_position = adjusted_x as i16;
+                    c.y_position = adjusted_y as i16;
+                    c.width = adjusted_width as u16;
+                    c.height = adjusted_height as u16;
+                    c.border_width = border_width as u16;
                 }
             }
         }
 
+        self.animate_layout(&transitions)?;
+
         for monitor_index in 0..self.monitors.len() {
             let stack_head = self.monitors[monitor_index].stack_head;
             self.showhide(stack_head)?;
@@ -4049,31 +8371,26 @@ impl WindowManager {
                         }
                     }
                 }
-            } else if self.show_bar {
-                if let Some(bar) = self.bars.get(monitor_index) {
+            } else if let Some(bar) = self.bars.get(monitor_index) {
+                if self.show_bar {
                     self.connection.map_window(bar.window())?;
+                } else {
+                    self.connection.unmap_window(bar.window())?;
                 }
             }
         }
 
         self.connection.flush()?;
 
-        let is_tabbed = self.layout.name() == LayoutType::Tabbed.as_str();
+        let any_tabbed = (0..self.tab_bars.len()).any(|i| self.is_tabbed(i));
 
-        if is_tabbed {
-            let outer_horizontal = if self.gaps_enabled {
-                self.config.gap_outer_horizontal
-            } else {
-                0
-            };
-            let outer_vertical = if self.gaps_enabled {
-                self.config.gap_outer_vertical
-            } else {
-                0
-            };
+        if any_tabbed {
+            let gaps = self.gap_config();
 
             for monitor_index in 0..self.tab_bars.len() {
-                if let Some(monitor) = self.monitors.get(monitor_index) {
+                if self.is_tabbed(monitor_index)
+                    && let Some(monitor) = self.monitors.get(monitor_index)
+                {
                     let bar_height = if self.show_bar {
                         self.bars
                             .get(monitor_index)
@@ -4083,12 +8400,12 @@ impl WindowManager {
                         0.0
                     };
 
-                    let tab_bar_x = (monitor.screen_x + outer_horizontal as i32) as i16;
+                    let tab_bar_x = (monitor.screen_x + gaps.outer_left as i32) as i16;
                     let tab_bar_y =
-                        (monitor.screen_y as f32 + bar_height + outer_vertical as f32) as i16;
+                        (monitor.screen_y as f32 + bar_height + gaps.outer_top as f32) as i16;
                     let tab_bar_width = monitor
                         .screen_width
-                        .saturating_sub(2 * outer_horizontal as i32)
+                        .saturating_sub((gaps.outer_left + gaps.outer_right) as i32)
                         as u16;
 
                     if let Err(e) = self.tab_bars[monitor_index].reposition(
@@ -4104,22 +8421,33 @@ impl WindowManager {
         }
 
         for monitor_index in 0..self.tab_bars.len() {
-            let has_visible_windows = self.windows.iter().any(|&window| {
-                if let Some(client) = self.clients.get(&window) {
-                    if client.monitor_index != monitor_index
-                        || self.floating_windows.contains(&window)
-                        || self.fullscreen_windows.contains(&window)
-                    {
-                        return false;
-                    }
-                    if let Some(monitor) = self.monitors.get(monitor_index) {
-                        return (client.tags & monitor.tagset[monitor.selected_tags_index]) != 0;
-                    }
-                }
-                false
+            let tags = self
+                .monitors
+                .get(monitor_index)
+                .map_or(0, |monitor| monitor.tagset[monitor.selected_tags_index]);
+            let has_visible_fullscreen = self.fullscreen_windows.iter().any(|window| {
+                self.clients.get(window).is_some_and(|client| {
+                    client.monitor_index == monitor_index && (client.tags & tags) != 0
+                })
             });
+            let has_visible_windows = !has_visible_fullscreen
+                && self.windows.iter().any(|&window| {
+                    if let Some(client) = self.clients.get(&window) {
+                        if client.monitor_index != monitor_index
+                            || self.floating_windows.contains(&window)
+                            || self.fullscreen_windows.contains(&window)
+                        {
+                            return false;
+                        }
+                        if let Some(monitor) = self.monitors.get(monitor_index) {
+                            return (client.tags & monitor.tagset[monitor.selected_tags_index])
+                                != 0;
+                        }
+                    }
+                    false
+                });
 
-            if is_tabbed && has_visible_windows {
+            if self.is_tabbed(monitor_index) && has_visible_windows {
                 if let Err(e) = self.tab_bars[monitor_index].show(&self.connection) {
                     eprintln!("Failed to show tab bar: {:?}", e);
                 }
@@ -4128,15 +8456,24 @@ impl WindowManager {
             }
         }
 
-        if is_tabbed {
+        if any_tabbed {
             self.update_tab_bars()?;
         }
 
         Ok(())
     }
 
+    fn clear_floating_on_monitor(&mut self, monitor_index: usize) {
+        let clients = &self.clients;
+        self.floating_windows.retain(|window| {
+            clients
+                .get(window)
+                .is_some_and(|client| client.monitor_index != monitor_index)
+        });
+    }
+
     pub fn change_layout<L: Layout + 'static>(&mut self, new_layout: L) -> WmResult<()> {
-        self.layout = Box::new(new_layout);
+        self.layouts[self.selected_monitor] = Box::new(new_layout);
         self.apply_layout()?;
         Ok(())
     }
@@ -4353,6 +8690,7 @@ impl WindowManager {
                 )?;
             } else if let Some(client) = self.clients.get_mut(&window) {
                 client.is_urgent = (flags & 256) != 0;
+                self.refresh_border(window)?;
             }
 
             if hints.value.len() >= 8 && (flags & 1) != 0 {
@@ -4380,16 +8718,35 @@ impl WindowManager {
             if state_atoms.contains(&self.atoms.net_wm_state_fullscreen) {
                 self.set_window_fullscreen(window, true)?;
             }
-        }
 
-        if let Ok(Some(type_atom)) =
-            self.get_window_atom_property(window, self.atoms.net_wm_window_type)
-            && type_atom == self.atoms.net_wm_window_type_dialog
-        {
+            let above = state_atoms.contains(&self.atoms.net_wm_state_above);
+            let below = state_atoms.contains(&self.atoms.net_wm_state_below);
+            let sticky = state_atoms.contains(&self.atoms.net_wm_state_sticky);
             if let Some(client) = self.clients.get_mut(&window) {
-                client.is_floating = true;
+                client.is_above |= above;
+                client.is_below |= below && !above;
+                client.is_sticky |= sticky;
+                client.is_floating |= above || sticky;
             }
-            self.floating_windows.insert(window);
+            self.sync_window_state(window);
+        }
+
+        let types = self
+            .get_window_atom_list_property(window, self.atoms.net_wm_window_type)
+            .unwrap_or_default();
+        let is_popup = [
+            self.atoms.net_wm_window_type_notification,
+            self.atoms.net_wm_window_type_utility,
+            self.atoms.net_wm_window_type_splash,
+        ]
+        .iter()
+        .any(|window_type| types.contains(window_type));
+        if (is_popup || types.contains(&self.atoms.net_wm_window_type_dialog))
+            && let Some(client) = self.clients.get_mut(&window)
+        {
+            client.is_floating = true;
+            client.is_popup = is_popup;
+            self.sync_window_state(window);
         }
 
         Ok(())
@@ -4458,7 +8815,7 @@ impl WindowManager {
             w = bh;
         }
 
-        if is_floating || self.layout.name() == "normie" {
+        if is_floating || self.monitor_layout(monitor_index).name() == "normie" {
             if !hints_valid {
                 let _ = self.update_size_hints(window);
                 hints_valid = self
@@ -4539,6 +8896,22 @@ impl WindowManager {
         (x, y, w, h, changed)
     }
 
+    fn tiled_count(&self, monitor_index: usize) -> usize {
+        let Some(monitor) = self.monitors.get(monitor_index) else {
+            return 0;
+        };
+        let mut count = 0;
+        let mut current = self.next_tiled(monitor.clients_head, monitor);
+        while let Some(window) = current {
+            count += 1;
+            current = self
+                .clients
+                .get(&window)
+                .and_then(|client| self.next_tiled(client.next, monitor));
+        }
+        count
+    }
+
     fn next_tiled(&self, start: Option<Window>, monitor: &Monitor) -> Option<Window> {
         let mut current = start;
         while let Some(window) = current {
@@ -4729,24 +9102,38 @@ impl WindowManager {
                 )?;
             }
             self.set_wm_state(window, 0)?;
+            self.connection
+                .delete_property(window, self.atoms.oxwm_client_state)?;
         }
 
+        let restored = self.restore_swallowed(window);
         if self.clients.contains_key(&window) {
             self.detach(window);
             self.detach_stack(window);
             self.clients.remove(&window);
         }
+        for client in self.clients.values_mut() {
+            if client.swallowing == Some(window) {
+                client.swallowing = None;
+            }
+        }
 
         self.windows.retain(|&w| w != window);
-        self.floating_windows.remove(&window);
+        self.sync_window_state(window);
+        self.scratchpads.retain(|_, &mut w| w != window);
+        self.history.forget(window);
         self.update_client_list()?;
 
         if self.windows.len() < initial_count {
-            if focused == Some(window) {
+            if let Some(terminal) = restored
+                && focused == Some(window)
+            {
+                self.focus(Some(terminal))?;
+            } else if focused == Some(window) {
                 let visible = self.visible_windows_on_monitor(self.selected_monitor);
                 if let Some(&new_win) = visible.last() {
                     self.focus(Some(new_win))?;
-                    if self.layout.name() == "scrolling" {
+                    if self.monitor_layout(self.selected_monitor).name() == "scrolling" {
                         self.scroll_to_window(new_win, true)?;
                     }
                 } else if let Some(monitor) = self.monitors.get_mut(self.selected_monitor) {
@@ -4785,9 +9172,592 @@ impl WindowManager {
     }
 
     fn run_autostart_commands(&self) {
-        for command in &self.config.autostart {
-            crate::signal::spawn_detached(command);
-            eprintln!("[autostart] Spawned: {}", command);
+        for autostart in &self.config.autostart {
+            // `once` entries run pgrep first, which must not hold up the event loop.
+            if autostart.delay_ms == 0 && !autostart.once {
+                run_autostart_command(autostart);
+                continue;
+            }
+            let autostart = autostart.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(autostart.delay_ms));
+                run_autostart_command(&autostart);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_list_bytes_is_empty_for_no_windows() {
+        assert!(window_list_bytes(&[]).is_empty());
+    }
+
+    #[test]
+    fn window_list_bytes_writes_four_native_bytes_per_window() {
+        let windows = [0x0040_0001, 0x0120_00ab, Window::MAX];
+        let bytes = window_list_bytes(&windows);
+        assert_eq!(bytes.len(), windows.len() * 4);
+
+        let decoded: Vec<Window> = bytes
+            .chunks_exact(4)
+            .map(|chunk| Window::from_ne_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_eq!(decoded, windows);
+        assert_eq!(&bytes[..4], &0x0040_0001u32.to_ne_bytes());
+    }
+
+    #[test]
+    fn window_list_bytes_keeps_the_order() {
+        let bytes = window_list_bytes(&[2, 1]);
+        assert_eq!(&bytes[..4], &2u32.to_ne_bytes());
+        assert_eq!(&bytes[4..], &1u32.to_ne_bytes());
+    }
+
+    #[test]
+    fn all_tags_mask_covers_every_tag_count() {
+        assert_eq!(all_tags_mask(0), 0);
+        assert_eq!(all_tags_mask(1), 0b1);
+        assert_eq!(all_tags_mask(9), 0x1ff);
+        assert_eq!(all_tags_mask(31), u32::MAX >> 1);
+        assert_eq!(all_tags_mask(32), u32::MAX);
+        assert_eq!(all_tags_mask(33), u32::MAX);
+    }
+
+    struct WindowSets {
+        floating: HashSet<Window>,
+        fullscreen: HashSet<Window>,
+    }
+
+    impl WindowSets {
+        fn new() -> Self {
+            Self {
+                floating: HashSet::new(),
+                fullscreen: HashSet::new(),
+            }
+        }
+
+        fn sync(&mut self, window: Window, client: Option<&mut Client>) {
+            sync_window_sets(window, client, &mut self.floating, &mut self.fullscreen);
+        }
+
+        fn state(&self, window: Window) -> (bool, bool) {
+            (
+                self.floating.contains(&window),
+                self.fullscreen.contains(&window),
+            )
+        }
+    }
+
+    #[test]
+    fn rule_floated_window_joins_the_floating_set() {
+        let mut sets = WindowSets::new();
+        let mut client = Client::new(1, 0, 1);
+        client.is_floating = true;
+        sets.sync(1, Some(&mut client));
+        assert_eq!(sets.state(1), (true, false));
+    }
+
+    #[test]
+    fn fullscreen_floating_window_stays_floating_after_leaving_fullscreen() {
+        let mut sets = WindowSets::new();
+        let mut client = Client::new(1, 0, 1);
+        client.is_floating = true;
+        sets.sync(1, Some(&mut client));
+
+        client.old_state = client.is_floating;
+        client.is_fullscreen = true;
+        sets.sync(1, Some(&mut client));
+        assert_eq!(sets.state(1), (true, true));
+
+        client.is_fullscreen = false;
+        client.is_floating = client.old_state;
+        sets.sync(1, Some(&mut client));
+        assert_eq!(sets.state(1), (true, false));
+    }
+
+    #[test]
+    fn fullscreen_tiled_window_returns_to_tiling() {
+        let mut sets = WindowSets::new();
+        let mut client = Client::new(1, 0, 1);
+        client.old_state = client.is_floating;
+        client.is_fullscreen = true;
+        sets.sync(1, Some(&mut client));
+        assert!(client.is_floating);
+        assert_eq!(sets.state(1), (true, true));
+
+        client.is_fullscreen = false;
+        client.is_floating = client.old_state;
+        sets.sync(1, Some(&mut client));
+        assert_eq!(sets.state(1), (false, false));
+    }
+
+    #[test]
+    fn toggling_floating_during_fullscreen_keeps_the_window_floating() {
+        let mut sets = WindowSets::new();
+        let mut client = Client::new(1, 0, 1);
+        client.is_fullscreen = true;
+        client.is_above = true;
+        sets.sync(1, Some(&mut client));
+
+        client.is_floating = false;
+        sets.sync(1, Some(&mut client));
+        assert!(client.is_floating);
+        assert!(client.is_above);
+        assert_eq!(sets.state(1), (true, true));
+    }
+
+    #[test]
+    fn tiling_a_window_clears_above_and_sticky() {
+        let mut sets = WindowSets::new();
+        let mut client = Client::new(1, 0, 1);
+        client.is_floating = true;
+        client.is_above = true;
+        client.is_sticky = true;
+        sets.sync(1, Some(&mut client));
+
+        client.is_floating = false;
+        sets.sync(1, Some(&mut client));
+        assert!(!client.is_above);
+        assert!(!client.is_sticky);
+        assert_eq!(sets.state(1), (false, false));
+    }
+
+    #[test]
+    fn removed_window_leaves_both_sets() {
+        let mut sets = WindowSets::new();
+        let mut client = Client::new(1, 0, 1);
+        client.is_fullscreen = true;
+        sets.sync(1, Some(&mut client));
+        let mut other = Client::new(2, 0, 1);
+        other.is_floating = true;
+        sets.sync(2, Some(&mut other));
+
+        sets.sync(1, None);
+        assert_eq!(sets.state(1), (false, false));
+        assert_eq!(sets.state(2), (true, false));
+    }
+
+    #[test]
+    fn new_window_on_the_selected_monitor_takes_focus() {
+        for steal in [false, true] {
+            assert_eq!(
+                new_window_focus(true, steal, false, 1, 1),
+                NewWindowFocus::Focus
+            );
+        }
+    }
+
+    #[test]
+    fn new_window_on_another_monitor_takes_focus_only_when_stealing() {
+        assert_eq!(
+            new_window_focus(true, true, false, 1, 0),
+            NewWindowFocus::Focus
+        );
+        assert_eq!(
+            new_window_focus(true, false, false, 1, 0),
+            NewWindowFocus::MarkUrgent
+        );
+    }
+
+    #[test]
+    fn new_window_is_marked_urgent_when_focus_new_windows_is_off() {
+        for (steal, monitor) in [(false, 0), (true, 0), (true, 1)] {
+            assert_eq!(
+                new_window_focus(false, steal, false, monitor, 0),
+                NewWindowFocus::MarkUrgent
+            );
+        }
+    }
+
+    #[test]
+    fn popups_never_take_focus_or_urgency() {
+        for (focus_new, steal, monitor) in [(true, true, 0), (true, false, 1), (false, false, 0)] {
+            assert_eq!(
+                new_window_focus(focus_new, steal, true, monitor, 0),
+                NewWindowFocus::Ignore
+            );
+        }
+    }
+
+    #[test]
+    fn swapping_tag_bits_moves_single_tag_windows() {
+        assert_eq!(swap_tag_bits(tag_mask(2), 2, 3), tag_mask(3));
+        assert_eq!(swap_tag_bits(tag_mask(3), 2, 3), tag_mask(2));
+        assert_eq!(swap_tag_bits(tag_mask(0), 2, 3), tag_mask(0));
+        assert_eq!(swap_tag_bits(tag_mask(31), 30, 31), tag_mask(30));
+    }
+
+    #[test]
+    fn swapping_tag_bits_moves_multi_tag_windows() {
+        let both = tag_mask(2) | tag_mask(3);
+        assert_eq!(swap_tag_bits(both, 2, 3), both);
+
+        let spread = tag_mask(0) | tag_mask(2) | tag_mask(5);
+        assert_eq!(
+            swap_tag_bits(spread, 2, 3),
+            tag_mask(0) | tag_mask(3) | tag_mask(5)
+        );
+        assert_eq!(swap_tag_bits(TagMask::MAX, 0, 31), TagMask::MAX);
+    }
+
+    #[test]
+    fn swapping_tag_entries_fills_partial_lists() {
+        let mut empty: Vec<usize> = Vec::new();
+        swap_tag_entries(&mut empty, 0, 1, |index| index);
+        assert!(empty.is_empty());
+
+        let mut partial = vec![10];
+        swap_tag_entries(&mut partial, 0, 2, |index| index);
+        assert_eq!(partial, vec![2, 1, 10]);
+
+        let mut full = vec![10, 11, 12];
+        swap_tag_entries(&mut full, 1, 2, |index| index);
+        assert_eq!(full, vec![10, 12, 11]);
+    }
+
+    #[test]
+    fn swapping_config_tags_keeps_labels_with_their_tag() {
+        let mut config = Config {
+            tags: strings(&["web", "code", "chat"]),
+            tag_labels_occupied: strings(&["W"]),
+            tag_layouts: vec![Some("monocle".to_string())],
+            ..Config::default()
+        };
+        config.monitor_configs = vec![crate::MonitorConfig {
+            tags: Some(strings(&["a", "b", "c"])),
+            ..Default::default()
+        }];
+
+        swap_config_tags(&mut config, 0, 1);
+
+        assert_eq!(config.tags, strings(&["code", "web", "chat"]));
+        assert_eq!(config.tag_labels_occupied, strings(&["code", "W"]));
+        assert!(config.tag_labels_empty.is_empty());
+        assert_eq!(config.tag_layouts, vec![None, Some("monocle".to_string())]);
+        assert_eq!(
+            config.monitor_configs[0].tags,
+            Some(strings(&["b", "a", "c"]))
+        );
+    }
+
+    #[test]
+    fn tag_order_swaps_replay_every_order() {
+        let orders: [&[usize]; 5] = [&[], &[0, 1, 2], &[1, 0, 2], &[2, 0, 1], &[3, 2, 1, 0]];
+        for order in orders {
+            let mut tags: Vec<usize> = (0..order.len()).collect();
+            for (first, second) in tag_order_swaps(order) {
+                tags.swap(first, second);
+            }
+            assert_eq!(tags, order);
+        }
+        assert!(tag_order_swaps(&[0, 1, 2]).is_empty());
+    }
+
+    #[test]
+    fn moving_tags_twice_round_trips_through_the_saved_order() {
+        let mut config = Config {
+            tags: strings(&["1", "2", "3", "4"]),
+            ..Config::default()
+        };
+        let mut order: Vec<usize> = (0..4).collect();
+        for (first, second) in [(0, 1), (1, 2), (3, 2)] {
+            swap_config_tags(&mut config, first, second);
+            order.swap(first, second);
+        }
+
+        let mut reloaded = Config {
+            tags: strings(&["1", "2", "3", "4"]),
+            ..Config::default()
+        };
+        for (first, second) in tag_order_swaps(&order) {
+            swap_config_tags(&mut reloaded, first, second);
         }
+        assert_eq!(reloaded.tags, config.tags);
+    }
+
+    #[test]
+    fn tag_orders_must_be_permutations() {
+        assert!(is_tag_order(&[], 0));
+        assert!(is_tag_order(&[2, 0, 1], 3));
+        assert!(!is_tag_order(&[0, 1], 3));
+        assert!(!is_tag_order(&[0, 0, 1], 3));
+        assert!(!is_tag_order(&[0, 1, 3], 3));
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn stat_parent_pid_skips_odd_command_names() {
+        assert_eq!(stat_parent_pid("1234 (st) S 1200 1234 1234"), Some(1200));
+        assert_eq!(
+            stat_parent_pid("1234 (my (odd) term) S 77 1234 1234"),
+            Some(77)
+        );
+        assert_eq!(stat_parent_pid("1234 (st"), None);
+        assert_eq!(stat_parent_pid(""), None);
+    }
+
+    #[test]
+    fn is_descendant_walks_the_whole_chain() {
+        // 100 (terminal) -> 200 (shell) -> 300 (mpv); 400 is unrelated.
+        let parents = HashMap::from([(100, 1), (200, 100), (300, 200), (400, 1)]);
+        let parent_of = |pid| parents.get(&pid).copied();
+
+        assert!(is_descendant(300, 100, &parent_of));
+        assert!(is_descendant(200, 100, &parent_of));
+        assert!(!is_descendant(100, 100, &parent_of));
+        assert!(!is_descendant(400, 100, &parent_of));
+        assert!(!is_descendant(999, 100, &parent_of));
+    }
+
+    #[test]
+    fn is_descendant_finds_a_spawned_child() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        let found = is_descendant(child.id(), std::process::id(), &parent_pid);
+        let _ = child.kill();
+        let _ = child.wait();
+        assert!(found);
+    }
+
+    fn swallow_clients(clients: &[(Window, u32, bool)]) -> HashMap<Window, Client> {
+        clients
+            .iter()
+            .map(|&(window, pid, is_terminal)| {
+                let mut client = Client::new(window, 0, 1);
+                client.pid = pid;
+                client.is_terminal = is_terminal;
+                (window, client)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn child_of_a_terminal_swallows_it() {
+        let parents = HashMap::from([(100, 1), (200, 100), (300, 200), (500, 1)]);
+        let parent_of = |pid| parents.get(&pid).copied();
+        let clients = swallow_clients(&[(1, 100, true), (2, 500, true), (3, 300, false)]);
+
+        assert_eq!(
+            swallowing_terminal(3, &[1, 2, 3], &clients, parent_of),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn swallowing_needs_a_terminal_ancestor() {
+        let parents = HashMap::from([(100, 1), (300, 100)]);
+        let parent_of = |pid| parents.get(&pid).copied();
+
+        let not_terminal = swallow_clients(&[(1, 100, false), (3, 300, false)]);
+        assert_eq!(
+            swallowing_terminal(3, &[1, 3], &not_terminal, parent_of),
+            None
+        );
+
+        let unrelated = swallow_clients(&[(1, 100, true), (3, 999, false)]);
+        assert_eq!(swallowing_terminal(3, &[1, 3], &unrelated, parent_of), None);
+    }
+
+    #[test]
+    fn floating_windows_terminals_and_hidden_terminals_do_not_swallow() {
+        let parents = HashMap::from([(100, 1), (200, 100), (300, 100)]);
+        let parent_of = |pid| parents.get(&pid).copied();
+        let mut clients = swallow_clients(&[(1, 100, true), (2, 200, true), (3, 300, false)]);
+
+        // A terminal started from a terminal keeps its own window.
+        assert_eq!(
+            swallowing_terminal(2, &[1, 2, 3], &clients, parent_of),
+            None
+        );
+
+        clients.get_mut(&3).unwrap().is_floating = true;
+        assert_eq!(
+            swallowing_terminal(3, &[1, 2, 3], &clients, parent_of),
+            None
+        );
+
+        // A terminal already hidden by another window has no tags.
+        clients.get_mut(&3).unwrap().is_floating = false;
+        clients.get_mut(&1).unwrap().tags = 0;
+        assert_eq!(
+            swallowing_terminal(3, &[1, 2, 3], &clients, parent_of),
+            None
+        );
+
+        clients.get_mut(&3).unwrap().pid = 0;
+        clients.get_mut(&1).unwrap().tags = 1;
+        assert_eq!(
+            swallowing_terminal(3, &[1, 2, 3], &clients, parent_of),
+            None
+        );
+    }
+
+    #[test]
+    fn cycle_index_wraps_in_both_directions() {
+        assert_eq!(cycle_index(3, Some(0), 1), 1);
+        assert_eq!(cycle_index(3, Some(2), 1), 0);
+        assert_eq!(cycle_index(3, Some(0), -1), 2);
+        assert_eq!(cycle_index(3, Some(1), -1), 0);
+        assert_eq!(cycle_index(1, Some(0), 1), 0);
+    }
+
+    #[test]
+    fn cycle_index_starts_at_an_end_from_outside_the_cycle() {
+        assert_eq!(cycle_index(3, None, 1), 0);
+        assert_eq!(cycle_index(3, None, -1), 2);
+    }
+
+    const AREA: Rect = (100, 20, 1000, 800);
+
+    #[test]
+    fn placed_geometry_is_relative_to_the_window_area() {
+        let geometry = [Some(50), Some(30), Some(400), Some(300)];
+        assert_eq!(
+            placed_geometry(geometry, (0, 0, 10, 10), AREA, 2),
+            (150, 50, 400, 300)
+        );
+    }
+
+    #[test]
+    fn placed_geometry_keeps_unset_values() {
+        let current = (300, 200, 640, 480);
+        assert_eq!(
+            placed_geometry([None, None, Some(200), None], current, AREA, 0),
+            (300, 200, 200, 480)
+        );
+        assert_eq!(
+            placed_geometry([Some(0), None, None, None], current, AREA, 0),
+            (100, 200, 640, 480)
+        );
+    }
+
+    #[test]
+    fn placed_geometry_fits_the_window_area() {
+        assert_eq!(
+            placed_geometry(
+                [None, None, Some(5000), Some(-20)],
+                (100, 20, 10, 10),
+                AREA,
+                2
+            ),
+            (100, 20, 996, 1)
+        );
+        assert_eq!(
+            placed_geometry(
+                [Some(950), Some(790), Some(200), Some(100)],
+                (0, 0, 1, 1),
+                AREA,
+                0
+            ),
+            (900, 720, 200, 100)
+        );
+        assert_eq!(
+            placed_geometry(
+                [Some(-500), Some(-500), None, None],
+                (0, 0, 200, 100),
+                AREA,
+                0
+            ),
+            (100, 20, 200, 100)
+        );
+    }
+
+    #[test]
+    fn placed_geometry_does_not_overflow() {
+        let geometry = [
+            Some(i32::MAX),
+            Some(i32::MIN),
+            Some(i32::MAX),
+            Some(i32::MAX),
+        ];
+        assert_eq!(
+            placed_geometry(geometry, (0, 0, 1, 1), AREA, 0),
+            (100, 20, 1000, 800)
+        );
+    }
+
+    const STATE_ATOMS: [Atom; 4] = [10, 11, 12, 13];
+
+    #[test]
+    fn net_wm_state_lists_every_set_flag() {
+        let mut client = Client::new(1, 0, 1);
+        assert!(net_wm_state_atoms(&client, STATE_ATOMS).is_empty());
+
+        client.is_fullscreen = true;
+        client.is_above = true;
+        client.is_sticky = true;
+        assert_eq!(net_wm_state_atoms(&client, STATE_ATOMS), vec![10, 11, 13]);
+
+        client.is_fullscreen = false;
+        client.is_above = false;
+        client.is_below = true;
+        assert_eq!(net_wm_state_atoms(&client, STATE_ATOMS), vec![12, 13]);
+    }
+
+    #[test]
+    fn net_wm_state_reports_fake_fullscreen() {
+        let mut client = Client::new(1, 0, 1);
+        client.is_fake_fullscreen = true;
+        client.is_below = true;
+        assert_eq!(net_wm_state_atoms(&client, STATE_ATOMS), vec![10, 12]);
+    }
+
+    #[test]
+    fn mirror_symbol_flips_brackets_and_keeps_words() {
+        assert_eq!(mirror_symbol("[]="), "=[]");
+        assert_eq!(mirror_symbol("[12]"), "[12]");
+        assert_eq!(mirror_symbol("[tile]"), "[tile]");
+        assert_eq!(mirror_symbol("[>>]"), "[<<]");
+        assert_eq!(mirror_symbol("3 []="), "=[] 3");
+    }
+
+    #[test]
+    fn is_local_machine_compares_host_names() {
+        assert!(is_local_machine(b"", b"laptop"));
+        assert!(is_local_machine(b"laptop", b"laptop"));
+        assert!(is_local_machine(b"laptop.home.lan", b"Laptop"));
+        assert!(!is_local_machine(b"server", b"laptop"));
+        assert!(!is_local_machine(b"server.home.lan", b"laptop.home.lan"));
+    }
+
+    #[test]
+    fn is_at_or_after_handles_sequence_wraparound() {
+        assert!(is_at_or_after(10, 10));
+        assert!(is_at_or_after(11, 10));
+        assert!(!is_at_or_after(9, 10));
+        assert!(is_at_or_after(3, 0x1_fffe));
+        assert!(!is_at_or_after(0xfffd, 0x2_fffe));
+    }
+
+    #[test]
+    fn rotating_the_stack_skips_floating_windows() {
+        let is_tiled = [true, false, true, true, false];
+
+        let mut order = [1, 2, 3, 4, 5];
+        assert!(rotate_tiled(&mut order, &is_tiled, 1));
+        assert_eq!(order, [4, 2, 1, 3, 5]);
+
+        let mut order = [1, 2, 3, 4, 5];
+        assert!(rotate_tiled(&mut order, &is_tiled, -1));
+        assert_eq!(order, [3, 2, 4, 1, 5]);
+    }
+
+    #[test]
+    fn rotating_the_stack_needs_a_direction_and_two_tiled_windows() {
+        let mut order = [1, 2, 3];
+        assert!(!rotate_tiled(&mut order, &[true, true, true], 0));
+        assert_eq!(order, [1, 2, 3]);
+
+        assert!(!rotate_tiled(&mut order, &[false, true, false], 1));
+        assert_eq!(order, [1, 2, 3]);
     }
 }
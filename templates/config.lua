@@ -59,20 +59,20 @@ local blocks = {
     }),
     oxwm.bar.block.static({
         text = " │  ",
-        interval = 999999999,
+        interval = 86400,
         color = colors.lavender,
         underline = false,
     }),
     oxwm.bar.block.shell({
         format = "{}",
         command = "uname -r",
-        interval = 999999999,
+        interval = 86400,
         color = colors.red,
         underline = true,
     }),
     oxwm.bar.block.static({
         text = " │  ",
-        interval = 999999999,
+        interval = 86400,
         color = colors.lavender,
         underline = false,
     }),
@@ -97,7 +97,7 @@ local blocks = {
     -- oxwm.bar.block.button({
     --     format = "POWER",
     --     command = "shutdown now"
-    --     interval = 999999999,
+    --     interval = 86400,
     --     color = colors.lavender,
     --     underline = false,
     -- }),
@@ -216,6 +216,9 @@ oxwm.key.bind({ modkey }, "F", oxwm.layout.set("normie"))
 oxwm.key.bind({ modkey }, "C", oxwm.layout.set("tiling"))
 -- Cycle through layouts
 oxwm.key.bind({ modkey }, "N", oxwm.layout.cycle())
+-- Shift the scrolling layout by one column
+oxwm.key.bind({ modkey }, "Left", oxwm.layout.scroll_left())
+oxwm.key.bind({ modkey }, "Right", oxwm.layout.scroll_right())
 
 -- Master area controls (tiling layout)
 
@@ -240,6 +243,19 @@ oxwm.key.bind({ modkey }, "K", oxwm.client.focus_stack(-1))
 -- Window movement (swap position in stack)
 oxwm.key.bind({ modkey, "Shift" }, "J", oxwm.client.move_stack(1))
 oxwm.key.bind({ modkey, "Shift" }, "K", oxwm.client.move_stack(-1))
+-- Swap the focused window with the master
+oxwm.key.bind({ modkey, "Shift" }, "Return", oxwm.client.zoom())
+
+-- Nudge floating windows
+oxwm.key.bind({ modkey, "Control" }, "H", oxwm.client.move(-20, 0))
+oxwm.key.bind({ modkey, "Control" }, "J", oxwm.client.move(0, 20))
+oxwm.key.bind({ modkey, "Control" }, "K", oxwm.client.move(0, -20))
+oxwm.key.bind({ modkey, "Control" }, "L", oxwm.client.move(20, 0))
+-- Resize floating windows
+oxwm.key.bind({ modkey, "Control", "Shift" }, "H", oxwm.client.resize(-20, 0))
+oxwm.key.bind({ modkey, "Control", "Shift" }, "J", oxwm.client.resize(0, 20))
+oxwm.key.bind({ modkey, "Control", "Shift" }, "K", oxwm.client.resize(0, -20))
+oxwm.key.bind({ modkey, "Control", "Shift" }, "L", oxwm.client.resize(20, 0))
 
 -- Multi-monitor support
 
@@ -318,3 +334,4 @@ oxwm.key.chord({
 -- oxwm.autostart("feh --bg-scale ~/wallpaper.jpg") 
 -- oxwm.autostart("dunst")
 -- oxwm.autostart("nm-applet")
+-- oxwm.autostart({ cmd = "picom", once = true, delay_ms = 500 })
@@ -17,33 +17,128 @@
 oxwm = {}
 
 ---Spawn a command
+---A string is run through sh; an array is executed directly, so its arguments need no quoting
 ---@param cmd string|string[] Command to spawn (string or array of strings)
+---@param env table<string, string>? Extra environment variables (e.g., {GTK_THEME = "Adwaita:dark"})
 ---@return table Action table for keybinding
-function oxwm.spawn(cmd) end
+function oxwm.spawn(cmd, env) end
 
 ---Spawn the configured terminal emulator
 ---@return table Action table for keybinding
 function oxwm.spawn_terminal() end
 
+---Load another config file, resolved relative to the config directory
+---Files in conf.d/*.lua are loaded automatically after config.lua in lexical order
+---@param path string File to load (e.g., "extra.lua")
+function oxwm.include(path) end
+
 ---Set the terminal emulator
 ---@param terminal string Terminal command (e.g., "st", "alacritty")
 function oxwm.set_terminal(terminal) end
 
+---Start the terminal in the working directory of the focused window's process
+---Falls back to the directory oxwm was started in when it can't be resolved
+---@param enabled boolean Default true
+function oxwm.set_terminal_follows_cwd(enabled) end
+
 ---Set the modifier key
 ---@param modkey string Modifier key ("Mod1", "Mod4", "Shift", "Control")
 function oxwm.set_modkey(modkey) end
 
 ---Set workspace tags
----@param tags string[] Array of tag names
+---An empty list falls back to a single tag named "1" and is reported as a warning
+---@param tags string[] Array of 1 to 32 tag names
 function oxwm.set_tags(tags) end
 
+---Set alternative tag labels depending on whether a tag has windows
+---Missing entries fall back to the names given to oxwm.set_tags
+---@param labels {occupied: string[]?, empty: string[]?} Labels for occupied and empty tags
+function oxwm.set_tag_labels(labels) end
+
 ---Enable or disable automatic tiling of new windows
 ---@param enabled boolean Enable or disable auto-tiling
 function oxwm.auto_tile(enabled) end
 
+---Focus newly opened windows; when disabled they open in the background and are marked urgent
+---A rule's `focus` field overrides this for matching windows
+---@param enabled boolean Default true
+function oxwm.set_focus_new_windows(enabled) end
+
+---Allow new windows placed on another monitor (e.g. by a rule) to take the selected monitor
+---When disabled they are arranged there and marked urgent instead of pulling focus
+---@param enabled boolean Default true
+function oxwm.set_steal_monitor_focus(enabled) end
+
+---Switch to and focus windows that ask to be activated (e.g. a browser opening a link)
+---When disabled they are only marked urgent
+---@param enabled boolean Default false
+function oxwm.set_focus_on_activate(enabled) end
+
+---Alias of oxwm.set_focus_on_activate
+---@param enabled boolean Default false
+function oxwm.set_focus_on_activation(enabled) end
+
+---Focus windows when the pointer enters them
+---When disabled focus only changes on click or keyboard navigation
+---@param enabled boolean Default true
+function oxwm.set_focus_follows_mouse(enabled) end
+
+---Move the cursor to the center of a window when it gets focus and the cursor is outside it,
+---or to the center of a monitor focused with oxwm.monitor.focus when it has no windows
+---@param enabled boolean Default false
+function oxwm.set_warp_cursor(enabled) end
+
+---Distance at which dragged floating windows snap to monitor edges and other floating windows
+---@param px integer Distance in pixels, 0 disables snapping (default 32)
+function oxwm.set_snap_distance(px) end
+
+---Toggle focus follows mouse at runtime; a config reload restores the configured setting
+---@return table Action table for keybinding
+function oxwm.toggle_focus_follows_mouse() end
+
+---Toggle presentation mode: new and activated windows don't take focus, notifications and
+---OSDs are suppressed, the selected monitor no longer follows the mouse and window titles can
+---be hidden from the bars
+---@return table Action table for keybinding
+function oxwm.toggle_presentation_mode() end
+
+---Print the monitors, tags, layouts and clients as JSON to stdout
+---The same data is available through `oxwm-msg dump`
+---@return table Action table for keybinding
+function oxwm.dump_state() end
+
+---Show a message in the middle of the screen, e.g. as volume or brightness feedback
+---The same is available to scripts through `oxwm-msg notify <TEXT>`
+---@param text string Message, may span several lines
+---@param duration_ms integer? How long to show it (default 2000)
+---@return table Action table for keybinding
+function oxwm.notify(text, duration_ms) end
+
+---Show a labeled progress bar near the bottom of the screen for about 800ms
+---Scripts changing the volume or brightness can call `oxwm-msg osd volume 75`
+---@param label string Label, e.g. "volume" or "brightness"
+---@param value number Percentage from 0 to 100
+---@return table Action table for keybinding
+function oxwm.osd(label, value) end
+
+---Focus the most recently used window whose WM_CLASS contains `class`, switching tag or
+---monitor when needed; spawn `command` when no such window exists
+---@param command string Command to spawn, e.g. "firefox"
+---@param class string Class to match, e.g. "firefox"
+---@return table Action table for keybinding
+function oxwm.run_or_raise(command, class) end
+
+---Whether presentation mode hides window titles in the bar and tab bar
+---@param enabled boolean Default true
+function oxwm.set_presentation_blank_titles(enabled) end
+
+---Set the bar indicator shown while presentation mode is on; an empty string hides it
+---@param text string Default "[presenting]"
+function oxwm.set_presentation_indicator(text) end
+
 ---Set layout symbol override
 ---@param name string Layout name (e.g., "tiling", "normie", "tabbed", "grid", "monocle")
----@param symbol string Symbol to display (e.g., "[T]", "[F]", "[=]")
+---@param symbol string Symbol to display (e.g., "[T]", "[F]", "[=]"); "{n}" is replaced with the number of visible tiled windows
 function oxwm.set_layout_symbol(name, symbol) end
 
 ---Window rule module
@@ -51,9 +146,36 @@ function oxwm.set_layout_symbol(name, symbol) end
 oxwm.rule = {}
 
 ---Add a window rule
----@param rule {class: string?, instance: string?, title: string?, role: string?, floating: boolean?, tag: integer?, fullscreen: boolean?, focus: boolean?} Rule configuration
+---@param rule {class: string?, instance: string?, title: string?, role: string?, floating: boolean?, tag: integer?, fullscreen: boolean?, focus: boolean?, scratchpad: string?, click_through: boolean?, swallow: boolean?, center: boolean?, fake_fullscreen: boolean?, border: boolean?, x: integer?, y: integer?, width: integer?, height: integer?} Rule configuration
+---focus = false opens matching windows in the background; focus = true focuses them and shows their tag
+---swallow = true marks a terminal: tiled windows started from it replace it until they close
+---center = true places matching windows in the middle of their monitor when they float
+---fake_fullscreen = true lets matching windows go fullscreen inside their tile instead of covering the monitor
+---role matches WM_WINDOW_ROLE exactly, e.g. Firefox's "Preferences" dialog
+---x, y, width and height set the initial geometry of floating windows; x and y are relative to the monitor
+---border = false draws no border around matching windows; border = true keeps it even when they ask to be undecorated
 function oxwm.rule.add(rule) end
 
+---Scratchpad module
+---@class oxwm.scratchpad
+oxwm.scratchpad = {}
+
+---Define a scratchpad with its command and geometry, applied on the selected monitor every time it is shown
+---width and height are fractions of the monitor; x and y are "center" (default) or fractions offsetting the window from the top left
+---exclusive = true hides every other visible scratchpad when this one is shown
+---@param name string Scratchpad name
+---@param config {command: string?, width: number?, height: number?, x: string|number?, y: string|number?, exclusive: boolean?} Scratchpad configuration
+function oxwm.scratchpad.define(name, config) end
+
+---Show or hide a scratchpad window on the selected monitor
+---Windows become scratchpads through rules, e.g. oxwm.rule.add({ class = "scratch-term", scratchpad = "term" })
+---With a command, or a command given to oxwm.scratchpad.define, it is spawned whenever the scratchpad has no window, e.g. oxwm.scratchpad.toggle("term", "st -n scratch")
+---The spawned window is matched by the instance given with -n/-name/--name, or by the scratchpad name otherwise
+---@param name string Scratchpad name
+---@param command string? Command that starts the scratchpad window
+---@return table Action table for keybinding
+function oxwm.scratchpad.toggle(name, command) end
+
 ---Quit the window manager
 ---@return table Action table for keybinding
 function oxwm.quit() end
@@ -62,6 +184,19 @@ function oxwm.quit() end
 ---@return table Action table for keybinding
 function oxwm.restart() end
 
+---Reload the config automatically when it or a conf.d snippet is saved
+---Errors are shown in the same overlay as a manual restart
+---@param enabled boolean Default false
+function oxwm.set_auto_reload(enabled) end
+
+---Undo the last tag, monitor, floating or fullscreen change made to a window
+---@return table Action table for keybinding
+function oxwm.undo() end
+
+---Manage any mapped windows that oxwm is not managing yet
+---@return table Action table for keybinding
+function oxwm.rescan() end
+
 ---Recompile the window manager
 ---@return table Action table for keybinding
 function oxwm.recompile() end
@@ -75,7 +210,7 @@ function oxwm.toggle_gaps() end
 function oxwm.show_keybinds() end
 
 ---Set master area factor (adjust master window width in tiling layout)
----@param delta integer Delta to adjust by (negative to decrease, positive to increase)
+---@param delta number Percentage points to adjust by, e.g. 5 or -2.5
 ---@return table Action table for keybinding
 function oxwm.set_master_factor(delta) end
 
@@ -94,11 +229,21 @@ oxwm.key = {}
 ---@param action table Action returned by oxwm functions
 function oxwm.key.bind(modifiers, key, action) end
 
+---Bind a key combination to an action that runs when the key is released
+---@param modifiers string|string[] Modifier keys (e.g., {"Mod4"}, {"Mod4", "Shift"})
+---@param key string Key name (e.g., "Return", "Q", "1")
+---@param action table Action returned by oxwm functions
+function oxwm.key.bind_release(modifiers, key, action) end
+
 ---Bind a keychord (multi-key sequence) to an action
 ---@param keys table[] Array of key presses, each: {{modifiers}, key}
 ---@param action table Action returned by oxwm functions
 function oxwm.key.chord(keys, action) end
 
+---Cancel a half-entered keychord when the next key doesn't follow within the timeout
+---@param ms integer Timeout in milliseconds, 100 to 60000 (default 1500)
+function oxwm.key.set_chord_timeout(ms) end
+
 ---Gap configuration module
 ---@class oxwm.gaps
 oxwm.gaps = {}
@@ -114,15 +259,24 @@ function oxwm.gaps.enable() end
 function oxwm.gaps.disable() end
 
 ---Set inner gaps
----@param horizontal integer Horizontal inner gap in pixels
----@param vertical integer Vertical inner gap in pixels
+---@param horizontal integer Horizontal inner gap in pixels (0-500)
+---@param vertical integer Vertical inner gap in pixels (0-500)
 function oxwm.gaps.set_inner(horizontal, vertical) end
 
----Set outer gaps
----@param horizontal integer Horizontal outer gap in pixels
----@param vertical integer Vertical outer gap in pixels
+---Set outer gaps. Every layout uses the same axes; monocle, grid and tabbed
+---used to take `horizontal` as their left and right gap, so swap the values
+---if those layouts moved after upgrading.
+---@param horizontal integer Outer gap above and below windows in pixels (0-500)
+---@param vertical integer Outer gap left and right of windows in pixels (0-500)
 function oxwm.gaps.set_outer(horizontal, vertical) end
 
+---Set outer gaps for each screen edge separately
+---@param top integer Top outer gap in pixels
+---@param bottom integer Bottom outer gap in pixels
+---@param left integer Left outer gap in pixels
+---@param right integer Right outer gap in pixels
+function oxwm.gaps.set_outer_sides(top, bottom, left, right) end
+
 ---Set smart gaps (disable outer gaps when only one window visible)
 ---@param enabled boolean Enable or disable smart gaps
 function oxwm.gaps.set_smart(enabled) end
@@ -132,9 +286,13 @@ function oxwm.gaps.set_smart(enabled) end
 oxwm.border = {}
 
 ---Set border width
----@param width integer Border width in pixels
+---@param width integer Border width in pixels (0-100)
 function oxwm.border.set_width(width) end
 
+---Hide the border when a monitor shows a single tiled window
+---@param enabled boolean Enable smart borders
+function oxwm.border.set_smart(enabled) end
+
 ---Set focused window border color
 ---@param color string|integer Color as hex string ("#ff0000", "0xff0000") or integer
 function oxwm.border.set_focused_color(color) end
@@ -143,6 +301,10 @@ function oxwm.border.set_focused_color(color) end
 ---@param color string|integer Color as hex string ("#666666", "0x666666") or integer
 function oxwm.border.set_unfocused_color(color) end
 
+---Set the border color of unfocused windows that requested attention
+---@param color string|integer Color as hex string ("#ff5555", "0xff5555") or integer
+function oxwm.border.set_urgent_color(color) end
+
 ---Client/window management module
 ---@class oxwm.client
 oxwm.client = {}
@@ -151,6 +313,14 @@ oxwm.client = {}
 ---@return table Action table for keybinding
 function oxwm.client.kill() end
 
+---Close every window on the current tag except the focused one
+---@return table Action table for keybinding
+function oxwm.client.kill_others() end
+
+---Close every window on the current tag
+---@return table Action table for keybinding
+function oxwm.client.kill_all() end
+
 ---Toggle fullscreen mode
 ---@return table Action table for keybinding
 function oxwm.client.toggle_fullscreen() end
@@ -159,6 +329,16 @@ function oxwm.client.toggle_fullscreen() end
 ---@return table Action table for keybinding
 function oxwm.client.toggle_floating() end
 
+---Keep the focused window above all other windows, floating it if needed
+---The flag is cleared when the window is tiled again
+---@return table Action table for keybinding
+function oxwm.client.toggle_above() end
+
+---Toggle fake fullscreen for the focused window: fullscreen requests are acknowledged
+---but the window keeps its tile instead of covering the monitor
+---@return table Action table for keybinding
+function oxwm.client.toggle_fake_fullscreen() end
+
 ---Focus stack (next/previous window)
 ---@param dir integer Direction (1 for next, -1 for previous)
 ---@return table Action table for keybinding
@@ -169,6 +349,72 @@ function oxwm.client.focus_stack(dir) end
 ---@return table Action table for keybinding
 function oxwm.client.move_stack(dir) end
 
+---Rotate all visible tiled windows by one position, keeping focus on the same window
+---@param dir integer Direction (1 moves windows down the stack, -1 up)
+---@return table Action table for keybinding
+function oxwm.client.rotate_stack(dir) end
+
+---Cycle focus through the floating windows on the current tag, raising each
+---Fullscreen windows are skipped
+---@param dir integer Direction (1 for next, -1 for previous)
+---@return table Action table for keybinding
+function oxwm.client.focus_floating(dir) end
+
+---Cycle focus through the windows of the focused window's application (same WM_CLASS),
+---most recently focused first, switching tag or monitor when needed
+---@param dir integer Direction (1 for next, -1 for previous)
+---@return table Action table for keybinding
+function oxwm.client.cycle_class(dir) end
+
+---Switch to and focus a window that set the urgency hint, preferring the current monitor
+---Does nothing when no window is urgent
+---@return table Action table for keybinding
+function oxwm.client.focus_urgent() end
+
+---Alt-Tab style switcher over visible windows in most-recently-used order
+---Each press while the modifier is held selects the next window; releasing the modifier focuses it and Escape cancels
+---@return table Action table for keybinding
+function oxwm.client.cycle_recent() end
+
+---Grow or shrink the focused window's share of the stack
+---Factors range from 0.25 to 4.0 and reset when the window is floated
+---@param delta integer Change in hundredths (e.g. 25 or -25)
+---@return table Action table for keybinding
+function oxwm.client.set_factor(delta) end
+
+---Swap the focused tiled window with the master window
+---If it is already master, the next tiled window is promoted instead
+---@return table Action table for keybinding
+function oxwm.client.zoom() end
+
+---Center the focused window on its monitor, floating it first if it is tiled
+---@param float_tiled boolean? Set to false to leave tiled windows alone (default true)
+---@return table Action table for keybinding
+function oxwm.client.center(float_tiled) end
+
+---Snap the focused floating window to a region of its monitor (no-op for tiled windows)
+---@param region "left"|"right"|"top"|"bottom"|"top-left"|"top-right"|"bottom-left"|"bottom-right"|"full" Region to fill
+---@return table Action table for keybinding
+function oxwm.client.snap(region) end
+
+---Toggle whether pointer input passes through the focused window to the windows below it
+---Click-through windows can't take focus, so focus moves on to the next window and toggling
+---again turns click-through off for the windows on the current monitor; requires the X SHAPE extension
+---@return table Action table for keybinding
+function oxwm.client.toggle_click_through() end
+
+---Move the focused floating window, keeping it inside the monitor (no-op for tiled windows)
+---@param dx integer|"left"|"right"|"up"|"down" Horizontal offset in pixels, or a direction
+---@param dy integer Vertical offset in pixels, or the step when a direction is given
+---@return table Action table for keybinding
+function oxwm.client.move(dx, dy) end
+
+---Resize the focused floating window within the monitor, respecting its size hints (no-op for tiled windows)
+---@param dw integer|"left"|"right"|"up"|"down" Width change in pixels, or a direction
+---@param dh integer Height change in pixels, or the step when a direction is given
+---@return table Action table for keybinding
+function oxwm.client.resize(dw, dh) end
+
 ---Monitor management module
 ---@class oxwm.monitor
 oxwm.monitor = {}
@@ -183,6 +429,18 @@ function oxwm.monitor.focus(dir) end
 ---@return table Action table for keybinding
 function oxwm.monitor.tag(dir) end
 
+---Send focused window to monitor, keeping its tags instead of taking the target's current ones
+---@param dir integer Direction (-1 for previous, 1 for next)
+---@return table Action table for keybinding
+function oxwm.monitor.send_keep_tag(dir) end
+
+---Configure a monitor by index (0-based, in detection order)
+---`tags` renames the tags shown on that monitor's bar and must have as many entries as oxwm.set_tags;
+---`default_layout` is used for tags without their own default; `tag` is the tag shown at startup
+---@param index integer Monitor index
+---@param config {tags: string[]?, default_layout: string?, tag: integer?} Monitor configuration
+function oxwm.monitor.configure(index, config) end
+
 ---Layout management module
 ---@class oxwm.layout
 oxwm.layout = {}
@@ -192,18 +450,36 @@ oxwm.layout = {}
 function oxwm.layout.cycle() end
 
 ---Set specific layout
----@param name string Layout name (e.g., "tiling", "normie", "tabbed", "grid", "monocle", "scrolling")
+---@param name string Layout name (e.g., "tiling", "normie", "tabbed", "grid", "monocle", "scrolling", "bstack", "spiral", "dwindle")
 ---@return table Action table for keybinding
 function oxwm.layout.set(name) end
 
----Scroll layout left (for scrolling layout)
+---Mirror the layout on the focused monitor so the master area sits on the right
+---Persists across tag switches; the bar shows the reversed layout symbol
+---@return table Action table for keybinding
+function oxwm.layout.mirror() end
+
+---Scroll layout left by one column (for scrolling layout), focusing the column scrolled into view
 ---@return table Action table for keybinding
 function oxwm.layout.scroll_left() end
 
----Scroll layout right (for scrolling layout)
+---Scroll layout right by one column (for scrolling layout), focusing the column scrolled into view
 ---@return table Action table for keybinding
 function oxwm.layout.scroll_right() end
 
+---Widen the focused column of the scrolling layout by 10% of the screen
+---@return table Action table for keybinding
+function oxwm.layout.scroll_column_grow() end
+
+---Narrow the focused column of the scrolling layout by 10% of the screen
+---@return table Action table for keybinding
+function oxwm.layout.scroll_column_shrink() end
+
+---Set the default column width of the scrolling layout as a fraction of the screen
+---Without it columns share the screen between the master count (default 2) of windows
+---@param factor number Between 0.1 and 1.0
+function oxwm.layout.set_scroll_column_factor(factor) end
+
 ---Tag/workspace management module
 ---@class oxwm.tag
 oxwm.tag = {}
@@ -213,6 +489,11 @@ oxwm.tag = {}
 ---@return table Action table for keybinding
 function oxwm.tag.view(index) end
 
+---Switch every monitor to the same tag, keeping focus on the current monitor
+---@param index integer Tag index (0-based)
+---@return table Action table for keybinding
+function oxwm.tag.view_all(index) end
+
 ---View/switch to next tag
 ---@return table Action table for keybinding
 function oxwm.tag.view_next() end
@@ -239,6 +520,13 @@ function oxwm.tag.move_to(index) end
 ---@return table Action table for keybinding
 function oxwm.tag.toggleview(index) end
 
+---Move the current tag one position left or right, taking its windows along
+---Keybindings and rules refer to tag positions, so they keep pointing at the same slot
+---The new order survives reloads and restarts as long as the number of tags stays the same
+---@param direction integer -1 for left, 1 for right
+---@return table Action table for keybinding
+function oxwm.tag.move(direction) end
+
 ---Toggle tag on focused window (allows window to appear on multiple tags)
 ---@param index integer Tag index (0-based)
 ---@return table Action table for keybinding
@@ -248,6 +536,11 @@ function oxwm.tag.toggletag(index) end
 ---@param enabled boolean Enable or disable tag_back_and_forth
 function oxwm.tag.set_back_and_forth(enabled) end
 
+---Set the layout a tag starts out with; later layout changes on that tag are remembered per tag
+---@param index integer Tag index (0-based)
+---@param layout string Layout name (e.g., "tiling", "monocle", "grid")
+function oxwm.tag.set_default_layout(index, layout) end
+
 ---Status bar configuration module
 ---@class oxwm.bar
 oxwm.bar = {}
@@ -256,12 +549,16 @@ oxwm.bar = {}
 ---@param font string Font string (e.g., "monospace:style=Bold:size=10")
 function oxwm.bar.set_font(font) end
 
+---Set status bar fonts, glyphs missing from the first font are looked up in the following ones
+---@param fonts string[] Font strings in order of preference (e.g., {"monospace:size=10", "Symbols Nerd Font:size=10"})
+function oxwm.bar.set_fonts(fonts) end
+
 ---DEPRECATED: Add a status bar block (use oxwm.bar.set_blocks with block constructors instead)
 ---@deprecated
 ---@param format string Format string with {} placeholders
 ---@param command "DateTime"|"Shell"|"Ram"|"Static" Block command type (Battery not supported)
 ---@param arg string|nil Command argument (format for DateTime, command for Shell, text for Static, nil for Ram)
----@param interval integer Update interval in seconds
+---@param interval integer Update interval in seconds (1-86400)
 ---@param color string|integer Color as hex string or integer
 ---@param underline boolean Whether to underline the block
 function oxwm.bar.add_block(format, command, arg, interval, color, underline) end
@@ -271,40 +568,66 @@ function oxwm.bar.add_block(format, command, arg, interval, color, underline) en
 function oxwm.bar.set_blocks(blocks) end
 
 ---Block constructors module
+---All constructors accept an optional min_width, either in pixels or as a sample string
+---(e.g. "100%") whose rendered width is reserved, and an align for text within that width
 ---@class oxwm.bar.block
 oxwm.bar.block = {}
 
 ---Create a RAM usage block
----@param config {format: string, interval: integer, color: string|integer, underline: boolean} Block configuration
+---@param config {format: string, interval: integer, color: string|integer, underline: boolean, min_width: integer|string?, align: "left"|"center"|"right"?} Block configuration
 ---@return table Block configuration
 function oxwm.bar.block.ram(config) end
 
+---Create a temperature block showing degrees Celsius in place of {} in format
+---zone is a thermal zone directory (e.g. "thermal_zone2") or type (e.g. "acpitz"); defaults to the x86_pkg_temp zone
+---@param config {format: string, zone: string?, interval: integer, color: string|integer, underline: boolean, min_width: integer|string?, align: "left"|"center"|"right"?} Block configuration
+---@return table Block configuration
+function oxwm.bar.block.temperature(config) end
+
+---Create a backlight brightness block showing a percentage in place of {} in format
+---Scrolling over the block runs set_command with {} replaced by the new percentage (e.g. "brightnessctl set {}%")
+---@param config {format: string, set_command: string?, step: integer?, device: string?, interval: integer, color: string|integer, underline: boolean, min_width: integer|string?, align: "left"|"center"|"right"?} Block configuration (step defaults to 5; device defaults to the first entry in /sys/class/backlight)
+---@return table Block configuration
+function oxwm.bar.block.brightness(config) end
+
 ---Create a date/time block
----@param config {format: string, date_format: string, interval: integer, color: string|integer, underline: boolean} Block configuration (format is display template with {}, date_format is strftime format)
+---@param config {format: string, date_format: string, interval: integer, color: string|integer, underline: boolean, min_width: integer|string?, align: "left"|"center"|"right"?} Block configuration (format is display template with {}, date_format is strftime format)
 ---@return table Block configuration
 function oxwm.bar.block.datetime(config) end
 
 ---Create a shell command block
----@param config {format: string, command: string, onclick_command: string, interval: integer, color: string|integer, underline: boolean} Block configuration
+---@param config {format: string, command: string, onclick_command: string, interval: integer, color: string|integer, underline: boolean, min_width: integer|string?, align: "left"|"center"|"right"?} Block configuration
 ---@return table Block configuration
 function oxwm.bar.block.shell(config) end
 
 ---Create a static text block
----@param config {format: string, text: string, interval: integer, color: string|integer, underline: boolean} Block configuration
+---@param config {format: string, text: string, interval: integer, color: string|integer, underline: boolean, min_width: integer|string?, align: "left"|"center"|"right"?} Block configuration
 ---@return table Block configuration
 function oxwm.bar.block.static(config) end
 
 ---Create a battery status block
----@param config {format: string, charging: string, discharging: string, full: string, interval: integer, color: string|integer, underline: boolean, battery_name: string} Block configuration
+---@param config {format: string, charging: string, discharging: string, full: string, interval: integer, color: string|integer, underline: boolean, min_width: integer|string?, align: "left"|"center"|"right"?, battery_name: string} Block configuration
 ---@return table Block configuration
 function oxwm.bar.block.battery(config) end
 
 ---Create a button block
 ---In the command {click_x} will be replaced by the x position of the cursor  
----@param config {format: string, command: string, interval: integer, color: string|integer, underline: boolean} Block configuration
+---@param config {format: string, command: string, interval: integer, color: string|integer, underline: boolean, min_width: integer|string?, align: "left"|"center"|"right"?} Block configuration
 ---@return table Block configuration
 function oxwm.bar.block.button(config) end
 
+---Create a block whose content is returned by a Lua function, called on every interval
+---The function must return quickly; slow or failing functions render "ERR"
+---@param config {format: string?, fn: fun(): string, interval: integer, color: string|integer, underline: boolean, min_width: integer|string?, align: "left"|"center"|"right"?} Block configuration
+---@return table Block configuration
+function oxwm.bar.block.custom(config) end
+
+---Create a block showing the title of the focused window on the bar's monitor
+---Titles longer than max_len characters (default 50) are truncated with an ellipsis
+---@param config {format: string, max_len: integer?, interval: integer?, color: string|integer, underline: boolean, min_width: integer|string?, align: "left"|"center"|"right"?} Block configuration
+---@return table Block configuration
+function oxwm.bar.block.window_title(config) end
+
 ---Set normal tag color scheme (unselected, no windows)
 ---@param foreground string|integer Foreground color
 ---@param background string|integer Background color
@@ -333,6 +656,74 @@ function oxwm.bar.set_scheme_urgent(foreground, background, underline) end
 ---@param hide boolean Whether to hide vacant tags
 function oxwm.bar.set_hide_vacant_tags(hide) end
 
+---Show or hide the bar at startup
+---@param visible boolean Default true
+function oxwm.bar.set_visible(visible) end
+
+---Show or hide the bar on the focused monitor's current tag
+---@return table Action table for keybinding
+function oxwm.bar.toggle() end
+
+---System integration module
+---@class oxwm.system
+oxwm.system = {}
+
+---Set the screen color temperature via RandR gamma ramps (6500 is neutral)
+---@param temperature integer Color temperature in Kelvin (e.g., 4500)
+---@return table Action table for keybinding
+function oxwm.system.set_gamma(temperature) end
+
+---Schedule the screen color temperature by time of day
+---@param config {day: integer?, night: integer, start: string, stop: string} Temperatures in Kelvin and times as "HH:MM" (e.g., start = "21:00", stop = "07:00")
+function oxwm.system.set_night_light(config) end
+
+---Animation settings module
+---@class oxwm.animations
+oxwm.animations = {}
+
+---Enable or disable all animations, including scrolling layout transitions
+---@param enabled boolean Default true
+function oxwm.animations.set_enabled(enabled) end
+
+---Slide tiled windows in when switching tags; pass nil to turn it off (the default)
+---@param config {duration_ms: integer?, easing: "linear"|"ease_out"|"ease_in_out"?}? Defaults to 150 ms with "ease_out"
+function oxwm.animations.set_tag_switch(config) end
+
+---Move and resize tiled windows smoothly when the layout changes; pass nil to turn it off (the default)
+---Durations above 150 ms are capped since input waits for the animation to finish
+---@param config {duration_ms: integer?, easing: "linear"|"ease_out"|"ease_in_out"?}? Defaults to 150 ms with "ease_out"
+function oxwm.animations.set_layout(config) end
+
+---Color theme module
+---@class oxwm.theme
+oxwm.theme = {}
+
+---Define a named set of border and bar colors; omitted colors use the values set elsewhere in the config
+---Schemes are given as { foreground, background, underline }; popups such as the switcher use scheme_normal and border_focused
+---@param name string Theme name
+---@param theme {border_focused: string|integer?, border_unfocused: string|integer?, border_urgent: string|integer?, scheme_normal: table?, scheme_occupied: table?, scheme_selected: table?, scheme_urgent: table?}
+function oxwm.theme.define(name, theme) end
+
+---Switch between two themes by time of day, e.g. set_schedule("light", "08:00", "dark", "20:00")
+---@param first string Theme used from `first_start` until `second_start`
+---@param first_start string Time as "HH:MM"
+---@param second string Theme used from `second_start` until `first_start`
+---@param second_start string Time as "HH:MM"
+function oxwm.theme.set_schedule(first, first_start, second, second_start) end
+
+---Switch to the next defined theme; a schedule takes over again at its next switch time
+---@return table Action table for keybinding
+function oxwm.theme.cycle() end
+
 ---Add an autostart command
----@param cmd string Command to run at startup
+---The table form can delay the command or skip it while a process with the same name is running
+---@param cmd string|{cmd: string, once: boolean?, delay_ms: integer?} Command to run at startup
 function oxwm.autostart(cmd) end
+
+---Run a shell command whenever a tag is viewed; the tag number (starting at 1) is appended as an argument
+---@param cmd string Command to run
+function oxwm.on_tag_change(cmd) end
+
+---Run a shell command whenever the layout of the selected monitor changes; the layout name is appended as an argument
+---@param cmd string Command to run
+function oxwm.on_layout_change(cmd) end
@@ -27,13 +27,13 @@ local blocks = {
     oxwm.bar.block.shell({
         format = " {}",
         command = "uname -r",
-        interval = 999999999,
+        interval = 86400,
         color = colors.red,
         underline = true,
     }),
     oxwm.bar.block.static({
         text = " │  ",
-        interval = 999999999,
+        interval = 86400,
         color = colors.sep,
         underline = false,
     }),
@@ -45,7 +45,7 @@ local blocks = {
     }),
     oxwm.bar.block.static({
         text = " │  ",
-        interval = 999999999,
+        interval = 86400,
         color = colors.sep,
         underline = false,
     }),
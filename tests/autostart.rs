@@ -0,0 +1,49 @@
+//! Starts oxwm on a virtual X server with an autostarted terminal and checks the
+//! terminal gets managed and tiled without a manual rescan. Ignored by default;
+//! run with `cargo test -- --ignored` where `Xvfb` and `xterm` are installed.
+
+mod common;
+
+use common::{SCREEN_HEIGHT, SCREEN_WIDTH, Session, wait_for};
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, MapState};
+
+const CLASS: &str = "OxwmAutostartTest";
+
+fn managed_window_with_class(session: &Session) -> Option<u32> {
+    session.managed_windows().into_iter().find(|&window| {
+        session
+            .connection
+            .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some_and(|reply| String::from_utf8_lossy(&reply.value).contains(CLASS))
+    })
+}
+
+#[test]
+#[ignore = "needs Xvfb and xterm"]
+fn autostarted_terminal_is_managed_and_tiled() {
+    let autostart = format!("oxwm.autostart(\"xterm -class {}\")", CLASS);
+    let session = Session::start("autostart", &autostart, &["xterm"]);
+    let connection = &session.connection;
+
+    let window = wait_for(|| managed_window_with_class(&session))
+        .expect("the autostarted terminal was never managed");
+
+    let tiled = wait_for(|| {
+        let attributes = connection
+            .get_window_attributes(window)
+            .ok()?
+            .reply()
+            .ok()?;
+        let geometry = connection.get_geometry(window).ok()?.reply().ok()?;
+        (attributes.map_state == MapState::VIEWABLE
+            && geometry.width > SCREEN_WIDTH / 2
+            && geometry.height > SCREEN_HEIGHT / 2)
+            .then_some(())
+    });
+    assert!(
+        tiled.is_some(),
+        "the autostarted terminal was managed but not tiled"
+    );
+}
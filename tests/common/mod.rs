@@ -0,0 +1,151 @@
+//! Shared setup for tests that run oxwm on a virtual X server.
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+use x11rb::rust_connection::RustConnection;
+
+pub const TEMPLATE: &str = include_str!("../../templates/config.lua");
+pub const SCREEN_WIDTH: u16 = 1280;
+pub const SCREEN_HEIGHT: u16 = 800;
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Kills the X server and oxwm and removes the scratch directory when dropped.
+pub struct Session {
+    children: Vec<Child>,
+    dir: PathBuf,
+    pub connection: RustConnection,
+    pub root: u32,
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        for child in self.children.iter_mut().rev() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+impl Session {
+    /// Starts Xvfb and oxwm with the template config followed by `extra_config`.
+    /// Panics when Xvfb or one of `programs` is not installed, so an ignored test
+    /// that is run explicitly never passes without doing anything.
+    pub fn start(name: &str, extra_config: &str, programs: &[&str]) -> Self {
+        if let Some(missing) = ["Xvfb"]
+            .iter()
+            .chain(programs)
+            .find(|program| !on_path(program))
+        {
+            panic!("{} is required to run this test", missing);
+        }
+
+        let display = free_display();
+        let dir = std::env::temp_dir().join(format!("oxwm-{}-test-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.lua");
+        std::fs::write(&config_path, format!("{}\n{}\n", TEMPLATE, extra_config)).unwrap();
+
+        let mut children = vec![
+            Command::new("Xvfb")
+                .args([
+                    display.as_str(),
+                    "-screen",
+                    "0",
+                    &format!("{}x{}x24", SCREEN_WIDTH, SCREEN_HEIGHT),
+                ])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap(),
+        ];
+
+        let connected = wait_for(|| x11rb::connect(Some(&display)).ok());
+        let Some((connection, screen_number)) = connected else {
+            for child in &mut children {
+                let _ = child.kill();
+            }
+            panic!("Xvfb did not start");
+        };
+        let root = connection.setup().roots[screen_number].root;
+
+        children.push(
+            Command::new(env!("CARGO_BIN_EXE_oxwm"))
+                .arg("--fresh-state")
+                .arg("--config")
+                .arg(&config_path)
+                .env("DISPLAY", &display)
+                .env("XDG_RUNTIME_DIR", &dir)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap(),
+        );
+
+        Self {
+            children,
+            dir,
+            connection,
+            root,
+        }
+    }
+
+    pub fn atom(&self, name: &str) -> u32 {
+        self.connection
+            .intern_atom(false, name.as_bytes())
+            .unwrap()
+            .reply()
+            .unwrap()
+            .atom
+    }
+
+    /// Windows listed in `_NET_CLIENT_LIST`, i.e. the ones oxwm manages.
+    pub fn managed_windows(&self) -> Vec<u32> {
+        let client_list = self.atom("_NET_CLIENT_LIST");
+        self.connection
+            .get_property(false, self.root, client_list, AtomEnum::WINDOW, 0, 1024)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| reply.value32().map(Iterator::collect))
+            .unwrap_or_default()
+    }
+
+    /// Whether a window manager owns `SubstructureRedirect` on the root window yet.
+    pub fn wm_running(&self) -> bool {
+        let supporting = self.atom("_NET_SUPPORTING_WM_CHECK");
+        self.connection
+            .get_property(false, self.root, supporting, AtomEnum::WINDOW, 0, 1)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some_and(|reply| reply.value_len > 0)
+    }
+}
+
+pub fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+fn free_display() -> String {
+    let first = 100 + std::process::id() % 400;
+    let number = (first..first + 100)
+        .find(|n| !Path::new(&format!("/tmp/.X11-unix/X{}", n)).exists())
+        .expect("no free display number");
+    format!(":{}", number)
+}
+
+pub fn wait_for<T>(mut check: impl FnMut() -> Option<T>) -> Option<T> {
+    let started = Instant::now();
+    while started.elapsed() < TIMEOUT {
+        if let Some(value) = check() {
+            return Some(value);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    None
+}
@@ -0,0 +1,112 @@
+//! Maps a synthetic `_NET_WM_WINDOW_TYPE_DESKTOP` window under oxwm on a virtual X
+//! server and checks it is left unmanaged, covers the monitor and stays below
+//! clients. Ignored by default; run with `cargo test -- --ignored` where `Xvfb`
+//! is installed.
+
+mod common;
+
+use common::{SCREEN_HEIGHT, SCREEN_WIDTH, Session, wait_for};
+use x11rb::COPY_DEPTH_FROM_PARENT;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{
+    AtomEnum, ConnectionExt, CreateWindowAux, MapState, PropMode, WindowClass,
+};
+use x11rb::wrapper::ConnectionExt as _;
+
+fn create_window(session: &Session, window_type: Option<&str>) -> u32 {
+    let connection = &session.connection;
+    let window = connection.generate_id().unwrap();
+    connection
+        .create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            session.root,
+            10,
+            10,
+            200,
+            150,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            0,
+            &CreateWindowAux::new(),
+        )
+        .unwrap();
+    if let Some(window_type) = window_type {
+        connection
+            .change_property32(
+                PropMode::REPLACE,
+                window,
+                session.atom("_NET_WM_WINDOW_TYPE"),
+                AtomEnum::ATOM,
+                &[session.atom(window_type)],
+            )
+            .unwrap();
+    }
+    connection.map_window(window).unwrap();
+    connection.flush().unwrap();
+    window
+}
+
+fn is_viewable(session: &Session, window: u32) -> bool {
+    session
+        .connection
+        .get_window_attributes(window)
+        .ok()
+        .and_then(|cookie| cookie.reply().ok())
+        .is_some_and(|attributes| attributes.map_state == MapState::VIEWABLE)
+}
+
+/// Position of `window` in the root's children, bottom-most first.
+fn stacking_index(session: &Session, window: u32) -> usize {
+    let tree = session
+        .connection
+        .query_tree(session.root)
+        .unwrap()
+        .reply()
+        .unwrap();
+    tree.children
+        .iter()
+        .position(|&child| child == window)
+        .unwrap()
+}
+
+#[test]
+#[ignore = "needs Xvfb"]
+fn desktop_window_is_unmanaged_spanning_and_lowest() {
+    let session = Session::start("desktop", "", &[]);
+    wait_for(|| session.wm_running().then_some(())).expect("oxwm did not start");
+
+    let first_client = create_window(&session, None);
+    wait_for(|| session.managed_windows().contains(&first_client).then_some(()))
+        .expect("the normal window was never managed");
+
+    let desktop = create_window(&session, Some("_NET_WM_WINDOW_TYPE_DESKTOP"));
+    wait_for(|| is_viewable(&session, desktop).then_some(()))
+        .expect("the desktop window was never mapped");
+
+    let second_client = create_window(&session, None);
+    wait_for(|| session.managed_windows().contains(&second_client).then_some(()))
+        .expect("the second normal window was never managed");
+
+    assert!(!session.managed_windows().contains(&desktop));
+
+    let geometry = session
+        .connection
+        .get_geometry(desktop)
+        .unwrap()
+        .reply()
+        .unwrap();
+    assert_eq!(
+        (geometry.x, geometry.y, geometry.width, geometry.height),
+        (0, 0, SCREEN_WIDTH, SCREEN_HEIGHT)
+    );
+    assert_eq!(geometry.border_width, 0);
+
+    let desktop_index = stacking_index(&session, desktop);
+    for client in [first_client, second_client] {
+        assert!(
+            desktop_index < stacking_index(&session, client),
+            "the desktop window was stacked above a client"
+        );
+    }
+}